@@ -0,0 +1,182 @@
+use cellumina::rule::{BoundaryBehaviour, EnvironmentRule, Pattern, PatternRule, Rule};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+
+/// The side lengths of the square grids all benchmarks are run on.
+const SIZES: [usize; 2] = [256, 1024];
+
+/// Creates a square grid of the passed size, with cells set to ```cell``` with the passed probability (only in the upper half, if requested).
+/// The generator is seeded, so every run measures the same setup.
+fn seeded_grid(size: usize, cell: u8, probability: f64, upper_half: bool) -> cellumina::CellGrid {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    grid::Grid::from_vec(
+        (0..size * size)
+            .map(|index| {
+                if (!upper_half || index < size * size / 2) && rng.gen_bool(probability) {
+                    cell
+                } else {
+                    0
+                }
+            })
+            .collect(),
+        size,
+    )
+}
+
+/// The falling sand rule set from the sand example, seeded.
+fn sand_rule() -> PatternRule {
+    let rule = PatternRule::from_patterns(
+        &[
+            // Sand falls down by one or even two spaces if possible.
+            Pattern {
+                before: grid::grid![[59][0][0]],
+                after: grid::grid![[0][0][59]],
+                priority: 1.0,
+                chance: 0.9,
+                ..Default::default()
+            },
+            Pattern {
+                before: grid::grid![[59][0]],
+                after: grid::grid![[0][59]],
+                priority: 0.5,
+                ..Default::default()
+            },
+            // Stacks of sand collapse to the left or right.
+            Pattern {
+                before: grid::grid![[59, 0][59, 0]],
+                after: grid::grid![[0, 127][127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid::grid![[0, 59][0, 59]],
+                after: grid::grid![[127, 0][59, 127]],
+                ..Default::default()
+            },
+            // Even 45 degree slopes of sand collapse.
+            Pattern {
+                before: grid::grid![[59, 0, 0][59, 59, 0]],
+                after: grid::grid![[0, 127, 127][127, 127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid::grid![[0, 0, 59][0, 59, 59]],
+                after: grid::grid![[127, 127, 0][59, 127, 127]],
+                ..Default::default()
+            },
+        ],
+        BoundaryBehaviour::Symbol(126),
+        BoundaryBehaviour::Symbol(126),
+    );
+    rule.reseed(0);
+    rule
+}
+
+/// John Conway's Game of Life on a torus.
+fn life_rule() -> EnvironmentRule {
+    EnvironmentRule {
+        environment_size: [1, 1, 1, 1],
+        row_boundary: BoundaryBehaviour::Periodic,
+        col_boundary: BoundaryBehaviour::Periodic,
+        cell_transform: |env| match cellumina::rule::helpers::count_neighbors(env, 1) {
+            2 => env[1][1],
+            3 => 1,
+            _ => 0,
+        },
+        ..Default::default()
+    }
+}
+
+/// Measures a single step of the falling sand rule set, starting with sand in the upper half of the grid.
+fn pattern_rule(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pattern_rule");
+    group.sample_size(10);
+    for size in SIZES {
+        let rule = sand_rule();
+        let mut grid = seeded_grid(size, 59, 0.3, true);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| rule.transform(&mut grid))
+        });
+    }
+    group.finish();
+}
+
+/// Measures a single step of the Game of Life on a randomly filled grid, reusing a buffer as the automaton does.
+fn environment_rule(c: &mut Criterion) {
+    let mut group = c.benchmark_group("environment_rule");
+    group.sample_size(10);
+    for size in SIZES {
+        let rule = life_rule();
+        let mut grid = seeded_grid(size, 1, 0.3, false);
+        let mut buffer = cellumina::CellGrid::new(0, 0);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                if rule.transform_buffered(&mut grid, &mut buffer) {
+                    std::mem::swap(&mut grid, &mut buffer);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Measures converting the state to an image after a step, as the live view does every frame.
+fn create_image_buffer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("create_image_buffer");
+    for size in SIZES {
+        let mut automaton = cellumina::AutomatonBuilder::new()
+            .from_grid(seeded_grid(size, 1, 0.3, false))
+            .with_rule(life_rule())
+            .with_color(1, [255, 255, 255, 255])
+            .build();
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            // only the conversion is timed, the step just makes sure the image is not cached
+            b.iter_custom(|iterations| {
+                let mut elapsed = std::time::Duration::ZERO;
+                for _ in 0..iterations {
+                    automaton.next_step();
+                    let start = std::time::Instant::now();
+                    criterion::black_box(automaton.create_image_buffer());
+                    elapsed += start.elapsed();
+                }
+                elapsed
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Measures a step of the Game of Life with and without dirty tracking, on an otherwise empty grid with a single blinker, so only a few cells are active.
+fn dirty_tracking(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dirty_tracking");
+    group.sample_size(10);
+    for size in SIZES {
+        for tracking in [false, true] {
+            let mut grid = cellumina::CellGrid::new(size, size);
+            for col in size / 2 - 1..size / 2 + 2 {
+                grid[size / 2][col] = 1;
+            }
+            let builder = cellumina::AutomatonBuilder::new()
+                .from_grid(grid)
+                .with_rule(life_rule());
+            let mut automaton = if tracking {
+                builder.with_dirty_tracking().build()
+            } else {
+                builder.build()
+            };
+            let name = if tracking { "on" } else { "off" };
+            group.bench_function(BenchmarkId::new(name, size), |b| {
+                b.iter(|| automaton.next_step())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    pattern_rule,
+    environment_rule,
+    create_image_buffer,
+    dirty_tracking
+);
+criterion_main!(benches);
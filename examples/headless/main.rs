@@ -0,0 +1,101 @@
+use cellumina::prelude::*;
+use cellumina::rule::GridTopology;
+
+/// The side length of the square grid.
+const SIZE: usize = 48;
+
+/// This example runs John Conway's Game of Life without a window, as a program embedding cellumina would:
+/// It builds an automaton, steps it, inspects the result and exports it, all without the ```display``` feature.
+/// Usage: ```cargo run --example headless -- [generations] [output directory]```, defaulting to 100 generations written to the temporary directory.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let generations = args
+        .next()
+        .map(|arg| {
+            arg.parse::<u64>()
+                .expect("Number of generations must be an integer.")
+        })
+        .unwrap_or(100);
+    let output = args
+        .next()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+
+    // Start with an R-pentomino in the center, which keeps changing for a long time, and a glider in the top left corner.
+    let mut cells = vec![0; SIZE * SIZE];
+    let center = SIZE / 2;
+    for (row, col) in [
+        (center - 1, center),
+        (center - 1, center + 1),
+        (center, center - 1),
+        (center, center),
+        (center + 1, center),
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (2, 1),
+        (2, 2),
+    ] {
+        cells[row * SIZE + col] = 1;
+    }
+
+    let mut automaton = AutomatonBuilder::new()
+        .from_vec(cells, SIZE as u32)
+        // The Game of Life, on a torus so nothing leaves the grid.
+        .with_rule(LifeLikeRule {
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
+            ..LifeLikeRule::parse("B3/S23").unwrap()
+        })
+        // Count the live cells in every generation.
+        .with_stats_recorder(vec![1])
+        .with_color(0, [0, 0, 0, 255])
+        .with_color(1, [95, 205, 228, 255])
+        .build();
+
+    // Without a minimum time step, every call of next_step performs a step.
+    for generation in 1..=generations {
+        automaton.next_step();
+        if generation % 10 == 0 {
+            println!(
+                "Generation {generation:>4}: {:>4} live cells",
+                automaton.positions_of(1).len()
+            );
+        }
+    }
+
+    // Inspect the population curve recorded along the way.
+    let stats = automaton
+        .stats_recorder()
+        .expect("The automaton was built with a stats recorder.");
+    let population = stats.series(1);
+    println!(
+        "Population over {} generations: started at {}, ranged from {} to {}, ended at {}.",
+        generations,
+        population.first().unwrap(),
+        population.iter().min().unwrap(),
+        population.iter().max().unwrap(),
+        population.last().unwrap(),
+    );
+
+    // Export the final state as a scaled image, a text snapshot and the population curve as CSV.
+    let image = output.join("headless.png");
+    automaton
+        .create_image_buffer_scaled(8, GridTopology::Square)
+        .save(&image)
+        .expect("Could not save the image.");
+    let text = output.join("headless.txt");
+    automaton
+        .save(&text)
+        .expect("Could not save the text snapshot.");
+    let csv = output.join("headless.csv");
+    stats
+        .write_csv(std::fs::File::create(&csv).expect("Could not create the CSV file."))
+        .expect("Could not write the CSV file.");
+    println!(
+        "Wrote {}, {} and {}.",
+        image.display(),
+        text.display(),
+        csv.display()
+    );
+}
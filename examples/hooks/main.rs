@@ -0,0 +1,51 @@
+use cellumina::prelude::*;
+
+/// This example shows how to interact with a running live view from code, by spawning sand at random locations.
+fn main() -> Result<(), CelluminaError> {
+    let size = 64;
+
+    AutomatonBuilder::new()
+        // Start with an empty grid.
+        .from_vec(vec![0; size * size], size as u32)
+        // Sand (X or 59) falls down and collapses to the sides.
+        .with_patterns(&[
+            Pattern {
+                before: grid![[59][0]],
+                after: grid![[0][59]],
+                priority: 1.0,
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[59, 0][59, 0]],
+                after: grid![[0, 127][127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[0, 59][0, 59]],
+                after: grid![[127, 0][59, 127]],
+                ..Default::default()
+            },
+        ])
+        .with_color(0, [20, 20, 30, 255])
+        .with_color(59, [194, 178, 128, 255])
+        .with_min_time_step(std::time::Duration::from_secs_f32(0.05))
+        .build()
+        .run_live_with_hooks(
+            cellumina::LiveHooks::new()
+                // Every 10 generations, a grain of sand appears somewhere.
+                .on_step(move |automaton, generation| {
+                    if generation % 10 == 0 {
+                        let (rows, cols) = automaton.dimensions();
+                        let (row, col) =
+                            (rand::random::<u32>() % rows, rand::random::<u32>() % cols);
+                        automaton.set_cell(row, col, 59).ok();
+                    }
+                })
+                // Painted cells are reported.
+                .on_edit(|row, col, cell| println!("Painted {cell} at ({row}, {col})."))
+                // When the window closes, report how far we got.
+                .on_exit(|automaton| {
+                    println!("Closed after {} generations.", automaton.generation())
+                }),
+        )
+}
@@ -1,8 +1,11 @@
+use cellumina::prelude::*;
+use cellumina::rule::helpers;
+
 /// This example implements a rock-paper-scissors cellular automaton.
-fn main() {
+fn main() -> Result<(), CelluminaError> {
     let size = 256 + 128;
 
-    cellumina::AutomatonBuilder::new()
+    AutomatonBuilder::new()
         // Generate a size x size initial state, with the top right, top left and bottom being the three colors
         .from_vec(
             (0..(size * size))
@@ -32,22 +35,20 @@ fn main() {
         .with_color(2, [61, 159, 235, 255])
         .with_color(3, [146, 199, 240, 255])
         // set progression rules
-        .with_rule(cellumina::rule::EnvironmentRule {
+        .with_rule(EnvironmentRule {
             environment_size: [1, 1, 1, 1],
             // Periodic boundaries in both directions
-            row_boundary: cellumina::rule::BoundaryBehaviour::Periodic,
-            col_boundary: cellumina::rule::BoundaryBehaviour::Periodic,
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
 
             cell_transform: |grid| {
-                let this = grid[1][1];
+                let this = helpers::center(grid);
                 let evil = (this + 1) % 4;
                 let neutral = (this + 2) % 4;
                 // if there are 3 or more cells that kill this one nearby -> kill this one with 70% chance
-                if grid.iter().filter(|&&val| val == evil).count() >= 3
-                    && rand::random::<f32>() < 0.7
-                {
+                if helpers::count_excluding_center(grid, evil) >= 3 && rand::random::<f32>() < 0.7 {
                     evil
-                } else if grid.iter().filter(|&&val| val == neutral).count() >= 6
+                } else if helpers::count_excluding_center(grid, neutral) >= 6
                 // 6 more neutral cells so we can swallow grains
                     && rand::random::<f32>() < 0.7
                 {
@@ -56,9 +57,10 @@ fn main() {
                     this
                 }
             },
+            ..Default::default()
         })
         // set time step
         .with_min_time_step(std::time::Duration::from_secs_f32(0.02))
         .build()
-        .run_live();
+        .run_live()
 }
@@ -0,0 +1,39 @@
+use cellumina::prelude::*;
+
+/// This example shows multiple automata one after another in the same process: First the Game of Life and, once its window is closed, a falling sand simulation.
+fn main() -> Result<(), CelluminaError> {
+    // A session reuses the same event loop for every window it opens.
+    let mut session = cellumina::LiveSession::new();
+
+    // Show the Game of Life until its window is closed.
+    let result = session.run(
+        AutomatonBuilder::new()
+            .from_text_file("./examples/game_of_life/gol_init.txt")
+            .with_preset(cellumina::rule::presets::game_of_life())
+            .with_min_time_step(std::time::Duration::from_secs_f32(0.1))
+            .build(),
+        Default::default(),
+    )?;
+
+    // The automaton is returned in the state it had when the window was closed.
+    if let Some(automaton) = result.into_automaton() {
+        println!(
+            "The Game of Life ran for {} generations, continuing with falling sand.",
+            automaton.generation()
+        );
+    }
+
+    // Then open a new window for the sand simulation.
+    let result = session.run(
+        AutomatonBuilder::new()
+            .from_text_file("./examples/sand/sand_init.txt")
+            .with_preset(cellumina::rule::presets::falling_sand())
+            .with_min_time_step(std::time::Duration::from_secs_f32(0.05))
+            .build(),
+        Default::default(),
+    )?;
+    if result.failed {
+        println!("The sand simulation stopped because drawing failed.");
+    }
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use cellumina::prelude::*;
+
+/// This example lets sand fall onto two kinds of terrain, stored in an auxiliary layer next to the state.
+/// On the left, sand slides off its piles as usual. On the right, the terrain is sticky and sand sticks wherever it lands, piling up much higher.
+fn main() -> Result<(), CelluminaError> {
+    const SIZE: usize = 64;
+
+    AutomatonBuilder::new()
+        // An empty grid with two sources (54) of sand in the top row.
+        .from_vec(
+            (0..SIZE * SIZE)
+                .map(|index| {
+                    if index == SIZE / 4 || index == 3 * SIZE / 4 {
+                        54
+                    } else {
+                        0
+                    }
+                })
+                .collect(),
+            SIZE as u32,
+        )
+        // The terrain: the right half of the grid is sticky (1), the left half is not (0).
+        // Rules may read it, but it never changes.
+        .with_aux_layer(Grid::from_vec(
+            (0..SIZE * SIZE)
+                .map(|index| u8::from(index % SIZE >= SIZE / 2))
+                .collect(),
+            SIZE,
+        ))
+        .with_pattern_edge_behaviour(
+            BoundaryBehaviour::Symbol(126),
+            BoundaryBehaviour::Symbol(126),
+        )
+        .with_patterns(&[
+            // The sources spawn sand (59) below them.
+            Pattern {
+                chance: 0.5,
+                before: grid![[54][0]],
+                after: grid![[54][59]],
+                ..Default::default()
+            },
+            // Sand falls down...
+            Pattern {
+                before: grid![[59][0]],
+                after: grid![[0][59]],
+                priority: 1.,
+                ..Default::default()
+            },
+            // ... and stacks of sand collapse to the left or right.
+            // Stuck sand (60) is not moved by any pattern, so sand on top of it stays in place.
+            Pattern {
+                before: grid![[59, 0][59, 0]],
+                after: grid![[0, 127][127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[0, 59][0, 59]],
+                after: grid![[127, 0][59, 127]],
+                ..Default::default()
+            },
+        ])
+        // Sand that has landed on sticky terrain gets stuck.
+        .with_rule(AuxEnvironmentRule {
+            // Each cell only looks at the cell below it.
+            environment_size: [0, 0, 1, 0],
+            // The bottom of the grid counts as ground.
+            row_boundary: BoundaryBehaviour::Symbol(126),
+            col_boundary: BoundaryBehaviour::Symbol(126),
+            // The first grid is the environment in the state, the second the same environment in the auxiliary layer.
+            cell_transform: |env, aux| match (env[0][0], env[1][0], aux[0][0]) {
+                (59, below, 1) if below != 0 => 60,
+                (cell, _, _) => cell,
+            },
+        })
+        .with_color(0, [61, 159, 184, 255])
+        .with_color(54, [128, 25, 14, 255])
+        .with_color(59, [224, 210, 159, 255])
+        .with_color(60, [184, 150, 90, 255])
+        .with_min_time_step(std::time::Duration::from_secs_f32(0.05))
+        .build()
+        .run_live()
+}
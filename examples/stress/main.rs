@@ -0,0 +1,118 @@
+use cellumina::prelude::*;
+use rand::{Rng, SeedableRng};
+
+/// This example runs large automata without a window and reports how many steps per second they manage.
+/// Usage: ```cargo run --release --example stress -- [steps] [size]```, defaulting to 100 steps on a 1024x1024 grid.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let steps = args
+        .next()
+        .map(|arg| {
+            arg.parse::<u64>()
+                .expect("Number of steps must be an integer.")
+        })
+        .unwrap_or(100);
+    let size = args
+        .next()
+        .map(|arg| arg.parse::<usize>().expect("Grid size must be an integer."))
+        .unwrap_or(1024);
+
+    // Falling sand, starting with sand in the upper half of the grid.
+    let sand = AutomatonBuilder::new()
+        .from_grid(seeded_grid(size, 59, 0.3, true))
+        .with_pattern_edge_behaviour(
+            BoundaryBehaviour::Symbol(126),
+            BoundaryBehaviour::Symbol(126),
+        )
+        .with_patterns(&[
+            // Sand falls down by one or even two spaces if possible.
+            Pattern {
+                before: grid![[59][0][0]],
+                after: grid![[0][0][59]],
+                priority: 1.0,
+                chance: 0.9,
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[59][0]],
+                after: grid![[0][59]],
+                priority: 0.5,
+                ..Default::default()
+            },
+            // Stacks of sand collapse to the left or right.
+            Pattern {
+                before: grid![[59, 0][59, 0]],
+                after: grid![[0, 127][127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[0, 59][0, 59]],
+                after: grid![[127, 0][59, 127]],
+                ..Default::default()
+            },
+            // Even 45 degree slopes of sand collapse.
+            Pattern {
+                before: grid![[59, 0, 0][59, 59, 0]],
+                after: grid![[0, 127, 127][127, 127, 59]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid![[0, 0, 59][0, 59, 59]],
+                after: grid![[127, 127, 0][59, 127, 127]],
+                ..Default::default()
+            },
+        ])
+        // Seed the randomness, so every run measures the same steps.
+        .with_seed(0)
+        .build();
+    run("sand", sand, steps);
+
+    // John Conway's Game of Life on a torus, starting from random noise.
+    let life = AutomatonBuilder::new()
+        .from_grid(seeded_grid(size, 1, 0.3, false))
+        .with_rule(EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
+            cell_transform: |env| match cellumina::rule::helpers::count_neighbors(env, 1) {
+                2 => env[1][1],
+                3 => 1,
+                _ => 0,
+            },
+            ..Default::default()
+        })
+        .build();
+    run("game of life", life, steps);
+}
+
+/// Performs the passed number of steps on the automaton and prints the resulting speed.
+fn run(name: &str, mut automaton: cellumina::Automaton, steps: u64) {
+    let (rows, cols) = automaton.dimensions();
+    let start = std::time::Instant::now();
+    while automaton.generation() < steps {
+        automaton.next_step();
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{name}, {rows}x{cols} grid: {steps} steps in {elapsed:.2}s, {:.1} steps/s",
+        steps as f64 / elapsed
+    );
+}
+
+/// Creates a square grid of the passed size, with cells set to ```cell``` with the passed probability (only in the upper half, if requested).
+/// The generator is seeded, so every run measures the same setup.
+fn seeded_grid(size: usize, cell: u8, probability: f64, upper_half: bool) -> cellumina::CellGrid {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    Grid::from_vec(
+        (0..size * size)
+            .map(|index| {
+                if (!upper_half || index < size * size / 2) && rng.gen_bool(probability) {
+                    cell
+                } else {
+                    0
+                }
+            })
+            .collect(),
+        size,
+    )
+}
@@ -1,34 +1,39 @@
+use cellumina::prelude::*;
+
 /// Tests the conversion of Pattern and PatternRule to and from String.
-fn main() {
+fn main() -> Result<(), CelluminaError> {
     // Let's create pretty basic rule.
-    let rule = cellumina::rule::PatternRule::from_patterns(
+    let rule = PatternRule::from_patterns(
         &[
-            cellumina::rule::Pattern {
+            Pattern {
                 chance: 1.0,
                 priority: 1.0,
-                before: grid::grid![[1][0]],
-                after: grid::grid![[0][1]],
+                before: grid![[1][0]],
+                after: grid![[0][1]],
+                ..Default::default()
             },
-            cellumina::rule::Pattern {
+            Pattern {
                 chance: 0.8,
                 priority: 0.5,
-                before: grid::grid![[1, 0][1, 0]],
-                after: grid::grid![[0, 0][1, 1]],
+                before: grid![[1, 0][1, 0]],
+                after: grid![[0, 0][1, 1]],
+                ..Default::default()
             },
-            cellumina::rule::Pattern {
+            Pattern {
                 chance: 0.8,
                 priority: 0.5,
-                before: grid::grid![[0, 1][0, 1]],
-                after: grid::grid![[0, 0][1, 1]],
+                before: grid![[0, 1][0, 1]],
+                after: grid![[0, 0][1, 1]],
+                ..Default::default()
             },
         ],
-        cellumina::rule::BoundaryBehaviour::Periodic,
-        cellumina::rule::BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
     );
 
     // Rules can be converted to strings.
     // The rule can be recreated from the display output.
-    let _rule2 = cellumina::rule::PatternRule::from(rule.to_string().as_str());
+    let _rule2 = PatternRule::from(rule.to_string().as_str());
 
     // Therefore, you can save this output to a file and reload the rule later (or even type such a file yourself so the rule and patterns do not need to be created in code.)
 
@@ -44,16 +49,16 @@ fn main() {
     )
     .expect("Could not write to file!");
 
-    let _rule3 = cellumina::rule::PatternRule::from(
+    let _rule3 = PatternRule::from(
         std::fs::read_to_string(path)
             .expect("Could not read file!")
             .as_str(),
     );
 
     // The sand_rules.cel file contains an exported copy of the sand rules from example 'sand'. Let's load it and run it.
-    cellumina::AutomatonBuilder::new()
+    AutomatonBuilder::new()
         // Load the rule from the file.
-        .with_rule(cellumina::rule::PatternRule::from(
+        .with_rule(PatternRule::from(
             std::fs::read_to_string("./examples/to_string/sand_rules.cel")
                 .expect("Could not read file.")
                 .as_str(),
@@ -63,18 +68,18 @@ fn main() {
         // Set the colors again
         .with_colors(std::collections::HashMap::from([
             // L is nothing, so well use a soft blue as our background.
-            (cellumina::char_to_id(' '), [61, 159, 184, 255]),
+            (char_to_id(' '), [61, 159, 184, 255]),
             // Sand
-            (cellumina::char_to_id('X'), [224, 210, 159, 255]),
+            (char_to_id('X'), [224, 210, 159, 255]),
             // Fire
-            (cellumina::char_to_id('F'), [224, 105, 54, 255]),
+            (char_to_id('F'), [224, 105, 54, 255]),
             // Ash
-            (cellumina::char_to_id('A'), [184, 182, 182, 255]),
+            (char_to_id('A'), [184, 182, 182, 255]),
             // The Source
-            (cellumina::char_to_id('S'), [128, 25, 14, 255]),
+            (char_to_id('S'), [128, 25, 14, 255]),
         ]))
         // Set a time step.
         .with_min_time_step(std::time::Duration::from_secs_f32(0.1))
         .build()
-        .run_live();
+        .run_live()
 }
@@ -1,12 +1,15 @@
+use cellumina::prelude::*;
+use cellumina::rule::helpers;
+
 /// This example creates a labyrith from a starting point
-fn main() {
-    tree();
+fn main() -> Result<(), CelluminaError> {
+    tree()
 }
 
-fn tree() {
+fn tree() -> Result<(), CelluminaError> {
     simple_logger::init_with_level(log::Level::Info).unwrap();
     let size = 128;
-    cellumina::AutomatonBuilder::new()
+    AutomatonBuilder::new()
         // Generate a size x size initial state, all being nothing.
         // The user needs to set one cell to 3 to start the algo
         .from_vec(vec![0; size * size], size as u32)
@@ -29,11 +32,11 @@ fn tree() {
         .with_color(21, [181, 69, 69, 255]) // ball outer
         .with_color(22, [181, 69, 69, 255]) // ball outer
         // set progression rules
-        .with_rule(cellumina::rule::EnvironmentRule {
+        .with_rule(EnvironmentRule {
             environment_size: [1, 1, 1, 1],
             // Periodic boundaries in both directions
-            row_boundary: cellumina::rule::BoundaryBehaviour::blocking_boundary(),
-            col_boundary: cellumina::rule::BoundaryBehaviour::blocking_boundary(),
+            row_boundary: BoundaryBehaviour::blocking_boundary(),
+            col_boundary: BoundaryBehaviour::blocking_boundary(),
 
             cell_transform: |grid| {
                 let this = grid[1][1];
@@ -93,11 +96,12 @@ fn tree() {
                     this
                 }
             },
+            ..Default::default()
         })
         // set time step
         .with_min_time_step(std::time::Duration::from_secs_f32(0.02))
         .build()
-        .run_live();
+        .run_live()
 }
 
 fn choose(chance: f32, def: u8, other: u8) -> u8 {
@@ -109,9 +113,9 @@ fn choose(chance: f32, def: u8, other: u8) -> u8 {
 }
 
 #[allow(dead_code)]
-fn labyrinth() {
+fn labyrinth() -> Result<(), CelluminaError> {
     let size = 128;
-    cellumina::AutomatonBuilder::new()
+    AutomatonBuilder::new()
         // Generate a size x size initial state, all being nothing.
         // The user needs to set one cell to 1 to start the algo
         .from_vec(vec![0; size * size], size as u32)
@@ -121,23 +125,23 @@ fn labyrinth() {
         .with_color(2, [88, 95, 107, 255]) // wall
         .with_color(3, [88, 95, 107, 255]) // wall
         // set progression rules
-        .with_rule(cellumina::rule::EnvironmentRule {
+        .with_rule(EnvironmentRule {
             environment_size: [1, 1, 1, 1],
             // Periodic boundaries in both directions
-            row_boundary: cellumina::rule::BoundaryBehaviour::Periodic,
-            col_boundary: cellumina::rule::BoundaryBehaviour::Periodic,
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
 
             cell_transform: |grid| {
-                let this = grid[1][1];
+                let this = helpers::center(grid);
 
                 if this == 0 {
                     //exactly one horizontal neighbor
-                    if grid[0][1] + grid[2][1] + grid[1][0] + grid[1][2] == 1
+                    if helpers::orthogonal(grid).iter().sum::<u8>() == 1
                         && rand::random::<f32>() < 0.3
                     {
                         1
                     } else {
-                        match grid.iter().filter(|&&val| val == 1).count() {
+                        match helpers::count_excluding_center(grid, 1) {
                             2 => {
                                 if rand::random::<f32>() < 0.2 {
                                     1
@@ -147,37 +151,28 @@ fn labyrinth() {
                                     0
                                 }
                             }
-                            3 => {
-                                if rand::random::<f32>() < 0.4 {
-                                    1
-                                } else {
-                                    0
-                                }
-                            }
-                            4 => {
-                                if rand::random::<f32>() < 0.6 {
-                                    1
-                                } else {
-                                    0
-                                }
-                            }
-                            5 | 6 | 7 | 8 | 9 => 1,
+                            3 if rand::random::<f32>() < 0.4 => 1,
+                            3 => 0,
+                            4 if rand::random::<f32>() < 0.6 => 1,
+                            4 => 0,
+                            5..=8 => 1,
                             _ => 0,
                         }
                     }
                 } else if this == 2 {
                     // clear 'pillars'
-                    match grid.iter().filter(|&&val| val == 1).count() {
-                        6 | 7 | 8 | 9 => 1,
+                    match helpers::count_excluding_center(grid, 1) {
+                        6..=8 => 1,
                         _ => 2,
                     }
                 } else {
                     this
                 }
             },
+            ..Default::default()
         })
         // set time step
         .with_min_time_step(std::time::Duration::from_secs_f32(0.02))
         .build()
-        .run_live();
+        .run_live()
 }
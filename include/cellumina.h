@@ -0,0 +1,112 @@
+#ifndef CELLUMINA_H
+#define CELLUMINA_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a call to a function of the C interface.
+typedef enum CelluminaStatus {
+  // The call succeeded.
+  CELLUMINA_STATUS_OK = 0,
+  // A required pointer was null.
+  CELLUMINA_STATUS_NULL_POINTER = 1,
+  // An argument was invalid, such as a string that is not UTF-8 or a state with zero columns.
+  CELLUMINA_STATUS_INVALID_ARGUMENT = 2,
+  // A rule could not be parsed.
+  CELLUMINA_STATUS_PARSE_ERROR = 3,
+  // The passed buffer is too small for the state.
+  CELLUMINA_STATUS_BUFFER_TOO_SMALL = 4,
+  // The configuration of the builder is invalid.
+  CELLUMINA_STATUS_BUILD_ERROR = 5,
+  // The call panicked, for example in a rule, and was aborted. The handles passed remain valid, but may hold a partially changed state.
+  CELLUMINA_STATUS_PANIC = 6,
+} CelluminaStatus;
+
+// A built automaton, see [Automaton].
+typedef struct CelluminaAutomaton CelluminaAutomaton;
+
+// An automaton under construction, see [AutomatonBuilder].
+typedef struct CelluminaBuilder CelluminaBuilder;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a new builder without a state or rules. Release it with [cellumina_builder_free] unless it is passed to [cellumina_builder_build].
+// Returns null if creating the builder panicked.
+CelluminaBuilder *cellumina_builder_new(void);
+
+// Sets the initial state to the ```len``` cells at ```cells```, row by row with ```cols``` cells per row.
+//
+// # Safety
+// ```builder``` has to be a handle created by [cellumina_builder_new] and ```cells``` has to point to at least ```len``` bytes.
+CelluminaStatus cellumina_builder_set_state(CelluminaBuilder *builder,
+                                            const uint8_t *cells,
+                                            size_t len,
+                                            size_t cols);
+
+// Adds a [LifeLikeRule](rule::LifeLikeRule) given in the B/S notation, such as ```B3/S23```.
+//
+// # Safety
+// ```builder``` has to be a handle created by [cellumina_builder_new] and ```notation``` a null-terminated string.
+CelluminaStatus cellumina_builder_add_life_like_rule(CelluminaBuilder *builder,
+                                                     const char *notation);
+
+// Adds a [PatternRule](rule::PatternRule) given in the ```.cel``` format.
+//
+// # Safety
+// ```builder``` has to be a handle created by [cellumina_builder_new] and ```cel``` a null-terminated string.
+CelluminaStatus cellumina_builder_add_pattern_rule(CelluminaBuilder *builder, const char *cel);
+
+// Builds the automaton and writes its handle to ```automaton```. Release it with [cellumina_automaton_free].
+//
+// The builder is consumed and must not be used afterwards, even if building fails.
+//
+// # Safety
+// ```builder``` has to be a handle created by [cellumina_builder_new] and ```automaton``` has to point to writable memory for a handle.
+CelluminaStatus cellumina_builder_build(CelluminaBuilder *builder,
+                                        CelluminaAutomaton **automaton);
+
+// Releases a builder that was not built. Passing null does nothing.
+//
+// # Safety
+// ```builder``` has to be null or a handle created by [cellumina_builder_new], which must not be used afterwards.
+void cellumina_builder_free(CelluminaBuilder *builder);
+
+// Performs ```steps``` time steps.
+//
+// # Safety
+// ```automaton``` has to be a handle created by [cellumina_builder_build].
+CelluminaStatus cellumina_automaton_step(CelluminaAutomaton *automaton, uint64_t steps);
+
+// Writes the number of rows and columns of the state to ```rows``` and ```cols```.
+//
+// # Safety
+// ```automaton``` has to be a handle created by [cellumina_builder_build], ```rows``` and ```cols``` have to point to writable memory.
+CelluminaStatus cellumina_automaton_size(const CelluminaAutomaton *automaton,
+                                         size_t *rows,
+                                         size_t *cols);
+
+// Copies the state into ```buffer```, row by row with one byte per cell. The buffer needs space for at least ```rows * cols``` cells.
+//
+// # Safety
+// ```automaton``` has to be a handle created by [cellumina_builder_build] and ```buffer``` has to point to at least ```len``` writable bytes.
+CelluminaStatus cellumina_automaton_read_state(const CelluminaAutomaton *automaton,
+                                               uint8_t *buffer,
+                                               size_t len);
+
+// Releases an automaton. Passing null does nothing.
+//
+// # Safety
+// ```automaton``` has to be null or a handle created by [cellumina_builder_build], which must not be used afterwards.
+void cellumina_automaton_free(CelluminaAutomaton *automaton);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* CELLUMINA_H */
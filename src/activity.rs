@@ -0,0 +1,141 @@
+use crate::CellGrid;
+
+/// Keeps track of how many generations ago each cell of an automaton's state last changed.
+///
+/// Feed it every new state using [ActivityMap::update] and then use [ActivityMap::tint] to highlight recently changed cells in an image of the state,
+/// for example one created by [Automaton::create_image_buffer](crate::Automaton::create_image_buffer).
+/// ```
+/// # use cellumina::ActivityMap;
+/// let mut auto = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![0, 1, 0, 0], 2)
+///     .with_color(1, [255, 255, 255, 255])
+///     .build();
+/// let mut activity = ActivityMap::new(auto.state());
+/// for _ in 0..10 {
+///     auto.next_step();
+///     activity.update(auto.state());
+/// }
+/// let mut image = auto.create_image_buffer();
+/// activity.tint(&mut image, 4.);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActivityMap {
+    /// The generation in which each cell last changed.
+    last_changed: grid::Grid<u64>,
+    /// The state passed in the last update, to compare the next one with.
+    previous: CellGrid,
+    /// The number of updates performed so far.
+    generation: u64,
+}
+
+impl ActivityMap {
+    /// The brightness factor applied to cells that have not changed in a long time.
+    const MIN_BRIGHTNESS: f32 = 0.25;
+
+    /// Creates a new activity map starting from the passed state. All cells count as having changed in generation 0.
+    pub fn new(state: &CellGrid) -> Self {
+        Self {
+            last_changed: grid::Grid::new(state.rows(), state.cols()),
+            previous: state.clone(),
+            generation: 0,
+        }
+    }
+
+    /// Compares the passed state with the previous one and records the changed cells.
+    /// If the dimensions of the state changed, all cells count as changed.
+    pub fn update(&mut self, state: &CellGrid) {
+        self.generation += 1;
+        if state.size() != self.previous.size() {
+            self.last_changed = grid::Grid::new(state.rows(), state.cols());
+            self.last_changed.fill(self.generation);
+        } else {
+            for ((last, old), new) in self
+                .last_changed
+                .iter_mut()
+                .zip(self.previous.iter())
+                .zip(state.iter())
+            {
+                if old != new {
+                    *last = self.generation;
+                }
+            }
+        }
+        self.previous.clone_from(state);
+    }
+
+    /// Returns the number of generations since the cell at the specified indices last changed, or [None] if the index is out of bounds.
+    pub fn age(&self, row: usize, col: usize) -> Option<u64> {
+        self.last_changed
+            .get(row, col)
+            .map(|last| self.generation - last)
+    }
+
+    /// Returns the brightness factor of a cell that last changed ```age``` generations ago.
+    ///
+    /// Cells that just changed have a brightness of 1, which then decays exponentially, halving every ```half_life``` generations,
+    /// towards a minimum brightness of 0.25 so that static cells stay visible.
+    pub fn brightness(age: u64, half_life: f32) -> f32 {
+        let decay = if half_life > 0. {
+            0.5f32.powf(age as f32 / half_life)
+        } else if age == 0 {
+            1.
+        } else {
+            0.
+        };
+        Self::MIN_BRIGHTNESS + (1. - Self::MIN_BRIGHTNESS) * decay
+    }
+
+    /// Scales the color channels (not alpha) of each pixel of the passed image with the brightness of the corresponding cell, see [ActivityMap::brightness].
+    /// The image must have the same dimensions as the tracked state, with one pixel per cell.
+    pub fn tint(&self, image: &mut image::RgbaImage, half_life: f32) {
+        for (col, row, pixel) in image.enumerate_pixels_mut() {
+            if let Some(age) = self.age(row as usize, col as usize) {
+                let brightness = Self::brightness(age, half_life);
+                for channel in pixel.0.iter_mut().take(3) {
+                    *channel = (*channel as f32 * brightness).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn brightness_test() {
+    // just changed: full brightness
+    assert_eq!(ActivityMap::brightness(0, 4.), 1.);
+    // one half life: half of the decaying part remains
+    assert_eq!(ActivityMap::brightness(4, 4.), 0.25 + 0.75 * 0.5);
+    assert_eq!(ActivityMap::brightness(8, 4.), 0.25 + 0.75 * 0.25);
+    // monotonously decreasing towards the minimum
+    assert!(ActivityMap::brightness(5, 4.) < ActivityMap::brightness(4, 4.));
+    assert!((ActivityMap::brightness(1000, 4.) - 0.25).abs() < 1e-6);
+    // a half life of zero only highlights the latest changes
+    assert_eq!(ActivityMap::brightness(0, 0.), 1.);
+    assert_eq!(ActivityMap::brightness(1, 0.), 0.25);
+}
+
+#[test]
+fn activity_map_test() {
+    let mut state = grid::grid![[0, 0][0, 0]];
+    let mut activity = ActivityMap::new(&state);
+    assert_eq!(activity.age(0, 0), Some(0));
+
+    state[0][1] = 1;
+    activity.update(&state);
+    state[1][1] = 1;
+    activity.update(&state);
+    activity.update(&state);
+
+    assert_eq!(activity.age(0, 0), Some(3));
+    assert_eq!(activity.age(0, 1), Some(2));
+    assert_eq!(activity.age(1, 1), Some(1));
+    assert_eq!(activity.age(2, 0), None);
+
+    let mut image = image::RgbaImage::from_pixel(2, 2, image::Rgba([200, 200, 200, 255]));
+    activity.tint(&mut image, 1.);
+    // alpha is untouched
+    assert_eq!(image.get_pixel(0, 0).0[3], 255);
+    // more recently changed cells are brighter
+    assert!(image.get_pixel(1, 1).0[0] > image.get_pixel(1, 0).0[0]);
+    assert!(image.get_pixel(1, 0).0[0] > image.get_pixel(0, 0).0[0]);
+}
@@ -0,0 +1,254 @@
+//! Metrics comparing two states of the same size, such as scoring how similar the end states of a parameter sweep are.
+//! ```
+//! # use cellumina::{analysis, testing::parse_grid};
+//! let a = parse_grid("0110\n1001");
+//! let b = parse_grid("0111\n1000");
+//! assert_eq!(analysis::hamming(&a, &b).unwrap(), 2);
+//! assert_eq!(analysis::similarity(&a, &b).unwrap(), 0.75);
+//! assert_eq!(analysis::per_symbol_confusion(&a, &b).unwrap()[&(1, 0)], 1);
+//! ```
+//! All metrics return a [DimensionMismatch](crate::CelluminaError::DimensionMismatch) error if the grids differ in size.
+//!
+//! With the ```mmap``` feature, states saved in the binary ```.cels``` format can be inspected with the unsafe ```open_binary``` without reading them into memory first.
+
+use std::collections::HashMap;
+
+use crate::CellGrid;
+
+/// Returns the number of cells that hold different symbols in both grids.
+///
+/// ## Error
+/// If the grids do not have the same dimensions.
+pub fn hamming(a: &CellGrid, b: &CellGrid) -> Result<usize, crate::CelluminaError> {
+    Ok(cell_pairs(a, b)?.filter(|(x, y)| x != y).count())
+}
+
+/// Returns the share of cells that hold the same symbol in both grids, from 0 (all cells differ) to 1 (the grids are equal).
+/// Two empty grids are considered equal.
+///
+/// ## Error
+/// If the grids do not have the same dimensions.
+pub fn similarity(a: &CellGrid, b: &CellGrid) -> Result<f64, crate::CelluminaError> {
+    let distance = hamming(a, b)?;
+    let cells = a.rows() * a.cols();
+    if cells == 0 {
+        return Ok(1.);
+    }
+    Ok(1. - distance as f64 / cells as f64)
+}
+
+/// Counts, for each pair of symbols ```(x, y)```, the number of cells holding ```x``` in the first grid and ```y``` in the second grid,
+/// showing which symbols turned into which. Pairs that do not occur are not contained in the map.
+///
+/// ## Error
+/// If the grids do not have the same dimensions.
+pub fn per_symbol_confusion(
+    a: &CellGrid,
+    b: &CellGrid,
+) -> Result<HashMap<(u8, u8), usize>, crate::CelluminaError> {
+    let mut res = HashMap::new();
+    for pair in cell_pairs(a, b)? {
+        *res.entry(pair).or_insert(0) += 1;
+    }
+    Ok(res)
+}
+
+/// Iterates over the symbols of both grids cell by cell, after checking that their dimensions match.
+pub(crate) fn cell_pairs<'a, T: Copy>(
+    a: &'a grid::Grid<T>,
+    b: &'a grid::Grid<T>,
+) -> Result<impl Iterator<Item = (T, T)> + 'a, crate::CelluminaError> {
+    if a.size() != b.size() {
+        return Err(crate::CelluminaError::DimensionMismatch {
+            expected: a.size(),
+            got: b.size(),
+        });
+    }
+    // both grids are row-major and of the same size, so their cells line up
+    Ok(a.iter().copied().zip(b.iter().copied()))
+}
+
+/// A read-only view of a state saved in the uncompressed binary ```.cels``` format, as written by [Automaton::save_binary](crate::Automaton::save_binary),
+/// with the file mapped into memory instead of being read, so even very large states open instantly and only the cells accessed are loaded.
+/// Created with [open_binary].
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub struct BinaryGridView {
+    /// The mapped file, including its header.
+    map: memmap2::Mmap,
+    /// The number of rows of the state.
+    rows: usize,
+    /// The number of columns of the state.
+    cols: usize,
+}
+
+/// Maps the uncompressed binary state at the passed path into memory for read-only access, see [BinaryGridView].
+///
+/// ## Safety
+/// The file must not be changed, truncated or removed, by this or any other process, while the view exists, for example by saving an automaton to the same path.
+/// As the cells are read directly from the mapped file, this would otherwise be undefined behaviour.
+/// To inspect files that may change, read them with [AutomatonBuilder::from_binary_file](crate::AutomatonBuilder::from_binary_file) instead.
+/// ```
+/// let auto = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![0, 1, 2, 3, 4, 5], 3)
+///     .build();
+/// let path = std::env::temp_dir().join("cellumina_open_binary_doctest.cels");
+/// auto.save_binary(&path, false).unwrap();
+///
+/// // Safety: the file is not changed before the view is dropped
+/// let view = unsafe { cellumina::analysis::open_binary(&path) }.unwrap();
+/// assert_eq!(view.size(), (2, 3));
+/// assert_eq!(view.get(1, 0), Some(3));
+/// assert_eq!(view.row(1), Some([3, 4, 5].as_slice()));
+/// assert_eq!(&view.to_grid(), auto.state());
+/// # drop(view);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+/// ## Error
+/// When the file can not be opened or mapped, or is not an uncompressed binary state. Compressed ```.celz``` files can not be mapped.
+#[cfg(feature = "mmap")]
+pub unsafe fn open_binary(
+    path: impl AsRef<std::path::Path>,
+) -> Result<BinaryGridView, crate::CelluminaError> {
+    let file = std::fs::File::open(path)?;
+    // Safety: the file is only read, and the caller guarantees it is not changed while it is mapped.
+    let map = unsafe { memmap2::Mmap::map(&file) }?;
+    let (rows, cols) = crate::binary::parse_header(&map)?;
+    Ok(BinaryGridView { map, rows, cols })
+}
+
+#[cfg(feature = "mmap")]
+impl BinaryGridView {
+    /// Returns the number of rows of the state.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns of the state.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the dimensions of the state as ```(rows, cols)```, like [grid::Grid::size].
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns all cells of the state, row by row.
+    pub fn cells(&self) -> &[u8] {
+        &self.map[crate::binary::HEADER_LEN..]
+    }
+
+    /// Returns the cell at the passed row and column, or [None] if it is out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        (row < self.rows && col < self.cols).then(|| self.cells()[row * self.cols + col])
+    }
+
+    /// Returns the cells of the passed row, or [None] if it is out of bounds.
+    pub fn row(&self, row: usize) -> Option<&[u8]> {
+        (row < self.rows).then(|| &self.cells()[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// Copies the state into a grid, for example to compare it with the metrics of this module.
+    pub fn to_grid(&self) -> CellGrid {
+        grid::Grid::from_vec(self.cells().to_vec(), self.cols)
+    }
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn binary_grid_view_test() {
+    let grid = crate::testing::parse_grid(
+        "0120
+         1201
+         2A0z",
+    );
+    let path = std::env::temp_dir().join("cellumina_binary_grid_view_test.cels");
+    std::fs::write(&path, crate::binary::encode(&grid, false).unwrap()).unwrap();
+    // Safety: the file is only changed after the view was dropped
+    let view = unsafe { open_binary(&path) }.unwrap();
+
+    // indexed access matches the grid
+    assert_eq!(view.size(), grid.size());
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            assert_eq!(view.get(row, col), Some(grid[row][col]));
+        }
+        assert_eq!(
+            view.row(row).unwrap(),
+            grid.iter_row(row).copied().collect::<Vec<_>>()
+        );
+    }
+    assert_eq!(view.get(3, 0), None);
+    assert_eq!(view.get(0, 4), None);
+    assert_eq!(view.row(3), None);
+    assert_eq!(view.to_grid(), grid);
+    drop(view);
+
+    // corrupted headers are rejected
+    let mut bytes = crate::binary::encode(&grid, false).unwrap();
+    bytes[9] = 5;
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(matches!(
+        unsafe { open_binary(&path) },
+        Err(crate::CelluminaError::InvalidBinary(_))
+    ));
+    std::fs::write(&path, b"CELZ").unwrap();
+    assert!(matches!(
+        unsafe { open_binary(&path) },
+        Err(crate::CelluminaError::UnsupportedFormat(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn analysis_test() {
+    use crate::testing::parse_grid;
+
+    let a = parse_grid(
+        "0120
+         1201",
+    );
+    let b = parse_grid(
+        "0100
+         1221",
+    );
+    assert_eq!(hamming(&a, &a).unwrap(), 0);
+    assert_eq!(hamming(&a, &b).unwrap(), 2);
+    assert_eq!(similarity(&a, &a).unwrap(), 1.);
+    assert_eq!(similarity(&a, &b).unwrap(), 0.75);
+    assert_eq!(
+        similarity(&parse_grid("01"), &parse_grid("10")).unwrap(),
+        0.
+    );
+    assert_eq!(
+        similarity(&CellGrid::new(0, 0), &CellGrid::new(0, 0)).unwrap(),
+        1.
+    );
+
+    let confusion = per_symbol_confusion(&a, &b).unwrap();
+    assert_eq!(
+        confusion,
+        HashMap::from([
+            ((0, 0), 2),
+            ((1, 1), 3),
+            ((2, 2), 1),
+            ((2, 0), 1),
+            ((0, 2), 1),
+        ])
+    );
+    assert_eq!(confusion.values().sum::<usize>(), 8);
+
+    let wide = parse_grid("0120");
+    assert!(matches!(
+        hamming(&a, &wide),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (2, 4),
+            got: (1, 4)
+        })
+    ));
+    assert!(similarity(&a, &wide).is_err());
+    assert!(per_symbol_confusion(&a, &wide).is_err());
+    // same number of cells, but transposed
+    assert!(hamming(&parse_grid("01"), &parse_grid("0\n1")).is_err());
+}
@@ -0,0 +1,122 @@
+use crate::{AutomatonGeneric, Cell};
+
+/// A condition on which a running automaton stops by itself, such as when all life has died out.
+///
+/// The live view pauses once the condition is met, see [LiveViewConfig](crate::LiveViewConfig), and [AutomatonGeneric::run_until] steps an automaton until it is met.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoPause<T: Cell = u8> {
+    /// Never stop.
+    #[default]
+    Off,
+    /// Stop once a step did not change any cell, as the automaton has frozen.
+    OnStable,
+    /// Stop once no cell contains the passed symbol anymore.
+    OnExtinct(T),
+    /// Stop once the automaton has reached the passed generation.
+    OnGeneration(u64),
+}
+
+impl<T: Cell> AutoPause<T> {
+    /// Returns wether the condition is met by a step that turned the ```previous``` state into the ```current``` one, reaching the passed generation.
+    pub fn is_met(
+        &self,
+        previous: &grid::Grid<T>,
+        current: &grid::Grid<T>,
+        generation: u64,
+    ) -> bool {
+        match self {
+            Self::Off => false,
+            Self::OnStable => previous == current,
+            Self::OnExtinct(symbol) => !current.iter().any(|cell| cell == symbol),
+            Self::OnGeneration(target) => generation >= *target,
+        }
+    }
+
+    /// Returns a short description of why the automaton stopped when this condition was met, such as for the window title of the live view.
+    pub fn reason(&self) -> String {
+        match self {
+            Self::Off => "never".to_string(),
+            Self::OnStable => "stable".to_string(),
+            Self::OnExtinct(symbol) => format!("{symbol:?} extinct"),
+            Self::OnGeneration(target) => format!("generation {target} reached"),
+        }
+    }
+}
+
+impl<T: Cell> AutomatonGeneric<T> {
+    /// Performs time steps, regardless of the step mode, until the passed condition is met or ```max_steps``` steps were performed.
+    /// Returns the number of steps performed.
+    ///
+    /// Conditions that do not depend on a step, i.e. extinction and the generation, are checked before the first step as well, so no step is performed if they are already met.
+    /// ```
+    /// # use cellumina::AutoPause;
+    /// let mut auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 1, 0, 0], 2)
+    ///     .with_fn_rule("clear", |grid| grid.fill(0))
+    ///     .build();
+    /// assert_eq!(auto.run_until(AutoPause::OnExtinct(1), 100), 1);
+    /// assert_eq!(auto.run_until(AutoPause::OnStable, 100), 1);
+    /// assert_eq!(auto.run_until(AutoPause::OnGeneration(10), 100), 8);
+    /// assert_eq!(auto.run_until(AutoPause::Off, 5), 5);
+    /// ```
+    pub fn run_until(&mut self, condition: AutoPause<T>, max_steps: u64) -> u64 {
+        if condition != AutoPause::OnStable
+            && condition.is_met(&self.state, &self.state, self.generation)
+        {
+            return 0;
+        }
+        let mut previous = grid::Grid::new(0, 0);
+        for steps in 1..=max_steps {
+            if condition == AutoPause::OnStable {
+                previous.clone_from(&self.state);
+            }
+            self.perform_step();
+            if condition.is_met(&previous, &self.state, self.generation) {
+                return steps;
+            }
+        }
+        max_steps
+    }
+}
+
+#[test]
+fn auto_pause_test() {
+    let grid = grid::grid![[1, 0][2, 0]];
+    let changed = grid::grid![[1, 0][0, 0]];
+
+    assert!(!AutoPause::Off.is_met(&grid, &grid, 1000));
+
+    assert!(AutoPause::OnStable.is_met(&grid, &grid, 1));
+    assert!(!AutoPause::OnStable.is_met(&grid, &changed, 1));
+
+    assert!(!AutoPause::OnExtinct(2).is_met(&changed, &grid, 1));
+    assert!(AutoPause::OnExtinct(2).is_met(&grid, &changed, 1));
+    assert!(AutoPause::OnExtinct(5).is_met(&grid, &grid, 1));
+
+    assert!(!AutoPause::<u8>::OnGeneration(10).is_met(&grid, &grid, 9));
+    assert!(AutoPause::<u8>::OnGeneration(10).is_met(&grid, &grid, 10));
+    assert!(AutoPause::<u8>::OnGeneration(10).is_met(&grid, &grid, 11));
+
+    assert_eq!(AutoPause::OnExtinct(2).reason(), "2 extinct");
+    assert_eq!(
+        AutoPause::<u8>::OnGeneration(10).reason(),
+        "generation 10 reached"
+    );
+
+    // a blinker never stabilizes, but a block does immediately
+    let mut blinker = crate::AutomatonBuilder::new()
+        .from_vec(
+            [[0; 5], [0; 5], [0, 1, 1, 1, 0], [0; 5], [0; 5]].concat(),
+            5,
+        )
+        .with_preset(crate::rule::presets::game_of_life())
+        .build();
+    assert_eq!(blinker.run_until(AutoPause::OnStable, 20), 20);
+    assert_eq!(blinker.generation(), 20);
+    let mut block = crate::AutomatonBuilder::new()
+        .from_vec([[1, 1, 0, 0], [1, 1, 0, 0], [0; 4], [0; 4]].concat(), 4)
+        .with_preset(crate::rule::presets::game_of_life())
+        .build();
+    assert_eq!(block.run_until(AutoPause::OnStable, 20), 1);
+    assert_eq!(block.run_until(AutoPause::OnGeneration(1), 20), 0);
+}
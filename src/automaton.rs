@@ -1,21 +1,88 @@
 use std::{collections::HashMap, time};
 
-use crate::{error::CelluminaError, rule, CellGrid};
+use crate::{error::CelluminaError, rule, Cell, CellGridExt};
 
 /// A struct that represents the current state and rule set of a cellular automaton.
 /// A cellular automaton has a state consisting of a (finite) character grid and a set of rules that describes how to process this grid to get the next state.
+///
+/// The cells of the grid may be of any [Cell] type, but most functionality, such as the text format and the live view, requires [u8] cells as used by [Automaton].
 #[derive(Debug)]
-pub struct Automaton {
+pub struct AutomatonGeneric<T: Cell> {
     /// The current state of the automaton.
-    pub(super) state: CellGrid,
+    pub(super) state: StateGrid<T>,
     /// The rule set of the automaton.
-    pub(super) rule: Box<dyn rule::Rule>,
+    pub(super) rule: Box<dyn rule::Rule<T>>,
+    /// Static information for each cell that rules may read but never change, with the same dimensions as the state.
+    pub(super) aux: Option<crate::CellGrid>,
     /// How often and on what conditions this automaton applies its rule set to its state to get to the next step.
     pub(super) step_mode: StepMode,
+    /// The factor the rate of timed steps is scaled by, dividing the interval between them.
+    pub(super) speed: f32,
     /// The colors this automaton uses to convert itself to an image.
-    pub(super) colors: HashMap<u8, [u8; 4]>,
+    pub(super) colors: HashMap<T, [u8; 4]>,
     /// The time at which the automaton was created or the last step was performed.
     pub(super) last_step: Option<time::Instant>,
+    /// The number of time steps performed so far.
+    pub(super) generation: u64,
+    /// Wether only the area around cells that changed is re-evaluated each step.
+    pub(super) dirty_tracking: bool,
+    /// If dirty tracking is enabled, the region containing all cells that changed since the last step.
+    pub(super) dirty: Option<rule::Region>,
+    /// A second grid rules may write their result to instead of allocating a new one, swapped with the state afterwards.
+    pub(super) buffer: grid::Grid<T>,
+    /// Decides on which threads the rule is applied.
+    pub(super) executor: crate::parallelism::Executor,
+    /// The image of the current state, rebuilt only where the state changed.
+    pub(super) image: ImageCache<T>,
+    /// Records the number of cells of some symbols after each step, if attached.
+    pub(super) stats: Option<crate::StatsRecorder<T>>,
+    /// Counts the transitions between symbols from step to step, if attached.
+    pub(super) transitions: Option<crate::TransitionTracker<T>>,
+    /// The names given to symbols, used in messages and the legend.
+    pub(super) names: HashMap<T, String>,
+    /// The symbols of cells that rules may not change.
+    pub(super) frozen: Vec<T>,
+    /// If step reports are enabled, the reports of the rules applied during the last step.
+    pub(super) step_report: Option<Vec<rule::RuleReport>>,
+    /// If changes are counted, the number of cells changed by the last step of [Self::next_step], summed over its substeps.
+    /// Only counted for [AutomatonHandle](crate::AutomatonHandle)s, which report it to their subscribers.
+    pub(super) changes: Option<usize>,
+    /// If a step is spread over multiple calls of [Self::next_step_with_budget], the next row to transform, with the rows before already written to the buffer.
+    pub(super) pending_rows: Option<usize>,
+    /// The number of times the rule is applied per time step, at least one.
+    pub(super) substeps: u32,
+    /// Wether each application of the rule counts as a generation, instead of each time step.
+    pub(super) count_substeps: bool,
+    /// Wether images saved by [Automaton::save] store the symbols in their alpha channel.
+    pub(super) alpha_encoding: bool,
+}
+
+/// An automaton with [u8] cells, as created by an [AutomatonBuilder](crate::AutomatonBuilder).
+pub type Automaton = AutomatonGeneric<u8>;
+
+/// An automaton shared between threads, as created by [Automaton::into_shared], for example to read it while it runs in a live view.
+pub type SharedAutomaton = std::sync::Arc<std::sync::RwLock<Automaton>>;
+
+/// A symbol in the [legend](Automaton::legend) of an automaton, with its character, name and color.
+pub type LegendEntry<'a> = (u8, char, Option<&'a str>, Option<[u8; 4]>);
+
+/// The result of a call of [AutomatonGeneric::next_step_with_budget].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetOutcome {
+    /// No step was due yet according to the minimum time step of the automaton, so nothing was done.
+    Waiting,
+    /// A step was completed, possibly finishing one started by earlier calls.
+    Complete,
+    /// A step was completed, but the rule ran out of time and only considered part of the grid, changing less than a full step would.
+    Partial,
+    /// A step was started or continued, but not completed, as the rule ran out of time.
+    /// Further calls continue the step until it is complete, during which the state remains unchanged.
+    InProgress {
+        /// The number of rows transformed so far.
+        rows_done: usize,
+        /// The total number of rows to transform.
+        rows: usize,
+    },
 }
 
 /// Describes how often an [Automaton] executes its time step.
@@ -27,36 +94,502 @@ pub(super) enum StepMode {
     Limited { interval: time::Duration },
 }
 
-impl Automaton {
+/// The color used for cells without a color of their own, unless configured otherwise with [AutomatonBuilder::with_default_color](crate::AutomatonBuilder::with_default_color).
+/// Debug builds use magenta to make missing colors stand out, release builds use transparent black.
+pub(super) const DEFAULT_COLOR: [u8; 4] = if cfg!(debug_assertions) {
+    [255, 0, 255, 255]
+} else {
+    [0; 4]
+};
+
+/// The state of an automaton, which its rule may keep in memory of its own instead, such as on the GPU, see [Rule::transform_resident](rule::Rule::transform_resident).
+/// Kept states are only read back once the cells are accessed, and accessing them mutably hands the state back to the CPU.
+pub(crate) struct StateGrid<T: Cell> {
+    /// The cells of the state, unset while they are only kept by the rule and not read back yet.
+    grid: std::sync::OnceLock<grid::Grid<T>>,
+    /// The state kept by the rule, if its last step kept its result and the cells were not changed since.
+    resident: Option<std::sync::Arc<dyn rule::ResidentState<T>>>,
+}
+
+impl<T: Cell> StateGrid<T> {
+    /// Returns the dimensions of the state as ```(rows, cols)```, without reading back a state kept by the rule.
+    pub(crate) fn size(&self) -> (usize, usize) {
+        match (self.grid.get(), &self.resident) {
+            (None, Some(resident)) => resident.size(),
+            _ => (**self).size(),
+        }
+    }
+
+    /// Returns the number of rows of the state, without reading back a state kept by the rule.
+    pub(crate) fn rows(&self) -> usize {
+        self.size().0
+    }
+
+    /// Returns the number of columns of the state, without reading back a state kept by the rule.
+    pub(crate) fn cols(&self) -> usize {
+        self.size().1
+    }
+
+    /// Returns wether the rule keeps the current state, so it can continue from it without the cells.
+    pub(super) fn is_resident(&self) -> bool {
+        self.resident.is_some()
+    }
+
+    /// Replaces the state by the result of a step kept by the rule.
+    pub(super) fn set_resident(&mut self, resident: std::sync::Arc<dyn rule::ResidentState<T>>) {
+        self.grid = std::sync::OnceLock::new();
+        self.resident = Some(resident);
+    }
+
+    /// Reads the state back, if necessary, and stops using the state kept by the rule, for example because the rule changes.
+    pub(super) fn detach(&mut self) {
+        let _ = &mut **self;
+    }
+}
+
+impl<T: Cell> From<grid::Grid<T>> for StateGrid<T> {
+    fn from(grid: grid::Grid<T>) -> Self {
+        Self {
+            grid: grid.into(),
+            resident: None,
+        }
+    }
+}
+
+impl<T: Cell> std::ops::Deref for StateGrid<T> {
+    type Target = grid::Grid<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.grid.get_or_init(|| {
+            self.resident
+                .as_ref()
+                .expect("The cells are only unset while the rule keeps the state.")
+                .read_back()
+        })
+    }
+}
+
+impl<T: Cell> std::ops::DerefMut for StateGrid<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let _ = &**self;
+        // the cells may be changed, so the rule has to start over from them
+        self.resident = None;
+        self.grid
+            .get_mut()
+            .expect("The cells were read back above.")
+    }
+}
+
+impl<T: Cell> std::fmt::Debug for StateGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: Cell> PartialEq for StateGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Cell> PartialEq<grid::Grid<T>> for StateGrid<T> {
+    fn eq(&self, other: &grid::Grid<T>) -> bool {
+        **self == *other
+    }
+}
+
+/// A lazily built RGBA image of an automaton's state.
+/// Once built, the image is updated in place for changes of known regions and only dropped entirely if the changed region is unknown.
+#[derive(Debug)]
+pub(super) struct ImageCache<T: Cell> {
+    /// The colors of all cells with a [lookup index](Cell::lookup_index), so most pixels are an indexed lookup instead of a hash map access.
+    lookup: Box<[[u8; 4]; 256]>,
+    /// Which cells with a lookup index have a color of their own.
+    mapped: [bool; 256],
+    /// The colors of all other cells.
+    colors: HashMap<T, [u8; 4]>,
+    /// The color of cells without a color of their own.
+    default_color: [u8; 4],
+    /// Wether cells without a color have already been reported.
+    reported: std::sync::atomic::AtomicBool,
+    /// The pixel data, row by row with four bytes per cell, if it has been built since the last invalidation.
+    data: std::sync::OnceLock<Vec<u8>>,
+}
+
+impl<T: Cell> ImageCache<T> {
+    /// Creates an empty cache using the passed colors, and the default color for all other cells.
+    pub(super) fn new(colors: &HashMap<T, [u8; 4]>, default_color: [u8; 4]) -> Self {
+        let mut lookup = Box::new([default_color; 256]);
+        let mut mapped = [false; 256];
+        let mut other = HashMap::new();
+        for (&cell, &color) in colors {
+            match cell.lookup_index() {
+                Some(index) => {
+                    lookup[index as usize] = color;
+                    mapped[index as usize] = true;
+                }
+                None => {
+                    other.insert(cell, color);
+                }
+            }
+        }
+        Self {
+            lookup,
+            mapped,
+            colors: other,
+            default_color,
+            reported: Default::default(),
+            data: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Returns the color of the passed cell.
+    fn color(&self, cell: T) -> [u8; 4] {
+        match cell.lookup_index() {
+            Some(index) => self.lookup[index as usize],
+            None => self
+                .colors
+                .get(&cell)
+                .copied()
+                .unwrap_or(self.default_color),
+        }
+    }
+
+    /// Returns the colors of all cells with a [lookup index](Cell::lookup_index), by index, which for [u8] cells are all cells.
+    /// Cells without a color of their own have the default color.
+    #[cfg(feature = "display")]
+    pub(crate) fn lookup_colors(&self) -> &[[u8; 4]; 256] {
+        &self.lookup
+    }
+
+    /// Returns wether the passed cell has a color of its own.
+    fn is_mapped(&self, cell: T) -> bool {
+        match cell.lookup_index() {
+            Some(index) => self.mapped[index as usize],
+            None => self.colors.contains_key(&cell),
+        }
+    }
+
+    /// Returns the image data of the passed state, building it first if necessary.
+    pub(super) fn bytes(&self, state: &grid::Grid<T>) -> &[u8] {
+        self.data.get_or_init(|| {
+            self.report_missing(state.iter().copied());
+            state.iter().flat_map(|&cell| self.color(cell)).collect()
+        })
+    }
+
+    /// Logs a warning listing all passed cells without a color, at most once per cache.
+    fn report_missing(&self, cells: impl Iterator<Item = T>) {
+        use std::sync::atomic::Ordering;
+        if self.reported.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut seen = std::collections::HashSet::new();
+        let missing = cells
+            .filter(|&cell| !self.is_mapped(cell) && seen.insert(cell))
+            .map(|cell| format!("{cell:?}"))
+            .collect::<Vec<String>>();
+        if !missing.is_empty() && !self.reported.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "No colors set for cells {}, using the default color instead.",
+                missing.join(", ")
+            );
+        }
+    }
+
+    /// Rewrites the pixels of the passed region, if the image has been built.
+    fn update(&mut self, state: &grid::Grid<T>, region: &rule::Region) {
+        if self.data.get().is_none() {
+            return;
+        }
+        let cells = || {
+            region
+                .rows
+                .clone()
+                .flat_map(|row| region.cols.clone().map(move |col| (row, col)))
+        };
+        self.report_missing(cells().map(|(row, col)| state[row][col]));
+
+        let colors = cells()
+            .map(|(row, col)| self.color(state[row][col]))
+            .collect::<Vec<[u8; 4]>>();
+        if let Some(data) = self.data.get_mut() {
+            let cols = state.cols();
+            for ((row, col), color) in cells().zip(colors) {
+                let index = 4 * (row * cols + col);
+                data[index..index + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Drops the image, so it is rebuilt entirely on the next access.
+    fn invalidate(&mut self) {
+        self.data.take();
+    }
+}
+
+impl<T: Cell> AutomatonGeneric<T> {
     /// Turns this automatons current state grid into an image buffer.
     pub fn create_image_buffer(&self) -> image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
-        image::ImageBuffer::from_fn(
-            self.state.size().1 as u32,
-            self.state.size().0 as u32,
-            |col, row| {
-                image::Rgba(
-                    self.colors
-                        .get(&self.state[row as usize][col as usize])
-                        .copied()
-                        .unwrap_or([0; 4]),
-                )
-            },
+        crate::convert::image_from_bytes(&self.state, self.image_bytes().to_vec())
+    }
+
+    /// Turns this automatons current state grid into an image buffer, drawing each cell as a square of ```scale``` by ```scale``` pixels.
+    ///
+    /// For the [Hex](rule::GridTopology::Hex) topology, every odd row is shifted right by half a cell, so the image shows the hexagonal neighborhoods without skew.
+    /// The image is then half a cell wider, with the pixels left empty at the row ends being transparent.
+    /// ```
+    /// # use cellumina::rule::GridTopology;
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 0, 0, 1], 2)
+    ///     .with_color(1, [255, 255, 255, 255])
+    ///     .build();
+    /// assert_eq!(auto.create_image_buffer_scaled(4, GridTopology::Square).dimensions(), (8, 8));
+    /// let hex = auto.create_image_buffer_scaled(4, GridTopology::Hex);
+    /// assert_eq!(hex.dimensions(), (10, 8));
+    /// assert_eq!(hex.get_pixel(0, 4).0, [0, 0, 0, 0]);
+    /// assert_eq!(hex.get_pixel(6, 4).0, [255, 255, 255, 255]);
+    /// ```
+    pub fn create_image_buffer_scaled(
+        &self,
+        scale: u32,
+        topology: rule::GridTopology,
+    ) -> image::RgbaImage {
+        let (rows, cols) = (self.state.rows() as u32, self.state.cols() as u32);
+        let offset = match topology {
+            rule::GridTopology::Hex if rows > 1 => scale / 2,
+            _ => 0,
+        };
+        let mut image = image::RgbaImage::new(cols * scale + offset, rows * scale);
+        for (((row, col), _), color) in self
+            .state
+            .indexed_iter()
+            .zip(self.image_bytes().chunks_exact(4))
+        {
+            let (row, col) = (row as u32, col as u32);
+            let x = col * scale + if row % 2 == 1 { offset } else { 0 };
+            let color = image::Rgba([color[0], color[1], color[2], color[3]]);
+            for d_row in 0..scale {
+                for d_col in 0..scale {
+                    image.put_pixel(x + d_col, row * scale + d_row, color);
+                }
+            }
+        }
+        image
+    }
+
+    /// Replaces the current state with the passed image, using the automaton's colors as described in [convert::image_to_grid](crate::convert::image_to_grid).
+    /// Returns wether any cell was changed.
+    /// ## Error
+    /// When the image does not have the same dimensions as the state. In that case, the state remains unchanged.
+    pub fn load_state_from_image(
+        &mut self,
+        image: &image::RgbaImage,
+        tolerance: u8,
+    ) -> Result<bool, CelluminaError> {
+        let got = (image.height() as usize, image.width() as usize);
+        if got != self.state.size() {
+            return Err(CelluminaError::DimensionMismatch {
+                expected: self.state.size(),
+                got,
+            });
+        }
+        self.set_region(
+            0,
+            0,
+            &crate::convert::image_to_grid(image, &self.colors, tolerance),
         )
     }
 
+    /// Returns the current state as RGBA pixel data, row by row with four bytes per cell, without copying.
+    /// The data is cached and only rebuilt where the state changed since the last call.
+    pub fn image_bytes(&self) -> &[u8] {
+        self.image.bytes(&self.state)
+    }
+
+    /// Returns a copy of the current state as a two-dimensional array, with the rows of the state along axis 0 and its columns along axis 1.
+    /// The cell at ```get_cell(row, col)``` is found at ```array[[row, col]]```.
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> ndarray::Array2<T> {
+        crate::convert::grid_to_ndarray(&self.state)
+    }
+
+    /// Replaces the current state with the passed array, reading axis 0 as rows and axis 1 as columns like [to_ndarray](AutomatonGeneric::to_ndarray).
+    /// Returns wether any cell was changed.
+    /// ## Error
+    /// When the array does not have the same dimensions as the state. In that case, the state remains unchanged.
+    #[cfg(feature = "ndarray")]
+    pub fn load_state_from_ndarray(
+        &mut self,
+        array: ndarray::ArrayView2<T>,
+    ) -> Result<bool, CelluminaError> {
+        if array.dim() != self.state.size() {
+            return Err(CelluminaError::DimensionMismatch {
+                expected: self.state.size(),
+                got: array.dim(),
+            });
+        }
+        self.set_region(0, 0, &crate::convert::ndarray_to_grid(array))
+    }
+
+    /// Scales the rate of timed steps, as set with [AutomatonBuilder::with_min_time_step](crate::AutomatonBuilder::with_min_time_step), by the passed factor, dividing the interval between steps.
+    /// For example, ```2.0``` runs the automaton at double speed, without having to know the interval it was built with.
+    ///
+    /// Automata stepping on every call to [Self::next_step] are not affected, as they can not run any faster.
+    /// ## Error
+    /// When the multiplier is not a positive finite number. In that case, the speed remains unchanged.
+    pub fn set_speed_multiplier(&mut self, multiplier: f32) -> Result<(), CelluminaError> {
+        if !(multiplier.is_finite() && multiplier > 0.) {
+            return Err(CelluminaError::ValidationError(vec![
+                crate::ValidationIssue::InvalidSpeedMultiplier,
+            ]));
+        }
+        self.speed = multiplier;
+        Ok(())
+    }
+
+    /// Returns the factor the rate of timed steps is scaled by, see [Self::set_speed_multiplier]. Initially, this is ```1.0```.
+    pub fn speed_multiplier(&self) -> f32 {
+        self.speed
+    }
+
+    /// Returns the number of time steps this automaton has performed so far.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns a reference to the current state grid of this automaton.
+    pub fn state(&self) -> &grid::Grid<T> {
+        &self.state
+    }
+
+    /// Returns the [StatsRecorder](crate::StatsRecorder) of this automaton, if one was attached with [AutomatonBuilder::with_stats_recorder](crate::AutomatonBuilder::with_stats_recorder).
+    pub fn stats_recorder(&self) -> Option<&crate::StatsRecorder<T>> {
+        self.stats.as_ref()
+    }
+
+    /// Returns the rule of this automaton.
+    pub fn rule(&self) -> &dyn rule::Rule<T> {
+        self.rule.as_ref()
+    }
+
+    /// Returns the rule of this automaton mutably, for example to disable parts of it with [Rule::set_enabled](rule::Rule::set_enabled) while it runs.
+    ///
+    /// As the rule may change in any way, the next step considers the entire state, even with dirty tracking.
+    pub fn rule_mut(&mut self) -> &mut dyn rule::Rule<T> {
+        self.pending_rows = None;
+        self.state.detach();
+        if self.dirty_tracking {
+            let (rows, cols) = self.state.size();
+            self.dirty = Some(rule::Region::full(rows, cols));
+        }
+        self.rule.as_mut()
+    }
+
+    /// Replaces the random number generator of this automaton's rule, see [AutomatonBuilder::with_rng](crate::AutomatonBuilder::with_rng).
+    pub fn set_rng(&mut self, rng: impl crate::CellRng + 'static) {
+        self.rule.set_rng(Box::new(rng));
+    }
+
+    /// Returns the [TransitionTracker](crate::TransitionTracker) of this automaton, if one was attached with [AutomatonBuilder::with_transition_tracker](crate::AutomatonBuilder::with_transition_tracker).
+    pub fn transition_tracker(&self) -> Option<&crate::TransitionTracker<T>> {
+        self.transitions.as_ref()
+    }
+
+    /// Returns the [TransitionTracker](crate::TransitionTracker) of this automaton mutably, for example to [reset](crate::TransitionTracker::reset) it.
+    pub fn transition_tracker_mut(&mut self) -> Option<&mut crate::TransitionTracker<T>> {
+        self.transitions.as_mut()
+    }
+
+    /// Returns which rules changed how many cells during the last step, one [RuleReport](rule::RuleReport) per rule in order of application,
+    /// if step reports were enabled with [AutomatonBuilder::with_step_reports](crate::AutomatonBuilder::with_step_reports).
+    /// Before the first step, and after steps that were skipped as nothing could change, the report is empty.
+    pub fn last_step_report(&self) -> Option<&[rule::RuleReport]> {
+        self.step_report.as_deref()
+    }
+
+    /// Returns the name given to the passed symbol with [AutomatonBuilder::with_symbol_name](crate::AutomatonBuilder::with_symbol_name), if any.
+    pub fn symbol_name(&self, symbol: T) -> Option<&str> {
+        self.names.get(&symbol).map(String::as_str)
+    }
+
+    /// Describes the passed symbol for messages, including its name if it has one.
+    pub(super) fn describe_symbol(&self, symbol: T) -> String {
+        match self.symbol_name(symbol) {
+            Some(name) => format!("{symbol:?} ({name})"),
+            None => format!("{symbol:?}"),
+        }
+    }
+
+    /// Returns a reference to the auxiliary layer of this automaton, if it has one.
+    /// See [AutomatonBuilder::with_aux_layer](crate::AutomatonBuilder::with_aux_layer).
+    pub fn aux_layer(&self) -> Option<&crate::CellGrid> {
+        self.aux.as_ref()
+    }
+
     /// Returns the dimensions of this automaton's state grid as a tuple, first are the number of rows (height), then the number of columns (width).
-    /// The reason for this order is the column-major layout of the underlying [grid::Grid] state representation.
+    /// The reason for this order is the row-major layout of the underlying [grid::Grid] state representation.
+    /// Use [Self::dimensions_wh] or [Self::dimensions_rc] to make the order explicit at the call site.
     pub fn dimensions(&self) -> (u32, u32) {
         (self.state.size().0 as u32, self.state.size().1 as u32)
     }
 
+    /// Returns the dimensions of this automaton's state grid as ```(width, height)```, the order used by images and windows.
+    pub fn dimensions_wh(&self) -> (u32, u32) {
+        let (rows, cols) = self.dimensions();
+        (cols, rows)
+    }
+
+    /// Returns the dimensions of this automaton's state grid as ```(rows, cols)```, the order used to index cells. Same as [Self::dimensions].
+    pub fn dimensions_rc(&self) -> (u32, u32) {
+        self.dimensions()
+    }
+
+    /// Returns the character of the cell at the specified indices.
+    /// ## Error
+    /// When the given index is out of bounds.
+    pub fn get_cell(&self, row: u32, col: u32) -> Result<T, CelluminaError> {
+        self.state.get(row as usize, col as usize).copied().ok_or(
+            CelluminaError::IndexOutOfBoundsError(
+                row,
+                col,
+                self.state.size().0 as u32,
+                self.state.size().1 as u32,
+            ),
+        )
+    }
+
+    /// Returns the positions of all cells of the state holding the passed symbol, see [CellQueries](crate::CellQueries).
+    pub fn positions_of(&self, symbol: T) -> Vec<(usize, usize)> {
+        crate::CellQueries::positions_of(&*self.state, symbol)
+    }
+
+    /// Returns the position of the first cell of the state holding the passed symbol in row-major order, if any.
+    pub fn first_cell_of(&self, symbol: T) -> Option<(usize, usize)> {
+        crate::CellQueries::first_cell_of(&*self.state, symbol)
+    }
+
+    /// Returns the position of a random cell of the state holding the passed symbol, if any, for example to spawn an effect there.
+    pub fn random_cell_of<R: rand::Rng + ?Sized>(
+        &self,
+        symbol: T,
+        rng: &mut R,
+    ) -> Option<(usize, usize)> {
+        crate::CellQueries::random_cell_of(&*self.state, symbol, rng)
+    }
+
+    /// Returns the color this automaton uses to display the passed character, if any.
+    pub fn get_color(&self, cell: T) -> Option<[u8; 4]> {
+        self.colors.get(&cell).copied()
+    }
+
     /// Sets the cell at the specified indices to the specified character.
     /// ## Error
     /// When the given index is out of bounds.
-    pub fn set_cell(&mut self, row: u32, col: u32, new_val: u8) -> Result<bool, CelluminaError> {
+    pub fn set_cell(&mut self, row: u32, col: u32, new_val: T) -> Result<bool, CelluminaError> {
         log::info!(
             "Manual cell set: Character {} at ({}, {}).",
-            new_val,
+            self.describe_symbol(new_val),
             row,
             col
         );
@@ -70,92 +603,3085 @@ impl Automaton {
         } else {
             let res = self.state[row as usize][col as usize] != new_val;
             self.state[row as usize][col as usize] = new_val;
+            if res {
+                self.mark_dirty(rule::Region {
+                    rows: row as usize..row as usize + 1,
+                    cols: col as usize..col as usize + 1,
+                });
+            }
             Ok(res)
         }
     }
 
-    /// Checks if and how many time steps should currently be executed and performs them.
-    /// A time step consists of applying this automatons rule to its state, thus transforming the state.
-    /// ## Returns
-    /// Wether or not the state has changed since the last invocation of [next_step](Automaton::next_step()), either because a time step was performed or by manual interaction between steps.
-    pub fn next_step(&mut self) -> bool {
-        // if the automaton has just started, set last step for the first time
-        if self.last_step.is_none() {
-            self.last_step = Some(time::Instant::now());
+    /// Copies the passed grid into the state, with its top left cell placed at the specified indices.
+    /// Returns wether any cell was changed.
+    /// ## Error
+    /// When the passed grid does not fit into the state at the given position. In that case, the state remains unchanged.
+    pub fn set_region(
+        &mut self,
+        row: u32,
+        col: u32,
+        region: &grid::Grid<T>,
+    ) -> Result<bool, CelluminaError> {
+        let (rows, cols) = self.state.size();
+        let (row, col) = (row as usize, col as usize);
+        if row + region.rows() > rows || col + region.cols() > cols {
+            return Err(CelluminaError::IndexOutOfBoundsError(
+                (row + region.rows()).saturating_sub(1) as u32,
+                (col + region.cols()).saturating_sub(1) as u32,
+                rows as u32,
+                cols as u32,
+            ));
         }
-        // set manual change to false, then return its previous state and OR it with the result of the transformation
-        match self.step_mode {
-            StepMode::Immediate => {
-                self.rule.transform(&mut self.state);
-                self.last_step = Some(time::Instant::now());
-                true
+        let mut res = false;
+        for row_del in 0..region.rows() {
+            for col_del in 0..region.cols() {
+                let cell = &mut self.state[row + row_del][col + col_del];
+                res |= *cell != region[row_del][col_del];
+                *cell = region[row_del][col_del];
+            }
+        }
+        if res {
+            self.mark_dirty(rule::Region {
+                rows: row..row + region.rows(),
+                cols: col..col + region.cols(),
+            });
+        }
+        Ok(res)
+    }
+
+    /// Sets every cell of the state to the passed character.
+    pub fn fill(&mut self, cell: T) {
+        self.state.fill(cell);
+        self.mark_dirty(rule::Region::full(self.state.rows(), self.state.cols()));
+    }
+
+    /// Draws a border of ```symbol``` around the state, setting all cells within ```thickness``` cells of an edge, such as a wall that is visible and can be painted over unlike a [boundary](rule::BoundaryBehaviour).
+    /// A border at least half as thick as the state is wide or high covers the entire state, see [gridops::draw_border](crate::gridops::draw_border).
+    /// Returns wether any cell was changed.
+    pub fn draw_border(&mut self, thickness: u32, symbol: T) -> bool {
+        let res = crate::gridops::draw_border(&mut self.state, thickness as usize, symbol);
+        if res {
+            self.mark_dirty(rule::Region::full(self.state.rows(), self.state.cols()));
+        }
+        res
+    }
+
+    /// Replaces every cell of the state with the symbol it is mapped to, leaving symbols that are not in the map unchanged.
+    /// The colors of remapped symbols move along with them and, if ```remap_rules``` is set, the rule is remapped as well, see [Rule::remap_symbols](rule::Rule::remap_symbols).
+    ///
+    /// Mapping several symbols to the same target is allowed, but logged as a warning, as they can not be told apart afterwards.
+    /// In that case, the target takes the color of one of the remapped symbols.
+    /// Returns wether any cell was changed.
+    pub fn remap_symbols(&mut self, map: &HashMap<T, T>, remap_rules: bool) -> bool {
+        // collect the symbols ending up as each target, including the target itself if it stays unchanged
+        let mut sources = HashMap::<T, Vec<T>>::new();
+        for (&from, &to) in map {
+            sources.entry(to).or_default().push(from);
+        }
+        for (to, mut from) in sources {
+            if !map.contains_key(&to) {
+                from.push(to);
+            }
+            if from.len() > 1 {
+                log::warn!("Symbols {from:?} are all mapped to {to:?}.");
+            }
+        }
+
+        let mut changed = false;
+        for cell in self.state.iter_mut() {
+            if let Some(&to) = map.get(cell) {
+                changed |= *cell != to;
+                *cell = to;
             }
-            StepMode::Limited { interval } => {
-                let step_permitted = self.last_step.unwrap().elapsed() >= interval;
-                if step_permitted {
-                    // let before = time::Instant::now();
-                    self.rule.transform(&mut self.state);
-                    self.last_step = Some(time::Instant::now());
-                    // log::info!(
-                    //     "Performed time step in {}s.",
-                    //     before.elapsed().as_secs_f32()
-                    // );
+        }
+
+        // colors of remapped symbols take precedence over those of symbols that stay unchanged
+        let (remapped, unchanged): (Vec<_>, Vec<_>) = std::mem::take(&mut self.colors)
+            .into_iter()
+            .partition(|(cell, _)| map.contains_key(cell));
+        self.colors.extend(unchanged);
+        self.colors.extend(
+            remapped
+                .into_iter()
+                .map(|(cell, color)| (map[&cell], color)),
+        );
+        self.image = ImageCache::new(&self.colors, self.image.default_color);
+
+        if remap_rules {
+            self.rule.remap_symbols(map);
+        }
+        if changed {
+            self.mark_dirty(rule::Region::full(self.state.rows(), self.state.cols()));
+        }
+        changed
+    }
+
+    /// Returns a copy of the cells in the region of ```rows``` rows and ```cols``` columns with its top left cell at the specified indices.
+    ///
+    /// If ```clip``` is set, the parts of the region outside the state are ignored and the returned grid may be smaller than requested.
+    /// ## Error
+    /// When ```clip``` is not set and the region does not fit into the state.
+    pub fn extract(
+        &self,
+        row: u32,
+        col: u32,
+        rows: u32,
+        cols: u32,
+        clip: bool,
+    ) -> Result<grid::Grid<T>, CelluminaError> {
+        let region = self.checked_region(row, col, rows, cols, clip)?;
+        Ok(sub_grid(&self.state, &region))
+    }
+
+    /// Creates a new automaton from the cells and auxiliary layer in the passed region, with a copy of this automaton's rule, colors and settings.
+    ///
+    /// The new automaton starts at generation zero and evolves independently of this one.
+    /// An attached [StatsRecorder](crate::StatsRecorder) is copied without its recorded generations.
+    /// Rules wrapping around the edges of the grid wrap around the edges of the region instead.
+    /// ## Error
+    /// When the region does not fit into the state or the rule can not be copied, see [Rule::clone_boxed](rule::Rule::clone_boxed).
+    pub fn sub_automaton(
+        &self,
+        region: &rule::Region,
+    ) -> Result<AutomatonGeneric<T>, CelluminaError> {
+        let region = self.checked_region(
+            region.rows.start as u32,
+            region.cols.start as u32,
+            region.rows.len() as u32,
+            region.cols.len() as u32,
+            false,
+        )?;
+        let rule = self.rule.clone_boxed().ok_or_else(|| {
+            CelluminaError::CustomError(format!("The rule {:?} can not be copied.", self.rule))
+        })?;
+        let state = sub_grid(&self.state, &region);
+        let mut res = AutomatonGeneric {
+            dirty: self
+                .dirty_tracking
+                .then(|| rule::Region::full(state.rows(), state.cols())),
+            dirty_tracking: self.dirty_tracking,
+            buffer: grid::Grid::new(0, 0),
+            executor: crate::parallelism::Executor::new(self.executor.parallelism()),
+            image: ImageCache::new(&self.colors, self.image.default_color),
+            aux: self.aux.as_ref().map(|aux| sub_grid(aux, &region)),
+            state: state.into(),
+            rule,
+            step_mode: self.step_mode,
+            speed: self.speed,
+            last_step: None,
+            generation: 0,
+            colors: self.colors.clone(),
+            stats: self.stats.as_ref().map(|stats| {
+                let mut stats = stats.clone();
+                stats.clear();
+                stats
+            }),
+            transitions: self
+                .transitions
+                .as_ref()
+                .map(|_| crate::TransitionTracker::new()),
+            names: self.names.clone(),
+            frozen: self.frozen.clone(),
+            step_report: self.step_report.as_ref().map(|_| Vec::new()),
+            changes: None,
+            pending_rows: None,
+            substeps: self.substeps,
+            count_substeps: self.count_substeps,
+            alpha_encoding: self.alpha_encoding,
+        };
+        res.record_stats();
+        Ok(res)
+    }
+
+    /// Renders ```steps``` consecutive states, starting with the current one, into a single image, for example to show the evolution of an automaton in documentation.
+    ///
+    /// The states are laid out left to right in rows of ```columns``` frames, each cell taking up ```scale``` by ```scale``` pixels.
+    /// Frames are separated by ```gap``` pixels of the ```background``` color, which also fills the slots left empty in the last row.
+    /// The states are computed on a copy made with [sub_automaton](AutomatonGeneric::sub_automaton), so this automaton remains unchanged.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+    ///     .with_rule(cellumina::rule::LifeLikeRule::parse("B3/S23").unwrap())
+    ///     .build();
+    /// let image = auto.render_filmstrip(3, 2, 4, 1, [0, 0, 0, 0]).unwrap();
+    /// assert_eq!(image.dimensions(), (25, 25));
+    /// ```
+    /// ## Error
+    /// When the rule can not be copied, see [Rule::clone_boxed](rule::Rule::clone_boxed).
+    pub fn render_filmstrip(
+        &self,
+        steps: u32,
+        columns: u32,
+        scale: u32,
+        gap: u32,
+        background: [u8; 4],
+    ) -> Result<image::RgbaImage, CelluminaError> {
+        let (columns, scale) = (columns.max(1), scale.max(1));
+        let cols = self.state.cols() as u32;
+        let (width, height) = (cols * scale, self.state.rows() as u32 * scale);
+        let mut sheet = image::RgbaImage::from_pixel(
+            (columns * (width + gap)).saturating_sub(gap),
+            (steps.div_ceil(columns) * (height + gap)).saturating_sub(gap),
+            image::Rgba(background),
+        );
+
+        self.for_each_frame(steps, |frame, bytes| {
+            let (x, y) = (
+                frame % columns * (width + gap),
+                frame / columns * (height + gap),
+            );
+            for (index, color) in bytes.chunks_exact(4).enumerate() {
+                let (row, col) = (index as u32 / cols, index as u32 % cols);
+                let color = image::Rgba([color[0], color[1], color[2], color[3]]);
+                for d_row in 0..scale {
+                    for d_col in 0..scale {
+                        sheet.put_pixel(x + col * scale + d_col, y + row * scale + d_row, color);
+                    }
                 }
-                step_permitted
             }
+        })?;
+        Ok(sheet)
+    }
+
+    /// Writes a self-contained HTML page to the passed path that plays ```steps``` consecutive states, starting with the current one,
+    /// with a button to play and pause and a slider to pick a frame, drawing each cell as a square of ```scale``` pixels.
+    ///
+    /// The page needs no server or internet connection.
+    /// To keep the file small, each color is stored once and the frames as runs of equally colored cells instead of images.
+    /// As for [render_filmstrip](AutomatonGeneric::render_filmstrip), the states are computed on a copy, so this automaton remains unchanged.
+    /// ## Error
+    /// When the rule can not be copied, see [Rule::clone_boxed](rule::Rule::clone_boxed), or the file can not be written.
+    pub fn export_html_player(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        steps: u32,
+        scale: u32,
+    ) -> Result<(), CelluminaError> {
+        let mut encoder = crate::player::FrameEncoder::new(self.state.cols(), self.state.rows());
+        self.for_each_frame(steps, |_, bytes| encoder.push(bytes))?;
+        std::fs::write(path, encoder.to_html("Cellumina", scale))?;
+        Ok(())
+    }
+
+    /// Calls ```f``` with the index and RGBA pixel data of ```steps``` consecutive states, starting with the current one, computed on a copy of this automaton.
+    fn for_each_frame(
+        &self,
+        steps: u32,
+        mut f: impl FnMut(u32, &[u8]),
+    ) -> Result<(), CelluminaError> {
+        let mut copy =
+            self.sub_automaton(&rule::Region::full(self.state.rows(), self.state.cols()))?;
+        for frame in 0..steps {
+            if frame > 0 {
+                copy.apply_substeps();
+            }
+            f(frame, copy.image_bytes());
         }
+        Ok(())
     }
 
-    /// Runs this automaton and displays it in a window.
-    /// ```next_step()``` is called every frame, so setting an appropriate time step may be helpful for a smooth display.
-    #[cfg(feature = "display")]
-    pub fn run_live(self) {
-        pollster::block_on(crate::graphic::run_live(self));
+    /// Returns the region of ```rows``` rows and ```cols``` columns with its top left cell at the specified indices, clipped to the state if ```clip``` is set.
+    fn checked_region(
+        &self,
+        row: u32,
+        col: u32,
+        rows: u32,
+        cols: u32,
+        clip: bool,
+    ) -> Result<rule::Region, CelluminaError> {
+        let (s_rows, s_cols) = self.state.size();
+        let (row, col) = (row as usize, col as usize);
+        let (end_row, end_col) = (row + rows as usize, col + cols as usize);
+        if clip {
+            Ok(rule::Region {
+                rows: row.min(s_rows)..end_row.min(s_rows),
+                cols: col.min(s_cols)..end_col.min(s_cols),
+            })
+        } else if end_row > s_rows || end_col > s_cols {
+            Err(CelluminaError::IndexOutOfBoundsError(
+                end_row.saturating_sub(1) as u32,
+                end_col.saturating_sub(1) as u32,
+                s_rows as u32,
+                s_cols as u32,
+            ))
+        } else {
+            Ok(rule::Region {
+                rows: row..end_row,
+                cols: col..end_col,
+            })
+        }
     }
-}
 
-#[test]
-fn automaton_test() {
-    let mut auto = Automaton {
-        state: grid::Grid::from_vec(vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0], 4),
-        rule: Box::new(rule::EnvironmentRule {
-            environment_size: [1, 1, 1, 1],
-            row_boundary: rule::BoundaryBehaviour::Symbol(0),
-            col_boundary: rule::BoundaryBehaviour::Symbol(0),
-            cell_transform: |env| match env
-                // Iterate over neighbors.
-                .iter().copied()
-                // Sum over these 9 values without the center
-                .sum::<u8>() - env[1][1]
-                // ... and map the sum to the new enty of our cell:
-            {
-                // 2 neighbors: The cell keeps its state.
-                2 => env[1][1],
-                // 3 neighbors: The cell gets born.
-                3 => 1,
-                // 0, 1 or more than 3 neighbors: The cell dies.
-                _ => 0,
-            },
-        }),
-        step_mode: StepMode::Immediate,
-        colors: HashMap::new(),
-        last_step: None,
-    };
+    /// Combines the state of the passed automaton into this one, replacing each cell with the result of ```f``` for the cells of both automata at the same position, this automaton's cell first.
+    /// Returns wether any cell was changed.
+    ///
+    /// Colors of the other automaton are added to the colors of this automaton, with colors already set for this automaton taking precedence for cells colored in both.
+    /// ## Error
+    /// When the other automaton does not have the same dimensions. In that case, this automaton remains unchanged.
+    pub fn merge(
+        &mut self,
+        other: &AutomatonGeneric<T>,
+        f: impl Fn(T, T) -> T,
+    ) -> Result<bool, CelluminaError> {
+        if other.state.size() != self.state.size() {
+            return Err(CelluminaError::DimensionMismatch {
+                expected: self.state.size(),
+                got: other.state.size(),
+            });
+        }
 
-    for _ in 0..5 {
-        auto.next_step();
+        let merged = grid::Grid::from_vec(
+            self.state
+                .iter()
+                .zip(other.state.iter())
+                .map(|(&own, &other)| f(own, other))
+                .collect(),
+            self.state.cols(),
+        );
+
+        let colors = self.colors.len();
+        for (&cell, &color) in &other.colors {
+            self.colors.entry(cell).or_insert(color);
+        }
+        if self.colors.len() != colors {
+            self.image = ImageCache::new(&self.colors, self.image.default_color);
+        }
+
+        self.set_region(0, 0, &merged)
     }
 
-    assert_eq!(
-        auto.state,
-        grid::Grid::from_vec(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0], 4)
-    );
+    /// Places the state of the passed automaton on top of this one: Cells of the other automaton replace the cells of this one, unless they are ```transparent```.
+    /// See [merge](AutomatonGeneric::merge) for colors and errors.
+    pub fn overlay(
+        &mut self,
+        other: &AutomatonGeneric<T>,
+        transparent: T,
+    ) -> Result<bool, CelluminaError> {
+        self.merge(
+            other,
+            |own, other| if other == transparent { own } else { other },
+        )
+    }
 
-    auto.next_step();
-    assert_eq!(
-        auto.state,
-        grid::Grid::from_vec(vec![0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0], 4)
-    );
+    /// Uses the state of the passed automaton as a mask: Cells of this automaton are kept where the other automaton has the ```mask``` cell and cleared to the default cell (0 for [u8]) everywhere else.
+    /// See [merge](AutomatonGeneric::merge) for colors and errors.
+    pub fn mask(&mut self, other: &AutomatonGeneric<T>, mask: T) -> Result<bool, CelluminaError> {
+        self.merge(
+            other,
+            |own, other| if other == mask { own } else { T::default() },
+        )
+    }
 
-    assert_ne!(auto.last_step, None);
+    /// Rotates the state by 90 degrees clockwise, swapping its dimensions, see [gridops::rotate_cw](crate::gridops::rotate_cw).
+    pub fn rotate_cw(&mut self) {
+        self.rearrange(crate::gridops::Transform::RotateCw);
+    }
+
+    /// Rotates the state by 90 degrees counterclockwise, swapping its dimensions, see [gridops::rotate_ccw](crate::gridops::rotate_ccw).
+    pub fn rotate_ccw(&mut self) {
+        self.rearrange(crate::gridops::Transform::RotateCcw);
+    }
+
+    /// Mirrors the state left to right, see [gridops::flip_horizontal](crate::gridops::flip_horizontal).
+    pub fn flip_horizontal(&mut self) {
+        self.rearrange(crate::gridops::Transform::FlipHorizontal);
+    }
+
+    /// Mirrors the state top to bottom, see [gridops::flip_vertical](crate::gridops::flip_vertical).
+    pub fn flip_vertical(&mut self) {
+        self.rearrange(crate::gridops::Transform::FlipVertical);
+    }
+
+    /// Swaps the rows and columns of the state, see [gridops::transpose](crate::gridops::transpose).
+    pub fn transpose(&mut self) {
+        self.rearrange(crate::gridops::Transform::Transpose);
+    }
+
+    /// Rearranges the state and the auxiliary layer with the passed operation, marking the entire state as changed.
+    /// A live view showing this automaton adapts to changed dimensions on its next frame.
+    pub fn rearrange(&mut self, transform: crate::gridops::Transform) {
+        self.state = transform.apply(&self.state).into();
+        self.pending_rows = None;
+        self.aux = self.aux.as_ref().map(|aux| transform.apply(aux));
+        if transform.swaps_dimensions() {
+            self.buffer = grid::Grid::new(0, 0);
+        }
+        self.image.invalidate();
+        if self.dirty_tracking {
+            self.dirty = Some(rule::Region::full(self.state.rows(), self.state.cols()));
+        }
+    }
+
+    /// Records that the cells of the passed region were changed, updating the image and, if dirty tracking is enabled, the dirty region.
+    pub(crate) fn mark_dirty(&mut self, region: rule::Region) {
+        // a step in progress would overwrite the change
+        self.pending_rows = None;
+        self.image.update(&self.state, &region);
+        if self.dirty_tracking {
+            self.dirty = Some(match &self.dirty {
+                Some(dirty) => dirty.union(&region),
+                None => region,
+            });
+        }
+    }
+
+    /// Records the current state with the attached [StatsRecorder](crate::StatsRecorder) and [TransitionTracker](crate::TransitionTracker), if any.
+    pub(super) fn record_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            stats.record(self.generation, &self.state);
+        }
+        if let Some(transitions) = &mut self.transitions {
+            transitions.update(&self.state);
+        }
+    }
+
+    /// Applies this automaton's rule to its state once.
+    /// With dirty tracking, only the area around the dirty region is re-evaluated and the dirty region is updated afterwards.
+    /// For local rules, only the cells within their reach of the dirty region are compared to find the changed ones, so a step costs time relative to the active area, not the entire grid.
+    fn apply_rule(&mut self) {
+        trace_span!(
+            "next_step",
+            generation = self.generation,
+            rows = self.state.rows(),
+            cols = self.state.cols(),
+        );
+        self.pending_rows = None;
+        if !self.dirty_tracking {
+            // rules keeping the state themselves, such as on the GPU, only hand it back once it is accessed
+            if self.frozen.is_empty()
+                && self.aux.is_none()
+                && self.step_report.is_none()
+                && self.changes.is_none()
+                && self.transform_resident()
+            {
+                self.image.invalidate();
+                return;
+            }
+            let frozen = self.frozen_cells();
+            if self.changes.is_some() {
+                // rules transforming in-place leave the buffer untouched, the others swap the state into it
+                self.buffer.clone_from(&self.state);
+            }
+            self.transform_buffered();
+            self.restore_cells(frozen);
+            // the buffer now holds the state before the step
+            self.count_changes(|state, buffer| rule::count_changed(buffer, state));
+            self.image.invalidate();
+            return;
+        }
+        self.dirty = match (self.rule.reach(), self.dirty.take()) {
+            // local rules can not change anything if nothing changed before
+            (Some(_), None) => return,
+            // reports need the changes of each rule, which regions do not provide
+            (Some(reach), Some(dirty)) if self.step_report.is_none() => {
+                // the rule can only change cells within its reach of the dirty region
+                let affected = dirty.expand(reach, self.state.rows(), self.state.cols());
+                let before = affected.cells(&self.state);
+                let frozen = self.frozen_cells_in(&affected);
+                let Self {
+                    rule,
+                    state,
+                    aux,
+                    executor,
+                    ..
+                } = self;
+                executor.run(|| match aux {
+                    Some(aux) => rule.transform_region_with_aux(state, &dirty, aux),
+                    None => rule.transform_region(state, &dirty),
+                });
+                self.restore_cells(frozen);
+                self.count_changes(|state, _| affected.count_changed_cells(&before, state));
+                affected.changed_cells(&before, &self.state)
+            }
+            _ => {
+                let before = self.state.clone();
+                let frozen = self.frozen_cells();
+                self.transform_buffered();
+                self.restore_cells(frozen);
+                self.count_changes(|state, _| rule::count_changed(&before, state));
+                rule::Region::changed(&before, &self.state)
+            }
+        };
+        if let Some(dirty) = &self.dirty {
+            self.image.update(&self.state, dirty);
+        }
+    }
+
+    /// Adds the number of cells changed by an application of the rule to the [counted changes](Self::changes), if they are counted.
+    /// The passed function receives the state and the buffer and counts the changed cells.
+    fn count_changes(&mut self, count: impl FnOnce(&grid::Grid<T>, &grid::Grid<T>) -> usize) {
+        if let Some(changes) = self.changes {
+            self.changes = Some(changes + count(&self.state, &self.buffer));
+        }
+    }
+
+    /// Returns the positions and values of all cells containing a frozen symbol.
+    fn frozen_cells(&self) -> Vec<(usize, usize, T)> {
+        self.frozen_cells_in(&rule::Region::full(self.state.rows(), self.state.cols()))
+    }
+
+    /// Returns the positions and values of all cells of the passed region containing a frozen symbol.
+    fn frozen_cells_in(&self, region: &rule::Region) -> Vec<(usize, usize, T)> {
+        if self.frozen.is_empty() {
+            return Vec::new();
+        }
+        region
+            .rows
+            .clone()
+            .flat_map(|row| region.cols.clone().map(move |col| (row, col)))
+            .map(|(row, col)| (row, col, self.state[row][col]))
+            .filter(|(_, _, cell)| self.frozen.contains(cell))
+            .collect()
+    }
+
+    /// Sets the cells at the passed positions back to the passed values.
+    fn restore_cells(&mut self, cells: Vec<(usize, usize, T)>) {
+        for (row, col, cell) in cells {
+            self.state[row][col] = cell;
+        }
+    }
+
+    /// Lets the rule transform the state in memory of its own, if it keeps one, see [Rule::transform_resident](rule::Rule::transform_resident).
+    /// Returns wether it did. If the rule already keeps the current state, it continues from there instead of receiving the cells.
+    fn transform_resident(&mut self) -> bool {
+        let resident = if self.state.is_resident() {
+            self.rule.transform_resident(None)
+        } else {
+            self.rule.transform_resident(Some(&self.state))
+        };
+        match resident {
+            Some(resident) => {
+                self.state.set_resident(resident);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies this automaton's rule to the entire state, swapping the state with the buffer if the rule wrote its result there.
+    fn transform_buffered(&mut self) {
+        let Self {
+            rule,
+            state,
+            aux,
+            buffer,
+            executor,
+            step_report,
+            ..
+        } = self;
+        if executor.run(|| match (step_report, aux) {
+            (Some(report), aux) => rule.transform_reported(state, buffer, aux.as_ref(), report),
+            (None, Some(aux)) => rule.transform_with_aux(state, buffer, aux),
+            (None, None) => rule.transform_buffered(state, buffer),
+        }) {
+            std::mem::swap(&mut **state, buffer);
+        }
+    }
+
+    /// Checks if and how many time steps should currently be executed and performs them.
+    /// A time step consists of applying this automatons rule to its state, thus transforming the state.
+    /// ## Returns
+    /// Wether or not the state has changed since the last invocation of [next_step](Automaton::next_step()), either because a time step was performed or by manual interaction between steps.
+    pub fn next_step(&mut self) -> bool {
+        // if the automaton has just started, set last step for the first time
+        if self.last_step.is_none() {
+            self.last_step = Some(time::Instant::now());
+        }
+        let step_permitted = self.step_due();
+        if step_permitted {
+            self.perform_step();
+        }
+        step_permitted
+    }
+
+    /// Returns wether a time step should be performed now according to the step mode.
+    fn step_due(&mut self) -> bool {
+        // if the automaton has just started, set last step for the first time
+        if self.last_step.is_none() {
+            self.last_step = Some(time::Instant::now());
+        }
+        self.time_until_next_step().is_zero()
+    }
+
+    /// Returns the interval between two timed steps, scaled by the speed multiplier, or [None] if steps are performed on every call.
+    fn step_interval(&self) -> Option<time::Duration> {
+        match self.step_mode {
+            StepMode::Immediate => None,
+            // very slow speeds may not fit the interval into a duration, never stepping instead
+            StepMode::Limited { interval } => Some(
+                time::Duration::try_from_secs_f64(interval.as_secs_f64() / self.speed as f64)
+                    .unwrap_or(time::Duration::MAX),
+            ),
+        }
+    }
+
+    /// Returns how long it takes until the next time step is due, which is zero if it is due already or steps are performed on every call.
+    pub(crate) fn time_until_next_step(&self) -> time::Duration {
+        match (self.step_interval(), self.last_step) {
+            (Some(interval), Some(last_step)) => interval.saturating_sub(last_step.elapsed()),
+            _ => time::Duration::ZERO,
+        }
+    }
+
+    /// Performs a time step like [Self::next_step], but tries to take no longer than the passed budget, for hosts that can only spare a limited time per frame.
+    ///
+    /// This changes the semantics of a step, so only use it if the budget matters more than exact steps:
+    /// * Rules that can transform single rows, such as [EnvironmentRule](crate::rule::EnvironmentRule), transform rows until the budget is used up and continue with the next row on the next call,
+    ///   so a step may be spread over several calls, returning [BudgetOutcome::InProgress] until it is complete. The state only changes once the step is complete.
+    ///   Changing the state in between, or calling [Self::next_step], starts the step over.
+    /// * [PatternRule](crate::rule::PatternRule)s stop scanning for patterns once the budget is used up and only apply the patterns found so far, returning [BudgetOutcome::Partial].
+    /// * All other rules ignore the budget.
+    ///
+    /// At least one row or band of rows is transformed per call, so steps always make progress, even with a budget of zero.
+    /// Steps performed this way always transform the entire grid, regardless of dirty tracking, and are not included in [step reports](crate::AutomatonBuilder::with_step_reports).
+    /// ```
+    /// use std::time::Duration;
+    /// use cellumina::BudgetOutcome;
+    ///
+    /// // every cell takes the value of the cell above it
+    /// let mut automaton = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 1, 0, 0, 0, 0], 2)
+    ///     .with_rule(cellumina::rule::EnvironmentRule {
+    ///         cell_transform: |env| env[0][1],
+    ///         ..Default::default()
+    ///     })
+    ///     .build();
+    /// // without any budget, one row is transformed per call
+    /// assert_eq!(automaton.next_step_with_budget(Duration::ZERO), BudgetOutcome::InProgress { rows_done: 1, rows: 3 });
+    /// assert_eq!(automaton.next_step_with_budget(Duration::ZERO), BudgetOutcome::InProgress { rows_done: 2, rows: 3 });
+    /// assert_eq!(automaton.next_step_with_budget(Duration::ZERO), BudgetOutcome::Complete);
+    /// assert_eq!(automaton.state(), &grid::grid![[0, 0][1, 1][0, 0]]);
+    /// ```
+    pub fn next_step_with_budget(&mut self, budget: time::Duration) -> BudgetOutcome {
+        let deadline = time::Instant::now() + budget;
+        if self.pending_rows.is_none() && !self.step_due() {
+            return BudgetOutcome::Waiting;
+        }
+        if let Some(report) = &mut self.step_report {
+            report.clear();
+        }
+
+        let (rows, cols) = self.state.size();
+        if self.pending_rows.is_none() {
+            if self.buffer.size() != (rows, cols) {
+                self.buffer = grid::Grid::new(rows, cols);
+            }
+            if self
+                .rule
+                .transform_rows(&self.state, &mut self.buffer, 0..0)
+            {
+                self.pending_rows = Some(0);
+            }
+        }
+
+        match self.pending_rows {
+            Some(start) => {
+                for row in start..rows {
+                    self.rule
+                        .transform_rows(&self.state, &mut self.buffer, row..row + 1);
+                    if row + 1 < rows && time::Instant::now() >= deadline {
+                        self.pending_rows = Some(row + 1);
+                        return BudgetOutcome::InProgress {
+                            rows_done: row + 1,
+                            rows,
+                        };
+                    }
+                }
+                self.pending_rows = None;
+                let frozen = self.frozen_cells();
+                std::mem::swap(&mut *self.state, &mut self.buffer);
+                self.restore_cells(frozen);
+                // the buffer still holds the state before the step
+                let dirty = self
+                    .dirty_tracking
+                    .then(|| rule::Region::changed(&self.buffer, &self.state))
+                    .flatten();
+                self.finish_budgeted_step(dirty);
+                BudgetOutcome::Complete
+            }
+            None => {
+                let before = self.dirty_tracking.then(|| self.state.clone());
+                let frozen = self.frozen_cells();
+                let Self {
+                    rule,
+                    state,
+                    aux,
+                    buffer,
+                    executor,
+                    ..
+                } = self;
+                let complete =
+                    executor.run(|| rule.transform_until(state, buffer, aux.as_ref(), deadline));
+                self.restore_cells(frozen);
+                let dirty = before.and_then(|before| rule::Region::changed(&before, &self.state));
+                self.finish_budgeted_step(dirty);
+                if complete {
+                    BudgetOutcome::Complete
+                } else {
+                    BudgetOutcome::Partial
+                }
+            }
+        }
+    }
+
+    /// Updates the image and dirty region after a step of [Self::next_step_with_budget] changed the cells in the passed region, and counts the step.
+    /// The changed region is only needed with dirty tracking.
+    fn finish_budgeted_step(&mut self, dirty: Option<rule::Region>) {
+        if self.dirty_tracking {
+            self.dirty = dirty;
+            if let Some(dirty) = &self.dirty {
+                self.image.update(&self.state, dirty);
+            }
+        } else {
+            self.image.invalidate();
+        }
+        self.generation += 1;
+        self.record_stats();
+        self.last_step = Some(time::Instant::now());
+    }
+
+    /// Performs a single time step, regardless of the step mode.
+    pub(crate) fn perform_step(&mut self) {
+        self.apply_substeps();
+        self.generation += self.generations_per_step();
+        self.record_stats();
+        self.last_step = Some(time::Instant::now());
+    }
+
+    /// Applies the rule as often as a time step requires, see [AutomatonBuilder::with_substeps](crate::AutomatonBuilder::with_substeps).
+    /// Step reports contain the applications of all substeps.
+    fn apply_substeps(&mut self) {
+        if let Some(report) = &mut self.step_report {
+            report.clear();
+        }
+        if self.changes.is_some() {
+            self.changes = Some(0);
+        }
+        for _ in 0..self.substeps {
+            self.apply_rule();
+        }
+    }
+
+    /// Returns the number of generations a time step counts as.
+    fn generations_per_step(&self) -> u64 {
+        if self.count_substeps {
+            self.substeps as u64
+        } else {
+            1
+        }
+    }
+
+    /// Undoes a single time step by applying the [inverse](rule::Rule::inverse) of this automaton's rule, regardless of the step mode, and decrements the generation.
+    /// Frozen cells are kept, the entire state is marked as changed.
+    /// ## Errors
+    /// Returns [CelluminaError::InitialGeneration](crate::CelluminaError::InitialGeneration) at generation 0
+    /// and [CelluminaError::IrreversibleRule](crate::CelluminaError::IrreversibleRule) if the rule has no inverse, changing nothing in both cases.
+    /// ```
+    /// use cellumina::rule::ShiftRule;
+    /// let mut auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 0, 0], 3)
+    ///     .with_rule(ShiftRule { dy: 0, dx: 1, fill: None })
+    ///     .build();
+    /// auto.next_step();
+    /// auto.step_back().unwrap();
+    /// assert_eq!(auto.generation(), 0);
+    /// assert_eq!(auto.state(), &grid::grid![[1, 0, 0]]);
+    /// ```
+    pub fn step_back(&mut self) -> Result<(), crate::CelluminaError> {
+        if self.generation < self.generations_per_step() {
+            return Err(crate::CelluminaError::InitialGeneration);
+        }
+        let inverse = self
+            .rule
+            .inverse()
+            .ok_or(crate::CelluminaError::IrreversibleRule)?;
+        self.pending_rows = None;
+        let frozen = self.frozen_cells();
+        let Self {
+            state,
+            aux,
+            buffer,
+            executor,
+            substeps,
+            ..
+        } = self;
+        for _ in 0..*substeps {
+            if executor.run(|| match aux {
+                Some(aux) => inverse.transform_with_aux(state, buffer, aux),
+                None => inverse.transform_buffered(state, buffer),
+            }) {
+                std::mem::swap(&mut **state, buffer);
+            }
+        }
+        self.restore_cells(frozen);
+        self.image.invalidate();
+        if self.dirty_tracking {
+            self.dirty = Some(rule::Region::full(self.state.rows(), self.state.cols()));
+        }
+        self.generation -= self.generations_per_step();
+        self.last_step = Some(time::Instant::now());
+        Ok(())
+    }
+
+    /// Turns this automaton into an iterator over its states, yielding the current state first and then the state after each further time step.
+    /// Steps are performed as the items are requested, regardless of the step mode, so only as many steps are calculated as items are taken.
+    ///
+    /// Every item is a copy of the entire state. For large grids where only some states are needed, skipping items with adapters such as ```step_by``` still copies them,
+    /// so consider stepping with [Self::states_hashed] or manually in that case.
+    /// ```
+    /// # use cellumina::rule::ShiftRule;
+    /// let automaton = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 0, 0, 0], 4)
+    ///     .with_rule(ShiftRule { dy: 0, dx: 1, fill: None })
+    ///     .build();
+    /// let positions = automaton
+    ///     .states()
+    ///     .take(6)
+    ///     .map(|state| state.iter().position(|&cell| cell == 1).unwrap())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(positions, vec![0, 1, 2, 3, 0, 1]);
+    /// ```
+    pub fn states(mut self) -> impl Iterator<Item = grid::Grid<T>> {
+        let mut first = true;
+        std::iter::from_fn(move || {
+            if !std::mem::take(&mut first) {
+                self.perform_step();
+            }
+            Some(self.state.clone())
+        })
+    }
+
+    /// Iterates over the states of this automaton like [Self::states], but borrows the automaton, so it can be used again after the iterator is dropped.
+    /// The automaton keeps all steps performed for the items taken.
+    pub fn states_ref(&mut self) -> impl Iterator<Item = grid::Grid<T>> + '_ {
+        let mut first = true;
+        std::iter::from_fn(move || {
+            if !std::mem::take(&mut first) {
+                self.perform_step();
+            }
+            Some(self.state.clone())
+        })
+    }
+
+    /// Iterates over the states of this automaton like [Self::states_ref], but yields a hash of each state along with its [generation](Self::generation) instead of a copy,
+    /// for example to find cycles by looking for repeated hashes.
+    ///
+    /// Equal states have equal hashes within the same run of a program, but the hashes may differ between versions of Rust and should not be stored.
+    /// Different states may have the same hash, though that is very unlikely.
+    pub fn states_hashed(&mut self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let mut first = true;
+        std::iter::from_fn(move || {
+            if !std::mem::take(&mut first) {
+                self.perform_step();
+            }
+            Some((hash_grid(&self.state), self.generation))
+        })
+    }
+}
+
+impl Automaton {
+    /// Turns this automatons current state grid into text, with one line per row and one character per cell as given by [crate::id_to_char].
+    /// Every row, including the last, ends with a newline, so the result can be loaded again with [AutomatonBuilder::from_text_file](crate::AutomatonBuilder::from_text_file).
+    pub fn to_text_string(&self) -> String {
+        let mut text = String::with_capacity(self.state.rows() * (self.state.cols() + 1));
+        for row in self.state.iter_rows() {
+            text.extend(row.map(|&cell| crate::id_to_char(cell)));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Lists all symbols that have a name or color or occur in the state, in ascending order,
+    /// each with its character (see [crate::id_to_char]), its name and its color, for example to show a legend next to an image of the state.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 1, 1, 0], 2)
+    ///     .with_color(41, [255, 80, 0, 255])
+    ///     .with_symbol_name(41, "fire")
+    ///     .build();
+    /// assert_eq!(
+    ///     auto.legend(),
+    ///     [
+    ///         (0, ' ', None, None),
+    ///         (1, '1', None, None),
+    ///         (41, 'F', Some("fire"), Some([255, 80, 0, 255])),
+    ///     ]
+    /// );
+    /// ```
+    pub fn legend(&self) -> Vec<LegendEntry<'_>> {
+        let mut used = [false; 256];
+        for &cell in self
+            .state
+            .iter()
+            .chain(self.names.keys())
+            .chain(self.colors.keys())
+        {
+            used[cell as usize] = true;
+        }
+        (0..=u8::MAX)
+            .filter(|&cell| used[cell as usize])
+            .map(|cell| {
+                (
+                    cell,
+                    crate::id_to_char(cell),
+                    self.symbol_name(cell),
+                    self.get_color(cell),
+                )
+            })
+            .collect()
+    }
+
+    /// Places ```shape``` with its top left cell at the passed position like [set_region](AutomatonGeneric::set_region),
+    /// but cells of the shape containing the wildcard ```*``` (```127```) leave the cells below them unchanged, like in the ```after``` grid of a [Pattern](crate::rule::Pattern).
+    /// This allows placing partial shapes over existing content.
+    ///
+    /// The position may lie partially or fully outside of the state, parts of the shape outside of it are cut off.
+    /// Returns the changed cells with their new values.
+    /// ```
+    /// let mut auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 1, 1, 1], 2)
+    ///     .build();
+    /// let changed = auto.stamp(-1, 0, &grid::grid![[5, 5][127, 2]]);
+    /// assert_eq!(changed, vec![(0, 1, 2)]);
+    /// assert_eq!(auto.state(), &grid::grid![[1, 2][1, 1]]);
+    /// ```
+    pub fn stamp(&mut self, row: i64, col: i64, shape: &crate::CellGrid) -> Vec<(u32, u32, u8)> {
+        self.apply_shape(row, col, shape, |cell| cell)
+    }
+
+    /// Clears the cells covered by ```shape```, placed with its top left cell at the passed position, to empty cells (```0```).
+    /// Cells of the shape containing the wildcard ```*``` (```127```) leave the cells below them unchanged, see [Self::stamp].
+    /// Returns the changed cells.
+    pub fn erase_shape(
+        &mut self,
+        row: i64,
+        col: i64,
+        shape: &crate::CellGrid,
+    ) -> Vec<(u32, u32, u8)> {
+        self.apply_shape(row, col, shape, |_| 0)
+    }
+
+    /// Replaces each cell covered by a non-wildcard cell of ```shape``` with the result of ```f``` for that cell of the shape, cutting off parts outside of the state.
+    fn apply_shape(
+        &mut self,
+        row: i64,
+        col: i64,
+        shape: &crate::CellGrid,
+        f: impl Fn(u8) -> u8,
+    ) -> Vec<(u32, u32, u8)> {
+        let (rows, cols) = self.state.size();
+        let row_range = row.max(0)..(row + shape.rows() as i64).min(rows as i64);
+        let col_range = col.max(0)..(col + shape.cols() as i64).min(cols as i64);
+        let mut changed = Vec::new();
+        for state_row in row_range.clone() {
+            for state_col in col_range.clone() {
+                let cell = match shape[(state_row - row) as usize][(state_col - col) as usize] {
+                    127 => continue,
+                    cell => f(cell),
+                };
+                let current = &mut self.state[state_row as usize][state_col as usize];
+                if *current != cell {
+                    *current = cell;
+                    changed.push((state_row as u32, state_col as u32, cell));
+                }
+            }
+        }
+        if !changed.is_empty() {
+            self.mark_dirty(rule::Region {
+                rows: row_range.start as usize..row_range.end as usize,
+                cols: col_range.start as usize..col_range.end as usize,
+            });
+        }
+        changed
+    }
+
+    /// Returns the color of the passed character in images of this automaton, which is the default color for characters without a color.
+    #[cfg(feature = "display")]
+    pub(crate) fn image_color(&self, cell: u8) -> [u8; 4] {
+        if !self.image.is_mapped(cell) {
+            self.image.report_missing(self.state.iter().copied());
+        }
+        self.image.color(cell)
+    }
+
+    /// Saves the current state of this automaton to a file, either as text, as an image or in a binary format depending on the file extension.
+    /// Files without extension are saved as text, see [Self::to_text_string()].
+    ///
+    /// Images only keep all symbols if every symbol present has a color of its own, otherwise a warning is logged.
+    /// Automata built [with alpha encoding](crate::AutomatonBuilder::with_alpha_encoding) instead store the symbols in the alpha channel of ```png```, ```ico``` and ```bmp``` files.
+    ///
+    /// ```cels``` files store the raw cell bytes, ```celz``` files compress them, see [Self::save_binary()].
+    /// ## Error
+    /// When the file can not be written or its extension is not one of ```txt```, ```png```, ```jpeg```, ```ico```, ```bmp```, ```cels``` or ```celz```.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), CelluminaError> {
+        let path = path.as_ref();
+        let (rows, cols) = self.dimensions();
+        match path.extension().and_then(std::ffi::OsStr::to_str) {
+            Some(ext @ ("png" | "jpeg" | "ico" | "bmp")) => {
+                let mut image = self.create_image_buffer();
+                // jpeg neither has an alpha channel nor stores colors exactly
+                if self.alpha_encoding && ext != "jpeg" {
+                    crate::convert::encode_alpha(&mut image, &self.state);
+                } else {
+                    for loss in self.image_loss() {
+                        log::warn!("{loss} Save as text or build the automaton with alpha encoding to keep all symbols.");
+                    }
+                }
+                Ok(image::save_buffer(
+                    path,
+                    &image,
+                    cols,
+                    rows,
+                    image::ColorType::Rgba8,
+                )?)
+            }
+            Some("txt") | None => Ok(std::fs::write(path, self.to_text_string())?),
+            Some("cels") => self.save_binary(path, false),
+            Some("celz") => self.save_binary(path, true),
+            Some(ext) => Err(CelluminaError::UnsupportedFormat(ext.to_string())),
+        }
+    }
+
+    /// Saves the current state of this automaton to a file in a binary format, storing its dimensions and one byte per cell, regardless of the file extension.
+    /// With ```compress``` set, the file is compressed, which makes states with large uniform areas much smaller.
+    ///
+    /// Compression requires the ```compression``` feature. [AutomatonBuilder::from_binary_file](crate::AutomatonBuilder::from_binary_file) reads both variants, telling them apart by their first bytes.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 1, 2, 3, 4, 5], 3)
+    ///     .build();
+    /// let path = std::env::temp_dir().join("cellumina_save_binary_doctest.cels");
+    /// auto.save_binary(&path, false).unwrap();
+    /// let loaded = cellumina::AutomatonBuilder::new()
+    ///     .from_binary_file(path.clone())
+    ///     .build();
+    /// # std::fs::remove_file(path).unwrap();
+    /// assert_eq!(loaded.state(), auto.state());
+    /// ```
+    /// ## Error
+    /// When the file can not be written, or compression is requested without the ```compression``` feature.
+    pub fn save_binary(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        compress: bool,
+    ) -> Result<(), CelluminaError> {
+        Ok(std::fs::write(
+            path,
+            crate::binary::encode(&self.state, compress)?,
+        )?)
+    }
+
+    /// Describes the symbols of the current state that would be lost when saving it as an image and loading it again with the same colors,
+    /// because they have no color or share their color with other symbols.
+    pub(crate) fn image_loss(&self) -> Vec<String> {
+        let present = self
+            .state
+            .iter()
+            .copied()
+            .collect::<std::collections::BTreeSet<_>>();
+        let mut res = Vec::new();
+
+        let uncolored = present
+            .iter()
+            .filter(|symbol| !self.colors.contains_key(symbol))
+            .collect::<Vec<_>>();
+        if !uncolored.is_empty() {
+            res.push(format!(
+                "The symbols {uncolored:?} have no color and are lost when loading the image."
+            ));
+        }
+
+        let mut by_color = std::collections::BTreeMap::<[u8; 4], Vec<u8>>::new();
+        for &symbol in &present {
+            if let Some(&color) = self.colors.get(&symbol) {
+                by_color.entry(color).or_default().push(symbol);
+            }
+        }
+        for (color, symbols) in by_color {
+            if symbols.len() > 1 {
+                res.push(format!(
+                    "The symbols {symbols:?} share the color {color:?} and are loaded as a single symbol."
+                ));
+            }
+        }
+        res
+    }
+
+    /// Runs this automaton and displays it in a window.
+    /// ```next_step()``` is called every frame, so setting an appropriate time step may be helpful for a smooth display.
+    ///
+    /// Returns once the window is closed, after which further automata may be shown. To get the automaton back, use a [LiveSession](crate::LiveSession).
+    ///
+    /// If the machine has no GPU, as is common for CI runners and virtual machines, a fallback adapter rendering on the CPU is used if available.
+    /// ## Error
+    /// A [GraphicsInitError](CelluminaError::GraphicsInitError) if the window could not be set up, for example [NoDisplay](crate::GraphicsInitError::NoDisplay) if there is no display.
+    /// The automaton is dropped in this case, to keep it, run it with a [LiveSession](crate::LiveSession) instead.
+    #[cfg(feature = "display")]
+    pub fn run_live(self) -> Result<(), CelluminaError> {
+        self.run_live_with(Default::default())
+    }
+
+    /// Runs this automaton and displays it in a window configured by the passed [LiveViewConfig](crate::LiveViewConfig).
+    /// ```next_step()``` is called every frame, so setting an appropriate time step may be helpful for a smooth display.
+    /// ## Error
+    /// Like [Self::run_live].
+    #[cfg(feature = "display")]
+    pub fn run_live_with(self, config: crate::LiveViewConfig) -> Result<(), CelluminaError> {
+        Self::run_live_shared(self.into_shared(), config)
+    }
+
+    /// Runs this automaton and displays it in a window, calling the passed [LiveHooks](crate::LiveHooks) when steps are performed, cells are edited or the window is closed.
+    /// This allows host code to collect data from or inject changes into the running automaton.
+    /// ## Error
+    /// Like [Self::run_live], without calling any hooks.
+    #[cfg(feature = "display")]
+    pub fn run_live_with_hooks(self, hooks: crate::LiveHooks) -> Result<(), CelluminaError> {
+        crate::LiveSession::new()
+            .run_shared(self.into_shared(), Default::default(), hooks)
+            .map(|_| ())
+    }
+
+    /// Runs the passed shared automaton and displays it in a window configured by the passed [LiveViewConfig](crate::LiveViewConfig),
+    /// while other threads holding a clone of it may read or change it, for example to save it periodically.
+    ///
+    /// The window locks the automaton only for single operations: for writing while it performs a step and handles input, and for reading while it copies the state to draw it.
+    /// No lock is held while waiting for the next frame or rendering it, so other threads get access at least once per frame.
+    /// Conversely, other threads should only hold their locks briefly, as the window can not progress while they do.
+    /// Changes made by other threads are drawn in the next frame.
+    /// ```no_run
+    /// let automaton = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+    ///     .with_preset(cellumina::rule::presets::game_of_life())
+    ///     .build()
+    ///     .into_shared();
+    /// let reader = automaton.clone();
+    /// std::thread::spawn(move || loop {
+    ///     std::thread::sleep(std::time::Duration::from_secs(10));
+    ///     let automaton = reader.read().unwrap();
+    ///     println!("generation {}:\n{}", automaton.generation(), automaton.to_text_string());
+    /// });
+    /// cellumina::Automaton::run_live_shared(automaton, Default::default())?;
+    /// # Ok::<(), cellumina::CelluminaError>(())
+    /// ```
+    /// ## Error
+    /// Like [Self::run_live].
+    #[cfg(feature = "display")]
+    pub fn run_live_shared(
+        automaton: SharedAutomaton,
+        config: crate::LiveViewConfig,
+    ) -> Result<(), CelluminaError> {
+        crate::LiveSession::new()
+            .run_shared(automaton, config, Default::default())
+            .map(|_| ())
+    }
+
+    /// Wraps this automaton to share it between threads, see [Self::run_live_shared].
+    pub fn into_shared(self) -> SharedAutomaton {
+        std::sync::Arc::new(std::sync::RwLock::new(self))
+    }
+}
+
+/// Returns a hash of the dimensions and cells of the passed grid.
+fn hash_grid<C: std::hash::Hash>(grid: &grid::Grid<C>) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    grid.size().hash(&mut hasher);
+    grid.iter().for_each(|cell| cell.hash(&mut hasher));
+    hasher.finish()
+}
+
+/// Returns a copy of the cells of the passed grid within the region, which has to lie within the grid.
+fn sub_grid<C: Copy>(grid: &grid::Grid<C>, region: &rule::Region) -> grid::Grid<C> {
+    let mut cells = Vec::with_capacity(region.rows.len() * region.cols.len());
+    for row in region.rows.clone() {
+        cells.extend(
+            grid.iter_row(row)
+                .skip(region.cols.start)
+                .take(region.cols.len())
+                .copied(),
+        );
+    }
+    grid::Grid::from_vec(cells, region.cols.len())
+}
+
+#[test]
+fn automaton_test() {
+    use crate::testing;
+
+    let mut auto = Automaton {
+        state: testing::parse_grid(
+            "0000
+             0100
+             0100
+             0100",
+        )
+        .into(),
+        rule: Box::new(rule::EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            row_boundary: rule::BoundaryBehaviour::Symbol(0),
+            col_boundary: rule::BoundaryBehaviour::Symbol(0),
+            cell_transform: |env| match rule::helpers::count_neighbors(env, 1)
+                // Count the live neighbors around the center and map the count to the new entry of our cell:
+            {
+                // 2 neighbors: The cell keeps its state.
+                2 => env[1][1],
+                // 3 neighbors: The cell gets born.
+                3 => 1,
+                // 0, 1 or more than 3 neighbors: The cell dies.
+                _ => 0,
+            },
+            ..Default::default()
+        }),
+        aux: None,
+        step_mode: StepMode::Immediate,
+        speed: 1.,
+        colors: HashMap::new(),
+        last_step: None,
+        generation: 0,
+        dirty_tracking: false,
+        dirty: None,
+        buffer: grid::Grid::new(0, 0),
+        executor: crate::parallelism::Executor::Global,
+        image: ImageCache::new(&HashMap::new(), DEFAULT_COLOR),
+        stats: None,
+        transitions: None,
+        names: HashMap::new(),
+        frozen: Vec::new(),
+        step_report: None,
+        changes: None,
+        pending_rows: None,
+        substeps: 1,
+        count_substeps: false,
+        alpha_encoding: false,
+    };
+
+    for _ in 0..5 {
+        auto.next_step();
+    }
+
+    testing::assert_grid_eq(
+        auto.state(),
+        "0000
+         0000
+         1110
+         0000",
+    );
+
+    auto.next_step();
+    testing::assert_grid_eq(
+        auto.state(),
+        "0000
+         0100
+         0100
+         0100",
+    );
+
+    assert_ne!(auto.last_step, None);
+    assert_eq!(auto.generation(), 6);
+}
+
+#[test]
+fn get_cell_test() {
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 1, 2, 3, 4, 5], 3)
+        .with_color(4, [1, 2, 3, 4])
+        .build();
+
+    assert_eq!(auto.get_cell(1, 1).unwrap(), 4);
+    assert!(auto.get_cell(2, 0).is_err());
+    assert!(auto.get_cell(0, 3).is_err());
+
+    auto.set_cell(0, 2, 36).unwrap();
+    assert_eq!(auto.get_cell(0, 2).unwrap(), 36);
+
+    assert_eq!(auto.get_color(4), Some([1, 2, 3, 4]));
+    assert_eq!(auto.get_color(36), None);
+}
+
+#[test]
+fn dirty_tracking_test() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+    let init = grid::Grid::from_vec(
+        (0..40 * 30)
+            .map(|index| {
+                if index < 15 * 30 && rng.gen_bool(0.4) {
+                    59
+                } else {
+                    0
+                }
+            })
+            .collect(),
+        30,
+    );
+    let build = |tracking: bool| {
+        let builder = crate::AutomatonBuilder::new()
+            .from_grid(init.clone())
+            .with_patterns(&[
+                rule::Pattern {
+                    before: grid::grid![[59][0]],
+                    after: grid::grid![[0][59]],
+                    priority: 2.0,
+                    chance: 1.0,
+                    ..Default::default()
+                },
+                rule::Pattern {
+                    before: grid::grid![[59, 127][59, 0]],
+                    after: grid::grid![[0, 127][127, 59]],
+                    priority: 1.0,
+                    chance: 1.0,
+                    ..Default::default()
+                },
+            ])
+            .with_pattern_edge_behaviour(
+                rule::BoundaryBehaviour::Symbol(126),
+                rule::BoundaryBehaviour::Symbol(126),
+            );
+        if tracking {
+            builder.with_dirty_tracking().build()
+        } else {
+            builder.build()
+        }
+    };
+
+    let mut plain = build(false);
+    let mut tracked = build(true);
+
+    for step in 0..120 {
+        if step == 50 {
+            // manual edits must be picked up as well
+            plain.set_cell(0, 3, 59).unwrap();
+            tracked.set_cell(0, 3, 59).unwrap();
+        }
+        plain.next_step();
+        tracked.next_step();
+        assert_eq!(plain.state, tracked.state);
+    }
+
+    // the sand has settled, so nothing is dirty anymore
+    assert_eq!(tracked.dirty, None);
+
+    // a blinker far from the edges only dirties the cells it changes
+    let mut init = crate::CellGrid::new(50, 50);
+    for col in 19..22 {
+        init[20][col] = 1;
+    }
+    let mut blinker = crate::AutomatonBuilder::new()
+        .from_grid(init)
+        .with_rule(rule::EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            cell_transform: |env| match rule::helpers::count_neighbors(env, 1) {
+                2 => env[1][1],
+                3 => 1,
+                _ => 0,
+            },
+            ..Default::default()
+        })
+        .with_dirty_tracking()
+        .build();
+    blinker.next_step();
+    blinker.next_step();
+    assert_eq!(
+        blinker.dirty,
+        Some(rule::Region {
+            rows: 19..22,
+            cols: 19..22
+        })
+    );
+}
+
+#[test]
+fn parallelism_test() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+    let init = grid::Grid::from_vec((0..100 * 70).map(|_| rng.gen_range(0..3)).collect(), 70);
+    let build = |parallelism: crate::Parallelism| {
+        crate::AutomatonBuilder::new()
+            .from_grid(init.clone())
+            .with_patterns(&[
+                rule::Pattern {
+                    before: grid::grid![[1][0]],
+                    after: grid::grid![[0][1]],
+                    priority: 1.0,
+                    chance: 0.5,
+                    ..Default::default()
+                },
+                rule::Pattern {
+                    before: grid::grid![[2, 0]],
+                    after: grid::grid![[0, 2]],
+                    priority: 1.0,
+                    chance: 0.7,
+                    ..Default::default()
+                },
+            ])
+            .with_seed(17)
+            .with_parallelism(parallelism)
+            .build()
+    };
+
+    let mut default = build(crate::Parallelism::Default);
+    let mut sequential = build(crate::Parallelism::Sequential);
+    let mut threads = build(crate::Parallelism::Threads(2));
+
+    for _ in 0..20 {
+        default.next_step();
+        sequential.next_step();
+        threads.next_step();
+        assert_eq!(default.state, sequential.state);
+        assert_eq!(default.state, threads.state);
+    }
+}
+
+#[test]
+fn image_cache_test() {
+    let reference = |automaton: &Automaton| {
+        automaton
+            .state
+            .iter()
+            .flat_map(|cell| automaton.colors.get(cell).copied().unwrap_or(DEFAULT_COLOR))
+            .collect::<Vec<u8>>()
+    };
+
+    for tracking in [false, true] {
+        let builder = crate::AutomatonBuilder::new()
+            .from_grid(grid::grid![[1, 200, 0][0, 1, 0][0, 0, 0][0, 0, 0]])
+            .with_patterns(&[rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                priority: 1.0,
+                chance: 1.0,
+                ..Default::default()
+            }])
+            .with_color(1, [255, 0, 0, 255])
+            .with_color(200, [0, 0, 255, 255]);
+        let mut automaton = if tracking {
+            builder.with_dirty_tracking().build()
+        } else {
+            builder.build()
+        };
+
+        assert_eq!(automaton.image_bytes(), reference(&automaton));
+
+        // manual edits
+        automaton.set_cell(3, 2, 200).unwrap();
+        assert_eq!(automaton.image_bytes(), reference(&automaton));
+        automaton.set_cell(0, 1, 1).unwrap();
+        assert_eq!(automaton.image_bytes(), reference(&automaton));
+
+        // rule steps
+        for _ in 0..4 {
+            automaton.next_step();
+            assert_eq!(automaton.image_bytes(), reference(&automaton));
+        }
+
+        automaton.fill(200);
+        assert_eq!(
+            automaton.create_image_buffer().into_raw(),
+            reference(&automaton)
+        );
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn thread_pool_test() {
+    use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+    use std::{collections::HashSet, sync::Mutex, thread};
+
+    /// A rule that does nothing but remember the threads it was applied on.
+    #[derive(Debug, Default)]
+    struct ThreadRule {
+        threads: std::sync::Arc<Mutex<HashSet<thread::ThreadId>>>,
+    }
+
+    impl rule::Rule for ThreadRule {
+        fn transform(&self, grid: &mut crate::CellGrid) {
+            (0..grid.rows()).into_par_iter().for_each(|_| {
+                self.threads.lock().unwrap().insert(thread::current().id());
+            });
+        }
+    }
+
+    let rule = ThreadRule::default();
+    let threads = rule.threads.clone();
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid::Grid::new(256, 4))
+        .with_rule(rule)
+        .with_parallelism(crate::Parallelism::Threads(2))
+        .build();
+
+    for _ in 0..10 {
+        automaton.next_step();
+    }
+
+    let threads = threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(threads.len() <= 2);
+    assert!(!threads.contains(&thread::current().id()));
+}
+
+/// Counts the allocations of all threads marked with [COUNTED], to verify that certain code paths do not allocate.
+/// Each test measuring allocations uses an id of its own, so tests running concurrently never count each other's allocations.
+#[cfg(test)]
+struct CountingAllocator;
+
+/// The number of allocations performed by the counted threads, for each id a test may mark them with.
+#[cfg(test)]
+static ALLOCATIONS: [std::sync::atomic::AtomicUsize; 4] =
+    [const { std::sync::atomic::AtomicUsize::new(0) }; 4];
+
+#[cfg(test)]
+thread_local! {
+    /// The id of the test counting the allocations of the current thread, if any, set by a test for itself and the workers of its thread pools.
+    static COUNTED: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+#[cfg(test)]
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        if let Ok(Some(id)) = COUNTED.try_with(|counted| counted.get()) {
+            ALLOCATIONS[id].fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations the passed function performs on the current thread and all other threads marked with the passed id, which must be unique to the test.
+#[cfg(test)]
+fn count_allocations(id: usize, f: impl FnOnce()) -> usize {
+    use std::sync::atomic::Ordering;
+
+    let previous = COUNTED.with(|counted| counted.replace(Some(id)));
+    let before = ALLOCATIONS[id].load(Ordering::SeqCst);
+    f();
+    let after = ALLOCATIONS[id].load(Ordering::SeqCst);
+    COUNTED.with(|counted| counted.set(previous));
+    after - before
+}
+
+#[test]
+fn double_buffer_test() {
+    use rand::Rng;
+
+    let life = rule::EnvironmentRule {
+        environment_size: [1, 1, 1, 1],
+        row_boundary: rule::BoundaryBehaviour::Periodic,
+        col_boundary: rule::BoundaryBehaviour::Symbol(0),
+        cell_transform: |env| match rule::helpers::count_neighbors(env, 1) {
+            2 => env[1][1],
+            3 => 1,
+            _ => 0,
+        },
+        ..Default::default()
+    };
+    // shifts everything one column to the right
+    let shift = rule::EnvironmentRule {
+        environment_size: [0, 0, 0, 1],
+        row_boundary: rule::BoundaryBehaviour::Periodic,
+        col_boundary: rule::BoundaryBehaviour::Periodic,
+        cell_transform: |env| env[0][0],
+        ..Default::default()
+    };
+
+    let mut rng = rand::thread_rng();
+    let init = grid::Grid::from_vec((0..64 * 48).map(|_| rng.gen_range(0..2)).collect(), 48);
+
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_grid(init.clone())
+        .with_rule(life)
+        .build();
+    let mut multi_auto = crate::AutomatonBuilder::new()
+        .from_grid(init.clone())
+        .with_rule(life)
+        .with_rule(shift)
+        .with_rule(shift)
+        .build();
+    // the same game of life, summing up the neighborhoods with sliding windows
+    let mut totalistic_auto = crate::AutomatonBuilder::new()
+        .from_grid(init.clone())
+        .with_rule(rule::LifeLikeRule {
+            row_boundary: rule::BoundaryBehaviour::Periodic,
+            col_boundary: rule::BoundaryBehaviour::Symbol(0),
+            ..rule::LifeLikeRule::parse("B3/S23").unwrap()
+        })
+        .build();
+
+    // compare against allocating transformations
+    let mut expected = init.clone();
+    let mut multi_expected = init;
+    for _ in 0..10 {
+        auto.next_step();
+        multi_auto.next_step();
+        totalistic_auto.next_step();
+        rule::Rule::transform(&life, &mut expected);
+        for rule in [&life, &shift, &shift] {
+            rule::Rule::transform(rule, &mut multi_expected);
+        }
+        assert_eq!(auto.state, expected);
+        assert_eq!(multi_auto.state, multi_expected);
+        assert_eq!(totalistic_auto.state, expected);
+    }
+
+    // once the buffers exist, stepping does not allocate anymore
+    let allocations = count_allocations(0, || {
+        for _ in 0..10 {
+            auto.next_step();
+            multi_auto.next_step();
+            totalistic_auto.next_step();
+        }
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_double_buffer_test() {
+    /// The id this test counts allocations with.
+    const ID: usize = 1;
+
+    // the workers of this pool count their allocations while marked
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let mark = |id: Option<usize>| {
+        pool.broadcast(|_| COUNTED.with(|counted| counted.set(id)));
+    };
+    mark(Some(ID));
+    let allocations = count_allocations(ID, || {
+        pool.install(|| drop(std::hint::black_box(Box::new(0u64))));
+    });
+    assert!(allocations > 0);
+    mark(None);
+
+    // convolutions transform rows on the workers of the pool
+    let mut init = crate::CellGrid::new(64, 48);
+    init[20][20] = 255;
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_grid(init.clone())
+        .with_rule(rule::ConvolutionRule::box_blur(1))
+        .build();
+    let mut expected = init;
+    pool.install(|| {
+        for _ in 0..3 {
+            auto.next_step();
+            rule::Rule::transform(&rule::ConvolutionRule::box_blur(1), &mut expected);
+        }
+    });
+    assert_eq!(auto.state, expected);
+
+    // once the buffers exist, stepping does not allocate anymore, on any thread
+    mark(Some(ID));
+    let allocations = count_allocations(ID, || {
+        pool.install(|| {
+            for _ in 0..10 {
+                auto.next_step();
+            }
+        })
+    });
+    mark(None);
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn default_color_test() {
+    let pixel = |automaton: &Automaton, row: u32, col: u32| {
+        *automaton.create_image_buffer().get_pixel(col, row)
+    };
+
+    let builder = || {
+        crate::AutomatonBuilder::new()
+            .from_grid(grid::grid![[0, 1][200, 3]])
+            .with_color(1, [255, 0, 0, 255])
+            .with_color(200, [0, 0, 255, 255])
+    };
+
+    // without configuration, unmapped cells use the default color
+    let automaton = builder().build();
+    assert_eq!(pixel(&automaton, 0, 0).0, DEFAULT_COLOR);
+    assert_eq!(pixel(&automaton, 1, 1).0, DEFAULT_COLOR);
+
+    // otherwise the configured one, while mapped cells keep their colors
+    let mut automaton = builder().with_default_color([1, 2, 3, 4]).build();
+    assert_eq!(pixel(&automaton, 0, 0).0, [1, 2, 3, 4]);
+    assert_eq!(pixel(&automaton, 1, 1).0, [1, 2, 3, 4]);
+    assert_eq!(pixel(&automaton, 0, 1).0, [255, 0, 0, 255]);
+    assert_eq!(pixel(&automaton, 1, 0).0, [0, 0, 255, 255]);
+
+    // also after updating the cached image
+    automaton.set_cell(0, 1, 7).unwrap();
+    automaton.set_cell(1, 1, 1).unwrap();
+    assert_eq!(pixel(&automaton, 0, 1).0, [1, 2, 3, 4]);
+    assert_eq!(pixel(&automaton, 1, 1).0, [255, 0, 0, 255]);
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_test() {
+    use std::sync::{Arc, Mutex};
+    use tracing::{field, span, Event, Metadata};
+
+    /// The name and fields of all spans created while it is active.
+    type Spans = Arc<Mutex<Vec<(String, Vec<(String, String)>)>>>;
+
+    /// A subscriber that only records the spans created.
+    struct Capture(Spans);
+
+    /// Collects the fields of a span.
+    struct Fields(Vec<(String, String)>);
+
+    impl field::Visit for Fields {
+        fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    impl tracing::Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let mut fields = Fields(Vec::new());
+            span.record(&mut fields);
+            let mut spans = self.0.lock().unwrap();
+            spans.push((span.metadata().name().to_string(), fields.0));
+            span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[1, 0, 0][0, 0, 0]])
+        .with_rule(rule::EnvironmentRule::default())
+        .with_pattern(rule::Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0][1]],
+            ..Default::default()
+        })
+        .with_parallelism(crate::Parallelism::Sequential)
+        .build();
+
+    let spans = Spans::default();
+    tracing::subscriber::with_default(Capture(spans.clone()), || automaton.next_step());
+
+    let fields = |list: &[(&str, &str)]| {
+        list.iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect::<Vec<(String, String)>>()
+    };
+    // the environment rule clears the grid before the pattern rule runs
+    assert_eq!(
+        *spans.lock().unwrap(),
+        [
+            (
+                "next_step".to_string(),
+                fields(&[("generation", "0"), ("rows", "2"), ("cols", "3")])
+            ),
+            ("rule".to_string(), fields(&[("index", "0")])),
+            ("rule".to_string(), fields(&[("index", "1")])),
+            (
+                "scan".to_string(),
+                fields(&[("rows", "2"), ("cols", "3"), ("patterns", "1")])
+            ),
+            ("sort".to_string(), fields(&[("replacements", "0")])),
+            ("commit".to_string(), fields(&[("replacements", "0")])),
+        ]
+    );
+}
+
+#[test]
+fn generic_cell_test() {
+    /// Adds 300 to every cell, wrapping at 1000.
+    #[derive(Debug)]
+    struct CountRule;
+
+    impl rule::Rule<u16> for CountRule {
+        fn transform(&self, grid: &mut grid::Grid<u16>) {
+            for cell in grid.iter_mut() {
+                *cell = (*cell + 300) % 1000;
+            }
+        }
+    }
+
+    let mut automaton = crate::AutomatonBuilderGeneric::<u16>::new()
+        .from_vec(vec![0, 5, 700], 2)
+        .with_rule(CountRule)
+        .with_color(300, [1, 2, 3, 4])
+        .with_color(5, [5, 6, 7, 8])
+        .with_default_color([0; 4])
+        .build_checked()
+        .unwrap();
+    // the last row is padded with the default cell
+    assert_eq!(automaton.state(), &grid::grid![[0, 5][700, 0]]);
+
+    automaton.next_step();
+    assert_eq!(automaton.state(), &grid::grid![[300, 305][0, 300]]);
+    assert_eq!(automaton.get_cell(0, 1).unwrap(), 305);
+
+    // cells beyond 255 use the hash map, smaller ones the lookup table
+    automaton.set_cell(1, 0, 5).unwrap();
+    assert_eq!(
+        automaton.image_bytes(),
+        [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 1, 2, 3, 4]
+    );
+    assert_eq!(automaton.get_color(300), Some([1, 2, 3, 4]));
+    assert_eq!(automaton.get_color(305), None);
+}
+
+#[test]
+fn aux_layer_test() {
+    let aux = grid::grid![[0, 1, 2][3, 0, 1]];
+    let builder = || {
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0; 6], 3)
+            .with_aux_layer(aux.clone())
+            // every cell counts up by its value in the auxiliary layer, twice
+            .with_rule(rule::AuxEnvironmentRule {
+                environment_size: [0, 0, 0, 0],
+                cell_transform: |env, aux| env[0][0] + aux[0][0],
+                ..Default::default()
+            })
+            .with_rule(rule::AuxEnvironmentRule {
+                environment_size: [0, 0, 0, 0],
+                cell_transform: |env, aux| env[0][0] + aux[0][0],
+                ..Default::default()
+            })
+    };
+
+    for mut automaton in [builder().build(), builder().with_dirty_tracking().build()] {
+        for _ in 0..3 {
+            automaton.next_step();
+        }
+        // the auxiliary layer is consulted, but never changes
+        assert_eq!(automaton.state(), &grid::grid![[0, 6, 12][18, 0, 6]]);
+        assert_eq!(automaton.aux_layer(), Some(&aux));
+    }
+
+    // without an auxiliary layer, it reads as zeros
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1; 6], 3)
+        .with_rule(rule::AuxEnvironmentRule {
+            environment_size: [0, 0, 0, 0],
+            cell_transform: |env, aux| env[0][0] + aux[0][0],
+            ..Default::default()
+        })
+        .build();
+    automaton.next_step();
+    assert_eq!(automaton.state(), &grid::grid![[1, 1, 1][1, 1, 1]]);
+    assert_eq!(automaton.aux_layer(), None);
+}
+
+#[test]
+fn text_save_test() {
+    let automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[0, 1, 2, 36, 100][37, 0, 0, 62, 9][255, 3, 0, 0, 1]])
+        .build();
+
+    let path = std::env::temp_dir().join("cellumina_text_save_test.txt");
+    automaton.save(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let reloaded = crate::AutomatonBuilder::new()
+        .from_text_file(path.clone())
+        .with_strict_parsing()
+        .build();
+    std::fs::remove_file(path).unwrap();
+
+    // every row ends with a newline, which the loader does not turn into an additional row
+    assert_eq!(text, automaton.to_text_string());
+    assert_eq!(text.lines().count(), 3);
+    assert!(text.ends_with('\n'));
+    assert_eq!(reloaded.state(), automaton.state());
+}
+
+#[test]
+fn binary_save_test() {
+    let automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[0, 1, 2, 36, 100][37, 0, 0, 62, 9][255, 3, 0, 0, 1]])
+        .build();
+
+    // the extension chooses the binary format, which keeps symbols without a character
+    let path = std::env::temp_dir().join("cellumina_binary_save_test.cels");
+    automaton.save(&path).unwrap();
+    let reloaded = crate::AutomatonBuilder::new()
+        .from_binary_file(path.clone())
+        .build();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(reloaded.state(), automaton.state());
+
+    // compressed files are recognized by their content, not their extension
+    let path = std::env::temp_dir().join("cellumina_binary_save_test.celz");
+    let saved = automaton.save(&path);
+    if cfg!(feature = "compression") {
+        saved.unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(bytes.starts_with(b"CELZ"));
+        let reloaded = crate::AutomatonBuilder::new()
+            .from_binary_reader(std::io::Cursor::new(bytes))
+            .build();
+        assert_eq!(reloaded.state(), automaton.state());
+    } else {
+        assert!(matches!(saved, Err(CelluminaError::UnsupportedFormat(_))));
+    }
+}
+
+#[test]
+fn merge_test() {
+    let terrain = || {
+        crate::AutomatonBuilder::new()
+            .from_grid(grid::grid![[1, 1, 1][2, 2, 2]])
+            .with_color(1, [0, 0, 255, 255])
+            .with_color(2, [0, 255, 0, 255])
+            .build()
+    };
+    let vegetation = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[0, 5, 0][5, 0, 2]])
+        .with_color(2, [255, 0, 0, 255])
+        .with_color(5, [10, 200, 10, 255])
+        .build();
+
+    // the overlay wins unless it is transparent
+    let mut overlaid = terrain();
+    assert!(overlaid.overlay(&vegetation, 0).unwrap());
+    assert_eq!(overlaid.state(), &grid::grid![[1, 5, 1][5, 2, 2]]);
+    assert!(!overlaid.overlay(&vegetation, 0).unwrap());
+
+    // own colors take precedence, new colors are added and used for the image
+    assert_eq!(overlaid.get_color(2), Some([0, 255, 0, 255]));
+    assert_eq!(overlaid.get_color(5), Some([10, 200, 10, 255]));
+    assert_eq!(
+        overlaid.create_image_buffer().get_pixel(1, 0).0,
+        [10, 200, 10, 255]
+    );
+
+    // masks keep only the cells under the mask cell
+    let mut terrain = terrain();
+    assert!(terrain.mask(&vegetation, 5).unwrap());
+    assert_eq!(terrain.state(), &grid::grid![[0, 1, 0][2, 0, 0]]);
+
+    // arbitrary functions
+    assert!(terrain
+        .merge(&vegetation, |own, other| own + other)
+        .unwrap());
+    assert_eq!(terrain.state(), &grid::grid![[0, 6, 0][7, 0, 2]]);
+
+    // automata of different sizes can not be merged
+    let small = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[0, 0][0, 0]])
+        .build();
+    assert!(matches!(
+        terrain.overlay(&small, 0),
+        Err(CelluminaError::DimensionMismatch {
+            expected: (2, 3),
+            got: (2, 2)
+        })
+    ));
+    assert_eq!(terrain.state(), &grid::grid![[0, 6, 0][7, 0, 2]]);
+}
+
+#[test]
+fn sub_automaton_test() {
+    let mut state = crate::CellGrid::new(5, 8);
+    // a vertical blinker on the left, a block on the right
+    for row in 1..4 {
+        state[row][2] = 1;
+    }
+    for (row, col) in [(1, 5), (1, 6), (2, 5), (2, 6)] {
+        state[row][col] = 1;
+    }
+    let mut aux = crate::CellGrid::new(5, 8);
+    aux[2][2] = 7;
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(state)
+        .with_aux_layer(aux)
+        .with_rule(rule::LifeLikeRule::parse("B3/S23").unwrap())
+        .with_color(1, [255; 4])
+        .build();
+
+    // extracting regions, clipped at the edges if requested
+    assert_eq!(
+        automaton.extract(1, 4, 2, 3, false).unwrap(),
+        grid::grid![[0, 1, 1][0, 1, 1]]
+    );
+    assert_eq!(
+        automaton.extract(3, 6, 4, 4, true).unwrap(),
+        grid::grid![[0, 0][0, 0]]
+    );
+    assert_eq!(automaton.extract(9, 9, 2, 2, true).unwrap().size(), (0, 0));
+    assert!(matches!(
+        automaton.extract(3, 6, 4, 4, false),
+        Err(CelluminaError::IndexOutOfBoundsError(6, 9, 5, 8))
+    ));
+
+    // the sub-automaton contains the region, its auxiliary layer and colors
+    let mut blinker = automaton
+        .sub_automaton(&rule::Region {
+            rows: 0..5,
+            cols: 0..5,
+        })
+        .unwrap();
+    assert_eq!(
+        blinker.state(),
+        &automaton.extract(0, 0, 5, 5, false).unwrap()
+    );
+    assert_eq!(blinker.aux_layer().unwrap()[2][2], 7);
+    assert_eq!(blinker.get_color(1), Some([255; 4]));
+
+    // both evolve independently
+    blinker.next_step();
+    assert_eq!(blinker.generation(), 1);
+    assert_eq!(automaton.generation(), 0);
+    assert_eq!(
+        blinker.extract(1, 1, 3, 3, false).unwrap(),
+        grid::grid![[0, 0, 0][1, 1, 1][0, 0, 0]]
+    );
+    assert_eq!(automaton.state()[1][2], 1);
+    automaton.next_step();
+    assert_eq!(
+        automaton.extract(0, 0, 5, 5, false).unwrap(),
+        *blinker.state()
+    );
+
+    // regions have to fit into the state
+    assert!(automaton
+        .sub_automaton(&rule::Region {
+            rows: 2..6,
+            cols: 0..1,
+        })
+        .is_err());
+
+    // rules that can not be copied can not be used in sub-automata
+    #[derive(Debug)]
+    struct UniqueRule;
+
+    impl rule::Rule for UniqueRule {
+        fn transform(&self, _grid: &mut crate::CellGrid) {}
+    }
+
+    let unique = crate::AutomatonBuilder::new()
+        .from_grid(grid::grid![[1, 2][3, 4]])
+        .with_rule(UniqueRule)
+        .build();
+    assert!(matches!(
+        unique.sub_automaton(&rule::Region::full(1, 1)),
+        Err(CelluminaError::CustomError(_))
+    ));
+}
+
+#[test]
+fn remap_symbols_test() {
+    let automaton = || {
+        crate::AutomatonBuilder::new()
+            .from_grid(grid::grid![[1, 0, 2][0, 0, 0]])
+            .with_pattern(rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            })
+            .with_color(1, [255, 0, 0, 255])
+            .with_color(2, [0, 255, 0, 255])
+            .with_color(59, [0, 0, 255, 255])
+            .build()
+    };
+    let map = HashMap::from([(1, 59), (2, 41), (7, 8)]);
+
+    // the grid and colors are remapped, remapped colors win over existing ones
+    let mut remapped = automaton();
+    assert!(remapped.remap_symbols(&map, true));
+    assert_eq!(remapped.state(), &grid::grid![[59, 0, 41][0, 0, 0]]);
+    assert_eq!(remapped.get_color(59), Some([255, 0, 0, 255]));
+    assert_eq!(remapped.get_color(41), Some([0, 255, 0, 255]));
+    assert_eq!(remapped.get_color(1), None);
+    assert_eq!(remapped.get_color(2), None);
+    assert_eq!(
+        remapped.create_image_buffer().get_pixel(2, 0).0,
+        [0, 255, 0, 255]
+    );
+
+    // the patterns are remapped along with the grid
+    remapped.next_step();
+    assert_eq!(remapped.state(), &grid::grid![[0, 0, 41][59, 0, 0]]);
+
+    // without remapping the rule, the old patterns no longer match
+    let mut unchanged = automaton();
+    unchanged.remap_symbols(&map, false);
+    unchanged.next_step();
+    assert_eq!(unchanged.state(), &grid::grid![[59, 0, 41][0, 0, 0]]);
+
+    // remapping symbols that do not occur changes nothing
+    assert!(!unchanged.remap_symbols(&HashMap::from([(1, 2)]), true));
+}
+
+#[test]
+fn symbol_name_test() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        /// The messages logged on this thread.
+        static MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Collects log messages per thread, so tests running in parallel do not interfere.
+    struct Capture;
+
+    impl log::Log for Capture {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            MESSAGES.with(|messages| messages.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture;
+    let _ = log::set_logger(&CAPTURE);
+    log::set_max_level(log::LevelFilter::Info);
+
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 41, 36, 0], 2)
+        .with_color(41, [255, 80, 0, 255])
+        .with_color(59, [0, 0, 255, 255])
+        .with_symbol_name(41, "fire")
+        .with_symbol_name(36, "ash")
+        .with_symbol_name(1, "wall")
+        .build();
+
+    assert_eq!(auto.symbol_name(41), Some("fire"));
+    assert_eq!(auto.symbol_name(0), None);
+    assert_eq!(
+        auto.legend(),
+        [
+            (0, ' ', None, None),
+            (1, '1', Some("wall"), None),
+            (36, 'A', Some("ash"), None),
+            (41, 'F', Some("fire"), Some([255, 80, 0, 255])),
+            (59, 'X', None, Some([0, 0, 255, 255])),
+        ]
+    );
+
+    // names appear in log messages
+    MESSAGES.with(|messages| messages.borrow_mut().clear());
+    auto.set_cell(0, 0, 41).unwrap();
+    auto.set_cell(0, 1, 2).unwrap();
+    MESSAGES.with(|messages| {
+        assert_eq!(
+            *messages.borrow(),
+            [
+                "Manual cell set: Character 41 (fire) at (0, 0).",
+                "Manual cell set: Character 2 at (0, 1)."
+            ]
+        )
+    });
+
+    // names have to be unique
+    assert!(matches!(
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0], 1)
+            .with_symbol_name(1, "sand")
+            .with_symbol_name(2, "water")
+            .with_symbol_name(3, "sand")
+            .build_checked(),
+        Err(CelluminaError::ValidationError(issues))
+            if issues == [crate::ValidationIssue::DuplicateSymbolName { name: "sand".to_string() }]
+    ));
+}
+
+#[test]
+fn frozen_symbols_test() {
+    use crate::testing::assert_grid_eq;
+
+    // sand eating everything below it, resting on a wall
+    for dirty_tracking in [false, true] {
+        let mut builder = crate::AutomatonBuilder::new()
+            .from_grid(crate::testing::parse_grid(
+                "111
+                 000
+                 999
+                 000",
+            ))
+            .with_pattern(rule::Pattern {
+                before: grid::grid![[1][127]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            })
+            .with_frozen_symbols(&[9]);
+        if dirty_tracking {
+            builder = builder.with_dirty_tracking();
+        }
+        let mut auto = builder.build();
+        for _ in 0..5 {
+            auto.next_step();
+        }
+        assert_grid_eq(auto.state(), "000\n111\n999\n000");
+
+        // cells can still be changed manually
+        assert!(auto.set_cell(2, 1, 0).unwrap());
+        auto.next_step();
+        assert_grid_eq(auto.state(), "000\n101\n919\n000");
+        auto.next_step();
+        assert_grid_eq(auto.state(), "000\n101\n909\n010");
+    }
+
+    // a wall of dead cells in the game of life
+    let state = crate::testing::parse_grid(
+        "00000
+         01110
+         22222
+         00000",
+    );
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_grid(state)
+        .with_rule(rule::LifeLikeRule::parse("B3/S23").unwrap())
+        .with_frozen_symbols(&[2])
+        .build();
+    for _ in 0..4 {
+        auto.next_step();
+        assert!(auto.state().iter_row(2).all(|&cell| cell == 2));
+    }
+}
+
+#[test]
+fn render_filmstrip_test() {
+    let build = || {
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![1, 0, 1, 0, 0, 0, 1, 1, 0, 0, 0, 0], 4)
+            .with_pattern(rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                chance: 0.5,
+                ..Default::default()
+            })
+            .with_color(0, [10, 20, 30, 255])
+            .with_color(1, [200, 100, 0, 255])
+            .with_seed(9)
+            .build()
+    };
+    let auto = build();
+    let background = [1, 2, 3, 4];
+    let sheet = auto.render_filmstrip(5, 2, 3, 1, background).unwrap();
+    // two columns of 4 * 3 pixels and three rows of 3 * 3 pixels, with one pixel between them
+    assert_eq!(sheet.dimensions(), (25, 29));
+
+    // the original automaton remains unchanged
+    assert_eq!(auto.generation(), 0);
+    assert_eq!(auto.state(), build().state());
+
+    // each frame matches the state after the same number of steps
+    let mut reference = build();
+    for frame in 0..5 {
+        let image = reference.create_image_buffer();
+        let (x, y) = (frame % 2 * 13, frame / 2 * 10);
+        for row in 0..9 {
+            for col in 0..12 {
+                assert_eq!(
+                    sheet.get_pixel(x + col, y + row),
+                    image.get_pixel(col / 3, row / 3),
+                    "frame {frame}, pixel ({row}, {col})"
+                );
+            }
+        }
+        reference.next_step();
+    }
+
+    // gaps and the empty last slot are filled with the background
+    assert_eq!(sheet.get_pixel(12, 0).0, background);
+    assert_eq!(sheet.get_pixel(0, 9).0, background);
+    assert!((13..25)
+        .flat_map(|x| (20..29).map(move |y| (x, y)))
+        .all(|(x, y)| sheet.get_pixel(x, y).0 == background));
+
+    // nothing to render
+    assert_eq!(
+        auto.render_filmstrip(0, 2, 3, 1, background)
+            .unwrap()
+            .dimensions(),
+        (25, 0)
+    );
+}
+
+#[test]
+fn html_player_test() {
+    let auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+        .with_rule(rule::LifeLikeRule::parse("B3/S23").unwrap())
+        .with_color(1, [255, 255, 255, 255])
+        .build();
+
+    let path = std::env::temp_dir().join("cellumina_html_player_test.html");
+    auto.export_html_player(&path, 4, 8).unwrap();
+    let html = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    // the blinker alternates between two frames
+    let mut encoder = crate::player::FrameEncoder::new(3, 3);
+    let (vertical, horizontal) = (
+        auto.image_bytes().to_vec(),
+        image::imageops::crop_imm(
+            &auto.render_filmstrip(2, 2, 1, 0, [0; 4]).unwrap(),
+            3,
+            0,
+            3,
+            3,
+        )
+        .to_image()
+        .into_raw(),
+    );
+    for frame in [&vertical, &horizontal, &vertical, &horizontal] {
+        encoder.push(frame);
+    }
+    assert!(html.contains(&format!("const data = {};", encoder.to_json())));
+    assert!(html.contains("const scale = 8;"));
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert_eq!(auto.generation(), 0);
+}
+
+#[test]
+fn stamp_test() {
+    let background = || {
+        crate::AutomatonBuilder::new()
+            .from_vec((1..=16).collect(), 4)
+            .build()
+    };
+    // an L-shaped piece, with wildcards where the L is open
+    let piece = grid::grid![[9, 127][9, 127][9, 9]];
+
+    // at the center, the cells under the wildcards keep their content
+    let mut auto = background();
+    assert_eq!(auto.stamp(1, 1, &piece).len(), 4);
+    assert_eq!(
+        auto.state(),
+        &grid::grid![[1, 2, 3, 4][5, 9, 7, 8][9, 9, 11, 12][13, 9, 9, 16]]
+    );
+    assert!(auto.stamp(1, 1, &piece).is_empty());
+    assert_eq!(auto.erase_shape(1, 1, &piece).len(), 4);
+    assert_eq!(
+        auto.state(),
+        &grid::grid![[1, 2, 3, 4][5, 0, 7, 8][9, 0, 11, 12][13, 0, 0, 16]]
+    );
+
+    // straddling the bottom right corner, only the top of the L fits
+    let mut auto = background();
+    assert_eq!(auto.stamp(3, 3, &piece), vec![(3, 3, 9)]);
+    assert_eq!(auto.get_cell(3, 3).unwrap(), 9);
+
+    // straddling the top left corner, only the foot of the L fits, the wildcard above it is ignored
+    let mut auto = background();
+    assert_eq!(auto.stamp(-2, -1, &piece), vec![(0, 0, 9)]);
+    assert_eq!(auto.erase_shape(-2, -1, &piece), vec![(0, 0, 0)]);
+    assert_eq!(
+        auto.state().iter().copied().collect::<Vec<_>>(),
+        [0].into_iter().chain(2..=16).collect::<Vec<_>>()
+    );
+
+    // fully outside, nothing changes
+    assert!(auto.stamp(-5, 2, &piece).is_empty());
+    assert!(auto.stamp(2, 4, &piece).is_empty());
+}
+
+#[test]
+fn speed_multiplier_test() {
+    // counts the steps performed while 4 seconds pass in steps of 100ms
+    let steps = |multiplier: f32| {
+        let mut auto = crate::AutomatonBuilder::new()
+            .from_vec(vec![0; 4], 2)
+            .with_min_time_step(time::Duration::from_millis(800))
+            .build();
+        auto.set_speed_multiplier(multiplier).unwrap();
+        auto.next_step();
+        for _ in 0..40 {
+            auto.last_step = auto
+                .last_step
+                .and_then(|last| last.checked_sub(time::Duration::from_millis(100)));
+            auto.next_step();
+        }
+        auto.generation()
+    };
+    assert_eq!(steps(1.), 5);
+    assert_eq!(steps(2.), 10);
+    assert_eq!(steps(4.), 20);
+    assert_eq!(steps(0.5), 2);
+    // at most one step per call
+    assert_eq!(steps(1000.), 40);
+
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .build();
+    assert_eq!(auto.speed_multiplier(), 1.);
+    for invalid in [0., -2., f32::NAN, f32::INFINITY] {
+        assert!(matches!(
+            auto.set_speed_multiplier(invalid),
+            Err(CelluminaError::ValidationError(issues))
+                if issues == [crate::ValidationIssue::InvalidSpeedMultiplier]
+        ));
+    }
+    assert_eq!(auto.speed_multiplier(), 1.);
+    // tiny multipliers never step instead of overflowing
+    auto.set_speed_multiplier(f32::MIN_POSITIVE).unwrap();
+    auto.step_mode = StepMode::Limited {
+        interval: time::Duration::from_secs(1_000_000),
+    };
+    auto.next_step();
+    assert!(!auto.next_step());
+}
+
+#[test]
+fn rearrange_test() {
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 2, 3, 4, 5, 6], 3)
+        .with_aux_layer(grid::grid![[0, 0, 7][0, 0, 0]])
+        .with_color(1, [255; 4])
+        .with_dirty_tracking()
+        .with_rule(rule::ShiftRule {
+            dy: 0,
+            dx: 1,
+            fill: Some(0),
+        })
+        .build();
+
+    automaton.rotate_cw();
+    assert_eq!(automaton.state(), &grid::grid![[4, 1][5, 2][6, 3]]);
+    assert_eq!(automaton.dimensions(), (3, 2));
+    assert_eq!(automaton.aux_layer().unwrap()[2][1], 7);
+    assert_eq!(automaton.create_image_buffer().dimensions(), (2, 3));
+    assert_eq!(automaton.create_image_buffer().get_pixel(1, 0).0, [255; 4]);
+
+    automaton.flip_horizontal();
+    assert_eq!(automaton.state(), &grid::grid![[1, 4][2, 5][3, 6]]);
+    automaton.transpose();
+    assert_eq!(automaton.state(), &grid::grid![[1, 2, 3][4, 5, 6]]);
+    automaton.flip_vertical();
+    automaton.rotate_ccw();
+    assert_eq!(automaton.state(), &grid::grid![[6, 3][5, 2][4, 1]]);
+
+    // the rule works on the new dimensions, including the entire state with dirty tracking
+    automaton.next_step();
+    assert_eq!(automaton.state(), &grid::grid![[0, 6][0, 5][0, 4]]);
+}
+
+#[test]
+fn draw_border_test() {
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1; 12], 4)
+        .with_dirty_tracking()
+        .build();
+    automaton.dirty = None;
+    assert!(automaton.draw_border(1, 126));
+    assert_eq!(
+        automaton.state(),
+        &grid::grid![[126, 126, 126, 126][126, 1, 1, 126][126, 126, 126, 126]]
+    );
+    assert!(automaton.dirty.is_some());
+    assert!(!automaton.draw_border(1, 126));
+    // a border too thick for the state covers all of it
+    assert!(automaton.draw_border(2, 3));
+    assert!(automaton.state().iter().all(|&cell| cell == 3));
+}
+
+#[test]
+fn step_back_test() {
+    use crate::rule::ShiftRule;
+
+    let start = grid::grid![[1, 2, 3][4, 5, 6][7, 8, 9][10, 11, 12]];
+    // multiple rules are undone in reverse order
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(start.clone().into_vec(), 3)
+        .with_rule(ShiftRule {
+            dy: 1,
+            dx: 0,
+            fill: None,
+        })
+        .with_rule(ShiftRule {
+            dy: 0,
+            dx: -1,
+            fill: None,
+        })
+        .with_dirty_tracking()
+        .build();
+    assert!(matches!(
+        automaton.step_back(),
+        Err(crate::CelluminaError::InitialGeneration)
+    ));
+    for _ in 0..3 {
+        automaton.next_step();
+    }
+    assert_ne!(automaton.state(), &start);
+    for _ in 0..3 {
+        automaton.step_back().unwrap();
+    }
+    assert_eq!(automaton.state(), &start);
+    assert_eq!(automaton.generation(), 0);
+    assert!(automaton.step_back().is_err());
+
+    // a single irreversible rule makes the whole rule irreversible
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(start.clone().into_vec(), 3)
+        .with_rule(ShiftRule {
+            dy: 1,
+            dx: 0,
+            fill: None,
+        })
+        .with_rule(crate::rule::LifeLikeRule::parse("B3/S23").unwrap())
+        .build();
+    automaton.next_step();
+    let after = automaton.state().clone();
+    assert!(matches!(
+        automaton.step_back(),
+        Err(crate::CelluminaError::IrreversibleRule)
+    ));
+    assert_eq!(automaton.state(), &after);
+    assert_eq!(automaton.generation(), 1);
+}
+
+#[test]
+fn shared_test() {
+    let automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 0, 0, 1, 1, 1, 0, 0, 0], 3)
+        .with_preset(crate::rule::presets::game_of_life())
+        .build()
+        .into_shared();
+
+    // another thread reads the automaton while it is stepped, always seeing a complete step
+    let reader = automaton.clone();
+    let reads = std::thread::spawn(move || {
+        let mut generations = Vec::new();
+        loop {
+            let automaton = reader.read().unwrap();
+            let generation = automaton.generation();
+            let expected = if generation.is_multiple_of(2) {
+                grid::grid![[0, 0, 0][1, 1, 1][0, 0, 0]]
+            } else {
+                grid::grid![[0, 1, 0][0, 1, 0][0, 1, 0]]
+            };
+            assert_eq!(automaton.state(), &expected);
+            generations.push(generation);
+            if generation == 20 {
+                return generations;
+            }
+            drop(automaton);
+            std::thread::yield_now();
+        }
+    });
+
+    // a stepping loop like the one of the live view, locking once per step
+    for _ in 0..20 {
+        automaton.write().unwrap().next_step();
+        std::thread::yield_now();
+    }
+    let generations = reads.join().unwrap();
+    assert!(generations.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(automaton.read().unwrap().generation(), 20);
+}
+
+#[test]
+fn step_report_test() {
+    use crate::rule::{FnRule, RuleReport};
+
+    let report = |name: &str, cells_changed| RuleReport {
+        name: name.to_string(),
+        changed: cells_changed > 0,
+        cells_changed,
+    };
+
+    // two rules changing disjoint parts of the grid
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 12], 4)
+        .with_rule(FnRule::new("top", |grid: &mut crate::CellGrid| {
+            grid.iter_row_mut(0).for_each(|cell| *cell = 1)
+        }))
+        .with_rule(FnRule::new("corner", |grid: &mut crate::CellGrid| {
+            grid[2][2] = 2;
+            grid[2][3] = 2;
+        }))
+        .with_step_reports()
+        .with_dirty_tracking()
+        .build();
+    assert_eq!(automaton.last_step_report(), Some(&[][..]));
+    automaton.next_step();
+    assert_eq!(
+        automaton.last_step_report(),
+        Some(&[report("top", 4), report("corner", 2)][..])
+    );
+    automaton.next_step();
+    assert_eq!(
+        automaton.last_step_report(),
+        Some(&[report("top", 0), report("corner", 0)][..])
+    );
+
+    // a single rule is reported by its debug output, and reports are off by default
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+        .with_rule(crate::rule::ShiftRule {
+            dy: 0,
+            dx: 1,
+            fill: None,
+        })
+        .with_step_reports()
+        .build();
+    automaton.next_step();
+    assert_eq!(
+        automaton.last_step_report(),
+        Some(&[report("ShiftRule { dy: 0, dx: 1, fill: None }", 6)][..])
+    );
+    assert_eq!(
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0], 1)
+            .build()
+            .last_step_report(),
+        None
+    );
+}
+
+#[test]
+fn budget_test() {
+    use std::time::Duration;
+
+    let start = crate::testing::parse_grid(
+        "000900
+         009900
+         000990
+         000000
+         090000",
+    );
+    let build = || {
+        crate::AutomatonBuilder::new()
+            .from_grid(start.clone())
+            .with_rule(crate::rule::EnvironmentRule {
+                row_boundary: crate::rule::BoundaryBehaviour::Periodic,
+                col_boundary: crate::rule::BoundaryBehaviour::Periodic,
+                // every cell becomes the sum of the cells above and below it, modulo the number of symbols
+                cell_transform: |env| env[0][1].wrapping_add(env[2][1]) % 50,
+                ..Default::default()
+            })
+            .with_dirty_tracking()
+            .build()
+    };
+
+    // a step spread over one call per row matches a single-shot step
+    let mut single = build();
+    let mut resumed = build();
+    for generation in 1..=3 {
+        single.next_step();
+        for row in 1..5 {
+            assert_eq!(
+                resumed.next_step_with_budget(Duration::ZERO),
+                BudgetOutcome::InProgress {
+                    rows_done: row,
+                    rows: 5
+                }
+            );
+            // the state only changes once the step is complete
+            assert_eq!(resumed.generation(), generation - 1);
+        }
+        assert_eq!(
+            resumed.next_step_with_budget(Duration::ZERO),
+            BudgetOutcome::Complete
+        );
+        assert_eq!(resumed.state(), single.state());
+        assert_eq!(resumed.generation(), generation);
+        assert_eq!(resumed.dirty, single.dirty);
+    }
+
+    // with enough time, a step completes at once
+    assert_eq!(
+        resumed.next_step_with_budget(Duration::from_secs(60)),
+        BudgetOutcome::Complete
+    );
+    single.next_step();
+    assert_eq!(resumed.state(), single.state());
+
+    // changing the state starts the step over
+    resumed.next_step_with_budget(Duration::ZERO);
+    resumed.set_cell(0, 0, 3).unwrap();
+    assert_eq!(
+        resumed.next_step_with_budget(Duration::ZERO),
+        BudgetOutcome::InProgress {
+            rows_done: 1,
+            rows: 5
+        }
+    );
+
+    // pattern rules out of time commit what they found, other rules ignore the budget
+    let build = |rule: Box<dyn rule::Rule>| {
+        let mut state = vec![0; 64];
+        state[..32].fill(1);
+        crate::AutomatonBuilder::new()
+            .from_vec(state, 1)
+            .with_boxed_rule(rule)
+            .build()
+    };
+    let falling = rule::PatternRule::from_patterns(
+        &[rule::Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0][1]],
+            ..Default::default()
+        }],
+        rule::BoundaryBehaviour::Symbol(2),
+        rule::BoundaryBehaviour::Symbol(2),
+    );
+    let mut partial = build(Box::new(falling.clone()));
+    assert_eq!(
+        partial.next_step_with_budget(Duration::ZERO),
+        BudgetOutcome::Partial
+    );
+    assert_eq!(partial.generation(), 1);
+    let mut complete = build(Box::new(falling));
+    assert_eq!(
+        complete.next_step_with_budget(Duration::from_secs(60)),
+        BudgetOutcome::Complete
+    );
+    assert_eq!(complete.get_cell(32, 0).unwrap(), 1);
+    assert_eq!(complete.get_cell(31, 0).unwrap(), 0);
+    let mut shifted = build(Box::new(rule::ShiftRule {
+        dy: 1,
+        dx: 0,
+        fill: None,
+    }));
+    assert_eq!(
+        shifted.next_step_with_budget(Duration::ZERO),
+        BudgetOutcome::Complete
+    );
+    assert_eq!(shifted.get_cell(32, 0).unwrap(), 1);
+}
+
+#[test]
+fn toggle_test() {
+    use crate::rule::{FnRule, Pattern, Rule};
+
+    // fire (1) decays to ash (2), water (3) evaporates to steam (4)
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 3, 1, 3], 4)
+        .with_patterns(&[
+            Pattern {
+                before: grid::grid![[1]],
+                after: grid::grid![[2]],
+                ..Default::default()
+            },
+            Pattern {
+                before: grid::grid![[3]],
+                after: grid::grid![[4]],
+                ..Default::default()
+            },
+        ])
+        .with_dirty_tracking()
+        .build();
+    assert_eq!(
+        automaton.rule().toggles(),
+        vec![
+            ("pattern 0".to_string(), true),
+            ("pattern 1".to_string(), true)
+        ]
+    );
+    assert!(automaton.rule_mut().set_enabled(0, false));
+    assert!(!automaton.rule_mut().set_enabled(2, false));
+    automaton.next_step();
+    assert_eq!(
+        automaton.state().iter().copied().collect::<Vec<_>>(),
+        [1, 4, 1, 4]
+    );
+    // nothing changed during the last step, but the fire decays once enabled again
+    automaton.next_step();
+    automaton.rule_mut().set_enabled(0, true);
+    automaton.next_step();
+    assert_eq!(
+        automaton.state().iter().copied().collect::<Vec<_>>(),
+        [2, 4, 2, 4]
+    );
+
+    // rules of a multi rule are skipped entirely
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 2], 2)
+        .with_rule(FnRule::new("ignite", |grid: &mut crate::CellGrid| {
+            grid[0][0] = 1
+        }))
+        .with_rule(FnRule::new("decay", |grid: &mut crate::CellGrid| {
+            grid.iter_mut()
+                .filter(|cell| **cell == 1)
+                .for_each(|cell| *cell = 2)
+        }))
+        .build();
+    assert!(automaton.rule_mut().set_enabled(1, false));
+    assert_eq!(
+        automaton.rule().toggles(),
+        vec![("ignite".to_string(), true), ("decay".to_string(), false)]
+    );
+    automaton.next_step();
+    assert_eq!(automaton.get_cell(0, 0).unwrap(), 1);
+    automaton.rule_mut().set_enabled(1, true);
+    automaton.next_step();
+    assert_eq!(automaton.get_cell(0, 0).unwrap(), 2);
+    assert!(automaton.rule().clone_boxed().unwrap().toggles()[1].1);
+}
+
+#[test]
+fn substep_test() {
+    use crate::rule::{Rule, ShiftRule};
+
+    let shift = ShiftRule {
+        dy: 0,
+        dx: 1,
+        fill: None,
+    };
+    let initial = grid::grid![[1, 2, 3, 4, 5]];
+    let build = |generations: bool| {
+        let builder = crate::AutomatonBuilder::new()
+            .from_grid(initial.clone())
+            .with_rule(shift)
+            .with_substeps(3)
+            .with_stats_recorder(vec![1]);
+        if generations {
+            builder.with_substep_generations().build()
+        } else {
+            builder.build()
+        }
+    };
+
+    // one visible step equals three applications of the rule
+    let mut expected = initial.clone();
+    for _ in 0..3 {
+        shift.transform(&mut expected);
+    }
+    let mut automaton = build(false);
+    assert!(automaton.next_step());
+    assert_eq!(automaton.state(), &expected);
+    assert_eq!(automaton.generation(), 1);
+    // the recorder only saw the final state
+    assert_eq!(automaton.stats_recorder().unwrap().generations(), [0, 1]);
+    assert_eq!(automaton.stats_recorder().unwrap().series(1), [1, 1]);
+
+    // stepping back undoes all substeps
+    automaton.step_back().unwrap();
+    assert_eq!(automaton.state(), &initial);
+    assert_eq!(automaton.generation(), 0);
+
+    // optionally, every substep counts as a generation
+    let mut automaton = build(true);
+    automaton.next_step();
+    assert_eq!(automaton.state(), &expected);
+    assert_eq!(automaton.generation(), 3);
+    assert_eq!(automaton.stats_recorder().unwrap().generations(), [0, 3]);
+    automaton.step_back().unwrap();
+    assert_eq!(automaton.generation(), 0);
+    assert!(matches!(
+        automaton.step_back(),
+        Err(crate::CelluminaError::InitialGeneration)
+    ));
+}
+
+#[test]
+fn states_test() {
+    use rule::presets;
+
+    let build = || {
+        crate::AutomatonBuilder::new()
+            .from_vec([vec![59; 24], vec![0; 24]].concat(), 6)
+            .with_preset(presets::falling_sand())
+            .with_seed(5)
+            .with_min_time_step(time::Duration::from_secs(1000))
+            .build()
+    };
+
+    // the first item is the initial state, so the fifth step is at index 5, stepping despite the step mode
+    let mut manual = build();
+    let initial = manual.state().clone();
+    for _ in 0..5 {
+        manual.perform_step();
+    }
+    let states = build().states().take(6).collect::<Vec<_>>();
+    assert_eq!(states[0], initial);
+    assert_eq!(&states[5], manual.state());
+    assert_ne!(states[1], initial);
+
+    // the borrowing variant leaves the automaton at the last state taken
+    let mut auto = build();
+    assert_eq!(auto.states_ref().nth(5).as_ref(), Some(manual.state()));
+    assert_eq!(auto.generation(), 5);
+    assert_eq!(auto.state(), manual.state());
+
+    // hashes match for equal states and count generations
+    let mut auto = build();
+    let hashes = auto.states_hashed().take(6).collect::<Vec<_>>();
+    assert_eq!(
+        hashes
+            .iter()
+            .map(|&(_, generation)| generation)
+            .collect::<Vec<_>>(),
+        (0..6).collect::<Vec<_>>()
+    );
+    assert_eq!(hashes[5].0, hash_grid(manual.state()));
+    assert_eq!(hashes[0].0, hash_grid(&initial));
+    assert_ne!(hashes[0].0, hashes[1].0);
+
+    // a periodic rule returns to its initial state
+    let mut shift = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 0, 0], 3)
+        .with_rule(rule::ShiftRule {
+            dy: 0,
+            dx: 1,
+            fill: None,
+        })
+        .build();
+    let hashes = shift
+        .states_hashed()
+        .take(4)
+        .map(|(hash, _)| hash)
+        .collect::<Vec<_>>();
+    assert_eq!(hashes[0], hashes[3]);
+    assert_ne!(hashes[0], hashes[1]);
+}
+
+#[test]
+fn alpha_encoding_test() {
+    let build = || {
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0, 1, 2, 3, 4, 200], 3)
+            .with_color(0, [0, 0, 0, 255])
+            .with_color(1, [255, 255, 255, 255])
+            .with_color(2, [255, 255, 255, 255])
+            .with_color(3, [255, 0, 0, 255])
+            .with_color(4, [255, 0, 0, 255])
+    };
+
+    // two pairs of symbols share a color and one has none
+    let auto = build().build();
+    let loss = auto.image_loss();
+    assert_eq!(loss.len(), 3);
+    assert!(loss[0].contains("[200]"));
+    assert!(loss.iter().any(|loss| loss.contains("[1, 2]")));
+    assert!(loss.iter().any(|loss| loss.contains("[3, 4]")));
+    let path = std::env::temp_dir().join("cellumina_lossy_image_test.png");
+    auto.save(&path).unwrap();
+    let reloaded = build().from_image_file(path.clone()).build();
+    std::fs::remove_file(path).unwrap();
+    assert_ne!(reloaded.state(), auto.state());
+
+    // with alpha encoding, all symbols survive
+    let auto = build().with_alpha_encoding().build();
+    let path = std::env::temp_dir().join("cellumina_alpha_encoding_test.png");
+    auto.save(&path).unwrap();
+    let pixels = image::open(&path).unwrap().into_rgba8();
+    assert_eq!(pixels.get_pixel(2, 1).0[3], 200);
+    let reloaded = crate::AutomatonBuilder::new()
+        .from_image_file(path.clone())
+        .with_alpha_encoding()
+        .build();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(reloaded.state(), auto.state());
+
+    // the colors of distinct symbols are not lossy
+    let auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 1], 2)
+        .with_color(0, [0, 0, 0, 255])
+        .with_color(1, [255, 255, 255, 255])
+        .with_color(2, [255, 255, 255, 255])
+        .build();
+    assert!(auto.image_loss().is_empty());
+}
+
+#[test]
+fn resident_state_test() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Shifts the cells one column to the right, keeping its result to itself and counting uploads and read backs.
+    #[derive(Debug, Default)]
+    struct ResidentShift {
+        state: std::sync::Mutex<Option<crate::CellGrid>>,
+        uploads: Arc<AtomicUsize>,
+        read_backs: Arc<AtomicUsize>,
+    }
+
+    struct Kept(crate::CellGrid, Arc<AtomicUsize>);
+
+    impl rule::ResidentState for Kept {
+        fn size(&self) -> (usize, usize) {
+            self.0.size()
+        }
+
+        fn read_back(&self) -> crate::CellGrid {
+            self.1.fetch_add(1, Ordering::SeqCst);
+            self.0.clone()
+        }
+    }
+
+    impl rule::Rule for ResidentShift {
+        fn transform(&self, grid: &mut crate::CellGrid) {
+            rule::Rule::transform(
+                &rule::ShiftRule {
+                    dy: 0,
+                    dx: 1,
+                    fill: None,
+                },
+                grid,
+            );
+        }
+
+        fn transform_resident(
+            &self,
+            grid: Option<&crate::CellGrid>,
+        ) -> Option<Arc<dyn rule::ResidentState>> {
+            let mut state = self.state.lock().unwrap();
+            if let Some(grid) = grid {
+                self.uploads.fetch_add(1, Ordering::SeqCst);
+                *state = Some(grid.clone());
+            }
+            let state = state.as_mut().unwrap();
+            self.transform(state);
+            Some(Arc::new(Kept(state.clone(), self.read_backs.clone())))
+        }
+    }
+
+    let rule = ResidentShift::default();
+    let (uploads, read_backs) = (rule.uploads.clone(), rule.read_backs.clone());
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 0, 0, 0, 0], 5)
+        .with_rule(rule)
+        .build();
+
+    // consecutive steps neither upload nor read back the state, not even for its size
+    for _ in 0..3 {
+        auto.next_step();
+    }
+    assert_eq!(auto.state.size(), (1, 5));
+    assert_eq!(uploads.load(Ordering::SeqCst), 1);
+    assert_eq!(read_backs.load(Ordering::SeqCst), 0);
+
+    // reading the state reads it back once
+    assert_eq!(auto.state(), &grid::grid![[0, 0, 0, 1, 0]]);
+    assert_eq!(auto.create_image_buffer().dimensions(), (5, 1));
+    assert_eq!(read_backs.load(Ordering::SeqCst), 1);
+
+    // reading does not hand the state back, but changing it does
+    auto.next_step();
+    assert_eq!(uploads.load(Ordering::SeqCst), 1);
+    auto.set_cell(0, 0, 1).unwrap();
+    auto.next_step();
+    assert_eq!(uploads.load(Ordering::SeqCst), 2);
+    assert_eq!(auto.state(), &grid::grid![[1, 1, 0, 0, 0]]);
 }
@@ -0,0 +1,198 @@
+//! The binary state format of ```.cels``` and ```.celz``` files, storing the dimensions of a state and its raw cell bytes.
+//!
+//! A ```.cels``` file starts with the magic bytes ```CELS``` and a version byte, followed by the number of rows and columns as little endian [u32]s
+//! and one byte per cell in row-major order. A ```.celz``` file starts with the magic bytes ```CELZ```, followed by a complete ```.cels``` file compressed with zlib.
+//! Compressed files can only be written and read with the ```compression``` feature.
+
+use crate::{CellGrid, CelluminaError};
+
+/// The magic bytes at the start of uncompressed files.
+const RAW_MAGIC: &[u8; 4] = b"CELS";
+/// The magic bytes at the start of compressed files.
+const COMPRESSED_MAGIC: &[u8; 4] = b"CELZ";
+/// The version of the format written, increased whenever the layout changes.
+const VERSION: u8 = 1;
+/// The length of the header of uncompressed files: magic bytes, version, rows and columns.
+pub(crate) const HEADER_LEN: usize = 4 + 1 + 4 + 4;
+
+/// Turns a grid into the bytes of a ```.cels``` file, or a ```.celz``` file if ```compress``` is set.
+/// ## Error
+/// When compression is requested without the ```compression``` feature, or the grid has more than [u32::MAX] rows or columns.
+pub(crate) fn encode(grid: &CellGrid, compress: bool) -> Result<Vec<u8>, CelluminaError> {
+    let dimension = |size: usize| {
+        u32::try_from(size).map_err(|_| {
+            CelluminaError::InvalidBinary(format!("dimension {size} does not fit into 32 bits"))
+        })
+    };
+    let mut bytes = Vec::with_capacity(HEADER_LEN + grid.rows() * grid.cols());
+    bytes.extend_from_slice(RAW_MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&dimension(grid.rows())?.to_le_bytes());
+    bytes.extend_from_slice(&dimension(grid.cols())?.to_le_bytes());
+    bytes.extend_from_slice(grid.flatten());
+
+    if compress {
+        compress_bytes(bytes)
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// Turns the bytes of a ```.cels``` or ```.celz``` file back into a grid, detecting the compression from the magic bytes.
+/// ## Error
+/// When the bytes are not a state in this format, or compressed without the ```compression``` feature enabled.
+pub(crate) fn decode(bytes: &[u8]) -> Result<CellGrid, CelluminaError> {
+    if bytes.starts_with(COMPRESSED_MAGIC) {
+        // compressed files always contain an uncompressed file, never another compressed one
+        let raw = decompress_bytes(&bytes[COMPRESSED_MAGIC.len()..])?;
+        let (_, cols) = parse_header(&raw)?;
+        return Ok(grid::Grid::from_vec(raw[HEADER_LEN..].to_vec(), cols));
+    }
+    let (_, cols) = parse_header(bytes)?;
+    Ok(grid::Grid::from_vec(bytes[HEADER_LEN..].to_vec(), cols))
+}
+
+/// Checks the header of an uncompressed ```.cels``` file and that the file contains exactly the cells it announces.
+/// Returns the number of rows and columns. The cells start at [HEADER_LEN].
+/// ## Error
+/// When the bytes are not an uncompressed state in this format.
+pub(crate) fn parse_header(bytes: &[u8]) -> Result<(usize, usize), CelluminaError> {
+    if bytes.starts_with(COMPRESSED_MAGIC) {
+        return Err(CelluminaError::UnsupportedFormat(
+            "compressed binary state where an uncompressed one is required".to_string(),
+        ));
+    }
+    if !bytes.starts_with(RAW_MAGIC) {
+        return Err(CelluminaError::UnsupportedFormat(
+            "binary data without cellumina magic bytes".to_string(),
+        ));
+    }
+    if bytes.len() < HEADER_LEN {
+        return Err(CelluminaError::InvalidBinary(
+            "header is truncated".to_string(),
+        ));
+    }
+    if bytes[4] != VERSION {
+        return Err(CelluminaError::InvalidBinary(format!(
+            "unknown version {}",
+            bytes[4]
+        )));
+    }
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]) as usize
+    };
+    let (rows, cols) = (read_u32(5), read_u32(9));
+    let cells = &bytes[HEADER_LEN..];
+    if rows.checked_mul(cols) != Some(cells.len()) {
+        return Err(CelluminaError::InvalidBinary(format!(
+            "expected {rows}x{cols} cells, found {} bytes",
+            cells.len()
+        )));
+    }
+    Ok((rows, cols))
+}
+
+/// Compresses the bytes of an uncompressed file and prepends the magic bytes of compressed files.
+#[cfg(feature = "compression")]
+fn compress_bytes(bytes: Vec<u8>) -> Result<Vec<u8>, CelluminaError> {
+    use std::io::Write;
+
+    let mut encoder =
+        flate2::write::ZlibEncoder::new(COMPRESSED_MAGIC.to_vec(), flate2::Compression::default());
+    encoder.write_all(&bytes)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(not(feature = "compression"))]
+fn compress_bytes(_bytes: Vec<u8>) -> Result<Vec<u8>, CelluminaError> {
+    Err(CelluminaError::UnsupportedFormat(
+        "celz (requires the compression feature)".to_string(),
+    ))
+}
+
+/// Decompresses the content of a compressed file after its magic bytes.
+#[cfg(feature = "compression")]
+fn decompress_bytes(bytes: &[u8]) -> Result<Vec<u8>, CelluminaError> {
+    use std::io::Read;
+
+    let mut res = Vec::new();
+    flate2::read::ZlibDecoder::new(bytes).read_to_end(&mut res)?;
+    Ok(res)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress_bytes(_bytes: &[u8]) -> Result<Vec<u8>, CelluminaError> {
+    Err(CelluminaError::UnsupportedFormat(
+        "celz (requires the compression feature)".to_string(),
+    ))
+}
+
+#[test]
+fn binary_test() {
+    let grid = crate::testing::parse_grid(
+        "0120
+         1201
+         2A0z",
+    );
+
+    let bytes = encode(&grid, false).unwrap();
+    assert_eq!(&bytes[..5], b"CELS\x01");
+    assert_eq!(bytes.len(), HEADER_LEN + 12);
+    assert_eq!(decode(&bytes).unwrap(), grid);
+
+    let empty = grid::Grid::new(0, 0);
+    assert_eq!(decode(&encode(&empty, false).unwrap()).unwrap(), empty);
+
+    // broken files are rejected
+    assert!(matches!(
+        decode(b"P6 not a state"),
+        Err(CelluminaError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        decode(&bytes[..bytes.len() - 1]),
+        Err(CelluminaError::InvalidBinary(_))
+    ));
+    assert!(matches!(
+        decode(&bytes[..7]),
+        Err(CelluminaError::InvalidBinary(_))
+    ));
+    let mut future = bytes.clone();
+    future[4] = VERSION + 1;
+    assert!(matches!(
+        decode(&future),
+        Err(CelluminaError::InvalidBinary(_))
+    ));
+
+    #[cfg(not(feature = "compression"))]
+    assert!(matches!(
+        encode(&grid, true),
+        Err(CelluminaError::UnsupportedFormat(_))
+    ));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compressed_binary_test() {
+    // a low-entropy grid: sparse dots on an empty background
+    let mut grid = grid::Grid::new(256, 256);
+    for row in (0..256).step_by(16) {
+        grid[row][row] = 1;
+    }
+
+    let raw = encode(&grid, false).unwrap();
+    let compressed = encode(&grid, true).unwrap();
+    assert!(compressed.starts_with(COMPRESSED_MAGIC));
+    assert!(compressed.len() * 50 < raw.len());
+
+    // the compression is detected from the magic bytes
+    assert_eq!(decode(&compressed).unwrap(), grid);
+    assert_eq!(decode(&raw).unwrap(), grid);
+
+    // corrupted compressed data is an error, not a panic
+    assert!(decode(&compressed[..compressed.len() / 2]).is_err());
+}
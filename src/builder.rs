@@ -1,4 +1,4 @@
-use crate::{automaton, CellGrid};
+use crate::{automaton, Cell, CellGrid};
 use std::collections::HashMap;
 
 use crate::rule;
@@ -15,6 +15,7 @@ use crate::rule;
 ///     Additionaly, you can add [rule::Pattern]s that will be added to an internal [Pattern Rule](rule::PatternRule).
 ///     In the building process, this [Pattern Rule](rule::PatternRule) will be added to the collection of rules supplied in other ways and be treated equally.
 ///     Therefore, supplying only patterns will create an automaton with only a single [Pattern Rule](rule::PatternRule).
+///     Its boundaries are walls of [rule::BoundaryBehaviour::blocking_boundary()] unless changed with [Self::with_pattern_edge_behaviour].
 ///
 ///     Supplying Patters both by adding separate [Pattern Rule](rule::PatternRule)s and adding [rule::Pattern]s manually is not recommended, as this will create two PatternRules that need to be applied seperately and cannot be convoluted and parallelized.
 /// -   One or multiple color mappings. These allow the state to be displayed or be converted into an image.
@@ -24,32 +25,98 @@ use crate::rule;
 /// Finally, the [Self::build()] function will consume this builder to create a [cellular automaton](automaton::Automaton).
 ///
 /// If the created automaton is running on a fixed time step, it will not start counting until [automaton::Automaton::next_step] is called for the first time.
+///
+/// Automata with cells other than [u8] are built with an [AutomatonBuilderGeneric] of their [Cell] type.
+/// Text files and patterns are only available for [u8] cells.
 #[derive(Debug)]
-pub struct AutomatonBuilder {
+pub struct AutomatonBuilderGeneric<T: Cell> {
     pattern_rule: rule::PatternRule,
-    rules: Vec<Box<dyn rule::Rule>>,
-    source: InitSource,
-    colors: HashMap<u8, [u8; 4]>,
+    /// Turns the internal pattern rule into a rule for this builder's cells, set once the first pattern is added.
+    pattern_conversion: Option<PatternConversion<T>>,
+    rules: Vec<Box<dyn rule::Rule<T>>>,
+    /// The regions with rules of their own, turning all other rules into the rule for the remaining cells once the first region is added.
+    regions: rule::RegionRules<T>,
+    source: InitSource<T>,
+    aux_source: InitSource<u8>,
+    aux_colors: HashMap<u8, [u8; 4]>,
+    colors: HashMap<T, [u8; 4]>,
+    /// The colors of gradients, which colors set explicitly take precedence over.
+    gradient_colors: HashMap<T, [u8; 4]>,
     step_mode: automaton::StepMode,
+    dirty_tracking: bool,
+    seed: Option<u64>,
+    /// The random number generator passed to the rules, taking precedence over the seed.
+    rng: Option<Box<dyn crate::CellRng>>,
+    parallelism: crate::Parallelism,
+    strict_parsing: bool,
+    default_color: [u8; 4],
+    stats: Option<crate::StatsRecorder<T>>,
+    /// Wether to count the transitions between symbols from step to step.
+    transitions: bool,
+    names: HashMap<T, String>,
+    frozen: Vec<T>,
+    step_reports: bool,
+    /// The number of times the rule is applied per time step.
+    substeps: u32,
+    /// Wether each application of the rule counts as a generation.
+    count_substeps: bool,
+    /// The operations applied to the initial state, in order.
+    transforms: Vec<crate::gridops::Transform>,
+    /// The thickness and symbol of the border drawn around the initial state, if any.
+    border: Option<(u32, T)>,
+    /// Reads the initial state of images from their alpha channel instead of their colors, if set.
+    alpha_decoder: Option<ImageDecoder<T>>,
 }
 
+/// Builder struct for an [Automaton](automaton::Automaton) with [u8] cells, see [AutomatonBuilderGeneric].
+pub type AutomatonBuilder = AutomatonBuilderGeneric<u8>;
+
+/// Turns the patterns collected by a builder into a rule for its cells.
+type PatternConversion<T> = fn(rule::PatternRule) -> Box<dyn rule::Rule<T>>;
+
+/// Turns an image into a grid without using any colors.
+type ImageDecoder<T> = fn(&image::RgbaImage) -> grid::Grid<T>;
+
+/// Turns the content of a text file into a grid, rejecting unknown characters if the flag is set.
+type TextParser<T> = fn(&str, bool) -> Result<grid::Grid<T>, crate::CelluminaError>;
+
+/// Turns the content of a binary file into a grid.
+type BinaryParser<T> = fn(&[u8]) -> Result<grid::Grid<T>, crate::CelluminaError>;
+
 /// Represents one of multiple ways a grid can be initialized.
-enum InitSource {
+enum InitSource<T: Cell> {
     /// No initial source, will result in an empty grid.
     None,
-    /// Initializes the character grid from the lines of a text file.
-    TextFile(Box<dyn AsRef<std::path::Path>>),
+    /// Initializes the character grid from the lines of a text file, parsing them with the passed function.
+    TextFile(Box<dyn AsRef<std::path::Path>>, TextParser<T>),
+    /// Initializes the character grid from text read from a reader, parsing it with the passed function.
+    TextReader(Box<dyn std::io::Read>, TextParser<T>),
+    /// Initializes the character grid from the bytes of a binary file, parsing them with the passed function.
+    BinaryFile(Box<dyn AsRef<std::path::Path>>, BinaryParser<T>),
+    /// Initializes the character grid from bytes read from a reader, parsing them with the passed function.
+    BinaryReader(Box<dyn std::io::Read>, BinaryParser<T>),
     /// Initializes the character grid from an image file.
     ImageFile(Box<dyn AsRef<std::path::Path>>),
+    /// Initializes the character grid from an image read from a reader, in the passed format or a format guessed from its content.
+    ImageReader(Box<dyn std::io::Read>, Option<image::ImageFormat>),
     /// Initializes the character grid directly from an already loaded image buffer.
     ImageBuffer(image::ImageBuffer<image::Rgba<u8>, Vec<u8>>),
     /// Directly receives a file grid and passes it on.
-    Grid(CellGrid),
+    Grid(grid::Grid<T>),
+    /// Initializes the character grid from a vector, filling rows of the passed length one after another.
+    Vec(Vec<T>, usize),
 }
 
-impl InitSource {
+impl<T: Cell> InitSource<T> {
     /// Turns an init source into a fully initialized CellGrid.
-    fn create_grid(self, colors: &HashMap<u8, [u8; 4]>) -> Result<CellGrid, crate::CelluminaError> {
+    /// With ```strict``` parsing, unknown characters in text files cause an error.
+    /// With a ```decoder```, images are turned into grids by the decoder instead of by their colors.
+    fn create_grid(
+        self,
+        colors: &HashMap<T, [u8; 4]>,
+        strict: bool,
+        decoder: Option<ImageDecoder<T>>,
+    ) -> Result<grid::Grid<T>, crate::CelluminaError> {
         match self {
             // No source -> empty grid
             InitSource::None => Err(crate::CelluminaError::CustomError(
@@ -57,101 +124,234 @@ impl InitSource {
             )),
             // Grid -> Directly return it back
             InitSource::Grid(grid) => Ok(grid),
-            InitSource::TextFile(path) => {
-                log::info!("Initializing automaton state from text file.");
-                // read file
-                let content = std::fs::read_to_string(path.as_ref())?;
-                // split into lines
-                let lines: Vec<&str> = content.split('\n').collect();
-                // get number of columns (chars in largest line)
-                // subtracting one from each line because of leftover newline
-                let cols = lines
-                    .iter()
-                    .map(|line| line.len().saturating_sub(1))
-                    .max()
-                    .unwrap_or_default();
-
-                // create grid to hold data
-                let mut grid = grid::Grid::<u8>::new(0, cols);
-
-                // iterate over lines and add them to the grid
-                for line in lines {
-                    // create char vector
-                    let mut chars: Vec<u8> = line
-                        .replace('\r', "")
-                        .chars()
-                        .map(crate::char_to_id)
-                        .collect();
-                    // make sure vector is neither to large nor to small
-                    chars.resize(cols, 0);
-                    // push to the grid
-                    grid.push_row(chars);
+            InitSource::Vec(mut vec, cols) => {
+                if cols == 0 {
+                    return Err(crate::CelluminaError::ValidationError(vec![
+                        crate::ValidationIssue::ZeroColumns,
+                    ]));
                 }
-
-                Ok(grid)
+                // pad the last row with empty cells
+                vec.resize(vec.len().div_ceil(cols) * cols, T::default());
+                Ok(grid::Grid::from_vec(vec, cols))
+            }
+            InitSource::TextFile(path, parse) => {
+                log::info!("Initializing automaton state from text file.");
+                Self::TextReader(Box::new(std::fs::File::open(path.as_ref())?), parse)
+                    .create_grid(colors, strict, decoder)
+            }
+            InitSource::TextReader(mut reader, parse) => {
+                let mut content = String::new();
+                reader.read_to_string(&mut content)?;
+                parse(&content, strict)
+            }
+            InitSource::BinaryFile(path, parse) => {
+                log::info!("Initializing automaton state from binary file.");
+                Self::BinaryReader(Box::new(std::fs::File::open(path.as_ref())?), parse)
+                    .create_grid(colors, strict, decoder)
+            }
+            InitSource::BinaryReader(mut reader, parse) => {
+                let mut content = Vec::new();
+                reader.read_to_end(&mut content)?;
+                parse(&content)
             }
             InitSource::ImageBuffer(buffer) => {
                 log::info!("Initializing automaton state from image buffer.");
-                let mut grid = grid::Grid::new(
-                    buffer.dimensions().1 as usize,
-                    buffer.dimensions().0 as usize,
-                );
-
-                for row in 0..grid.rows() {
-                    for col in 0..grid.cols() {
-                        grid[row][col] = colors
-                            .iter()
-                            .find_map(|(key, value)| {
-                                if value == &buffer.get_pixel(col as u32, row as u32).0 {
-                                    Some(key)
-                                } else {
-                                    None
-                                }
-                            })
-                            .copied()
-                            .unwrap_or(0)
-                    }
+                if let Some(decode) = decoder {
+                    return Ok(decode(&buffer));
+                }
+                let (grid, unmatched) = grid_from_image(&buffer, colors)?;
+                if unmatched > 0 {
+                    log::warn!(
+                        "{unmatched} of {} pixels did not match any color and were set to {:?}.",
+                        grid.rows() * grid.cols(),
+                        T::default()
+                    );
                 }
-
                 Ok(grid)
             }
-            InitSource::ImageFile(path) => Self::ImageBuffer(
-                image::io::Reader::open(path.as_ref())?
-                    .decode()?
-                    .into_rgba8(),
+            InitSource::ImageFile(path) => Self::ImageReader(
+                Box::new(std::fs::File::open(path.as_ref())?),
+                image::ImageFormat::from_path(path.as_ref()).ok(),
             )
-            .create_grid(colors),
+            .create_grid(colors, strict, decoder),
+            InitSource::ImageReader(mut reader, format) => {
+                // decoders need to seek, which readers such as stdin can not
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                let mut image = image::io::Reader::new(std::io::Cursor::new(bytes));
+                image = match format {
+                    Some(format) => {
+                        image.set_format(format);
+                        image
+                    }
+                    None => image.with_guessed_format()?,
+                };
+                Self::ImageBuffer(image.decode()?.into_rgba8()).create_grid(colors, strict, decoder)
+            }
+        }
+    }
+}
+
+/// Turns a text into a grid with one row per line and as many columns as the longest line has characters.
+/// Shorter lines are padded with zeros, blank lines become empty rows, but a final newline does not create an additional row.
+/// Unknown characters cause an error in ```strict``` mode and are replaced with zeros otherwise.
+pub(crate) fn grid_from_text(text: &str, strict: bool) -> Result<CellGrid, crate::CelluminaError> {
+    // lines() strips '\n' and '\r\n' and ignores a single trailing newline
+    let lines = text
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect::<Vec<&str>>();
+
+    // get number of columns (chars in largest line)
+    let cols = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    // create grid to hold data
+    let mut grid = grid::Grid::<u8>::new(0, cols);
+
+    // iterate over lines and add them to the grid
+    let mut substituted = 0;
+    for (index, line) in lines.into_iter().enumerate() {
+        let mut chars = crate::parse_line(
+            line,
+            crate::SourceKind::State,
+            index + 1,
+            strict,
+            &mut substituted,
+        )?;
+        // make sure the row is filled up to full length
+        chars.resize(cols, 0);
+        grid.push_row(chars);
+    }
+
+    if substituted > 0 {
+        log::warn!("Replaced {substituted} unknown characters with empty cells.");
+    }
+
+    Ok(grid)
+}
+
+/// Turns CSV text into a grid with one row per line and one cell per comma-separated value, padding shorter rows with zeros.
+/// Values that are not numbers from 0 to 255 cause an error in ```strict``` mode and are replaced with zeros otherwise.
+fn grid_from_csv(text: &str, strict: bool) -> Result<CellGrid, crate::CelluminaError> {
+    let mut substituted = 0;
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let mut row = Vec::new();
+        let mut column = 1;
+        for value in line.split(',') {
+            let trimmed = value.trim();
+            match trimmed.parse::<u8>() {
+                Ok(cell) => row.push(cell),
+                Err(_) if trimmed.is_empty() => row.push(0),
+                Err(err) if strict => {
+                    return Err(crate::CelluminaError::ParseError {
+                        source_kind: crate::SourceKind::State,
+                        line: index + 1,
+                        column,
+                        message: format!("invalid cell value {trimmed:?}: {err}"),
+                    })
+                }
+                Err(_) => {
+                    substituted += 1;
+                    row.push(0);
+                }
+            }
+            column += value.chars().count() + 1;
         }
+        rows.push(row);
+    }
+
+    if substituted > 0 {
+        log::warn!("Replaced {substituted} invalid values with empty cells.");
+    }
+
+    let cols = rows.iter().map(Vec::len).max().unwrap_or_default();
+    let mut grid = grid::Grid::<u8>::new(0, cols);
+    for mut row in rows {
+        row.resize(cols, 0);
+        grid.push_row(row);
+    }
+    Ok(grid)
+}
+
+/// Turns an image into a grid by finding the cell of each pixel's color.
+/// Returns the grid and the number of pixels that did not match any color and were set to the default cell.
+///
+/// Without any colors, no pixel could match, so this is reported as an error.
+fn grid_from_image<T: Cell>(
+    buffer: &image::RgbaImage,
+    colors: &HashMap<T, [u8; 4]>,
+) -> Result<(grid::Grid<T>, usize), crate::CelluminaError> {
+    if colors.is_empty() {
+        return Err(crate::CelluminaError::ValidationError(vec![
+            crate::ValidationIssue::EmptyPalette,
+        ]));
     }
+
+    Ok(crate::convert::image_to_grid_counted(buffer, colors, 0))
 }
 
-impl std::fmt::Debug for InitSource {
+impl<T: Cell> std::fmt::Debug for InitSource<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::None => write!(f, "None"),
-            Self::TextFile(arg0) => f
+            Self::TextFile(arg0, _) => f
                 .debug_tuple("TextFile")
                 .field(&(*arg0.as_ref()).as_ref().to_str())
                 .finish(),
+            Self::TextReader(_, _) => f.debug_tuple("TextReader").finish(),
+            Self::BinaryFile(arg0, _) => f
+                .debug_tuple("BinaryFile")
+                .field(&(*arg0.as_ref()).as_ref().to_str())
+                .finish(),
+            Self::BinaryReader(_, _) => f.debug_tuple("BinaryReader").finish(),
             Self::ImageFile(arg0) => f
                 .debug_tuple("ImageFile")
                 .field(&(*arg0.as_ref()).as_ref().to_str())
                 .finish(),
+            Self::ImageReader(_, arg1) => f.debug_tuple("ImageReader").field(arg1).finish(),
             Self::ImageBuffer(arg0) => f.debug_tuple("ImageBuffer").field(arg0).finish(),
             Self::Grid(arg0) => f.debug_tuple("Grid").field(arg0).finish(),
+            Self::Vec(arg0, arg1) => f.debug_tuple("Vec").field(arg0).field(arg1).finish(),
         }
     }
 }
 
-impl AutomatonBuilder {
-    /// Create a new [AutomatonBuilder] with no rules, state or time interval.
+impl<T: Cell> AutomatonBuilderGeneric<T> {
+    /// Create a new builder with no rules, state or time interval.
     pub fn new() -> Self {
         Self {
             pattern_rule: rule::PatternRule::new_empty(),
+            pattern_conversion: None,
             rules: Vec::new(),
+            regions: rule::RegionRules::new(),
             source: InitSource::None,
+            aux_source: InitSource::None,
+            aux_colors: HashMap::new(),
             colors: HashMap::new(),
+            gradient_colors: HashMap::new(),
             step_mode: automaton::StepMode::Immediate,
+            dirty_tracking: false,
+            seed: None,
+            rng: None,
+            parallelism: Default::default(),
+            strict_parsing: false,
+            default_color: automaton::DEFAULT_COLOR,
+            stats: None,
+            transitions: false,
+            names: HashMap::new(),
+            frozen: Vec::new(),
+            step_reports: false,
+            substeps: 1,
+            count_substeps: false,
+            transforms: Vec::new(),
+            border: None,
+            alpha_decoder: None,
         }
     }
 
@@ -167,26 +367,131 @@ impl AutomatonBuilder {
         self
     }
 
-    /// Use a text file to supply the initial state of the automaton.
+    /// Apply the rule ```substeps``` times per time step, for rules that need several iterations per visible step, such as relaxing pressure.
+    /// Values below one are treated as one.
     ///
-    /// The automaton will have as many rows as the file has lines, and as many columns as the longest line in the file is long.
-    /// Will strip newlines.
-    pub fn from_text_file(mut self, path: impl AsRef<std::path::Path> + 'static) -> Self {
-        self.source = InitSource::TextFile(Box::new(path));
+    /// The intermediate states are never seen outside of the automaton: [stats](Self::with_stats_recorder), [transitions](Self::with_transition_tracker),
+    /// images and the live view only see the state after the last substep, and each time step counts as a single generation,
+    /// unless [Self::with_substep_generations] is used. [Automaton::step_back](automaton::Automaton::step_back) undoes all substeps of a time step.
+    /// Steps performed with [Automaton::next_step_with_budget](automaton::Automaton::next_step_with_budget) only apply the rule once.
+    pub fn with_substeps(mut self, substeps: u32) -> Self {
+        self.substeps = substeps.max(1);
+        self
+    }
+
+    /// Count each application of the rule as a generation when using [Self::with_substeps], so the generation advances by the number of substeps each time step.
+    /// By default, the generation counts visible time steps.
+    pub fn with_substep_generations(mut self) -> Self {
+        self.count_substeps = true;
+        self
+    }
+
+    /// Enable dirty tracking for the automaton.
+    ///
+    /// With dirty tracking, the automaton remembers the region of cells that changed during the last step (or were set manually) and only re-evaluates the area around this region during the next step.
+    /// If only a small part of the state is active, such as a falling sand simulation that has mostly settled, this can considerably speed up each step.
+    ///
+    /// This is only valid for local, deterministic rules: A cell may only change if something within the [reach](rule::Rule::reach) of the rule changed.
+    /// Rules that do not report a reach are always applied to the entire grid.
+    /// Patterns with a ```chance``` below 1 may however behave differently, as patterns that failed their chance roll are not retried in regions that did not change.
+    pub fn with_dirty_tracking(mut self) -> Self {
+        self.dirty_tracking = true;
+        self
+    }
+
+    /// Record the number of cells of each of the passed symbols in every generation, starting with the initial state.
+    ///
+    /// The counts of the last [DEFAULT_STATS_CAPACITY](crate::DEFAULT_STATS_CAPACITY) generations can be read using [Automaton::stats_recorder](automaton::Automaton::stats_recorder), for example to plot population curves or export them as CSV.
+    pub fn with_stats_recorder(mut self, symbols: Vec<T>) -> Self {
+        self.stats = Some(crate::StatsRecorder::new(symbols));
+        self
+    }
+
+    /// Count how many cells change from which symbol to which other symbol in every step, including changes made between steps.
+    ///
+    /// The counts can be read using [Automaton::transition_tracker](automaton::Automaton::transition_tracker), for example to find the most frequent transitions or export them as CSV.
+    pub fn with_transition_tracker(mut self) -> Self {
+        self.transitions = true;
+        self
+    }
+
+    /// Record which rule changed how many cells during each step, readable with [Automaton::last_step_report](automaton::Automaton::last_step_report) after the step, for example to find out which rule of a [MultiRule](rule::MultiRule) misbehaves.
+    ///
+    /// Reports are meant for debugging: Each rule compares the grid before and after its application, and dirty tracking no longer restricts steps to the changed region.
+    /// The reports are taken before cells of [frozen symbols](Self::with_frozen_symbols) are restored.
+    pub fn with_step_reports(mut self) -> Self {
+        self.step_reports = true;
+        self
+    }
+
+    /// Protect cells containing any of the passed symbols, such as walls, from being changed by rules.
+    ///
+    /// [Pattern rules](rule::PatternRule) skip replacements that would overwrite a frozen symbol, so nothing moves into a wall.
+    /// Cells of other rules are set back to their frozen symbol after each step, so these rules need not take care of them.
+    /// Custom rules moving cells around should skip such changes as well, see [Rule::set_frozen_symbols](rule::Rule::set_frozen_symbols), as anything they move onto a frozen cell is lost when it is restored.
+    /// Cells can still be changed manually, for example using [Automaton::set_cell](automaton::Automaton::set_cell).
+    pub fn with_frozen_symbols(mut self, symbols: &[T]) -> Self {
+        self.frozen.extend_from_slice(symbols);
+        self
+    }
+
+    /// Seed the random number generators of all rules of the automaton, making its evolution reproducible.
+    ///
+    /// Without a seed, rules using randomness, such as patterns with a ```chance``` below 1 or competing patterns of equal priority, are seeded from entropy.
+    /// Note that seeded results are only reproducible within the same version of cellumina, as changes to a rule may change the way it consumes random numbers.
+    ///
+    /// The automaton does not hold a generator of its own that is passed to the rules during each step.
+    /// Instead, when building the automaton, each rule is seeded once with a seed derived from this one, see [Rule::reseed](crate::rule::Rule::reseed), and keeps drawing from its own generator for all following steps.
+    /// The numbers drawn by one rule therefore do not depend on the other rules, and the same seed, rules and initial state always result in the same evolution.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Supply the random number generator used by all rules of the automaton, see [CellRng](crate::CellRng).
+    ///
+    /// Rules consisting of multiple rules pass a [fork](crate::CellRng::fork) of the generator to each of them. A generator takes precedence over a [seed](Self::with_seed).
+    pub fn with_rng(mut self, rng: impl crate::CellRng + 'static) -> Self {
+        self.rng = Some(Box::new(rng));
+        self
+    }
+
+    /// Set how the automaton distributes the work of applying its rules across threads.
+    ///
+    /// By default, rules use all cores via the global [rayon](https://github.com/rayon-rs/rayon) thread pool.
+    /// [Parallelism::Threads](crate::Parallelism::Threads) limits the automaton to a dedicated pool of threads, while [Parallelism::Sequential](crate::Parallelism::Sequential) avoids the overhead of parallelism for small grids.
+    /// Seeded results do not depend on this setting.
+    pub fn with_parallelism(mut self, parallelism: crate::Parallelism) -> Self {
+        self.parallelism = parallelism;
         self
     }
 
     /// Use an image file to supply the initial state of the automaton.
     ///
     /// The automatons dimensions (rows, columns) will be equal to the image dimensions (height, width).
+    /// Each pixel becomes the cell of its color as set with [Self::with_color()], so at least one color is required. Pixels matching no color become the default cell, 0 for [u8].
     pub fn from_image_file(mut self, path: impl AsRef<std::path::Path> + 'static) -> Self {
         self.source = InitSource::ImageFile(Box::new(path));
         self
     }
 
+    /// Use an image read from the passed reader, such as [std::io::stdin], to supply the initial state of the automaton, see [Self::from_image_file()].
+    ///
+    /// The image is decoded in the passed format, or in the format guessed from its content if [None] is passed.
+    /// The reader is only read when the automaton is built.
+    pub fn from_image_reader(
+        mut self,
+        reader: impl std::io::Read + 'static,
+        format: Option<image::ImageFormat>,
+    ) -> Self {
+        self.source = InitSource::ImageReader(Box::new(reader), format);
+        self
+    }
+
     /// Use an image buffer to supply the initial state of the automaton.
     ///
     /// The automatons dimensions (rows, columns) will be equal to the image dimensions (height, width).
+    /// Each pixel becomes the cell of its color as set with [Self::with_color()], so at least one color is required. Pixels matching no color become the default cell, 0 for [u8].
     pub fn from_image_buffer(
         mut self,
         buffer: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
@@ -195,117 +500,623 @@ impl AutomatonBuilder {
         self
     }
 
-    /// Use an already prepared [CellGrid] as the initial state of the automaton.
+    /// Use an already prepared grid, such as a [CellGrid], as the initial state of the automaton.
     ///
     /// The automatons dimensions will be the dimensions of the grid.
-    pub fn from_grid(mut self, grid: CellGrid) -> Self {
+    pub fn from_grid(mut self, grid: grid::Grid<T>) -> Self {
         self.source = InitSource::Grid(grid);
         self
     }
 
+    /// Use a two-dimensional array as the initial state of the automaton, with axis 0 of the array as rows and axis 1 as columns.
+    ///
+    /// The automatons dimensions will be the dimensions of the array.
+    #[cfg(feature = "ndarray")]
+    pub fn from_ndarray(self, array: ndarray::ArrayView2<T>) -> Self {
+        self.from_grid(crate::convert::ndarray_to_grid(array))
+    }
+
     /// Use a vector to supply the initial state of the automaton.
     /// The automaton will have as many columns as specified and as many rows as the vector can fill, ```ceil(vec.len() / columns)``` many.
-    /// If the vector can't fully fill the last row, it will be padded with spaces.
-    pub fn from_vec(mut self, mut vec: Vec<u8>, columns: u32) -> Self {
-        vec.resize(
-            vec.len().checked_div(columns as usize).unwrap_or(0) * (columns as usize),
-            0,
-        );
-        self.source = InitSource::Grid(grid::Grid::from_vec(vec, columns as usize));
+    /// If the vector can't fully fill the last row, it will be padded with empty cells (zeros for [u8]).
+    /// Passing zero columns is an error, causing the build process to fall back to an empty grid.
+    pub fn from_vec(mut self, vec: Vec<T>, columns: u32) -> Self {
+        self.source = InitSource::Vec(vec, columns as usize);
         self
     }
 
-    /// Adds a [Pattern](rule::Pattern) to this automaton that will be used for replacement each step.
-    pub fn with_pattern(mut self, pattern: rule::Pattern) -> Self {
-        self.pattern_rule.patterns.push(pattern);
+    /// Adds a rule to this automaton.
+    ///
+    /// Adding multiple rules will combine them into a single [MultiRule](rule::MultiRule) on construction.
+    ///
+    /// It is not suggested to use this function to add a [Pattern Rule](rule::PatternRule) and instead use [Self::with_pattern] or [Self::with_patterns].
+    /// Only use this to add a [Pattern Rule](rule::PatternRule) when you have already constructed it elsewhere or reuse the same [Pattern Rule](rule::PatternRule) for multiple automata.
+    pub fn with_rule(mut self, rule: impl rule::Rule<T> + 'static) -> Self {
+        self.rules.push(Box::new(rule));
         self
     }
 
-    /// Adds multiple [Patterns](rule::Pattern) to this automaton that will be used for replacement each step.
-    pub fn with_patterns(mut self, patterns: &[rule::Pattern]) -> Self {
-        self.pattern_rule.patterns.extend(patterns.iter().cloned());
+    /// Adds an already boxed rule to this automaton, like [Self::with_rule], such as a rule chosen at runtime.
+    /// ```
+    /// # use cellumina::rule::{FnRule, LifeLikeRule, Rule};
+    /// let name = "life";
+    /// let rule: Box<dyn Rule> = match name {
+    ///     "life" => Box::new(LifeLikeRule::parse("B3/S23").unwrap()),
+    ///     _ => Box::new(FnRule::new("identity", |_: &mut cellumina::CellGrid| {})),
+    /// };
+    /// let auto = cellumina::AutomatonBuilder::new().with_boxed_rule(rule).build();
+    /// ```
+    pub fn with_boxed_rule(mut self, rule: Box<dyn rule::Rule<T>>) -> Self {
+        self.rules.push(rule);
         self
     }
 
-    /// Modifies the [rule::EdgeBehaviour] of the internal [rule::PatternRule].
-    pub fn with_pattern_edge_behaviour(
+    /// Adds a named region in which ```rule``` is applied instead of the other rules of this builder, such as a water zone with physics of its own, see [RegionRules](rule::RegionRules).
+    ///
+    /// Once a region is added, all other rules, including the patterns, only apply to the cells covered by no region.
+    /// Regions are applied in the order they are added, so where regions overlap, the region added last has the final say.
+    pub fn with_region(
         mut self,
-        row_boundary: rule::BoundaryBehaviour,
-        col_boundary: rule::BoundaryBehaviour,
+        name: &str,
+        mask: impl Into<rule::RegionMask>,
+        rule: impl rule::Rule<T> + 'static,
     ) -> Self {
-        self.pattern_rule.row_boundary = row_boundary;
-        self.pattern_rule.col_boundary = col_boundary;
+        self.regions.add_region(name, mask.into(), Box::new(rule));
         self
     }
 
-    /// Adds a rule to this automaton.
-    ///
-    /// Adding multiple rules will combine them into a single [MultiRule](rule::MultiRule) on construction.
-    ///
-    /// It is not suggested to use this function to add a [Pattern Rule](rule::PatternRule) and instead use [Self::with_pattern] or [Self::with_patterns].
-    /// Only use this to add a [Pattern Rule](rule::PatternRule) when you have already constructed it elsewhere or reuse the same [Pattern Rule](rule::PatternRule) for multiple automata.
-    pub fn with_rule(mut self, rule: impl rule::Rule + 'static) -> Self {
-        self.rules.push(Box::new(rule));
-        self
+    /// Adds a rule applying the passed function to the entire grid each step, see [FnRule](rule::FnRule).
+    pub fn with_fn_rule(
+        self,
+        name: &str,
+        function: impl Fn(&mut grid::Grid<T>) + Send + Sync + 'static,
+    ) -> Self {
+        self.with_rule(rule::FnRule::new(name, function))
     }
 
     /// Adds a color mapping to this automaton.
     /// Cells containing the character ```cell``` will be displayed as color ```color```.
     /// These colors are also used when converting to and from image buffers.
-    pub fn with_color(mut self, cell: u8, color: [u8; 4]) -> Self {
-        self.colors.insert(cell, color);
+    ///
+    /// Colors can be passed as a [CellColor](crate::CellColor), such as one parsed from a hexadecimal code, or as an array of 8-bit sRGB values.
+    pub fn with_color(mut self, cell: T, color: impl Into<crate::CellColor>) -> Self {
+        self.colors.insert(cell, color.into().to_srgb8());
+        self
+    }
+
+    /// Gives the passed symbol a name, such as ```"fire"```.
+    /// Names appear in log messages, the hovered cell information of the live view and the [legend](automaton::Automaton::legend) of the automaton.
+    ///
+    /// Using the same name for multiple symbols is reported by [Self::build_checked()].
+    pub fn with_symbol_name(mut self, symbol: T, name: &str) -> Self {
+        self.names.insert(symbol, name.to_string());
         self
     }
 
     /// Adds multiple color mappings at once.
     /// Cells containing the character ```key``` will be displayed as color ```colors[key]```.
     /// These colors are also used when converting to and from image buffers.
-    pub fn with_colors(mut self, colors: HashMap<u8, [u8; 4]>) -> Self {
-        self.colors.extend(colors);
+    pub fn with_colors(mut self, colors: HashMap<T, impl Into<crate::CellColor>>) -> Self {
+        self.colors.extend(
+            colors
+                .into_iter()
+                .map(|(cell, color)| (cell, color.into().to_srgb8())),
+        );
+        self
+    }
+
+    /// Rearranges the initial state with the passed operation, such as rotating a state that was authored in the wrong orientation.
+    /// Multiple operations are applied in the order they were added.
+    ///
+    /// Only the initial state is rearranged, an [auxiliary layer](Self::with_aux_layer()) needs to match the dimensions of the rearranged state.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1, 2, 3, 4, 5, 6], 3)
+    ///     .with_transform(cellumina::gridops::Transform::RotateCw)
+    ///     .build();
+    /// assert_eq!(auto.state(), &grid::grid![[4, 1][5, 2][6, 3]]);
+    /// ```
+    pub fn with_transform(mut self, transform: crate::gridops::Transform) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    /// Draws a border of ```symbol``` around the initial state, such as a wall of symbol 126 around the playfield, see [Automaton::draw_border](automaton::AutomatonGeneric::draw_border).
+    ///
+    /// The border is drawn after the initial state was loaded and rearranged with [Self::with_transform()], overwriting the loaded cells at the edges.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![1; 9], 3)
+    ///     .with_border(1, 126)
+    ///     .build();
+    /// assert_eq!(auto.state(), &grid::grid![[126, 126, 126][126, 1, 126][126, 126, 126]]);
+    /// ```
+    pub fn with_border(mut self, thickness: u32, symbol: T) -> Self {
+        self.border = Some((thickness, symbol));
+        self
+    }
+
+    /// Applies the operations added with [Self::with_transform()] to the initial state and draws the border, if any.
+    fn transform_state(&self, state: grid::Grid<T>) -> grid::Grid<T> {
+        let mut state = self
+            .transforms
+            .iter()
+            .fold(state, |state, transform| transform.apply(&state));
+        if let Some((thickness, symbol)) = self.border {
+            crate::gridops::draw_border(&mut state, thickness as usize, symbol);
+        }
+        state
+    }
+
+    /// Merges the colors of gradients into the explicitly set colors, which take precedence.
+    fn merge_gradients(&mut self) {
+        if !self.gradient_colors.is_empty() {
+            let mut colors = std::mem::take(&mut self.gradient_colors);
+            colors.extend(self.colors.drain());
+            self.colors = colors;
+        }
+    }
+
+    /// Sets the color used to display cells without a color of their own, see [Self::with_color].
+    /// By default, this is magenta in debug builds, to make missing colors stand out, and transparent black in release builds.
+    pub fn with_default_color(mut self, color: impl Into<crate::CellColor>) -> Self {
+        self.default_color = color.into().to_srgb8();
+        self
+    }
+
+    /// Adds a read-only auxiliary layer to the automaton, holding static information for each cell such as terrain or materials.
+    ///
+    /// Rules can read the auxiliary layer, most notably [AuxEnvironmentRule](rule::AuxEnvironmentRule), but it never changes.
+    /// It needs to have the same dimensions as the initial state, otherwise the build process logs an error and discards it, or [Self::build_checked()] fails.
+    pub fn with_aux_layer(mut self, grid: CellGrid) -> Self {
+        self.aux_source = InitSource::Grid(grid);
+        self
+    }
+
+    /// Adds a read-only auxiliary layer from an image file, see [Self::with_aux_layer()].
+    ///
+    /// Each pixel becomes the cell of its color in ```colors```, which are separate from the colors of the state. Pixels matching no color become 0.
+    pub fn with_aux_image_file(
+        mut self,
+        path: impl AsRef<std::path::Path> + 'static,
+        colors: HashMap<u8, impl Into<crate::CellColor>>,
+    ) -> Self {
+        self.aux_source = InitSource::ImageFile(Box::new(path));
+        self.aux_colors = colors
+            .into_iter()
+            .map(|(cell, color)| (cell, color.into().to_srgb8()))
+            .collect();
         self
     }
 
     // TODO: colors from file
 
     /// Completes the build process and produces an [cellular automaton](automaton::Automaton) as specified.
-    pub fn build(mut self) -> automaton::Automaton {
+    ///
+    /// If the initial state can not be created, an error is logged and an empty 16x16 grid is used instead.
+    /// Use [Self::build_checked()] to handle such errors yourself.
+    pub fn build(mut self) -> automaton::AutomatonGeneric<T> {
+        self.merge_gradients();
         log::debug!(
             "Building automaton from the following parameters: {:?}",
             &self
         );
-        automaton::Automaton {
-            state: std::mem::replace(&mut self.source, InitSource::None)
-                .create_grid(&self.colors)
-                .unwrap_or_else(|err| {
-                    log::error!(
-                        "Encountered error while attempting to initialize automaton state. Falling back to empty 16x16 grid. Error:\n{err}"
-                    );
-                    grid::Grid::new(16, 16)
-                }),
-            rule: {
-                if !self.pattern_rule.patterns.is_empty() {
-                    log::info!("Patterns were supplied to builder, initialization will use presupplied pattern rule.");
-                    self.rules.push(Box::new(self.pattern_rule));
-                } else {
-                    log::info!("No patterns were supplied to builder, presupplied pattern rule will be discarded.");
-                }
+        let state = std::mem::replace(&mut self.source, InitSource::None)
+            .create_grid(&self.colors, self.strict_parsing, self.alpha_decoder)
+            .map(|state| self.transform_state(state))
+            .unwrap_or_else(|err| {
+                log::error!(
+                    "Encountered error while attempting to initialize automaton state. Falling back to empty 16x16 grid. Error:\n{err}"
+                );
+                grid::Grid::new(16, 16)
+            });
+        if let Err(err) = self.pattern_rule.validate() {
+            log::error!("Supplied patterns are not valid: {err}");
+        }
+        if let Err(err) = self.validate_names() {
+            log::error!("Supplied symbol names are not valid: {err}");
+        }
+        let aux = self.create_aux(state.size()).unwrap_or_else(|err| {
+            log::error!(
+                "Encountered error while attempting to initialize auxiliary layer. Continuing without it. Error:\n{err}"
+            );
+            None
+        });
+        self.assemble(state, aux)
+    }
 
-                if self.rules.len() == 1 {
-                    log::info!("Initializing automaton with a single rule.");
-                    self.rules.pop().unwrap()
-                } else {
-                    log::info!("Initializing automaton with {} rules, wrapping in MultiRule.", self.rules.len());
-                    Box::new(rule::MultiRule { rules: self.rules })
-                }
-            },
+    /// Completes the build process like [Self::build()], but returns an error instead of falling back if the initial state can not be created
+    /// or the supplied [Patterns](rule::Pattern) are not valid.
+    pub fn build_checked(
+        mut self,
+    ) -> Result<automaton::AutomatonGeneric<T>, crate::CelluminaError> {
+        self.merge_gradients();
+        log::debug!(
+            "Building automaton from the following parameters: {:?}",
+            &self
+        );
+        let state = std::mem::replace(&mut self.source, InitSource::None)
+            .create_grid(&self.colors, self.strict_parsing, self.alpha_decoder)
+            .map(|state| self.transform_state(state))?;
+        self.pattern_rule.validate()?;
+        self.validate_names()?;
+        let aux = self.create_aux(state.size())?;
+        Ok(self.assemble(state, aux))
+    }
+
+    /// Checks that no name was given to multiple symbols.
+    fn validate_names(&self) -> Result<(), crate::CelluminaError> {
+        let mut names = self.names.values().collect::<Vec<_>>();
+        names.sort();
+        let mut duplicates = names
+            .windows(2)
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| pair[0])
+            .collect::<Vec<_>>();
+        duplicates.dedup();
+        if duplicates.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::CelluminaError::ValidationError(
+                duplicates
+                    .into_iter()
+                    .map(|name| crate::ValidationIssue::DuplicateSymbolName { name: name.clone() })
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Creates the auxiliary layer, if one was supplied, and checks that it has the passed dimensions of the state.
+    fn create_aux(
+        &mut self,
+        dimensions: (usize, usize),
+    ) -> Result<Option<CellGrid>, crate::CelluminaError> {
+        let aux = match std::mem::replace(&mut self.aux_source, InitSource::None) {
+            InitSource::None => return Ok(None),
+            source => source.create_grid(&self.aux_colors, self.strict_parsing, None)?,
+        };
+        if aux.size() != dimensions {
+            return Err(crate::CelluminaError::DimensionMismatch {
+                expected: dimensions,
+                got: aux.size(),
+            });
+        }
+        Ok(Some(aux))
+    }
+
+    /// Creates the automaton from the passed initial state, auxiliary layer and the rest of this builder's configuration.
+    fn assemble(
+        mut self,
+        state: grid::Grid<T>,
+        aux: Option<CellGrid>,
+    ) -> automaton::AutomatonGeneric<T> {
+        let mut rule: Box<dyn rule::Rule<T>> = {
+            if let (Some(convert), false) = (
+                self.pattern_conversion,
+                self.pattern_rule.patterns.is_empty(),
+            ) {
+                log::info!("Patterns were supplied to builder, initialization will use presupplied pattern rule.");
+                self.rules.push(convert(self.pattern_rule));
+            } else {
+                log::info!("No patterns were supplied to builder, presupplied pattern rule will be discarded.");
+            }
+
+            if self.rules.len() == 1 {
+                log::info!("Initializing automaton with a single rule.");
+                self.rules.pop().unwrap()
+            } else {
+                log::info!(
+                    "Initializing automaton with {} rules, wrapping in MultiRule.",
+                    self.rules.len()
+                );
+                Box::new(rule::MultiRule::new(self.rules))
+            }
+        };
+        if self.regions.names().next().is_some() {
+            log::info!("Regions were supplied to builder, other rules only apply outside of them.");
+            self.regions.set_remainder(rule);
+            rule = Box::new(self.regions);
+        }
+        if !self.frozen.is_empty() {
+            rule.set_frozen_symbols(&self.frozen);
+        }
+        if let Some(seed) = self.seed {
+            rule.reseed(seed);
+        }
+        if let Some(rng) = self.rng.take() {
+            rule.set_rng(rng);
+        }
+        let mut res = automaton::AutomatonGeneric {
+            // initially, the entire state is dirty
+            dirty: self
+                .dirty_tracking
+                .then(|| rule::Region::full(state.rows(), state.cols())),
+            dirty_tracking: self.dirty_tracking,
+            buffer: grid::Grid::new(0, 0),
+            executor: crate::parallelism::Executor::new(self.parallelism),
+            image: automaton::ImageCache::new(&self.colors, self.default_color),
+            state: state.into(),
+            rule,
+            aux,
             step_mode: self.step_mode,
+            speed: 1.,
             last_step: None,
+            generation: 0,
             colors: self.colors,
+            stats: self.stats,
+            transitions: self.transitions.then(crate::TransitionTracker::new),
+            names: self.names,
+            frozen: self.frozen,
+            step_report: self.step_reports.then(Vec::new),
+            changes: None,
+            pending_rows: None,
+            substeps: self.substeps,
+            count_substeps: self.count_substeps,
+            alpha_encoding: self.alpha_decoder.is_some(),
+        };
+        res.record_stats();
+        res
+    }
+}
+
+impl AutomatonBuilder {
+    /// Colors symbols with the colors of a palette in the GIMP palette (```.gpl```) format, as exported by many image editors.
+    ///
+    /// With a ```symbol_order```, the colors of the palette are assigned to the passed symbols in order, ignoring the names of the entries and any additional entries.
+    /// Without, each entry has to be named by the character of the symbol it colors, see [crate::char_to_id], such as ```224 210 159 X```.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 1], 2)
+    ///     .with_colors_from_gpl("tests/fixtures/palette.gpl", Some(&[1, 0]))
+    ///     .unwrap()
+    ///     .build();
+    /// assert_eq!(auto.get_color(0), Some([255, 255, 255, 255]));
+    /// ```
+    /// ## Error
+    /// When the file can not be read or contains a malformed line, an entry is not named by a single known character,
+    /// or the palette has fewer entries than the ```symbol_order```.
+    pub fn with_colors_from_gpl(
+        mut self,
+        path: impl AsRef<std::path::Path>,
+        symbol_order: Option<&[u8]>,
+    ) -> Result<Self, crate::CelluminaError> {
+        let entries = crate::convert::parse_gpl(&std::fs::read_to_string(path)?)?;
+        match symbol_order {
+            Some(symbols) => {
+                if entries.len() < symbols.len() {
+                    return Err(crate::CelluminaError::CustomError(format!(
+                        "palette has {} colors for {} symbols",
+                        entries.len(),
+                        symbols.len()
+                    )));
+                }
+                for (&symbol, (_, color, _)) in symbols.iter().zip(entries) {
+                    self = self.with_color(symbol, color);
+                }
+            }
+            None => {
+                for (line, color, name) in entries {
+                    let mut chars = name.chars();
+                    let symbol = match (chars.next(), chars.next()) {
+                        (Some(symbol), None) => crate::try_char_to_id(symbol)?,
+                        _ => {
+                            return Err(crate::CelluminaError::ParseError {
+                                source_kind: crate::SourceKind::Palette,
+                                line,
+                                column: 1,
+                                message: format!(
+                                    "expected the entry to be named by a single character, found {name:?}"
+                                ),
+                            })
+                        }
+                    };
+                    self = self.with_color(symbol, color);
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Colors all symbols in the passed range with a gradient from ```from``` to ```to```, such as a heat scale where each symbol represents a temperature.
+    /// The first symbol of the range receives ```from```, the last one ```to```, and the symbols in between are interpolated evenly, see [interpolate_color](crate::convert::interpolate_color).
+    ///
+    /// Colors set with [Self::with_color] or [Self::with_colors] take precedence over gradients, no matter the order they are set in.
+    /// Where gradients overlap, the one set last is used.
+    /// ```
+    /// let auto = cellumina::AutomatonBuilder::new()
+    ///     .from_vec(vec![0, 2, 4], 3)
+    ///     .with_color_gradient(0..=4, [0, 0, 0, 255], [255, 0, 0, 255])
+    ///     .with_color(2, [0, 255, 0, 255])
+    ///     .build();
+    /// let image = auto.create_image_buffer();
+    /// assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]);
+    /// assert_eq!(image.get_pixel(1, 0).0, [0, 255, 0, 255]);
+    /// assert_eq!(image.get_pixel(2, 0).0, [255, 0, 0, 255]);
+    /// ```
+    pub fn with_color_gradient(
+        mut self,
+        range: std::ops::RangeInclusive<u8>,
+        from: impl Into<crate::CellColor>,
+        to: impl Into<crate::CellColor>,
+    ) -> Self {
+        let (from, to) = (from.into().to_srgb8(), to.into().to_srgb8());
+        let (start, end) = (*range.start(), *range.end());
+        for symbol in range {
+            let t = if end > start {
+                f32::from(symbol - start) / f32::from(end - start)
+            } else {
+                0.
+            };
+            self.gradient_colors
+                .insert(symbol, crate::convert::interpolate_color(from, to, t));
+        }
+        self
+    }
+
+    /// Reject unknown characters when reading the initial state from a text file.
+    ///
+    /// By default, characters without an associated cell value (see [char_to_id](crate::char_to_id)) are read as empty cells and only a warning is logged.
+    /// With strict parsing, they instead cause an error naming the character and its position, making the build process fall back to an empty grid.
+    pub fn with_strict_parsing(mut self) -> Self {
+        self.strict_parsing = true;
+        self
+    }
+
+    /// Store the symbol of each cell in the alpha channel of images, so states saved as images can be loaded again without losing symbols,
+    /// even if some symbols have no color or share a color with another symbol.
+    ///
+    /// Images supplying the initial state, for example with [Self::from_image_file()], are then read from their alpha channel as written by [convert::encode_alpha](crate::convert::encode_alpha), ignoring the colors.
+    /// [Automaton::save](crate::Automaton::save) writes the symbols to the alpha channel of ```png```, ```bmp``` and ```ico``` files, making the transparency of the saved images meaningless for viewing.
+    pub fn with_alpha_encoding(mut self) -> Self {
+        self.alpha_decoder = Some(crate::convert::decode_alpha);
+        self
+    }
+
+    /// Use a text file to supply the initial state of the automaton.
+    ///
+    /// The automaton will have as many rows as the file has lines, and as many columns as the longest line in the file has characters.
+    /// Shorter lines are padded with empty cells. Both Unix and Windows line endings are supported, and a final newline does not add an empty row.
+    pub fn from_text_file(mut self, path: impl AsRef<std::path::Path> + 'static) -> Self {
+        self.source = InitSource::TextFile(Box::new(path), grid_from_text);
+        self
+    }
+
+    /// Use text read from the passed reader, such as [std::io::stdin], to supply the initial state of the automaton, see [Self::from_text_file()].
+    /// The reader is only read when the automaton is built.
+    pub fn from_text_reader(mut self, reader: impl std::io::Read + 'static) -> Self {
+        self.source = InitSource::TextReader(Box::new(reader), grid_from_text);
+        self
+    }
+
+    /// Use a CSV file to supply the initial state of the automaton, with one row of comma-separated cell values from ```0``` to ```255``` per line.
+    ///
+    /// Like text files, shorter rows are padded with empty cells and a final newline does not add an empty row. Whitespace around values is ignored, and empty values are empty cells.
+    /// Values that are not numbers in range are read as empty cells, unless [strict parsing](Self::with_strict_parsing) is enabled.
+    pub fn from_csv_file(mut self, path: impl AsRef<std::path::Path> + 'static) -> Self {
+        self.source = InitSource::TextFile(Box::new(path), grid_from_csv);
+        self
+    }
+
+    /// Use CSV read from the passed reader, such as [std::io::stdin], to supply the initial state of the automaton, see [Self::from_csv_file()].
+    /// The reader is only read when the automaton is built.
+    pub fn from_csv_reader(mut self, reader: impl std::io::Read + 'static) -> Self {
+        self.source = InitSource::TextReader(Box::new(reader), grid_from_csv);
+        self
+    }
+
+    /// Use a binary ```.cels``` or ```.celz``` file, as written by [Automaton::save_binary](crate::Automaton::save_binary), to supply the initial state of the automaton.
+    ///
+    /// Compressed files are recognized by their first bytes, whatever their extension, and can only be read with the ```compression``` feature.
+    pub fn from_binary_file(mut self, path: impl AsRef<std::path::Path> + 'static) -> Self {
+        self.source = InitSource::BinaryFile(Box::new(path), crate::binary::decode);
+        self
+    }
+
+    /// Use the bytes of a binary state read from the passed reader to supply the initial state of the automaton, see [Self::from_binary_file()].
+    /// The reader is only read when the automaton is built.
+    pub fn from_binary_reader(mut self, reader: impl std::io::Read + 'static) -> Self {
+        self.source = InitSource::BinaryReader(Box::new(reader), crate::binary::decode);
+        self
+    }
+
+    /// Adds a [Pattern](rule::Pattern) to this automaton that will be used for replacement each step.
+    /// Its chance is clamped into ```[0, 1]```, its priority is checked by [Self::build_checked()].
+    pub fn with_pattern(mut self, mut pattern: rule::Pattern) -> Self {
+        pattern.clamp_chance();
+        self.pattern_rule.patterns.push(pattern);
+        self.pattern_conversion = Some(|rule| Box::new(rule));
+        self
+    }
+
+    /// Adds a [Pattern](rule::Pattern) loaded from two image files, see [rule::Pattern::from_images].
+    /// ## Error
+    /// When an image can not be read, the images differ in size or a pixel matches no color.
+    pub fn with_pattern_images(
+        self,
+        before: impl AsRef<std::path::Path>,
+        after: impl AsRef<std::path::Path>,
+        colors: &HashMap<u8, [u8; 4]>,
+        wildcard_color: Option<[u8; 4]>,
+    ) -> Result<Self, crate::CelluminaError> {
+        Ok(self.with_pattern(rule::Pattern::from_image_files(
+            before,
+            after,
+            colors,
+            wildcard_color,
+        )?))
+    }
+
+    /// Adds multiple [Patterns](rule::Pattern) to this automaton that will be used for replacement each step.
+    pub fn with_patterns(mut self, patterns: &[rule::Pattern]) -> Self {
+        for pattern in patterns {
+            self = self.with_pattern(pattern.clone());
         }
+        self
+    }
+
+    /// Adds the rule of a [Preset](rule::presets::Preset) to this automaton, like [Self::with_rule], and its colors, like [Self::with_colors].
+    /// Colors set for the same cells before are replaced, colors set afterwards take precedence.
+    pub fn with_preset(mut self, preset: rule::presets::Preset) -> Self {
+        self.rules.push(preset.rule);
+        self.with_colors(preset.colors)
+    }
+
+    /// Modifies the [rule::BoundaryBehaviour] of the internal [rule::PatternRule] the patterns added with [Self::with_pattern] are collected in.
+    /// Without calling this function, both are [rule::BoundaryBehaviour::blocking_boundary()], so patterns do not wrap around the grid edges.
+    pub fn with_pattern_edge_behaviour(
+        mut self,
+        row_boundary: rule::BoundaryBehaviour,
+        col_boundary: rule::BoundaryBehaviour,
+    ) -> Self {
+        self.pattern_rule.row_boundary = row_boundary;
+        self.pattern_rule.col_boundary = col_boundary;
+        self
+    }
+
+    /// Sets the order in which the internal [rule::PatternRule] applies replacements of equally prioritized patterns, see [rule::PatternOrdering].
+    /// Without calling this function, they are applied in a random order.
+    pub fn with_pattern_ordering(mut self, ordering: rule::PatternOrdering) -> Self {
+        self.pattern_rule.ordering = ordering;
+        self
+    }
+
+    /// Sets what the internal [rule::PatternRule] does with replacement cells that fall outside the grid, see [rule::WriteBoundary].
+    /// Without calling this function, replacements wrap around periodic boundaries.
+    pub fn with_pattern_write_boundary(mut self, write_boundary: rule::WriteBoundary) -> Self {
+        self.pattern_rule.write_boundary = write_boundary;
+        self
+    }
+
+    /// Sets how the internal [rule::PatternRule] uses the priorities of its patterns, see [rule::PriorityMode].
+    /// Without calling this function, higher priorities always win.
+    pub fn with_pattern_priority_mode(mut self, priority_mode: rule::PriorityMode) -> Self {
+        self.pattern_rule.priority_mode = priority_mode;
+        self
+    }
+
+    /// Sets wether the patterns of the internal [rule::PatternRule] may match against the symbols of [rule::BoundaryBehaviour::Symbol] boundaries, see [rule::PatternBoundaryMode].
+    /// Without calling this function, patterns are only checked where they lie entirely within the grid.
+    pub fn with_pattern_boundary_mode(mut self, boundary_mode: rule::PatternBoundaryMode) -> Self {
+        self.pattern_rule.boundary_mode = boundary_mode;
+        self
+    }
+
+    /// Adds an [EdgeSourceRule](rule::EdgeSourceRule), turning each empty cell along the passed edge into ```symbol``` with the passed probability each step.
+    ///
+    /// Together with a [rule::BoundaryBehaviour::Sink] on the opposite edge, this creates an open world that cells stream into and out of, such as sand pouring in from the top and falling off the bottom.
+    pub fn with_edge_source(self, edge: rule::Edge, symbol: u8, probability: f32) -> Self {
+        self.with_rule(rule::EdgeSourceRule::new(edge, symbol, probability))
+    }
+
+    /// Adds a [DecayRule](rule::DecayRule) with the passed transitions, each turning cells of the first symbol into the second symbol once they held it for the given number of steps.
+    ///
+    /// This replaces long chains of intermediate symbols and patterns for effects such as fire burning out to ash after exactly a number of steps.
+    pub fn with_decay(self, chain: &[(u8, u8, u32)]) -> Self {
+        self.with_rule(rule::DecayRule::new(chain.to_vec()))
     }
 }
 
-impl Default for AutomatonBuilder {
+impl<T: Cell> Default for AutomatonBuilderGeneric<T> {
     fn default() -> Self {
         Self::new()
     }
@@ -324,6 +1135,7 @@ fn builder_test() {
             after: grid::grid![[0][0][59]],
             priority: 1.0,
             chance: 0.9,
+            ..Default::default()
         })
         .with_min_time_step(std::time::Duration::from_secs_f32(0.5))
         .build();
@@ -344,12 +1156,8 @@ fn builder_test() {
             environment_size: [1, 1, 1, 1],
             row_boundary: rule::BoundaryBehaviour::Symbol(0),
             col_boundary: rule::BoundaryBehaviour::Symbol(0),
-            cell_transform: |env| match env
-                // Iterate over neighbors.
-                .iter().copied()
-                // Sum over these 9 values without the center
-                .sum::<u8>() - env[1][1]
-                // ... and map the sum to the new enty of our cell:
+            cell_transform: |env| match rule::helpers::count_neighbors(env, 1)
+                // Count the live neighbors around the center and map the count to the new entry of our cell:
             {
                 // 2 neighbors: The cell keeps its state.
                 2 => env[1][1],
@@ -358,6 +1166,7 @@ fn builder_test() {
                 // 0, 1 or more than 3 neighbors: The cell dies.
                 _ => 0,
             },
+            ..Default::default()
         })
         .with_color(1, [95, 205, 228, 255])
         .with_color(0, [3, 40, 50, 250])
@@ -373,3 +1182,477 @@ fn builder_test() {
         grid::grid![[1,0,1,0] [0,1,0,0] [0,0,0,0] [0,1,1,0]]
     );
 }
+
+#[test]
+fn from_vec_test() {
+    // the last row is padded
+    assert_eq!(
+        InitSource::Vec(vec![1, 2, 3, 4, 5], 3)
+            .create_grid(&HashMap::new(), false, None)
+            .unwrap(),
+        grid::grid![[1, 2, 3][4, 5, 0]]
+    );
+    // exact multiples are taken as they are
+    assert_eq!(
+        InitSource::Vec(vec![1, 2, 3, 4, 5, 6], 2)
+            .create_grid(&HashMap::new(), false, None)
+            .unwrap(),
+        grid::grid![[1, 2][3, 4][5, 6]]
+    );
+    // zero columns can not hold anything
+    assert!(matches!(
+        InitSource::Vec(vec![1, 2, 3], 0).create_grid(&HashMap::new(), false, None),
+        Err(crate::CelluminaError::ValidationError(issues)) if issues == [crate::ValidationIssue::ZeroColumns]
+    ));
+
+    let auto = AutomatonBuilder::new().from_vec(vec![7; 7], 4).build();
+    assert_eq!(auto.state, grid::grid![[7, 7, 7, 7][7, 7, 7, 0]]);
+}
+
+#[test]
+fn text_file_test() {
+    let expected = grid::grid![[10, 11, 0][0, 0, 0][12, 13, 14]];
+
+    // with and without final newline, with windows line endings
+    for text in [
+        "ab\n\ncde",
+        "ab\n\ncde\n",
+        "ab\r\n\r\ncde",
+        "ab\r\n\r\ncde\r\n",
+    ] {
+        assert_eq!(grid_from_text(text, false).unwrap(), expected, "{text:?}");
+    }
+
+    // the longest line is the last one, blank lines at the end are kept except for the final newline
+    assert_eq!(
+        grid_from_text("a\nbcd\n\n", false).unwrap(),
+        grid::grid![[10, 0, 0][11, 12, 13][0, 0, 0]]
+    );
+
+    // the same through an actual file
+    let path = std::env::temp_dir().join("cellumina_text_file_test.txt");
+    std::fs::write(&path, "ab\r\n\r\ncde\r\n").unwrap();
+    assert_eq!(
+        InitSource::TextFile(Box::new(path.clone()), grid_from_text)
+            .create_grid(&HashMap::new(), false, None)
+            .unwrap(),
+        expected
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn strict_parsing_test() {
+    let path = std::env::temp_dir().join("cellumina_strict_parsing_test.txt");
+    std::fs::write(&path, "ab\nc\u{1F980}d\n").unwrap();
+
+    // lenient parsing replaces the emoji with an empty cell
+    assert_eq!(
+        InitSource::TextFile(Box::new(path.clone()), grid_from_text)
+            .create_grid(&HashMap::new(), false, None)
+            .unwrap(),
+        grid::grid![[10, 11, 0][12, 0, 13]]
+    );
+
+    // strict parsing reports it
+    match InitSource::TextFile(Box::new(path.clone()), grid_from_text).create_grid(
+        &HashMap::new(),
+        true,
+        None,
+    ) {
+        Err(crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::State,
+            line: 2,
+            column: 2,
+            ..
+        }) => {}
+        res => panic!("Expected parse error, got {res:?}"),
+    }
+
+    // which makes the build fall back to an empty grid
+    let auto = AutomatonBuilder::new()
+        .from_text_file(path.clone())
+        .with_strict_parsing()
+        .build();
+    assert_eq!(auto.state, grid::Grid::new(16, 16));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn build_checked_test() {
+    // errors of the initial state are returned
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_vec(vec![1, 2], 0)
+            .build_checked(),
+        Err(crate::CelluminaError::ValidationError(_))
+    ));
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_text_file("./does/not/exist.txt")
+            .build_checked(),
+        Err(crate::CelluminaError::IOError(_))
+    ));
+
+    // as well as invalid patterns
+    match AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .with_pattern(rule::Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0, 1]],
+            ..Default::default()
+        })
+        .build_checked()
+    {
+        Err(crate::CelluminaError::ValidationError(issues)) => assert_eq!(
+            issues,
+            [crate::ValidationIssue::PatternSizeMismatch {
+                pattern: 0,
+                before: (2, 1),
+                after: (1, 2)
+            }]
+        ),
+        res => panic!("Expected validation error, got {res:?}"),
+    }
+
+    let auto = AutomatonBuilder::new()
+        .from_vec(vec![0, 1, 2, 3], 2)
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.state, grid::grid![[0, 1][2, 3]]);
+}
+
+#[test]
+fn pattern_values_test() {
+    // chances are clamped when adding patterns
+    let builder = AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .with_pattern(rule::Pattern {
+            chance: -1.,
+            ..Default::default()
+        })
+        .with_patterns(&[rule::Pattern {
+            chance: 3.,
+            ..Default::default()
+        }]);
+    assert_eq!(builder.pattern_rule.patterns[0].chance, 0.);
+    assert_eq!(builder.pattern_rule.patterns[1].chance, 1.);
+    assert!(builder.build_checked().is_ok());
+
+    // priorities that are not finite are rejected
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_vec(vec![0; 4], 2)
+            .with_pattern(rule::Pattern {
+                priority: f32::NAN,
+                ..Default::default()
+            })
+            .build_checked(),
+        Err(crate::CelluminaError::ValidationError(issues)) if issues == [crate::ValidationIssue::InvalidPriority { pattern: 0 }]
+    ));
+}
+
+#[test]
+fn image_palette_test() {
+    let buffer = image::RgbaImage::from_fn(3, 2, |x, y| {
+        image::Rgba(match (x + y) % 3 {
+            0 => [0, 0, 0, 255],
+            1 => [255, 255, 255, 255],
+            _ => [255, 0, 0, 255],
+        })
+    });
+
+    // without colors, nothing can be matched
+    assert!(matches!(
+        grid_from_image::<u8>(&buffer, &HashMap::new()),
+        Err(crate::CelluminaError::ValidationError(issues)) if issues == [crate::ValidationIssue::EmptyPalette]
+    ));
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_image_buffer(buffer.clone())
+            .build_checked(),
+        Err(crate::CelluminaError::ValidationError(_))
+    ));
+
+    // a partial palette leaves the red pixels unmatched
+    let (grid, unmatched) = grid_from_image(
+        &buffer,
+        &HashMap::from([(1, [255, 255, 255, 255]), (2, [0, 0, 0, 255])]),
+    )
+    .unwrap();
+    assert_eq!(grid, grid::grid![[2, 1, 0][1, 0, 2]]);
+    assert_eq!(unmatched, 2);
+}
+
+#[test]
+fn pattern_boundary_test() {
+    // a cell falling down by one row each step
+    let builder = || {
+        AutomatonBuilder::new()
+            .from_grid(grid::grid![[1][0][0]])
+            .with_pattern(rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            })
+    };
+
+    // without specifying boundaries, the internal pattern rule agrees with a default one and uses walls
+    let default_rule = rule::PatternRule::default();
+    assert_eq!(
+        default_rule.row_boundary,
+        rule::BoundaryBehaviour::blocking_boundary()
+    );
+    assert_eq!(
+        default_rule.col_boundary,
+        rule::BoundaryBehaviour::blocking_boundary()
+    );
+    let builder_rule = builder().pattern_rule;
+    assert_eq!(builder_rule.row_boundary, default_rule.row_boundary);
+    assert_eq!(builder_rule.col_boundary, default_rule.col_boundary);
+
+    // so the cell stops at the bottom
+    let mut automaton = builder().build();
+    for _ in 0..4 {
+        automaton.next_step();
+    }
+    assert_eq!(automaton.state, grid::grid![[0][0][1]]);
+
+    // while periodic boundaries let it wrap around
+    let mut automaton = builder()
+        .with_pattern_edge_behaviour(
+            rule::BoundaryBehaviour::Periodic,
+            rule::BoundaryBehaviour::Periodic,
+        )
+        .build();
+    for _ in 0..3 {
+        automaton.next_step();
+    }
+    assert_eq!(automaton.state, grid::grid![[1][0][0]]);
+}
+
+#[test]
+fn aux_layer_dimensions_test() {
+    let builder = || AutomatonBuilder::new().from_vec(vec![0; 6], 3);
+
+    // auxiliary layers need the dimensions of the state
+    assert!(matches!(
+        builder()
+            .with_aux_layer(grid::grid![[0, 0][0, 0]])
+            .build_checked(),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (2, 3),
+            got: (2, 2)
+        })
+    ));
+    // and are discarded otherwise
+    let auto = builder().with_aux_layer(grid::grid![[0, 0]]).build();
+    assert_eq!(auto.aux, None);
+
+    let auto = builder()
+        .with_aux_layer(grid::grid![[1, 2, 3][4, 5, 6]])
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.aux, Some(grid::grid![[1, 2, 3][4, 5, 6]]));
+}
+
+#[test]
+fn reader_test() {
+    use std::io::Cursor;
+
+    // text
+    let auto = AutomatonBuilder::new()
+        .from_text_reader(Cursor::new(b"ab\n c\n".to_vec()))
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.state, grid::grid![[10, 11][0, 12]]);
+
+    // csv, with padding, whitespace and empty values
+    let auto = AutomatonBuilder::new()
+        .from_csv_reader(Cursor::new(b"1, 2,3\r\n255,,\n4\n".to_vec()))
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.state, grid::grid![[1, 2, 3][255, 0, 0][4, 0, 0]]);
+    assert_eq!(grid_from_csv("", false).unwrap(), grid::Grid::new(0, 0));
+    assert_eq!(
+        grid_from_csv("1,x,300", false).unwrap(),
+        grid::grid![[1, 0, 0]]
+    );
+    match grid_from_csv("1,2\n3, x", true) {
+        Err(crate::CelluminaError::ParseError {
+            line: 2, column: 3, ..
+        }) => {}
+        res => panic!("Expected parse error, got {res:?}"),
+    }
+
+    // images, with a given or guessed format
+    let colors = HashMap::from([(1, [255, 0, 0, 255]), (2, [0, 0, 255, 255])]);
+    let image = image::RgbaImage::from_fn(3, 2, |x, y| {
+        image::Rgba(if x == y { colors[&1] } else { colors[&2] })
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+        .unwrap();
+    for format in [Some(image::ImageFormat::Png), None] {
+        let auto = AutomatonBuilder::new()
+            .from_image_reader(Cursor::new(bytes.clone()), format)
+            .with_colors(colors.clone())
+            .build_checked()
+            .unwrap();
+        assert_eq!(auto.state, grid::grid![[1, 2, 2][2, 1, 2]]);
+    }
+
+    // errors of the underlying readers are kept
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_image_reader(Cursor::new(b"not an image".to_vec()), None)
+            .with_colors(colors.clone())
+            .build_checked(),
+        Err(crate::CelluminaError::ImageError(_))
+    ));
+    assert!(matches!(
+        AutomatonBuilder::new()
+            .from_text_reader(Cursor::new(vec![0xff, 0xfe]))
+            .build_checked(),
+        Err(crate::CelluminaError::IOError(_))
+    ));
+}
+
+#[test]
+fn color_gradient_test() {
+    let (black, white, red) = ([0, 0, 0, 255], [255, 255, 255, 255], [255, 0, 0, 255]);
+    let auto = AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .with_color(11, red)
+        .with_color_gradient(10..=20, black, white)
+        .with_color_gradient(20..=30, white, black)
+        .with_color_gradient(40..=40, red, white)
+        .build();
+
+    // endpoints and midpoints
+    assert_eq!(auto.colors[&10], black);
+    assert_eq!(auto.colors[&15], [99, 99, 99, 255]);
+    assert_eq!(auto.colors[&25], [99, 99, 99, 255]);
+    assert_eq!(auto.colors[&30], black);
+    assert_eq!(auto.colors[&40], red);
+    // later gradients take precedence over earlier ones, explicit colors over both
+    assert_eq!(auto.colors[&20], white);
+    assert_eq!(auto.colors[&11], red);
+    assert!(!auto.colors.contains_key(&9) && !auto.colors.contains_key(&31));
+    // shades grow brighter along the gradient
+    for symbol in 13..20 {
+        assert!(auto.colors[&symbol][0] > auto.colors[&(symbol - 1)][0]);
+    }
+
+    // gradient colors are also used to read images
+    let image =
+        image::RgbaImage::from_fn(2, 1, |x, _| image::Rgba(if x == 0 { black } else { white }));
+    let auto = AutomatonBuilder::new()
+        .from_image_buffer(image)
+        .with_color_gradient(0..=1, black, white)
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.state, grid::grid![[0, 1]]);
+}
+
+#[test]
+fn border_test() {
+    // the border overwrites the content of loaded files, after rotating them
+    let auto = AutomatonBuilder::new()
+        .from_text_reader(std::io::Cursor::new(b"aaa\nbbb\n".to_vec()))
+        .with_transform(crate::gridops::Transform::RotateCw)
+        .with_border(1, 126)
+        .build_checked()
+        .unwrap();
+    assert_eq!(auto.state, grid::grid![[126, 126][126, 126][126, 126]]);
+
+    let auto = AutomatonBuilder::new()
+        .from_vec(vec![1; 20], 5)
+        .with_border(1, 126)
+        .build();
+    assert_eq!(auto.state[(1, 1)], 1);
+    assert_eq!(auto.state[(0, 0)], 126);
+    assert_eq!(auto.state[(3, 4)], 126);
+    assert_eq!(auto.state.iter().filter(|&&cell| cell == 1).count(), 6);
+}
+
+#[test]
+fn boxed_rule_test() {
+    let choose = |name: &str| -> Box<dyn rule::Rule> {
+        match name {
+            "life" => Box::new(rule::LifeLikeRule::parse("B3/S23").unwrap()),
+            "shift" => Box::new(rule::ShiftRule {
+                dy: 0,
+                dx: 1,
+                fill: Some(0),
+            }),
+            _ => Box::new(rule::FnRule::new("identity", |_: &mut CellGrid| {})),
+        }
+    };
+    let blinker = vec![0, 0, 0, 1, 1, 1, 0, 0, 0];
+
+    let mut auto = AutomatonBuilder::new()
+        .from_vec(blinker.clone(), 3)
+        .with_boxed_rule(choose("life"))
+        .with_dirty_tracking()
+        .build();
+    auto.next_step();
+    assert_eq!(auto.state, grid::grid![[0, 1, 0][0, 1, 0][0, 1, 0]]);
+
+    // boxed rules are rules themselves, keeping the properties of the boxed rule
+    assert_eq!(choose("life").reach(), Some(1));
+    let shift = choose("shift");
+    assert!(shift.clone_boxed().is_some());
+    let mut auto = AutomatonBuilder::new()
+        .from_vec(blinker.clone(), 3)
+        .with_rule(shift)
+        .with_rule(choose("other"))
+        .build();
+    auto.next_step();
+    assert_eq!(auto.state, grid::grid![[0, 0, 0][0, 1, 1][0, 0, 0]]);
+}
+
+#[test]
+fn gpl_colors_test() {
+    let path = "tests/fixtures/palette.gpl";
+    let auto = AutomatonBuilder::new()
+        .from_vec(vec![0], 1)
+        .with_colors_from_gpl(path, Some(&[5, 6, 7]))
+        .unwrap()
+        .build();
+    assert_eq!(auto.get_color(5), Some([0, 0, 0, 255]));
+    assert_eq!(auto.get_color(7), Some([224, 210, 159, 255]));
+    assert_eq!(auto.get_color(0), None);
+
+    assert!(AutomatonBuilder::new()
+        .with_colors_from_gpl(path, Some(&[0; 6]))
+        .is_err());
+
+    // names are characters, except for the last entry
+    assert!(matches!(
+        AutomatonBuilder::new().with_colors_from_gpl(path, None),
+        Err(crate::CelluminaError::ParseError { line: 11, .. })
+    ));
+    let dir = std::env::temp_dir().join("cellumina_gpl_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let named = dir.join("named.gpl");
+    let text = std::fs::read_to_string(path).unwrap();
+    std::fs::write(&named, text.replace("\tdeep sea", "\t_")).unwrap();
+    let auto = AutomatonBuilder::new()
+        .from_vec(vec![0], 1)
+        .with_colors_from_gpl(&named, None)
+        .unwrap()
+        .build();
+    assert_eq!(
+        auto.get_color(crate::char_to_id('X')),
+        Some([224, 210, 159, 255])
+    );
+    assert_eq!(
+        auto.get_color(crate::char_to_id('F')),
+        Some([255, 80, 0, 255])
+    );
+    assert_eq!(auto.get_color(1), Some([255, 255, 255, 255]));
+    assert_eq!(auto.get_color(126), Some([12, 34, 56, 255]));
+}
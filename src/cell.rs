@@ -0,0 +1,54 @@
+/// A type that can be used for the cells of an automaton's state grid.
+///
+/// Most of cellumina, including the text format, the built-in rules and the live view, works with [u8] cells, as described in the crate documentation.
+/// [AutomatonGeneric](crate::AutomatonGeneric) and [Rule](crate::rule::Rule) however accept any cell type, allowing more than 256 states or cells carrying multiple values.
+///
+/// This trait is implemented for all integer types and [bool].
+/// To use your own type, derive the required traits and implement it without any methods:
+/// ```
+/// #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// struct Material {
+///     kind: u8,
+///     temperature: i16,
+/// }
+///
+/// impl cellumina::Cell for Material {}
+/// ```
+pub trait Cell:
+    Copy + Eq + std::hash::Hash + Default + std::fmt::Debug + Send + Sync + 'static
+{
+    /// Returns the position of this cell in tables of up to 256 entries, if it has one.
+    ///
+    /// The colors of cells with such an index are looked up in a table instead of a hash map when creating images, which is considerably faster.
+    /// The default implementation returns [None].
+    fn lookup_index(self) -> Option<u8> {
+        None
+    }
+}
+
+impl Cell for u8 {
+    fn lookup_index(self) -> Option<u8> {
+        Some(self)
+    }
+}
+
+impl Cell for bool {
+    fn lookup_index(self) -> Option<u8> {
+        Some(self as u8)
+    }
+}
+
+/// Implements [Cell] for integer types, using the values ```0..256``` as lookup indices.
+macro_rules! impl_cell {
+    ($($t:ty),*) => {
+        $(
+            impl Cell for $t {
+                fn lookup_index(self) -> Option<u8> {
+                    u8::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_cell!(u16, u32, u64, usize, i8, i16, i32, i64, isize);
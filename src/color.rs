@@ -0,0 +1,229 @@
+/// A color of a cell, as used by [AutomatonBuilder::with_color](crate::AutomatonBuilder::with_color), image matching and the live view.
+///
+/// Colors are stored as 8-bit sRGB with straight (not premultiplied) alpha, the color space of image files and the values shown by design tools and color pickers.
+/// All places taking colors as ```[u8; 4]``` interpret them the same way, so arrays and [CellColor] can be converted into each other without any change.
+///
+/// The pipeline stages expect the following color spaces:
+///  - Colors set on the builder, default and gradient colors: sRGB, gradients interpolate in the perceptual Oklab space, see [interpolate_color](crate::convert::interpolate_color).
+///  - Image matching, such as [image_to_grid](crate::convert::image_to_grid): sRGB, compared directly with the bytes of the image's pixels.
+///  - Images of the state, such as [create_image_buffer](crate::AutomatonGeneric::create_image_buffer): sRGB, written directly as the bytes of the pixels.
+///  - Live view: the image of the state is uploaded unchanged into an sRGB texture, which the GPU converts to linear values when sampling.
+///    The palette and gradient color modes upload [linear](Self::to_linear_f32) colors instead, as they are used by the shader directly.
+///
+/// Colors calculated in a linear color space, for example by blending or lighting, should be created with [Self::from_linear_f32].
+/// ```
+/// use cellumina::CellColor;
+///
+/// let orange = CellColor::from_hex("#FF8000").unwrap();
+/// assert_eq!(orange, CellColor::from_srgb8([255, 128, 0, 255]));
+/// assert_eq!(orange.to_hex(), "#FF8000FF");
+///
+/// // arrays are sRGB as well
+/// assert_eq!(CellColor::from([255, 128, 0, 255]), orange);
+/// let auto = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![0, 1], 2)
+///     .with_color(0, orange)
+///     .with_color(1, [0, 0, 255, 255])
+///     .build();
+/// assert_eq!(auto.create_image_buffer().get_pixel(0, 0).0, [255, 128, 0, 255]);
+///
+/// // half the light of white is not the sRGB value 128
+/// let gray = CellColor::from_linear_f32([0.5, 0.5, 0.5, 1.]);
+/// assert_eq!(gray.to_srgb8(), [188, 188, 188, 255]);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellColor([u8; 4]);
+
+impl CellColor {
+    /// Creates a color from its red, green, blue and alpha values in 8-bit sRGB.
+    pub const fn from_srgb8(rgba: [u8; 4]) -> Self {
+        Self(rgba)
+    }
+
+    /// Creates a color from its red, green, blue and alpha values in linear color space from 0 to 1.
+    /// The color channels are converted to sRGB, while the alpha channel is only scaled. Values outside of ```[0, 1]``` are clamped.
+    pub fn from_linear_f32(rgba: [f32; 4]) -> Self {
+        let [r, g, b, a] = rgba;
+        let convert = |c: f32| {
+            let c = c.clamp(0., 1.);
+            let c = if c <= 0.003_130_8 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1. / 2.4) - 0.055
+            };
+            (c * 255.).round() as u8
+        };
+        Self([
+            convert(r),
+            convert(g),
+            convert(b),
+            (a.clamp(0., 1.) * 255.).round() as u8,
+        ])
+    }
+
+    /// Parses a color in hexadecimal notation, such as ```#FF8000``` or ```#FF800080```, with the channels in the order red, green, blue and, optionally, alpha.
+    /// The leading ```#``` is optional and both upper and lower case letters are accepted. Colors without an alpha channel are opaque.
+    /// ## Error
+    /// When the text does not consist of six or eight hexadecimal digits, with the column of the problem.
+    pub fn from_hex(hex: &str) -> Result<Self, crate::CelluminaError> {
+        let error = |column: usize, message: &str| crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::Color,
+            line: 1,
+            column,
+            message: message.to_string(),
+        };
+        let offset = usize::from(hex.starts_with('#'));
+        let digits = &hex[offset..];
+        if let Some((index, _)) = digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(error(offset + index + 1, "expected a hexadecimal digit"));
+        }
+        if digits.len() != 6 && digits.len() != 8 {
+            return Err(error(1, "expected six or eight hexadecimal digits"));
+        }
+
+        let mut rgba = [255; 4];
+        for (channel, index) in rgba.iter_mut().zip((0..digits.len()).step_by(2)) {
+            *channel = u8::from_str_radix(&digits[index..index + 2], 16)
+                .expect("Digits were checked to be hexadecimal.");
+        }
+        Ok(Self(rgba))
+    }
+
+    /// Returns the red, green, blue and alpha values of this color in 8-bit sRGB.
+    pub const fn to_srgb8(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// Returns the red, green, blue and alpha values of this color in linear color space from 0 to 1, as done by the GPU when sampling an sRGB texture.
+    /// The alpha channel is not converted, only scaled.
+    pub fn to_linear_f32(self) -> [f32; 4] {
+        let convert = |c: u8| {
+            let c = f32::from(c) / 255.;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let [r, g, b, a] = self.0;
+        [convert(r), convert(g), convert(b), f32::from(a) / 255.]
+    }
+
+    /// Returns this color in the hexadecimal notation ```#RRGGBBAA```, which [Self::from_hex] reads.
+    pub fn to_hex(self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<[u8; 4]> for CellColor {
+    fn from(rgba: [u8; 4]) -> Self {
+        Self(rgba)
+    }
+}
+
+impl From<CellColor> for [u8; 4] {
+    fn from(color: CellColor) -> Self {
+        color.0
+    }
+}
+
+impl From<image::Rgba<u8>> for CellColor {
+    fn from(pixel: image::Rgba<u8>) -> Self {
+        Self(pixel.0)
+    }
+}
+
+impl From<CellColor> for image::Rgba<u8> {
+    fn from(color: CellColor) -> Self {
+        image::Rgba(color.0)
+    }
+}
+
+impl std::str::FromStr for CellColor {
+    type Err = crate::CelluminaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+impl std::fmt::Display for CellColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [r, g, b, a] = self.0;
+        write!(f, "#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+#[test]
+fn cell_color_test() {
+    // hex parsing with and without alpha, hash and in either case
+    assert_eq!(
+        CellColor::from_hex("#12abEF").unwrap(),
+        CellColor::from_srgb8([0x12, 0xAB, 0xEF, 255])
+    );
+    assert_eq!(
+        CellColor::from_hex("12ABEF80").unwrap(),
+        CellColor::from_srgb8([0x12, 0xAB, 0xEF, 0x80])
+    );
+    assert_eq!(
+        "#00000000".parse::<CellColor>().unwrap(),
+        CellColor::default()
+    );
+    for (text, column) in [
+        ("#12ABE", 1),
+        ("#12ABEF8", 1),
+        ("", 1),
+        ("#12AG00", 5),
+        ("#ÄÄÄ", 2),
+    ] {
+        match CellColor::from_hex(text) {
+            Err(crate::CelluminaError::ParseError {
+                source_kind: crate::SourceKind::Color,
+                column: found,
+                ..
+            }) => assert_eq!(found, column, "{text}"),
+            result => panic!("{text} parsed as {result:?}"),
+        }
+    }
+
+    // round trips through all representations
+    for rgba in [
+        [0, 0, 0, 0],
+        [255, 255, 255, 255],
+        [1, 10, 128, 200],
+        [12, 34, 56, 78],
+    ] {
+        let color = CellColor::from(rgba);
+        assert_eq!(<[u8; 4]>::from(color), rgba);
+        assert_eq!(CellColor::from_hex(&color.to_hex()).unwrap(), color);
+        assert_eq!(CellColor::from_linear_f32(color.to_linear_f32()), color);
+        assert_eq!(CellColor::from(image::Rgba::from(color)), color);
+    }
+
+    // image matching works the same with arrays and colors
+    let image = image::RgbaImage::from_raw(2, 1, vec![255, 128, 0, 255, 0, 0, 255, 255]).unwrap();
+    let legacy =
+        std::collections::HashMap::from([(1u8, [255, 128, 0, 255]), (2, [0, 0, 255, 255])]);
+    let colors = std::collections::HashMap::from([
+        (1u8, CellColor::from_hex("#FF8000").unwrap()),
+        (2, CellColor::from_hex("#0000FF").unwrap()),
+    ]);
+    assert_eq!(
+        crate::convert::image_to_grid(&image, &legacy, 0),
+        grid::grid![[1, 2]]
+    );
+    assert_eq!(
+        crate::convert::image_to_grid(&image, &colors, 0),
+        grid::grid![[1, 2]]
+    );
+
+    // linear values are clamped and converted at the documented reference points
+    assert_eq!(
+        CellColor::from_linear_f32([-1., 0.5, 2., 0.5]).to_srgb8(),
+        [0, 188, 255, 128]
+    );
+    assert_eq!(
+        CellColor::from_srgb8([128, 0, 255, 51]).to_linear_f32()[..2],
+        [0.21586053, 0.]
+    );
+}
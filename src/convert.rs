@@ -0,0 +1,419 @@
+//! Conversions between grids of cells and images, as used when building automata from images and creating images of their state.
+use std::collections::HashMap;
+
+use crate::Cell;
+
+/// Turns an image into a grid by finding the cell of each pixel's color, with one row per row of pixels.
+///
+/// A pixel matches a color if none of its channels differ by more than ```tolerance``` from it, so a tolerance of 0 requires exact matches.
+/// If multiple colors match, the pixel becomes the cell of the closest one. Pixels without any matching color become the default cell, 0 for [u8].
+///
+/// Colors are compared as 8-bit sRGB, the color space of the pixels of images, see [CellColor](crate::CellColor).
+/// ```
+/// # use std::collections::HashMap;
+/// let colors = HashMap::from([(1, [255, 255, 255, 255]), (2, [255, 0, 0, 255])]);
+/// let image = image::RgbaImage::from_raw(3, 1, vec![250, 250, 250, 255, 255, 0, 0, 255, 0, 0, 255, 255]).unwrap();
+/// assert_eq!(cellumina::convert::image_to_grid(&image, &colors, 0), grid::grid![[0, 2, 0]]);
+/// assert_eq!(cellumina::convert::image_to_grid(&image, &colors, 10), grid::grid![[1, 2, 0]]);
+///
+/// let colors = HashMap::from([(3, cellumina::CellColor::from_hex("#0000FF").unwrap())]);
+/// assert_eq!(cellumina::convert::image_to_grid(&image, &colors, 0), grid::grid![[0, 0, 3]]);
+/// ```
+pub fn image_to_grid<T: Cell, C: Into<crate::CellColor> + Copy>(
+    image: &image::RgbaImage,
+    colors: &HashMap<T, C>,
+    tolerance: u8,
+) -> grid::Grid<T> {
+    image_to_grid_counted(image, colors, tolerance).0
+}
+
+/// Turns an image into a grid like [image_to_grid], additionally returning the number of pixels that did not match any color.
+pub(crate) fn image_to_grid_counted<T: Cell, C: Into<crate::CellColor> + Copy>(
+    image: &image::RgbaImage,
+    colors: &HashMap<T, C>,
+    tolerance: u8,
+) -> (grid::Grid<T>, usize) {
+    // sort the colors, so ties between equally close colors are always broken the same way
+    let mut palette = colors
+        .iter()
+        .map(|(&cell, &color)| (color.into().to_srgb8(), cell))
+        .collect::<Vec<([u8; 4], T)>>();
+    palette.sort_by_key(|&(color, _)| color);
+
+    // look up cells by color, remembering the result for every distinct pixel color
+    let mut cells = palette
+        .iter()
+        .map(|&(color, cell)| (color, Some(cell)))
+        .collect::<HashMap<[u8; 4], Option<T>>>();
+
+    let mut unmatched = 0;
+    let grid = grid::Grid::from_vec(
+        image
+            .pixels()
+            .map(|pixel| {
+                let cell = *cells
+                    .entry(pixel.0)
+                    .or_insert_with(|| closest(&palette, pixel.0, tolerance));
+                cell.unwrap_or_else(|| {
+                    unmatched += 1;
+                    T::default()
+                })
+            })
+            .collect(),
+        image.width() as usize,
+    );
+    (grid, unmatched)
+}
+
+/// Returns the cell of the color in the palette closest to the passed one, if any is within the tolerance.
+fn closest<T: Cell>(palette: &[([u8; 4], T)], pixel: [u8; 4], tolerance: u8) -> Option<T> {
+    palette
+        .iter()
+        .map(|&(color, cell)| {
+            let differences = color.iter().zip(pixel).map(|(&a, b)| a.abs_diff(b));
+            (
+                differences.clone().max().unwrap_or_default(),
+                differences.map(u32::from).sum::<u32>(),
+                cell,
+            )
+        })
+        .filter(|&(max, _, _)| max <= tolerance)
+        .min_by_key(|&(max, sum, _)| (max, sum))
+        .map(|(_, _, cell)| cell)
+}
+
+/// Turns a grid into an image, with one pixel of the cell's color per cell. Cells without a color become transparent black.
+/// ```
+/// # use std::collections::HashMap;
+/// let colors = HashMap::from([(1, [255, 255, 255, 255])]);
+/// let image = cellumina::convert::grid_to_image(&grid::grid![[1, 0]], &colors);
+/// assert_eq!(image.into_raw(), [255, 255, 255, 255, 0, 0, 0, 0]);
+/// ```
+pub fn grid_to_image<T: Cell>(
+    grid: &grid::Grid<T>,
+    colors: &HashMap<T, [u8; 4]>,
+) -> image::RgbaImage {
+    let cache = crate::automaton::ImageCache::new(colors, [0; 4]);
+    image_from_bytes(grid, cache.bytes(grid).to_vec())
+}
+
+/// Writes the symbol of each cell into the alpha channel of its pixel, so the grid can be read back from the image with [decode_alpha] even if several symbols share a color.
+/// The image has to have the same dimensions as the grid. Pixels of cell 0 become fully transparent, but keep their color.
+/// ```
+/// # use std::collections::HashMap;
+/// let grid = grid::grid![[1, 2, 0]];
+/// // both symbols are white
+/// let colors = HashMap::from([(1, [255; 4]), (2, [255; 4])]);
+/// let mut image = cellumina::convert::grid_to_image(&grid, &colors);
+/// cellumina::convert::encode_alpha(&mut image, &grid);
+/// assert_eq!(image.get_pixel(1, 0).0, [255, 255, 255, 2]);
+/// assert_eq!(cellumina::convert::decode_alpha(&image), grid);
+/// ```
+pub fn encode_alpha(image: &mut image::RgbaImage, grid: &crate::CellGrid) {
+    for (pixel, &cell) in image.pixels_mut().zip(grid.iter()) {
+        pixel.0[3] = cell;
+    }
+}
+
+/// Turns an image into a grid by reading the symbol of each pixel from its alpha channel, as written by [encode_alpha], with one row per row of pixels.
+pub fn decode_alpha(image: &image::RgbaImage) -> crate::CellGrid {
+    grid::Grid::from_vec(
+        image.pixels().map(|pixel| pixel.0[3]).collect(),
+        image.width() as usize,
+    )
+}
+
+/// Interpolates between two colors, returning ```from``` for ```t = 0``` and ```to``` for ```t = 1```. Values of ```t``` outside of ```[0, 1]``` are clamped.
+///
+/// The color channels are interpolated in the perceptual Oklab color space, so the brightness of a gradient changes evenly, while the alpha channel is interpolated linearly.
+/// ```
+/// let (black, white) = ([0, 0, 0, 255], [255, 255, 255, 255]);
+/// assert_eq!(cellumina::convert::interpolate_color(black, white, 0.), black);
+/// assert_eq!(cellumina::convert::interpolate_color(black, white, 1.), white);
+/// assert_eq!(cellumina::convert::interpolate_color(black, white, 0.5), [99, 99, 99, 255]);
+/// ```
+pub fn interpolate_color(from: [u8; 4], to: [u8; 4], t: f32) -> [u8; 4] {
+    let t = t.clamp(0., 1.);
+    let (from_lab, to_lab) = (srgb_to_oklab(from), srgb_to_oklab(to));
+    let lab = [0, 1, 2].map(|i| from_lab[i] + (to_lab[i] - from_lab[i]) * t);
+    let [r, g, b] = oklab_to_srgb(lab);
+    let alpha = f32::from(from[3]) + (f32::from(to[3]) - f32::from(from[3])) * t;
+    [r, g, b, alpha.round() as u8]
+}
+
+/// Parses a palette in the GIMP palette (```.gpl```) format, returning the line (starting at 1), opaque color and name of each entry in order.
+/// Entries without a name have an empty name.
+///
+/// The first line has to be ```GIMP Palette```, followed by optional ```Name:``` and ```Columns:``` lines and one entry per line,
+/// consisting of the red, green and blue values and an optional name. Empty lines and lines starting with ```#``` are ignored.
+pub(crate) fn parse_gpl(
+    text: &str,
+) -> Result<Vec<(usize, [u8; 4], String)>, crate::CelluminaError> {
+    let error = |line: usize, message: &str| crate::CelluminaError::ParseError {
+        source_kind: crate::SourceKind::Palette,
+        line,
+        column: 1,
+        message: message.to_string(),
+    };
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+    match lines.next() {
+        Some((_, "GIMP Palette")) => {}
+        _ => return Err(error(1, "expected the header \"GIMP Palette\"")),
+    }
+
+    let mut entries = Vec::new();
+    for (line, content) in lines {
+        if content.is_empty()
+            || content.starts_with('#')
+            || content.starts_with("Name:")
+            || content.starts_with("Columns:")
+        {
+            continue;
+        }
+        let mut parts = content.split_whitespace();
+        let mut color = [0, 0, 0, 255];
+        for channel in color.iter_mut().take(3) {
+            *channel = parts
+                .next()
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| error(line, "expected three color values from 0 to 255"))?;
+        }
+        entries.push((line, color, parts.collect::<Vec<_>>().join(" ")));
+    }
+    Ok(entries)
+}
+
+/// Converts the color channels of an sRGB color to Oklab, see <https://bottosson.github.io/posts/oklab/>.
+fn srgb_to_oklab(color: [u8; 4]) -> [f32; 3] {
+    let [r, g, b, _] = crate::CellColor::from(color).to_linear_f32();
+    let [l, m, s] = [
+        0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b,
+        0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b,
+        0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b,
+    ]
+    .map(f32::cbrt);
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// Converts an Oklab color back to the color channels of an sRGB color, rounding and clamping them.
+fn oklab_to_srgb([l, a, b]: [f32; 3]) -> [u8; 3] {
+    let [l, m, s] = [
+        l + 0.396_337_78 * a + 0.215_803_76 * b,
+        l - 0.105_561_346 * a - 0.063_854_17 * b,
+        l - 0.089_484_18 * a - 1.291_485_5 * b,
+    ]
+    .map(|c| c * c * c);
+    let [r, g, b, _] = crate::CellColor::from_linear_f32([
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        1.,
+    ])
+    .to_srgb8();
+    [r, g, b]
+}
+
+/// Wraps the RGBA pixel data of the passed grid, as created by an image cache, into an image.
+pub(crate) fn image_from_bytes<T: Cell>(grid: &grid::Grid<T>, bytes: Vec<u8>) -> image::RgbaImage {
+    image::RgbaImage::from_raw(grid.cols() as u32, grid.rows() as u32, bytes)
+        .expect("Image data does not match the grid size.")
+}
+
+/// Turns a grid into a two-dimensional array. The rows of the grid map to axis 0 and its columns to axis 1, so ```grid[row][col] == array[[row, col]]```.
+#[cfg(feature = "ndarray")]
+pub fn grid_to_ndarray<T: Cell>(grid: &grid::Grid<T>) -> ndarray::Array2<T> {
+    ndarray::Array2::from_shape_vec(grid.size(), grid.iter().copied().collect())
+        .expect("Grid data does not match the grid size.")
+}
+
+/// Turns a two-dimensional array of any memory layout into a grid. Axis 0 of the array maps to the rows of the grid and axis 1 to its columns, so ```grid[row][col] == array[[row, col]]```.
+#[cfg(feature = "ndarray")]
+pub fn ndarray_to_grid<T: Cell>(array: ndarray::ArrayView2<T>) -> grid::Grid<T> {
+    // iter() visits the elements in logical row-major order, regardless of the memory layout
+    grid::Grid::from_vec(array.iter().copied().collect(), array.ncols())
+}
+
+#[test]
+fn round_trip_test() {
+    let colors = HashMap::from([
+        (0, [0, 0, 0, 255]),
+        (1, [255, 255, 255, 255]),
+        (59, [224, 210, 159, 255]),
+    ]);
+    let grid = grid::grid![[0, 1, 59][59, 59, 0][1, 0, 1][0, 0, 0]];
+
+    let image = grid_to_image(&grid, &colors);
+    assert_eq!(image.dimensions(), (3, 4));
+    assert_eq!(image.get_pixel(2, 0).0, [224, 210, 159, 255]);
+    assert_eq!(image_to_grid(&image, &colors, 0), grid);
+    assert_eq!(image_to_grid(&image, &colors, 255), grid);
+
+    // the automaton creates the same image
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid.clone())
+        .with_colors(colors.clone())
+        .build();
+    assert_eq!(automaton.create_image_buffer(), image);
+
+    // and reads it back
+    automaton.fill(1);
+    assert!(automaton.load_state_from_image(&image, 0).unwrap());
+    assert_eq!(automaton.state(), &grid);
+    assert!(!automaton.load_state_from_image(&image, 0).unwrap());
+    assert!(matches!(
+        automaton.load_state_from_image(&image::RgbaImage::new(4, 3), 0),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (4, 3),
+            got: (3, 4)
+        })
+    ));
+}
+
+#[test]
+fn tolerance_test() {
+    let colors = HashMap::from([(1, [200, 0, 0, 255]), (2, [220, 0, 0, 255])]);
+    let pixels = [
+        [200, 0, 0, 255],
+        [205, 4, 0, 255],
+        [212, 0, 0, 255],
+        [210, 0, 0, 255],
+        [240, 0, 0, 255],
+        [200, 0, 0, 0],
+    ];
+    let image = image::RgbaImage::from_raw(6, 1, pixels.concat()).unwrap();
+
+    // exact matches only
+    assert_eq!(
+        image_to_grid_counted(&image, &colors, 0),
+        (grid::grid![[1, 0, 0, 0, 0, 0]], 5)
+    );
+    // the closest color wins, ties go to the smaller color
+    assert_eq!(
+        image_to_grid_counted(&image, &colors, 10),
+        (grid::grid![[1, 1, 2, 1, 0, 0]], 2)
+    );
+    // the alpha channel counts as well
+    assert_eq!(
+        image_to_grid_counted(&image, &colors, 255),
+        (grid::grid![[1, 1, 2, 1, 2, 1]], 0)
+    );
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn ndarray_test() {
+    let grid = grid::grid![[1, 2, 3, 4][5, 6, 7, 8][9, 10, 11, 12]];
+
+    // rows map to axis 0, columns to axis 1
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid.clone())
+        .build();
+    let array = automaton.to_ndarray();
+    assert_eq!(array.dim(), (3, 4));
+    for row in 0..3 {
+        for col in 0..4 {
+            assert_eq!(array[[row, col]], grid[row][col]);
+        }
+    }
+    assert_eq!(array.row(1).to_vec(), [5, 6, 7, 8]);
+
+    // building and loading agree with grid indexing, also for transposed (column-major) views
+    let built = crate::AutomatonBuilder::new()
+        .from_ndarray(array.view())
+        .build();
+    assert_eq!(built.state(), &grid);
+
+    let transposed = array.t().to_owned();
+    assert_eq!(transposed.dim(), (4, 3));
+    assert_eq!(
+        ndarray_to_grid(transposed.t()),
+        grid,
+        "layout of the view must not matter"
+    );
+
+    let mut flipped = array.clone();
+    flipped.invert_axis(ndarray::Axis(0));
+    assert!(automaton.load_state_from_ndarray(flipped.view()).unwrap());
+    assert_eq!(automaton.state()[0], [9, 10, 11, 12]);
+    assert_eq!(automaton.state()[2], [1, 2, 3, 4]);
+
+    assert!(matches!(
+        automaton.load_state_from_ndarray(transposed.view()),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (3, 4),
+            got: (4, 3)
+        })
+    ));
+    assert_eq!(automaton.state()[0], [9, 10, 11, 12]);
+}
+
+#[test]
+fn interpolate_color_test() {
+    let colors = [
+        [0, 0, 0, 0],
+        [255, 255, 255, 255],
+        [255, 0, 0, 255],
+        [0, 128, 255, 100],
+        [12, 200, 37, 255],
+    ];
+    // every color survives the round trip through Oklab
+    for from in colors {
+        for to in colors {
+            assert_eq!(interpolate_color(from, to, 0.), from);
+            assert_eq!(interpolate_color(from, to, 1.), to);
+            assert_eq!(interpolate_color(from, to, -3.), from);
+        }
+    }
+    // the midpoint of a gradient between equal colors is that color, alpha is interpolated linearly
+    assert_eq!(
+        interpolate_color([255, 0, 0, 0], [255, 0, 0, 200], 0.5),
+        [255, 0, 0, 100]
+    );
+    // the midpoint of black and white is a gray of half the lightness, which is darker than the average of the channels
+    assert_eq!(
+        interpolate_color([0, 0, 0, 255], [255, 255, 255, 255], 0.5),
+        [99, 99, 99, 255]
+    );
+}
+
+#[test]
+fn gpl_test() {
+    let palette = std::fs::read_to_string("tests/fixtures/palette.gpl").unwrap();
+    assert_eq!(
+        parse_gpl(&palette).unwrap(),
+        vec![
+            (6, [0, 0, 0, 255], "0".to_string()),
+            (7, [255, 255, 255, 255], "1".to_string()),
+            (9, [224, 210, 159, 255], "X".to_string()),
+            (10, [255, 80, 0, 255], "F".to_string()),
+            (11, [12, 34, 56, 255], "deep sea".to_string()),
+        ]
+    );
+
+    // malformed lines are reported with their line number
+    for (text, line) in [
+        ("Palette\n0 0 0", 1),
+        ("GIMP Palette\n0 0 0\n\n0 0", 4),
+        ("GIMP Palette\n# comment\n0 0 256 red", 3),
+        ("GIMP Palette\nred 0 0", 2),
+    ] {
+        assert!(
+            matches!(
+                parse_gpl(text),
+                Err(crate::CelluminaError::ParseError {
+                    source_kind: crate::SourceKind::Palette,
+                    line: l,
+                    ..
+                }) if l == line
+            ),
+            "{text:?}"
+        );
+    }
+}
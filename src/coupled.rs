@@ -0,0 +1,256 @@
+use std::{collections::HashMap, time};
+
+use crate::{automaton::StepMode, Automaton, CellGrid};
+
+/// A rule transforming the state of one automaton of [CoupledAutomata] with read access to the states of all others.
+///
+/// Closures taking the own state and the other states by name implement this trait, so simple couplings need no type of their own.
+pub trait CoupledRule: Send + Sync {
+    /// Transforms the own state in-place. ```others``` contains the states of all other automata as they were before the current step, by name.
+    fn transform(&self, own: &mut CellGrid, others: &HashMap<String, &CellGrid>);
+}
+
+impl<F> CoupledRule for F
+where
+    F: Fn(&mut CellGrid, &HashMap<String, &CellGrid>) + Send + Sync,
+{
+    fn transform(&self, own: &mut CellGrid, others: &HashMap<String, &CellGrid>) {
+        self(own, others)
+    }
+}
+
+/// A single automaton of [CoupledAutomata] with its name and coupled rule.
+struct Member {
+    /// The name other rules find the state of the automaton under.
+    name: String,
+    /// The automaton.
+    automaton: Automaton,
+    /// The rule reading the other automata.
+    rule: Box<dyn CoupledRule>,
+}
+
+/// Multiple named automata evolving side by side on grids of their own, such as predators and their prey, where each automaton's rule may read the states of the others.
+///
+/// In each time step, the states of all automata are copied first.
+/// Then every automaton applies its [CoupledRule], which reads the copied states of the others, followed by its own rule.
+/// So all coupled rules see the states of the previous step, no matter the order the automata were added in.
+///
+/// The step modes of the individual automata are ignored in favor of the one set for the container with [CoupledAutomata::with_min_time_step].
+/// ```
+/// # use cellumina::{CellGrid, CoupledAutomata};
+/// # use std::collections::HashMap;
+/// let automaton = |cells| cellumina::AutomatonBuilder::new().from_vec(cells, 2).build();
+/// let mut coupled = CoupledAutomata::new()
+///     .with_automaton("prey", automaton(vec![1, 1, 0, 1]), |_: &mut CellGrid, _: &HashMap<String, &CellGrid>| {})
+///     .with_automaton(
+///         "predators",
+///         automaton(vec![0, 0, 0, 0]),
+///         |own: &mut CellGrid, others: &HashMap<String, &CellGrid>| {
+///             // predators appear wherever there was prey
+///             for (cell, prey) in own.iter_mut().zip(others["prey"].iter()) {
+///                 *cell = *prey;
+///             }
+///         },
+///     );
+/// coupled.next_step();
+/// assert_eq!(coupled.get("predators").unwrap().state(), &grid::grid![[1, 1][0, 1]]);
+/// ```
+pub struct CoupledAutomata {
+    /// The automata, in the order they were added.
+    members: Vec<Member>,
+    /// Wether steps are performed on every call to [CoupledAutomata::next_step] or only after a minimum time.
+    step_mode: StepMode,
+    /// The time at which the container was stepped for the first time or the last step was performed.
+    last_step: Option<time::Instant>,
+}
+
+impl std::fmt::Debug for CoupledAutomata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoupledAutomata")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .field("step_mode", &self.step_mode)
+            .finish()
+    }
+}
+
+impl Default for CoupledAutomata {
+    /// Creates an empty container, see [CoupledAutomata::new].
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoupledAutomata {
+    /// Creates a container without any automata, performing steps on every call to [CoupledAutomata::next_step].
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            step_mode: StepMode::Immediate,
+            last_step: None,
+        }
+    }
+
+    /// Adds an automaton under the passed name, transformed by the passed coupled rule before its own rule in each step.
+    /// An automaton already added under the same name is replaced.
+    pub fn with_automaton(
+        mut self,
+        name: &str,
+        automaton: Automaton,
+        rule: impl CoupledRule + 'static,
+    ) -> Self {
+        let member = Member {
+            name: name.to_string(),
+            automaton,
+            rule: Box::new(rule),
+        };
+        match self.members.iter_mut().find(|member| member.name == name) {
+            Some(existing) => {
+                log::warn!("Replaced the coupled automaton named {name:?}.");
+                *existing = member;
+            }
+            None => self.members.push(member),
+        }
+        self
+    }
+
+    /// Only perform a step if at least ```interval``` has elapsed since the previous one, see [AutomatonBuilder::with_min_time_step](crate::AutomatonBuilder::with_min_time_step).
+    pub fn with_min_time_step(mut self, interval: time::Duration) -> Self {
+        self.step_mode = StepMode::Limited { interval };
+        self
+    }
+
+    /// Returns the names of the automata, in the order they were added.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|member| member.name.as_str())
+    }
+
+    /// Returns the automaton with the passed name, if any.
+    pub fn get(&self, name: &str) -> Option<&Automaton> {
+        self.members
+            .iter()
+            .find(|member| member.name == name)
+            .map(|member| &member.automaton)
+    }
+
+    /// Returns the automaton with the passed name, if any, for example to set cells manually.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Automaton> {
+        self.members
+            .iter_mut()
+            .find(|member| member.name == name)
+            .map(|member| &mut member.automaton)
+    }
+
+    /// Performs a time step of all automata if permitted by the step mode, see [Automaton::next_step](crate::Automaton::next_step).
+    /// Returns wether a step was performed.
+    pub fn next_step(&mut self) -> bool {
+        let last_step = *self.last_step.get_or_insert_with(time::Instant::now);
+        if let StepMode::Limited { interval } = self.step_mode {
+            if last_step.elapsed() < interval {
+                return false;
+            }
+        }
+        self.step();
+        self.last_step = Some(time::Instant::now());
+        true
+    }
+
+    /// Performs a single time step of all automata, regardless of the step mode.
+    fn step(&mut self) {
+        let snapshots = self
+            .members
+            .iter()
+            .map(|member| (member.name.clone(), member.automaton.state().clone()))
+            .collect::<Vec<_>>();
+
+        for (index, member) in self.members.iter_mut().enumerate() {
+            let others = snapshots
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index)
+                .map(|(_, (name, state))| (name.clone(), state))
+                .collect::<HashMap<_, _>>();
+            let mut own = snapshots[index].1.clone();
+            member.rule.transform(&mut own, &others);
+            if own.size() == snapshots[index].1.size() {
+                // the sizes match, so this can not fail
+                let _ = member.automaton.set_region(0, 0, &own);
+            } else {
+                log::error!(
+                    "The coupled rule of {:?} changed the size of its state from {:?} to {:?}, discarding the result.",
+                    member.name,
+                    snapshots[index].1.size(),
+                    own.size()
+                );
+            }
+            member.automaton.perform_step();
+        }
+    }
+}
+
+#[test]
+fn coupled_automata_test() {
+    use crate::rule;
+
+    let automaton = |cells| {
+        crate::AutomatonBuilder::new()
+            .from_vec(cells, 3)
+            .with_min_time_step(time::Duration::from_secs(1000))
+            .build()
+    };
+    // copies the state of another automaton
+    let copy = |from: &'static str| {
+        move |own: &mut CellGrid, others: &HashMap<String, &CellGrid>| {
+            *own = others[from].clone();
+        }
+    };
+
+    // both automata read the state before the step, so they swap instead of both ending up equal
+    let mut coupled = CoupledAutomata::new()
+        .with_automaton("a", automaton(vec![1, 0, 0]), copy("b"))
+        .with_automaton("b", automaton(vec![0, 0, 2]), copy("a"));
+    assert_eq!(coupled.names().collect::<Vec<_>>(), ["a", "b"]);
+    for step in 1..=3 {
+        assert!(coupled.next_step());
+        let (a, b) = (coupled.get("a").unwrap(), coupled.get("b").unwrap());
+        if step % 2 == 1 {
+            assert_eq!(a.state(), &grid::grid![[0, 0, 2]]);
+            assert_eq!(b.state(), &grid::grid![[1, 0, 0]]);
+        } else {
+            assert_eq!(a.state(), &grid::grid![[1, 0, 0]]);
+            assert_eq!(b.state(), &grid::grid![[0, 0, 2]]);
+        }
+        // the step modes of the automata themselves are ignored
+        assert_eq!(a.generation(), step);
+    }
+
+    // the own rule is applied after the coupled rule
+    let shifting = crate::AutomatonBuilder::new()
+        .from_vec(vec![0, 0, 0], 3)
+        .with_rule(rule::ShiftRule {
+            dy: 0,
+            dx: 1,
+            fill: Some(0),
+        })
+        .build();
+    let mut coupled = coupled.with_automaton("b", shifting, copy("a"));
+    assert_eq!(coupled.names().collect::<Vec<_>>(), ["a", "b"]);
+    coupled.get_mut("a").unwrap().set_cell(0, 0, 5).unwrap();
+    assert!(coupled.next_step());
+    assert_eq!(coupled.get("b").unwrap().state(), &grid::grid![[0, 5, 0]]);
+    assert!(coupled.get("c").is_none());
+
+    // with a minimum time step, the first call only starts the clock
+    let mut coupled = coupled.with_min_time_step(time::Duration::from_secs(1000));
+    coupled.last_step = None;
+    assert!(!coupled.next_step());
+    assert_eq!(coupled.get("b").unwrap().generation(), 1);
+
+    // rules changing the size of their state are ignored
+    let mut coupled = CoupledAutomata::new().with_automaton(
+        "a",
+        automaton(vec![1, 2, 3]),
+        |own: &mut CellGrid, _: &HashMap<String, &CellGrid>| *own = CellGrid::new(1, 1),
+    );
+    coupled.next_step();
+    assert_eq!(coupled.get("a").unwrap().state(), &grid::grid![[1, 2, 3]]);
+}
@@ -6,6 +6,38 @@ pub enum CelluminaError {
     /// Any different type of error.
     #[error("{0}")]
     CustomError(String),
+    /// A character that has no associated cell value.
+    #[error("unknown cell character {0:?}")]
+    UnknownCharacterError(char),
+    /// A text could not be parsed, with the line and column (both starting at 1) of the problem.
+    #[error("could not parse {source_kind} in line {line}, column {column}: {message}")]
+    ParseError {
+        /// What kind of text was parsed.
+        source_kind: SourceKind,
+        /// The line of the problem.
+        line: usize,
+        /// The column of the problem.
+        column: usize,
+        /// A description of the problem.
+        message: String,
+    },
+    /// A configuration was read successfully, but is not valid.
+    #[error("invalid configuration: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    ValidationError(Vec<ValidationIssue>),
+    /// A file format that can not be read or written.
+    #[error("unsupported file format: {0}")]
+    UnsupportedFormat(String),
+    /// A state in the binary ```.cels``` or ```.celz``` format that is truncated or otherwise damaged.
+    #[error("invalid binary state: {0}")]
+    InvalidBinary(String),
+    /// Two grids that need to have the same dimensions (rows, columns) do not.
+    #[error("expected dimensions {expected:?}, got {got:?}")]
+    DimensionMismatch {
+        /// The expected dimensions.
+        expected: (usize, usize),
+        /// The actual dimensions.
+        got: (usize, usize),
+    },
     /// An Index-Out-Of-Bounds-Error when accessing the underlying state grid of an automaton.
     #[error("index ({0}, {1}) out of bounds for state grid of size ({2}, {3})")]
     IndexOutOfBoundsError(u32, u32, u32, u32),
@@ -15,4 +47,125 @@ pub enum CelluminaError {
     /// Error passed on from the image crate.
     #[error(transparent)]
     ImageError(#[from] image::error::ImageError),
+    /// Error setting up or using the GPU.
+    #[error("GPU error: {0}")]
+    GpuError(String),
+    /// The window of the live view could not be set up, for example because there is no display or no GPU adapter is available, see [GraphicsInitError].
+    #[error("could not initialize the live view: {0}")]
+    GraphicsInitError(#[from] GraphicsInitError),
+    /// A pixel of an image, at the passed column (```x```) and row (```y```), has a color that belongs to no symbol.
+    #[error("pixel ({x}, {y}) has the color {color:?}, which belongs to no symbol")]
+    UnmatchedPixel {
+        /// The column of the pixel.
+        x: u32,
+        /// The row of the pixel.
+        y: u32,
+        /// The color of the pixel.
+        color: [u8; 4],
+    },
+    /// An automaton was supposed to step back, but its rule has no [inverse](crate::rule::Rule::inverse).
+    #[error("the rule of the automaton can not be reversed")]
+    IrreversibleRule,
+    /// An automaton was supposed to step back from its initial generation.
+    #[error("the automaton is at generation 0 and can not step back")]
+    InitialGeneration,
+}
+
+/// Why the window of the live view could not be set up, see [CelluminaError::GraphicsInitError].
+#[derive(Debug, Error)]
+pub enum GraphicsInitError {
+    /// No windowing system could be connected to, for example on a headless CI machine, so no window can be opened at all.
+    /// Holds the description of the problem given by the windowing library.
+    #[error("no display available: {0}")]
+    NoDisplay(String),
+    /// The window, its surface or the GPU adapter or device could not be created, with the underlying cause.
+    #[error("{0}")]
+    Setup(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The kinds of text cellumina parses, used to describe where a [CelluminaError::ParseError] occured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// An initial state from a text file.
+    State,
+    /// A [PatternRule](crate::rule::PatternRule) in the ```.cel``` format.
+    PatternRule,
+    /// A [LifeLikeRule](crate::rule::LifeLikeRule) in the B/S notation.
+    RuleNotation,
+    /// A Lua script of a ```ScriptedEnvironmentRule```, only available with the ```scripting``` feature.
+    Script,
+    /// A palette in the GIMP palette (```.gpl```) format.
+    Palette,
+    /// A color in hexadecimal notation, see [CellColor::from_hex](crate::CellColor::from_hex).
+    Color,
+}
+
+impl std::fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceKind::State => write!(f, "state"),
+            SourceKind::PatternRule => write!(f, "pattern rule"),
+            SourceKind::RuleNotation => write!(f, "rule notation"),
+            SourceKind::Script => write!(f, "script"),
+            SourceKind::Palette => write!(f, "palette"),
+            SourceKind::Color => write!(f, "color"),
+        }
+    }
+}
+
+/// A single problem found when validating a configuration, see [CelluminaError::ValidationError].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ValidationIssue {
+    /// The ```before``` and ```after``` grids of the pattern with the passed index differ in size.
+    #[error("pattern {pattern} replaces a {before:?} grid with a {after:?} grid")]
+    PatternSizeMismatch {
+        /// The index of the pattern within its rule.
+        pattern: usize,
+        /// The dimensions of the ```before``` grid.
+        before: (usize, usize),
+        /// The dimensions of the ```after``` grid.
+        after: (usize, usize),
+    },
+    /// The pattern with the passed index contains no cells.
+    #[error("pattern {pattern} is empty")]
+    EmptyPattern {
+        /// The index of the pattern within its rule.
+        pattern: usize,
+    },
+    /// The pattern with the passed index has a priority that is not a finite number.
+    #[error("pattern {pattern} has a priority that is not a finite number")]
+    InvalidPriority {
+        /// The index of the pattern within its rule.
+        pattern: usize,
+    },
+    /// The pattern with the passed index replaces a cell matching the symbol of a boundary it can match against, see [PatternBoundaryMode::MatchSymbol](crate::rule::PatternBoundaryMode::MatchSymbol).
+    #[error("pattern {pattern} replaces a cell matching the boundary symbol, which has to be a wildcard in its after grid")]
+    BoundaryReplacement {
+        /// The index of the pattern within its rule.
+        pattern: usize,
+    },
+    /// The pattern with the passed index scales its chance by a factor that is not a finite number.
+    #[error("pattern {pattern} has a chance factor that is not a finite number")]
+    InvalidChanceFactor {
+        /// The index of the pattern within its rule.
+        pattern: usize,
+    },
+    /// A pattern rule weighs its priorities with a temperature that is not a positive finite number, see [PriorityMode::Softmax](crate::rule::PriorityMode::Softmax).
+    #[error("priority temperatures need to be positive finite numbers")]
+    InvalidTemperature,
+    /// An image was supposed to be converted to cells without any colors to match its pixels against.
+    #[error("no colors to match the image against, add them with AutomatonBuilder::with_color")]
+    EmptyPalette,
+    /// A grid was supposed to be created with zero columns.
+    #[error("grids need at least one column")]
+    ZeroColumns,
+    /// A speed multiplier that is not a positive finite number.
+    #[error("speed multipliers need to be positive finite numbers")]
+    InvalidSpeedMultiplier,
+    /// Multiple symbols were given the passed name, so they can not be told apart by it.
+    #[error("the name {name:?} is used for multiple symbols")]
+    DuplicateSymbolName {
+        /// The name used more than once.
+        name: String,
+    },
 }
@@ -0,0 +1,344 @@
+//! A minimal C interface for running automata from other languages, enabled by the ```ffi``` feature.
+//!
+//! Automata are assembled from a [CelluminaBuilder], which is turned into a [CelluminaAutomaton] by [cellumina_builder_build].
+//! Both are opaque handles that have to be released with [cellumina_builder_free] and [cellumina_automaton_free].
+//! All other functions return a [CelluminaStatus], which is ```CELLUMINA_STATUS_OK``` (0) on success.
+//! Panics, for example in a rule, never unwind into the calling language, but are reported as ```CELLUMINA_STATUS_PANIC```.
+//!
+//! The header ```include/cellumina.h``` declares all functions and is generated with [cbindgen](https://github.com/mozilla/cbindgen):
+//! ```text
+//! cbindgen --config cbindgen.toml --crate cellumina --output include/cellumina.h
+//! ```
+//!
+//! To load the interface from other languages, build cellumina as a C dynamic library with the ```ffi``` feature.
+//! Only this build produces the dynamic library, normal builds of cellumina, for example as a dependency, do not:
+//! ```text
+//! cargo rustc --release --lib --features ffi --crate-type cdylib
+//! ```
+//! The library can then be loaded from Python using ```ctypes```:
+//! ```python
+//! import ctypes
+//!
+//! lib = ctypes.CDLL("target/release/libcellumina.so")
+//! lib.cellumina_builder_new.restype = ctypes.c_void_p
+//! lib.cellumina_builder_set_state.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t, ctypes.c_size_t]
+//! lib.cellumina_builder_add_life_like_rule.argtypes = [ctypes.c_void_p, ctypes.c_char_p]
+//! lib.cellumina_builder_build.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_void_p)]
+//! lib.cellumina_automaton_step.argtypes = [ctypes.c_void_p, ctypes.c_uint64]
+//! lib.cellumina_automaton_read_state.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t]
+//! lib.cellumina_automaton_free.argtypes = [ctypes.c_void_p]
+//!
+//! builder = lib.cellumina_builder_new()
+//! lib.cellumina_builder_set_state(builder, bytes([0, 1, 0] * 3), 9, 3)
+//! lib.cellumina_builder_add_life_like_rule(builder, b"B3/S23")
+//! automaton = ctypes.c_void_p()
+//! assert lib.cellumina_builder_build(builder, ctypes.byref(automaton)) == 0
+//!
+//! lib.cellumina_automaton_step(automaton, 1)
+//! state = ctypes.create_string_buffer(9)
+//! lib.cellumina_automaton_read_state(automaton, state, 9)
+//! print(list(state.raw))  # [0, 0, 0, 1, 1, 1, 0, 0, 0]
+//! lib.cellumina_automaton_free(automaton)
+//! ```
+use std::ffi::{c_char, CStr};
+
+use crate::{rule, Automaton, AutomatonBuilder, CelluminaError};
+
+/// An automaton under construction, see [AutomatonBuilder].
+pub struct CelluminaBuilder(AutomatonBuilder);
+
+/// A built automaton, see [Automaton].
+pub struct CelluminaAutomaton(Automaton);
+
+/// The result of a call to a function of the C interface.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CelluminaStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// An argument was invalid, such as a string that is not UTF-8 or a state with zero columns.
+    InvalidArgument = 2,
+    /// A rule could not be parsed.
+    ParseError = 3,
+    /// The passed buffer is too small for the state.
+    BufferTooSmall = 4,
+    /// The configuration of the builder is invalid.
+    BuildError = 5,
+    /// The call panicked, for example in a rule, and was aborted. The handles passed remain valid, but may hold a partially changed state.
+    Panic = 6,
+}
+
+impl From<CelluminaError> for CelluminaStatus {
+    fn from(value: CelluminaError) -> Self {
+        match value {
+            CelluminaError::ParseError { .. } | CelluminaError::UnknownCharacterError(_) => {
+                Self::ParseError
+            }
+            _ => Self::BuildError,
+        }
+    }
+}
+
+/// Runs the body of a function of the C interface, returning [CelluminaStatus::Panic] if it panics instead of unwinding into the calling language, which would abort the whole process.
+fn guarded(body: impl FnOnce() -> CelluminaStatus) -> CelluminaStatus {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or(CelluminaStatus::Panic)
+}
+
+/// Reads the passed C string, which has to be valid UTF-8.
+///
+/// # Safety
+/// ```string``` has to be null or point to a null-terminated string.
+unsafe fn read_str<'a>(string: *const c_char) -> Result<&'a str, CelluminaStatus> {
+    if string.is_null() {
+        return Err(CelluminaStatus::NullPointer);
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| CelluminaStatus::InvalidArgument)
+}
+
+/// Replaces the builder behind the passed handle with the result of the passed function.
+///
+/// # Safety
+/// ```builder``` has to be null or a handle created by [cellumina_builder_new].
+unsafe fn modify(
+    builder: *mut CelluminaBuilder,
+    f: impl FnOnce(AutomatonBuilder) -> Result<AutomatonBuilder, CelluminaStatus>,
+) -> CelluminaStatus {
+    let Some(builder) = builder.as_mut() else {
+        return CelluminaStatus::NullPointer;
+    };
+    match f(std::mem::take(&mut builder.0)) {
+        Ok(res) => {
+            builder.0 = res;
+            CelluminaStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// Creates a new builder without a state or rules. Release it with [cellumina_builder_free] unless it is passed to [cellumina_builder_build].
+/// Returns null if creating the builder panicked.
+#[no_mangle]
+pub extern "C" fn cellumina_builder_new() -> *mut CelluminaBuilder {
+    std::panic::catch_unwind(|| Box::into_raw(Box::new(CelluminaBuilder(AutomatonBuilder::new()))))
+        .unwrap_or(std::ptr::null_mut())
+}
+
+/// Sets the initial state to the ```len``` cells at ```cells```, row by row with ```cols``` cells per row.
+///
+/// # Safety
+/// ```builder``` has to be a handle created by [cellumina_builder_new] and ```cells``` has to point to at least ```len``` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_builder_set_state(
+    builder: *mut CelluminaBuilder,
+    cells: *const u8,
+    len: usize,
+    cols: usize,
+) -> CelluminaStatus {
+    guarded(|| {
+        if cells.is_null() {
+            return CelluminaStatus::NullPointer;
+        }
+        let Ok(cols) = u32::try_from(cols) else {
+            return CelluminaStatus::InvalidArgument;
+        };
+        if cols == 0 || !len.is_multiple_of(cols as usize) {
+            return CelluminaStatus::InvalidArgument;
+        }
+        let cells = std::slice::from_raw_parts(cells, len).to_vec();
+        modify(builder, |builder| Ok(builder.from_vec(cells, cols)))
+    })
+}
+
+/// Adds a [LifeLikeRule](rule::LifeLikeRule) given in the B/S notation, such as ```B3/S23```.
+///
+/// # Safety
+/// ```builder``` has to be a handle created by [cellumina_builder_new] and ```notation``` a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_builder_add_life_like_rule(
+    builder: *mut CelluminaBuilder,
+    notation: *const c_char,
+) -> CelluminaStatus {
+    guarded(|| {
+        let rule = match read_str(notation).map(rule::LifeLikeRule::parse) {
+            Ok(Ok(rule)) => rule,
+            Ok(Err(err)) => return err.into(),
+            Err(status) => return status,
+        };
+        modify(builder, |builder| Ok(builder.with_rule(rule)))
+    })
+}
+
+/// Adds a [PatternRule](rule::PatternRule) given in the ```.cel``` format.
+///
+/// # Safety
+/// ```builder``` has to be a handle created by [cellumina_builder_new] and ```cel``` a null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_builder_add_pattern_rule(
+    builder: *mut CelluminaBuilder,
+    cel: *const c_char,
+) -> CelluminaStatus {
+    guarded(|| {
+        let rule = match read_str(cel).map(|cel| rule::PatternRule::parse(cel, false)) {
+            Ok(Ok(rule)) => rule,
+            Ok(Err(err)) => return err.into(),
+            Err(status) => return status,
+        };
+        modify(builder, |builder| Ok(builder.with_rule(rule)))
+    })
+}
+
+/// Builds the automaton and writes its handle to ```automaton```. Release it with [cellumina_automaton_free].
+///
+/// The builder is consumed and must not be used afterwards, even if building fails.
+///
+/// # Safety
+/// ```builder``` has to be a handle created by [cellumina_builder_new] and ```automaton``` has to point to writable memory for a handle.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_builder_build(
+    builder: *mut CelluminaBuilder,
+    automaton: *mut *mut CelluminaAutomaton,
+) -> CelluminaStatus {
+    guarded(|| {
+        if builder.is_null() {
+            return CelluminaStatus::NullPointer;
+        }
+        let builder = Box::from_raw(builder);
+        if automaton.is_null() {
+            return CelluminaStatus::NullPointer;
+        }
+        match builder.0.build_checked() {
+            Ok(res) => {
+                *automaton = Box::into_raw(Box::new(CelluminaAutomaton(res)));
+                CelluminaStatus::Ok
+            }
+            Err(err) => err.into(),
+        }
+    })
+}
+
+/// Releases a builder that was not built. Passing null does nothing.
+///
+/// # Safety
+/// ```builder``` has to be null or a handle created by [cellumina_builder_new], which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_builder_free(builder: *mut CelluminaBuilder) {
+    // a panic while dropping can not be reported, but must not unwind into the caller either
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if !builder.is_null() {
+            drop(Box::from_raw(builder));
+        }
+    }));
+}
+
+/// Performs ```steps``` time steps.
+///
+/// # Safety
+/// ```automaton``` has to be a handle created by [cellumina_builder_build].
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_automaton_step(
+    automaton: *mut CelluminaAutomaton,
+    steps: u64,
+) -> CelluminaStatus {
+    guarded(|| {
+        let Some(automaton) = automaton.as_mut() else {
+            return CelluminaStatus::NullPointer;
+        };
+        for _ in 0..steps {
+            automaton.0.next_step();
+        }
+        CelluminaStatus::Ok
+    })
+}
+
+/// Writes the number of rows and columns of the state to ```rows``` and ```cols```.
+///
+/// # Safety
+/// ```automaton``` has to be a handle created by [cellumina_builder_build], ```rows``` and ```cols``` have to point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_automaton_size(
+    automaton: *const CelluminaAutomaton,
+    rows: *mut usize,
+    cols: *mut usize,
+) -> CelluminaStatus {
+    guarded(|| {
+        let Some(automaton) = automaton.as_ref() else {
+            return CelluminaStatus::NullPointer;
+        };
+        if rows.is_null() || cols.is_null() {
+            return CelluminaStatus::NullPointer;
+        }
+        (*rows, *cols) = automaton.0.state().size();
+        CelluminaStatus::Ok
+    })
+}
+
+/// Copies the state into ```buffer```, row by row with one byte per cell. The buffer needs space for at least ```rows * cols``` cells.
+///
+/// # Safety
+/// ```automaton``` has to be a handle created by [cellumina_builder_build] and ```buffer``` has to point to at least ```len``` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_automaton_read_state(
+    automaton: *const CelluminaAutomaton,
+    buffer: *mut u8,
+    len: usize,
+) -> CelluminaStatus {
+    guarded(|| {
+        let Some(automaton) = automaton.as_ref() else {
+            return CelluminaStatus::NullPointer;
+        };
+        if buffer.is_null() {
+            return CelluminaStatus::NullPointer;
+        }
+        let state = automaton.0.state();
+        let (rows, cols) = state.size();
+        if len < rows * cols {
+            return CelluminaStatus::BufferTooSmall;
+        }
+        let buffer = std::slice::from_raw_parts_mut(buffer, rows * cols);
+        for (target, &cell) in buffer.iter_mut().zip(state.iter()) {
+            *target = cell;
+        }
+        CelluminaStatus::Ok
+    })
+}
+
+/// Releases an automaton. Passing null does nothing.
+///
+/// # Safety
+/// ```automaton``` has to be null or a handle created by [cellumina_builder_build], which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cellumina_automaton_free(automaton: *mut CelluminaAutomaton) {
+    // a panic while dropping can not be reported, but must not unwind into the caller either
+    let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if !automaton.is_null() {
+            drop(Box::from_raw(automaton));
+        }
+    }));
+}
+
+#[test]
+fn panic_test() {
+    let builder = AutomatonBuilder::new()
+        .from_vec(vec![0, 1], 2)
+        .with_fn_rule("panicking", |grid| {
+            let _ = grid[5][5];
+        });
+    let mut automaton = CelluminaAutomaton(builder.build());
+
+    // the panic of the rule is reported instead of unwinding
+    assert_eq!(
+        unsafe { cellumina_automaton_step(&mut automaton, 1) },
+        CelluminaStatus::Panic
+    );
+    // and the automaton can still be read
+    let mut state = [9; 2];
+    assert_eq!(
+        unsafe { cellumina_automaton_read_state(&automaton, state.as_mut_ptr(), state.len()) },
+        CelluminaStatus::Ok
+    );
+    assert_eq!(state, [0, 1]);
+}
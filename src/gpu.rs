@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::CelluminaError;
+
+/// A handle to a GPU device and queue.
+///
+/// Rules running on the GPU, such as [GpuEnvironmentRule](crate::rule::GpuEnvironmentRule), are created from a context.
+/// When an automaton using such a rule is run live, the view reuses the rule's context instead of creating a second device, as long as the adapter supports drawing to the window.
+/// Cloning a context is cheap and shares the underlying device.
+#[derive(Debug, Clone)]
+pub struct GpuContext {
+    /// The WGPU instance the adapter was created from.
+    pub(crate) instance: Arc<wgpu::Instance>,
+    /// The adapter representing the physical GPU.
+    pub(crate) adapter: Arc<wgpu::Adapter>,
+    /// The logical device.
+    pub(crate) device: Arc<wgpu::Device>,
+    /// The queue of the device.
+    pub(crate) queue: Arc<wgpu::Queue>,
+}
+
+impl GpuContext {
+    /// Creates a new context on the default GPU, without any window attached.
+    /// If no GPU is available, a fallback adapter, such as a software renderer, is used instead.
+    /// ## Error
+    /// When no suitable adapter or device could be found.
+    pub fn new() -> Result<Self, CelluminaError> {
+        pollster::block_on(Self::request(
+            Arc::new(wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends: wgpu::Backends::all(),
+                dx12_shader_compiler: Default::default(),
+            })),
+            None,
+        ))
+    }
+
+    /// Requests an adapter, device and queue from the passed instance, optionally compatible with the passed surface.
+    /// Falls back to a fallback adapter if no other adapter is available, see [request_with_fallback].
+    pub(crate) async fn request(
+        instance: Arc<wgpu::Instance>,
+        surface: Option<&wgpu::Surface>,
+    ) -> Result<Self, CelluminaError> {
+        // create adapter
+        let adapter = request_with_fallback(|force_fallback_adapter| {
+            instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::default(),
+                compatible_surface: surface,
+                force_fallback_adapter,
+            })
+        })
+        .await?;
+
+        // create device & queue
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    features: wgpu::Features::empty(),
+                    limits: wgpu::Limits::default(),
+                },
+                None, // Trace path
+            )
+            .await
+            .map_err(|err| CelluminaError::GpuError(err.to_string()))?;
+
+        Ok(Self {
+            instance,
+            adapter: Arc::new(adapter),
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+        })
+    }
+}
+
+/// Calls the passed adapter request first for any adapter and then, if none was found, for a fallback adapter, returning the first adapter found.
+///
+/// Machines without a GPU, such as CI runners and some virtual machines, often still offer a fallback adapter rendering on the CPU, which is slow, but better than no window at all.
+async fn request_with_fallback<A, F: std::future::Future<Output = Option<A>>>(
+    mut request: impl FnMut(bool) -> F,
+) -> Result<A, CelluminaError> {
+    if let Some(adapter) = request(false).await {
+        return Ok(adapter);
+    }
+    log::warn!("Could not find a GPU adapter, trying a fallback adapter.");
+    request(true).await.ok_or_else(|| {
+        CelluminaError::GpuError("could not find an adapter, not even a fallback one".to_string())
+    })
+}
+
+#[test]
+fn request_with_fallback_test() {
+    use std::cell::RefCell;
+
+    // records the requests made and answers them with the passed adapters in order
+    let answer = |adapters: Vec<Option<&'static str>>| {
+        let requests = RefCell::new(Vec::new());
+        let mut adapters = adapters.into_iter();
+        let result = pollster::block_on(request_with_fallback(|force_fallback| {
+            requests.borrow_mut().push(force_fallback);
+            std::future::ready(adapters.next().flatten())
+        }));
+        (result, requests.into_inner())
+    };
+
+    // a regular adapter is used without trying the fallback
+    let (result, requests) = answer(vec![Some("gpu"), Some("fallback")]);
+    assert_eq!(result.unwrap(), "gpu");
+    assert_eq!(requests, [false]);
+
+    // without one, the fallback adapter is used
+    let (result, requests) = answer(vec![None, Some("fallback")]);
+    assert_eq!(result.unwrap(), "fallback");
+    assert_eq!(requests, [false, true]);
+
+    // without either, an error describes the problem instead of panicking
+    let (result, requests) = answer(vec![None, None]);
+    assert!(matches!(result, Err(CelluminaError::GpuError(_))));
+    assert_eq!(requests, [false, true]);
+}
+
+#[test]
+fn graphics_init_error_test() {
+    use std::error::Error;
+
+    // the live view wraps the cause, keeping both its message and the error itself
+    let error = CelluminaError::from(crate::GraphicsInitError::Setup(
+        CelluminaError::GpuError("could not find an adapter".to_string()).into(),
+    ));
+    assert_eq!(
+        error.to_string(),
+        "could not initialize the live view: GPU error: could not find an adapter"
+    );
+    let cause = error.source().unwrap().source().unwrap();
+    assert!(matches!(
+        cause.downcast_ref::<CelluminaError>(),
+        Some(CelluminaError::GpuError(_))
+    ));
+
+    // causes without an error type of their own are described by text
+    let error = CelluminaError::from(crate::GraphicsInitError::Setup(
+        "the adapter can not draw to the window".into(),
+    ));
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "the adapter can not draw to the window"
+    );
+}
@@ -0,0 +1,138 @@
+/// The maximum zoom factor, at which a 1024x1024 automaton shows roughly 16x16 cells.
+const MAX_ZOOM: f32 = 64.;
+
+/// Describes which part of the automaton's state is currently visible in the live view.
+///
+/// All positions are given in texture coordinates, i.e. ```(0, 0)``` is the top left corner and ```(1, 1)``` the bottom right corner of the state grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Camera {
+    /// The center of the visible region.
+    center: [f32; 2],
+    /// How far the view is zoomed in. A zoom of 1 shows the entire state.
+    zoom: f32,
+}
+
+impl Camera {
+    /// Creates a camera showing the entire state.
+    pub(super) fn new() -> Self {
+        Self {
+            center: [0.5, 0.5],
+            zoom: 1.,
+        }
+    }
+
+    /// Returns the currently visible region as ```[left, top, right, bottom]```.
+    pub(super) fn region(&self) -> [f32; 4] {
+        let half = 0.5 / self.zoom;
+        [
+            self.center[0] - half,
+            self.center[1] - half,
+            self.center[0] + half,
+            self.center[1] + half,
+        ]
+    }
+
+    /// Multiplies the zoom by ```factor``` while keeping the passed point at the same position on screen.
+    pub(super) fn zoom_at(&mut self, factor: f32, point: [f32; 2]) {
+        let new_zoom = (self.zoom * factor).clamp(1., MAX_ZOOM);
+        for (center, point) in self.center.iter_mut().zip(point) {
+            *center = point + (*center - point) * self.zoom / new_zoom;
+        }
+        self.zoom = new_zoom;
+        self.clamp();
+    }
+
+    /// Moves the visible region by the passed offset.
+    pub(super) fn pan(&mut self, delta: [f32; 2]) {
+        self.center[0] += delta[0];
+        self.center[1] += delta[1];
+        self.clamp();
+    }
+
+    /// Centers the visible region on the passed point, as far as possible.
+    pub(super) fn jump_to(&mut self, point: [f32; 2]) {
+        self.center = point;
+        self.clamp();
+    }
+
+    /// Zooms in to at least ```zoom```, and centers the visible region on the passed point, as far as possible.
+    pub(super) fn focus(&mut self, point: [f32; 2], zoom: f32) {
+        self.zoom = self.zoom.max(zoom).clamp(1., MAX_ZOOM);
+        self.jump_to(point);
+    }
+
+    /// Moves the center so that the visible region stays within the state.
+    fn clamp(&mut self) {
+        let half = 0.5 / self.zoom;
+        for center in self.center.iter_mut() {
+            *center = center.clamp(half, 1. - half);
+        }
+    }
+}
+
+/// Calculates the rectangle the minimap is drawn to in a window of the passed size (width, height), in normalized device coordinates ```[left, bottom, right, top]```.
+///
+/// The minimap sits in the bottom right corner and its longer side is a quarter of the shorter window side.
+pub(super) fn minimap_rect(window_size: (u32, u32), model_dimensions: (u32, u32)) -> [f32; 4] {
+    // margin to the window borders in pixels
+    const MARGIN: f32 = 10.;
+    let (width, height) = (window_size.0.max(1) as f32, window_size.1.max(1) as f32);
+    let longest = model_dimensions.0.max(model_dimensions.1).max(1) as f32;
+    let side = width.min(height) / 4.;
+
+    // size of the minimap in pixels
+    let map_width = side * model_dimensions.1 as f32 / longest;
+    let map_height = side * model_dimensions.0 as f32 / longest;
+
+    let right = 1. - 2. * MARGIN / width;
+    let bottom = -1. + 2. * MARGIN / height;
+    [
+        right - 2. * map_width / width,
+        bottom,
+        right,
+        bottom + 2. * map_height / height,
+    ]
+}
+
+#[test]
+fn camera_test() {
+    let mut camera = Camera::new();
+    assert_eq!(camera.region(), [0., 0., 1., 1.]);
+
+    // can not zoom out further than the whole state, or pan while seeing everything
+    camera.zoom_at(0.5, [0.2, 0.2]);
+    camera.pan([0.3, 0.]);
+    assert_eq!(camera.region(), [0., 0., 1., 1.]);
+
+    // zooming keeps the point under the cursor fixed
+    camera.zoom_at(2., [0.5, 0.5]);
+    assert_eq!(camera.region(), [0.25, 0.25, 0.75, 0.75]);
+    camera.zoom_at(2., [0.25, 0.25]);
+    assert_eq!(camera.region(), [0.25, 0.25, 0.5, 0.5]);
+
+    // panning and jumping stops at the borders
+    camera.pan([-1., 0.1]);
+    assert_eq!(camera.region(), [0., 0.35, 0.25, 0.6]);
+    camera.jump_to([1., 1.]);
+    assert_eq!(camera.region(), [0.75, 0.75, 1., 1.]);
+
+    // focusing zooms in, but never out
+    camera.focus([0.5, 0.5], 2.);
+    assert_eq!(camera.region(), [0.375, 0.375, 0.625, 0.625]);
+    camera.focus([0.5, 0.5], 1000.);
+    assert_eq!(camera.region()[2] - camera.region()[0], 1. / MAX_ZOOM);
+}
+
+#[test]
+fn minimap_rect_test() {
+    // square grid, square window: quarter of the window plus margin
+    let rect = minimap_rect((400, 400), (64, 64));
+    assert_eq!(rect, [0.45, -0.95, 0.95, -0.45]);
+
+    // wide grid: half as high as wide
+    let rect = minimap_rect((400, 400), (32, 64));
+    assert_eq!(rect, [0.45, -0.95, 0.95, -0.7]);
+
+    // degenerate sizes do not produce NaNs
+    assert!(minimap_rect((0, 0), (0, 0)).iter().all(|v| v.is_finite()));
+}
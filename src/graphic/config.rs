@@ -0,0 +1,138 @@
+/// Configuration of the live view window opened by [Automaton::run_live_with](crate::Automaton::run_live_with).
+///
+/// Create it using struct update syntax on the default configuration:
+/// ```no_run
+/// # use cellumina::{LiveViewConfig, PresentMode};
+/// let config = LiveViewConfig {
+///     present_mode: PresentMode::Mailbox,
+///     max_frame_rate: Some(30.),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveViewConfig {
+    /// How finished frames are presented to the screen.
+    /// If the requested mode is not supported by the surface, [PresentMode::Fifo] is used instead.
+    pub present_mode: PresentMode,
+    /// The maximum number of times per second the live view checks the automaton for a new step and redraws it.
+    ///
+    /// The window is only redrawn when the state changed (or the window requires it), so slow automata use little CPU time.
+    /// Set this to [None] to check as often as possible, which is only useful for automata using an immediate step mode.
+    pub max_frame_rate: Option<f32>,
+    /// How cell states are converted to colors on screen.
+    pub color_mode: ColorMode,
+    /// Named patterns of cells, such as a glider or a fire source, that can be selected with the keys ```1``` to ```9``` and placed with a click.
+    ///
+    /// Stamps are placed centered on the clicked cell. Cells of a stamp containing the wildcard ```*``` (```127```) leave the cells below them unchanged.
+    /// Use [LiveViewConfig::load_stamp] to load stamps from text files.
+    pub stamps: Vec<(String, crate::CellGrid)>,
+    /// A condition on which the simulation pauses by itself, such as everything having died out, to not keep running a finished simulation.
+    ///
+    /// The window title shows why the simulation was paused. The condition only pauses the simulation once, resuming it with ```Enter``` keeps it running.
+    pub auto_pause: crate::AutoPause,
+    /// The characters typed with the keys, selecting the symbol painted with the mouse.
+    pub key_bindings: super::KeyBindings,
+    /// How many steps are performed each frame. By default, this follows the step mode of the automaton.
+    pub stepping: LiveStepping,
+}
+
+impl Default for LiveViewConfig {
+    fn default() -> Self {
+        Self {
+            present_mode: PresentMode::Fifo,
+            max_frame_rate: Some(60.),
+            color_mode: ColorMode::Rgba,
+            stamps: Vec::new(),
+            auto_pause: crate::AutoPause::Off,
+            key_bindings: super::KeyBindings::default(),
+            stepping: LiveStepping::WallClock,
+        }
+    }
+}
+
+impl LiveViewConfig {
+    /// Loads a stamp from a text file with one row of characters per line, like [AutomatonBuilder::from_text_file](crate::AutomatonBuilder::from_text_file).
+    /// The stamp is named after the file, without its extension.
+    /// ```no_run
+    /// # use cellumina::LiveViewConfig;
+    /// let config = LiveViewConfig {
+    ///     stamps: vec![LiveViewConfig::load_stamp("stamps/glider.txt").unwrap()],
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub fn load_stamp(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(String, crate::CellGrid), crate::CelluminaError> {
+        let path = path.as_ref();
+        let grid = crate::builder::grid_from_text(&std::fs::read_to_string(path)?, false)?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok((name, grid))
+    }
+}
+
+/// Describes how many steps the live view performs each frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LiveStepping {
+    /// Steps are performed as the step mode of the automaton permits, at most one per frame.
+    /// For automata with a time step, the number of frames between two steps depends on the machine, see [AutomatonBuilder::with_min_time_step](crate::AutomatonBuilder::with_min_time_step).
+    #[default]
+    WallClock,
+    /// Every frame performs exactly ```steps_per_frame``` steps, ignoring the step mode and speed multiplier of the automaton.
+    ///
+    /// As the generation shown in each frame no longer depends on timing, recording the window at a fixed frame rate produces the same video on every machine,
+    /// given a seeded rule. Pausing the simulation stops the steps as usual.
+    Deterministic {
+        /// The number of steps performed each frame.
+        steps_per_frame: u32,
+    },
+}
+
+/// Describes how the live view presents finished frames to the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Frames are synchronized with the vertical blank of the screen. Supported everywhere.
+    #[default]
+    Fifo,
+    /// Frames are synchronized with the vertical blank, but newer frames replace queued ones.
+    Mailbox,
+    /// Frames are presented immediately, possibly causing tearing.
+    Immediate,
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(value: PresentMode) -> Self {
+        match value {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+/// Describes how the live view converts cell states to colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// The state is converted to an image on the CPU using the color map of the automaton and then uploaded to the GPU.
+    #[default]
+    Rgba,
+    /// The raw cell states are uploaded to the GPU and colored in the fragment shader using the color map of the automaton.
+    /// Cells without a color of their own are drawn in the default color of the automaton, exactly as in images of the state.
+    ///
+    /// Avoids the conversion to an image on the CPU, which may speed up the display of large automata.
+    Palette,
+    /// The raw cell states are uploaded to the GPU and colored in the fragment shader by linearly interpolating between two colors.
+    /// Cells with a state of ```0``` receive the color ```from```, cells with a state of ```max``` or larger the color ```to```.
+    ///
+    /// Useful for automata whose cell states describe a continuous quantity such as height or age.
+    Gradient {
+        /// The color of cells with state ```0```.
+        from: [u8; 4],
+        /// The color of cells with state ```max```.
+        to: [u8; 4],
+        /// The cell state receiving the color ```to```.
+        max: u8,
+    },
+}
@@ -3,51 +3,124 @@
 pub(super) struct AutomatonController {
     /// The cell the user's mouse is currently hovering.
     hovered_cell: Option<(u32, u32)>,
+    /// The last known position of the cursor in pixels.
+    cursor_position: Option<(f64, f64)>,
+    /// Wether the view is currently dragged with the right mouse button.
+    panning: bool,
+    /// Wether the main mouse button was pressed on the minimap and is still held.
+    minimap_drag: bool,
     /// The current state of the main mouse button.
     mouse_down: bool,
     /// The current state of the Ctrl-Key
     ctrl_down: bool,
     /// The current state of the Shift Key
     alt_down: bool,
-    /// Wether a clear is currently queued up
-    clear_char: Option<char>,
-    /// The char the currently hovered cell is replaced with on mouse click.
-    replacement_char: char,
-    /// The keymap used to convert from VirtualKeyCode to character
-    keymap: std::collections::HashMap<winit::event::VirtualKeyCode, char>,
+    /// The cell the screen is cleared with, if a clear is currently queued up.
+    clear_cell: Option<u8>,
+    /// Wether the texture needs to be rewritten even if the state did not change.
+    redraw_queued: bool,
+    /// The cell the currently hovered cell is replaced with on mouse click.
+    replacement_cell: u8,
+    /// The characters typed with the keys, converted to the replacement cell.
+    key_bindings: super::KeyBindings,
+    /// The named stamps that can be selected with the number keys.
+    stamps: Vec<(String, crate::CellGrid)>,
+    /// The index of the stamp placed on mouse click instead of painting single cells, if any.
+    active_stamp: Option<usize>,
+    /// Wether the active stamp is to be placed at the hovered cell.
+    stamp_queued: bool,
+    /// Wether the debug mode is active, in which the number keys enable and disable parts of the rule.
+    debug_mode: bool,
+    /// The coordinates of the cell to jump to typed so far, if they are currently being entered.
+    goto_input: Option<super::goto::CoordinateInput>,
 }
 
+/// The number of cells along the longer side of the state that are visible after jumping to a cell, unless the view is zoomed in further already.
+const GOTO_VISIBLE_CELLS: f32 = 32.;
+
 impl AutomatonController {
     /// Creates a new AutomatonController with default state.
     pub fn new() -> Self {
         Self {
             hovered_cell: None,
+            cursor_position: None,
+            panning: false,
+            minimap_drag: false,
             mouse_down: false,
             ctrl_down: false,
-            clear_char: None,
+            clear_cell: None,
+            redraw_queued: false,
             alt_down: false,
-            replacement_char: 'X',
-            keymap: get_keymap(),
+            replacement_cell: crate::char_to_id('X'),
+            key_bindings: super::KeyBindings::default(),
+            stamps: Vec::new(),
+            active_stamp: None,
+            stamp_queued: false,
+            debug_mode: false,
+            goto_input: None,
+        }
+    }
+
+    /// Makes the passed stamps available for selection with the number keys.
+    pub fn with_stamps(mut self, stamps: Vec<(String, crate::CellGrid)>) -> Self {
+        if stamps.len() > 9 {
+            log::warn!(
+                "Only the first 9 of {} stamps can be selected.",
+                stamps.len()
+            );
         }
+        self.stamps = stamps;
+        self
+    }
+
+    /// Uses the passed key bindings to select the replacement cell.
+    pub fn with_key_bindings(mut self, key_bindings: super::KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
     }
 
     /// Modifies the passed model as orderd by the user input.
     pub(crate) fn modify(&mut self, model: &mut super::AutomatonModel) -> bool {
+        if std::mem::take(&mut self.stamp_queued) {
+            if let (Some((row, col)), Some((name, stamp))) = (
+                self.hovered_cell,
+                self.active_stamp.and_then(|index| self.stamps.get(index)),
+            ) {
+                let changed = place_stamp(&mut model.automaton_mut(), (row, col), stamp);
+                log::info!("Placed stamp {name} at ({row}, {col}).");
+                for &(row, col, cell) in changed.iter() {
+                    model.hooks.edit(row, col, cell);
+                }
+                return !changed.is_empty();
+            }
+        }
         if self.mouse_down {
             if let Some((row, col)) = self.hovered_cell {
-                return model
-                    .cell_state
-                    .set_cell(row, col, crate::char_to_id(self.replacement_char))
+                let cell = self.replacement_cell;
+                let changed = model
+                    .automaton_mut()
+                    .set_cell(row, col, cell)
                     .unwrap_or_else(|err| {
                         log::error!("Could not set cell state: {}.", err);
                         false
                     });
+                if changed {
+                    model.hooks.edit(row, col, cell);
+                }
+                return changed;
             }
         }
-        // if a clear is queued, replace the entire state with that char
-        if let Some(replacement) = self.clear_char.take() {
-            model.cell_state.state.fill(crate::char_to_id(replacement));
-            log::info!("Cleared the screen with character {}.", replacement);
+        // changes to the display of the model count as modifications
+        if std::mem::take(&mut self.redraw_queued) {
+            return true;
+        }
+        // if a clear is queued, replace the entire state with that cell
+        if let Some(replacement) = self.clear_cell.take() {
+            model.automaton_mut().fill(replacement);
+            log::info!(
+                "Cleared the screen with character {}.",
+                crate::id_to_char(replacement)
+            );
             return true;
         }
         false
@@ -57,9 +130,11 @@ impl AutomatonController {
     pub(crate) fn handle_event(
         &mut self,
         model: &mut super::AutomatonModel,
-        config: &wgpu::SurfaceConfiguration,
+        view: &mut super::AutomatonView,
         event: &winit::event::WindowEvent<'_>,
     ) -> bool {
+        let dimensions = model.automaton().dimensions();
+        let window_size = (view.config.width, view.config.height);
         match event {
             // Check for Keyboard events
             winit::event::WindowEvent::KeyboardInput {
@@ -73,10 +148,20 @@ impl AutomatonController {
             } => {
                 // Differ based on keycode
                 match virtual_keycode {
+                    // While coordinates are entered, all keys go to the input.
+                    Some(code) if self.goto_input.is_some() => {
+                        self.enter_coordinate(*code, dimensions, view);
+                        true
+                    }
+                    // G: If control is down, start entering the coordinates of a cell to jump to
+                    Some(winit::event::VirtualKeyCode::G) if self.ctrl_down => {
+                        log::info!("Type the row and column of a cell, separated by a comma, and press Enter to jump to it or Escape to cancel.");
+                        self.goto_input = Some(Default::default());
+                        true
+                    }
                     // S: If control is down, try to save
                     Some(winit::event::VirtualKeyCode::S) if self.ctrl_down => {
                         log::info!("Attempting to save current state to file.");
-                        let (rows, cols) = model.cell_state.state.size();
                         match native_dialog::FileDialog::new()
                             .set_location("~")
                             .set_filename("cellumina_output")
@@ -85,51 +170,15 @@ impl AutomatonController {
                             .add_filter("JPEG Image", &["jpeg"])
                             .add_filter("ICO image", &["ico"])
                             .add_filter("BMP Image", &["bmp"])
+                            .add_filter("Cellumina Binary", &["cels"])
                             .show_save_single_file()
                         {
                             Err(e) => log::error!("File Dialog Error: {e}"),
                             Ok(pathbuff_option) => match pathbuff_option {
                                 None => log::info!("File Dialog aborted."),
                                 Some(pathbuffer) => {
-                                    match pathbuffer.extension().and_then(std::ffi::OsStr::to_str) {
-                                        Some("png") | Some("jpeg") | Some("ico") | Some("bmp") => {
-                                            if let Err(e) = image::save_buffer(
-                                                pathbuffer,
-                                                &model.cell_state.create_image_buffer(),
-                                                cols as u32,
-                                                rows as u32,
-                                                image::ColorType::Rgba8,
-                                            ) {
-                                                log::error!(
-                                                    "Writing automaton to image file failed: {e}"
-                                                );
-                                            }
-                                        }
-                                        Some("txt") | None => {
-                                            if let Err(e) = std::fs::write(
-                                                pathbuffer,
-                                                model.cell_state.state.iter().fold(
-                                                    String::with_capacity((cols + 1) * rows),
-                                                    |mut container, &cell| {
-                                                        if container.len() % (cols + 1) == cols {
-                                                            container.push('\n');
-                                                        }
-                                                        container.push(crate::id_to_char(cell));
-                                                        container
-                                                    },
-                                                ),
-                                            ) {
-                                                log::error!(
-                                                    "Writing automaton to text file failed: {e}"
-                                                )
-                                            }
-                                        }
-                                        Some(ext) => {
-                                            log::error!(
-                                                "Detected unsupported file extension: {}",
-                                                ext
-                                            );
-                                        }
+                                    if let Err(e) = model.automaton().save(&pathbuffer) {
+                                        log::error!("Saving automaton to file failed: {e}");
                                     }
                                 }
                             },
@@ -137,6 +186,64 @@ impl AutomatonController {
 
                         true
                     }
+                    // M: If control is down, show or hide the minimap
+                    Some(winit::event::VirtualKeyCode::M) if self.ctrl_down => {
+                        view.toggle_minimap();
+                        true
+                    }
+                    // T: If control is down, show or hide the activity heatmap
+                    Some(winit::event::VirtualKeyCode::T) if self.ctrl_down => {
+                        self.redraw_queued |= model.toggle_activity();
+                        true
+                    }
+                    // Up and Down: If control is down, double or halve the speed of timed steps
+                    Some(
+                        code @ (winit::event::VirtualKeyCode::Up
+                        | winit::event::VirtualKeyCode::Down),
+                    ) if self.ctrl_down => {
+                        let factor = if *code == winit::event::VirtualKeyCode::Up {
+                            2.
+                        } else {
+                            0.5
+                        };
+                        let speed = model.automaton().speed_multiplier() * factor;
+                        match model.automaton_mut().set_speed_multiplier(speed) {
+                            Ok(()) => log::info!("Simulation speed set to {speed}x."),
+                            Err(err) => log::error!("Could not change simulation speed: {err}"),
+                        }
+                        true
+                    }
+                    // D: If control is down, enter or leave the debug mode
+                    Some(winit::event::VirtualKeyCode::D) if self.ctrl_down => {
+                        self.debug_mode = !self.debug_mode;
+                        if self.debug_mode {
+                            log::info!(
+                                "Debug mode entered, number keys toggle parts of the rule:\n{}",
+                                describe_toggles(&model.automaton().rule().toggles())
+                            );
+                        } else {
+                            log::info!("Debug mode left.");
+                        }
+                        true
+                    }
+                    // Number keys enable or disable parts of the rule in debug mode.
+                    Some(code) if self.debug_mode && stamp_index(*code).is_some() => {
+                        // checked by the guard
+                        let index = stamp_index(*code).unwrap_or_default();
+                        let mut automaton = model.automaton_mut();
+                        match automaton.rule().toggles().get(index) {
+                            Some(&(ref name, enabled)) => {
+                                automaton.rule_mut().set_enabled(index, !enabled);
+                                log::info!(
+                                    "{} {name}:\n{}",
+                                    if enabled { "Disabled" } else { "Enabled" },
+                                    describe_toggles(&automaton.rule().toggles())
+                                );
+                            }
+                            None => log::info!("The rule has no part {}.", index + 1),
+                        }
+                        true
+                    }
                     // Return pauses and unpauses.
                     Some(winit::event::VirtualKeyCode::Return) => {
                         log::info!(
@@ -144,23 +251,60 @@ impl AutomatonController {
                             if model.paused { "unpaused" } else { "paused" }
                         );
                         model.paused = !model.paused;
+                        model.pause_reason = None;
+                        true
+                    }
+                    // Number keys select a stamp, if there are enough, or deselect the active one.
+                    Some(code)
+                        if !self.alt_down
+                            && stamp_index(*code)
+                                .is_some_and(|index| index < self.stamps.len()) =>
+                    {
+                        // checked by the guard
+                        let index = stamp_index(*code).unwrap_or_default();
+                        self.active_stamp = if self.active_stamp == Some(index) {
+                            log::info!("Stamp deselected.");
+                            None
+                        } else {
+                            log::info!("Stamp {} selected.", self.stamps[index].0);
+                            Some(index)
+                        };
                         true
                     }
                     // All other chars (including S): Set the replacement char
                     Some(code) => {
-                        self.replacement_char = self.keymap.get(code).copied().unwrap_or(' ');
+                        let cell = match self.key_bindings.char_for_key(*code) {
+                            Some(symbol) => match crate::try_char_to_id(symbol) {
+                                Ok(cell) => cell,
+                                Err(_) => {
+                                    log::info!(
+                                        "Key {code:?} types {symbol:?}, which is not associated with a cell."
+                                    );
+                                    return false;
+                                }
+                            },
+                            // modifiers are pressed for shortcuts all the time
+                            None if super::keys::is_modifier(*code) => return false,
+                            None => {
+                                log::info!("Key {code:?} is not bound to a character.");
+                                return false;
+                            }
+                        };
+                        self.active_stamp = None;
+                        self.replacement_cell = cell;
 
-                        log::info!("Replacement Character set to {}.", self.replacement_char);
+                        log::info!(
+                            "Replacement Character set to {}.",
+                            crate::id_to_char(self.replacement_cell)
+                        );
 
                         // if alt is pressed, queue a screen clear with that character
                         if self.alt_down {
-                            self.clear_char = self.keymap.get(code).copied();
-                            if self.clear_char.is_some() {
-                                log::info!(
-                                    "Screen clear queued with character {}.",
-                                    self.replacement_char
-                                );
-                            }
+                            self.clear_cell = Some(cell);
+                            log::info!(
+                                "Screen clear queued with character {}.",
+                                crate::id_to_char(self.replacement_cell)
+                            );
                         }
 
                         true
@@ -176,89 +320,416 @@ impl AutomatonController {
             }
             // Permantly know what cell the cursor is hovering
             winit::event::WindowEvent::CursorMoved { position, .. } => {
-                // calculate the height and width of a cell if the state was stretched to the whole window
-                let pixels_per_col = config.width as f64 / model.cell_state.dimensions().1 as f64; // pixels per cell
-                let pixels_per_row = config.height as f64 / model.cell_state.dimensions().0 as f64; // pixels per cell
-
-                // since the state is only stretched until either direction reaches the window borders, the true side length of a cell is the minimum
-                let pixels_per_cell = pixels_per_col.min(pixels_per_row);
-
-                let (cell_row, cell_col) = (
-                    ((position.y - config.height as f64 / 2.) / pixels_per_cell
-                        + model.cell_state.dimensions().0 as f64 / 2.),
-                    ((position.x - config.width as f64 / 2.) / pixels_per_cell
-                        + model.cell_state.dimensions().1 as f64 / 2.),
-                );
+                let position = (position.x, position.y);
+                let previous = self.cursor_position.replace(position);
 
-                if 0. <= cell_col
-                    && cell_col < model.cell_state.dimensions().1 as f64
-                    && 0. <= cell_row
-                    && cell_row < model.cell_state.dimensions().0 as f64
-                {
-                    self.hovered_cell = Some((cell_row as u32, cell_col as u32));
-                } else {
-                    self.hovered_cell = None
+                // drag the view along with the cursor
+                if let (true, Some(previous)) = (self.panning, previous) {
+                    let region = view.camera.region();
+                    // size of the drawn state in pixels
+                    let quad_width =
+                        (view.quad[2] - view.quad[0]) as f64 / 2. * window_size.0 as f64;
+                    let quad_height =
+                        (view.quad[3] - view.quad[1]) as f64 / 2. * window_size.1 as f64;
+                    let delta = [
+                        ((previous.0 - position.0) / quad_width) as f32 * (region[2] - region[0]),
+                        ((previous.1 - position.1) / quad_height) as f32 * (region[3] - region[1]),
+                    ];
+                    view.move_camera(dimensions, |camera| camera.pan(delta));
                 }
 
+                let minimap_point = view.minimap(dimensions).and_then(|minimap| {
+                    pixel_to_texture(position, window_size, minimap, [0., 0., 1., 1.])
+                });
+
+                // jump the view to the point on the minimap while dragging on it
+                if let (true, Some(point)) = (self.minimap_drag, minimap_point) {
+                    view.move_camera(dimensions, |camera| camera.jump_to(point));
+                }
+
+                // cells below the minimap can not be hovered
+                self.hovered_cell = if minimap_point.is_some() {
+                    None
+                } else {
+                    pixel_to_cell(
+                        position,
+                        window_size,
+                        view.quad,
+                        view.camera.region(),
+                        dimensions,
+                    )
+                };
+
                 true
             }
-            // Mouse click set the cell state.
+            // Mouse click set the cell state, or moves the view if on the minimap.
             winit::event::WindowEvent::MouseInput {
                 state,
                 button: winit::event::MouseButton::Left,
                 ..
             } => {
                 match state {
-                    winit::event::ElementState::Pressed => self.mouse_down = true,
-                    winit::event::ElementState::Released => self.mouse_down = false,
+                    winit::event::ElementState::Pressed => {
+                        let minimap_point = view
+                            .minimap(dimensions)
+                            .zip(self.cursor_position)
+                            .and_then(|(minimap, position)| {
+                                pixel_to_texture(position, window_size, minimap, [0., 0., 1., 1.])
+                            });
+                        if let Some(point) = minimap_point {
+                            self.minimap_drag = true;
+                            view.move_camera(dimensions, |camera| camera.jump_to(point));
+                        } else if self.active_stamp.is_some() {
+                            // stamps are placed once per click instead of painting while held
+                            self.stamp_queued = true;
+                        } else {
+                            self.mouse_down = true;
+                        }
+                    }
+                    winit::event::ElementState::Released => {
+                        self.mouse_down = false;
+                        self.minimap_drag = false;
+                    }
                 }
                 true
             }
+            // Right mouse button drags the view.
+            winit::event::WindowEvent::MouseInput {
+                state,
+                button: winit::event::MouseButton::Right,
+                ..
+            } => {
+                self.panning = *state == winit::event::ElementState::Pressed;
+                true
+            }
+            // Mouse wheel zooms in and out around the cursor.
+            winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                    winit::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 40.,
+                };
+                let region = view.camera.region();
+                let point = self
+                    .cursor_position
+                    .and_then(|position| pixel_to_texture(position, window_size, view.quad, region))
+                    .unwrap_or([(region[0] + region[2]) / 2., (region[1] + region[3]) / 2.]);
+                view.move_camera(dimensions, |camera| {
+                    camera.zoom_at(1.2f32.powf(lines), point)
+                });
+                true
+            }
 
             _ => false,
         }
     }
 }
 
-/// Returns a basic keymap mapping VirtualKeyCodes to chars.
-fn get_keymap() -> std::collections::HashMap<winit::event::VirtualKeyCode, char> {
-    std::collections::HashMap::from([
-        (winit::event::VirtualKeyCode::Space, ' '),
-        (winit::event::VirtualKeyCode::A, 'A'),
-        (winit::event::VirtualKeyCode::B, 'B'),
-        (winit::event::VirtualKeyCode::C, 'C'),
-        (winit::event::VirtualKeyCode::D, 'D'),
-        (winit::event::VirtualKeyCode::E, 'E'),
-        (winit::event::VirtualKeyCode::F, 'F'),
-        (winit::event::VirtualKeyCode::G, 'G'),
-        (winit::event::VirtualKeyCode::H, 'H'),
-        (winit::event::VirtualKeyCode::I, 'I'),
-        (winit::event::VirtualKeyCode::J, 'J'),
-        (winit::event::VirtualKeyCode::K, 'K'),
-        (winit::event::VirtualKeyCode::L, 'L'),
-        (winit::event::VirtualKeyCode::M, 'M'),
-        (winit::event::VirtualKeyCode::N, 'N'),
-        (winit::event::VirtualKeyCode::O, 'O'),
-        (winit::event::VirtualKeyCode::P, 'P'),
-        (winit::event::VirtualKeyCode::Q, 'Q'),
-        (winit::event::VirtualKeyCode::R, 'R'),
-        (winit::event::VirtualKeyCode::S, 'S'),
-        (winit::event::VirtualKeyCode::T, 'T'),
-        (winit::event::VirtualKeyCode::U, 'U'),
-        (winit::event::VirtualKeyCode::V, 'V'),
-        (winit::event::VirtualKeyCode::W, 'W'),
-        (winit::event::VirtualKeyCode::X, 'X'),
-        (winit::event::VirtualKeyCode::Y, 'Y'),
-        (winit::event::VirtualKeyCode::Z, 'Z'),
-        (winit::event::VirtualKeyCode::Key1, '1'),
-        (winit::event::VirtualKeyCode::Key2, '2'),
-        (winit::event::VirtualKeyCode::Key3, '3'),
-        (winit::event::VirtualKeyCode::Key4, '4'),
-        (winit::event::VirtualKeyCode::Key5, '5'),
-        (winit::event::VirtualKeyCode::Key6, '6'),
-        (winit::event::VirtualKeyCode::Key7, '7'),
-        (winit::event::VirtualKeyCode::Key8, '8'),
-        (winit::event::VirtualKeyCode::Key9, '9'),
-        (winit::event::VirtualKeyCode::Key0, '0'),
-    ])
+impl AutomatonController {
+    /// Passes a pressed key to the coordinate input, jumping to the cell once the coordinates are confirmed.
+    /// The cell is centered, zoomed in on and shown in the window title like a hovered cell.
+    fn enter_coordinate(
+        &mut self,
+        key: winit::event::VirtualKeyCode,
+        dimensions: (u32, u32),
+        view: &mut super::AutomatonView,
+    ) {
+        let Some(input) = self.goto_input.as_mut() else {
+            return;
+        };
+        match input.press(key) {
+            super::goto::CoordinateInputResult::Continue => {}
+            super::goto::CoordinateInputResult::Invalid => {
+                log::info!(
+                    "{:?} is not a row and column, such as \"12,34\".",
+                    input.text()
+                );
+            }
+            super::goto::CoordinateInputResult::Cancelled => {
+                log::info!("Jump to cell cancelled.");
+                self.goto_input = None;
+            }
+            super::goto::CoordinateInputResult::Confirmed(row, col)
+                if row >= dimensions.0 || col >= dimensions.1 =>
+            {
+                log::info!("Cell ({row}, {col}) is outside of the state of size {dimensions:?}.");
+            }
+            super::goto::CoordinateInputResult::Confirmed(row, col) => {
+                let point = [
+                    (col as f32 + 0.5) / dimensions.1 as f32,
+                    (row as f32 + 0.5) / dimensions.0 as f32,
+                ];
+                let zoom = dimensions.0.max(dimensions.1) as f32 / GOTO_VISIBLE_CELLS;
+                view.move_camera(dimensions, |camera| camera.focus(point, zoom));
+                // shown in the title until the mouse moves again
+                self.hovered_cell = Some((row, col));
+                self.goto_input = None;
+                log::info!("Jumped to cell ({row}, {col}).");
+            }
+        }
+    }
+
+    /// Returns a short description of the currently hovered cell, containing its position, character, id and display color.
+    /// Used as the window title.
+    pub(crate) fn hover_info(&self, model: &super::AutomatonModel) -> String {
+        let automaton = model.automaton();
+        let info = match self
+            .hovered_cell
+            .and_then(|(row, col)| Some((row, col, automaton.get_cell(row, col).ok()?)))
+        {
+            Some((row, col, cell)) => format!(
+                "Cellumina - ({}, {}): '{}' ({}) {}",
+                row,
+                col,
+                crate::id_to_char(cell),
+                automaton.describe_symbol(cell),
+                automaton
+                    .get_color(cell)
+                    .map(|color| format!("{:?}", color))
+                    .unwrap_or_else(|| "no color".to_string()),
+            ),
+            None => "Cellumina".to_string(),
+        };
+        let info = match self.active_stamp.and_then(|index| self.stamps.get(index)) {
+            Some((name, _)) => format!("{info} - Stamp: {name}"),
+            None => info,
+        };
+        let info = match &model.pause_reason {
+            Some(reason) => format!("{info} - Paused: {reason}"),
+            None => info,
+        };
+        let info = match &self.goto_input {
+            Some(input) => format!("{info} - Go to: {}_", input.text()),
+            None => info,
+        };
+        if self.debug_mode {
+            format!("{info} - Debug")
+        } else {
+            info
+        }
+    }
+}
+
+/// Converts a position in pixels within a window of the passed size (width, height) into the texture coordinates displayed at that position.
+/// ```quad``` is the rectangle the texture is drawn to in normalized device coordinates ```[left, bottom, right, top]```,
+/// and ```region``` the part of the texture shown on it as ```[left, top, right, bottom]```.
+/// Returns [None] if the position is not within the quad.
+pub(super) fn pixel_to_texture(
+    position: (f64, f64),
+    window_size: (u32, u32),
+    quad: [f32; 4],
+    region: [f32; 4],
+) -> Option<[f32; 2]> {
+    // convert to normalized device coordinates, y pointing up
+    let x_ndc = 2. * position.0 / window_size.0 as f64 - 1.;
+    let y_ndc = 1. - 2. * position.1 / window_size.1 as f64;
+
+    // relative position within the quad, starting at its top left
+    let rel_x = (x_ndc - quad[0] as f64) / (quad[2] - quad[0]) as f64;
+    let rel_y = (quad[3] as f64 - y_ndc) / (quad[3] - quad[1]) as f64;
+
+    if (0. ..1.).contains(&rel_x) && (0. ..1.).contains(&rel_y) {
+        Some([
+            region[0] + rel_x as f32 * (region[2] - region[0]),
+            region[1] + rel_y as f32 * (region[3] - region[1]),
+        ])
+    } else {
+        None
+    }
+}
+
+/// Converts a position in pixels within a window of the passed size (width, height) into the (row, column) of the cell displayed at that position.
+/// ```quad``` and ```region``` describe where and what part of the state is drawn, see [pixel_to_texture].
+/// Returns [None] if the position is not within the drawn state.
+pub(super) fn pixel_to_cell(
+    position: (f64, f64),
+    window_size: (u32, u32),
+    quad: [f32; 4],
+    region: [f32; 4],
+    dimensions: (u32, u32),
+) -> Option<(u32, u32)> {
+    pixel_to_texture(position, window_size, quad, region).map(|[u, v]| {
+        (
+            ((v * dimensions.0 as f32) as u32).min(dimensions.0.saturating_sub(1)),
+            ((u * dimensions.1 as f32) as u32).min(dimensions.1.saturating_sub(1)),
+        )
+    })
+}
+
+/// Places ```stamp``` centered on the passed (row, column) of the automaton, see [Automaton::stamp](crate::Automaton::stamp).
+/// Returns the changed cells with their new values.
+pub(super) fn place_stamp(
+    automaton: &mut crate::Automaton,
+    center: (u32, u32),
+    stamp: &crate::CellGrid,
+) -> Vec<(u32, u32, u8)> {
+    automaton.stamp(
+        center.0 as i64 - stamp.rows() as i64 / 2,
+        center.1 as i64 - stamp.cols() as i64 / 2,
+        stamp,
+    )
+}
+
+/// Lists the parts of a rule as returned by [Rule::toggles](crate::rule::Rule::toggles), one per line, numbered by the keys toggling them.
+fn describe_toggles(toggles: &[(String, bool)]) -> String {
+    if toggles.is_empty() {
+        return "  (the rule has no parts that can be toggled)".to_string();
+    }
+    toggles
+        .iter()
+        .enumerate()
+        .map(|(index, (name, enabled))| {
+            format!(
+                "  {}: {name} ({})",
+                index + 1,
+                if *enabled { "enabled" } else { "disabled" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the index of the stamp selected by a number key, if the key is one of ```1``` to ```9```.
+fn stamp_index(code: winit::event::VirtualKeyCode) -> Option<usize> {
+    use winit::event::VirtualKeyCode::*;
+    [Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9]
+        .iter()
+        .position(|&key| key == code)
+}
+
+#[test]
+fn pixel_to_cell_test() {
+    // full window, 10 rows and 20 columns on a 200x100 window => 10x10 pixels per cell
+    let quad = [-1., -1., 1., 1.];
+    let full = [0., 0., 1., 1.];
+    assert_eq!(
+        pixel_to_cell((0., 0.), (200, 100), quad, full, (10, 20)),
+        Some((0, 0))
+    );
+    assert_eq!(
+        pixel_to_cell((15., 25.), (200, 100), quad, full, (10, 20)),
+        Some((2, 1))
+    );
+    assert_eq!(
+        pixel_to_cell((199.9, 99.9), (200, 100), quad, full, (10, 20)),
+        Some((9, 19))
+    );
+    assert_eq!(
+        pixel_to_cell((200., 50.), (200, 100), quad, full, (10, 20)),
+        None
+    );
+
+    // letterboxed: 4x4 grid on a 200x100 window only covers the middle 100 pixels horizontally
+    let quad = [-0.5, -1., 0.5, 1.];
+    assert_eq!(
+        pixel_to_cell((40., 50.), (200, 100), quad, full, (4, 4)),
+        None
+    );
+    assert_eq!(
+        pixel_to_cell((160., 50.), (200, 100), quad, full, (4, 4)),
+        None
+    );
+    assert_eq!(
+        pixel_to_cell((50., 0.), (200, 100), quad, full, (4, 4)),
+        Some((0, 0))
+    );
+    assert_eq!(
+        pixel_to_cell((149., 99.), (200, 100), quad, full, (4, 4)),
+        Some((3, 3))
+    );
+
+    // zoomed in on the bottom right quarter of the 4x4 grid
+    let region = [0.5, 0.5, 1., 1.];
+    assert_eq!(
+        pixel_to_cell((50., 0.), (200, 100), quad, region, (4, 4)),
+        Some((2, 2))
+    );
+    assert_eq!(
+        pixel_to_cell((149., 99.), (200, 100), quad, region, (4, 4)),
+        Some((3, 3))
+    );
+    assert_eq!(
+        pixel_to_texture((100., 50.), (200, 100), quad, region),
+        Some([0.75, 0.75])
+    );
+}
+
+#[test]
+fn replacement_cell_test() {
+    let mut controller = AutomatonController::new();
+    assert_eq!(controller.replacement_cell, crate::char_to_id('X'));
+    assert_eq!(
+        controller
+            .key_bindings
+            .char_for_key(winit::event::VirtualKeyCode::A)
+            .map(crate::char_to_id),
+        Some(crate::char_to_id('A'))
+    );
+
+    // a cell without letter or digit survives painting, saving and reloading
+    controller.replacement_cell = 100;
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_grid(grid::Grid::new(2, 3))
+        .build();
+    automaton
+        .set_cell(1, 2, controller.replacement_cell)
+        .unwrap();
+
+    let path = std::env::temp_dir().join("cellumina_replacement_cell_test.txt");
+    automaton.save(&path).unwrap();
+    let reloaded = crate::AutomatonBuilder::new()
+        .from_text_file(path.clone())
+        .with_strict_parsing()
+        .build();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(reloaded.state(), automaton.state());
+    assert_eq!(reloaded.get_cell(1, 2).unwrap(), 100);
+
+    assert!(matches!(
+        automaton.save("state.xyz"),
+        Err(crate::CelluminaError::UnsupportedFormat(ext)) if ext == "xyz"
+    ));
+}
+
+#[test]
+fn place_stamp_test() {
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1; 16], 4)
+        .build();
+    // a plus shape with transparent corners
+    let stamp = grid::grid![[127, 2, 127][2, 3, 2][127, 2, 127]];
+
+    // fully inside, the corners keep their cells
+    let changed = place_stamp(&mut automaton, (1, 1), &stamp);
+    assert_eq!(changed.len(), 5);
+    assert_eq!(
+        automaton.state(),
+        &grid::grid![[1, 2, 1, 1][2, 3, 2, 1][1, 2, 1, 1][1, 1, 1, 1]]
+    );
+
+    // at the bottom right corner, the parts outside of the state are cut off
+    let changed = place_stamp(&mut automaton, (3, 3), &stamp);
+    assert_eq!(changed, vec![(2, 3, 2), (3, 2, 2), (3, 3, 3)]);
+    assert_eq!(
+        automaton.state(),
+        &grid::grid![[1, 2, 1, 1][2, 3, 2, 1][1, 2, 1, 2][1, 1, 2, 3]]
+    );
+
+    // at the top left corner, transparent cells never overwrite the state
+    let changed = place_stamp(&mut automaton, (0, 0), &grid::grid![[127, 127][127, 0]]);
+    assert_eq!(changed, vec![(0, 0, 0)]);
+    assert_eq!(automaton.get_cell(0, 0).unwrap(), 0);
+    assert_eq!(automaton.get_cell(1, 1).unwrap(), 3);
+
+    // placing the same stamp again changes nothing
+    assert!(place_stamp(&mut automaton, (3, 3), &stamp).is_empty());
+}
+
+#[test]
+fn describe_toggles_test() {
+    assert_eq!(
+        describe_toggles(&[("fire".to_string(), true), ("decay".to_string(), false)]),
+        "  1: fire (enabled)\n  2: decay (disabled)"
+    );
+    assert!(describe_toggles(&[]).contains("no parts"));
 }
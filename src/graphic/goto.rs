@@ -0,0 +1,124 @@
+use winit::event::VirtualKeyCode;
+
+/// The coordinates typed after pressing ```Ctrl + G```, as digits separated by a comma, until they are confirmed with ```Enter``` or cancelled with ```Escape```.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct CoordinateInput {
+    /// The characters typed so far.
+    text: String,
+}
+
+/// What happened to a [CoordinateInput] when a key was pressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CoordinateInputResult {
+    /// The input continues, possibly with a changed text.
+    Continue,
+    /// The input was confirmed with the passed (row, column).
+    Confirmed(u32, u32),
+    /// The input was confirmed, but does not describe a cell. The input continues so it can be corrected.
+    Invalid,
+    /// The input was cancelled.
+    Cancelled,
+}
+
+impl CoordinateInput {
+    /// Handles a pressed key. Digits and the separator are typed, ```Backspace``` deletes the last character, other keys are ignored.
+    /// The number row and the numpad both type digits, and ```Space``` or ```Tab``` may be used instead of a comma.
+    pub(super) fn press(&mut self, key: VirtualKeyCode) -> CoordinateInputResult {
+        match key {
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => match self.parse() {
+                Some((row, col)) => CoordinateInputResult::Confirmed(row, col),
+                None => CoordinateInputResult::Invalid,
+            },
+            VirtualKeyCode::Escape => CoordinateInputResult::Cancelled,
+            VirtualKeyCode::Back => {
+                self.text.pop();
+                CoordinateInputResult::Continue
+            }
+            VirtualKeyCode::Tab => self.type_char(','),
+            key => match super::KeyBindings::default_char_for_key(key) {
+                Some(',' | ' ') => self.type_char(','),
+                Some(digit @ '0'..='9') => self.type_char(digit),
+                _ => CoordinateInputResult::Continue,
+            },
+        }
+    }
+
+    /// Appends the passed character, allowing only a single separator.
+    fn type_char(&mut self, character: char) -> CoordinateInputResult {
+        if character != ',' || !self.text.contains(',') {
+            self.text.push(character);
+        }
+        CoordinateInputResult::Continue
+    }
+
+    /// Returns the (row, column) described by the text typed so far, if it is complete.
+    fn parse(&self) -> Option<(u32, u32)> {
+        let (row, col) = self.text.split_once(',')?;
+        Some((row.parse().ok()?, col.parse().ok()?))
+    }
+
+    /// Returns the text typed so far.
+    pub(super) fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[test]
+fn coordinate_input_test() {
+    use VirtualKeyCode::*;
+
+    let type_keys = |keys: &[VirtualKeyCode]| {
+        let mut input = CoordinateInput::default();
+        let results = keys.iter().map(|&key| input.press(key)).collect::<Vec<_>>();
+        (input, results)
+    };
+
+    // "12,34⏎", mixing the number row and the numpad
+    let (input, results) = type_keys(&[Key1, Numpad2, Comma, Key3, Numpad4, Return]);
+    assert_eq!(input.text(), "12,34");
+    assert_eq!(
+        results.last(),
+        Some(&CoordinateInputResult::Confirmed(12, 34))
+    );
+    assert!(results[..5]
+        .iter()
+        .all(|&result| result == CoordinateInputResult::Continue));
+
+    // space and tab separate as well, but only once, and letters are ignored
+    let (input, results) = type_keys(&[Key5, Space, Tab, A, Key0, NumpadEnter]);
+    assert_eq!(input.text(), "5,0");
+    assert_eq!(
+        results.last(),
+        Some(&CoordinateInputResult::Confirmed(5, 0))
+    );
+
+    // backspace corrects typos
+    let (input, results) = type_keys(&[Key1, Key9, Back, Key2, Comma, Key3, Return]);
+    assert_eq!(input.text(), "12,3");
+    assert_eq!(
+        results.last(),
+        Some(&CoordinateInputResult::Confirmed(12, 3))
+    );
+
+    // incomplete coordinates are rejected, but can still be completed
+    let (mut input, results) = type_keys(&[Key7, Return, Comma, Return]);
+    assert_eq!(results[1], CoordinateInputResult::Invalid);
+    assert_eq!(results[3], CoordinateInputResult::Invalid);
+    input.press(Key8);
+    assert_eq!(input.press(Return), CoordinateInputResult::Confirmed(7, 8));
+
+    // escape cancels at any point
+    let (_, results) = type_keys(&[Key1, Comma, Key2, Escape]);
+    assert_eq!(results.last(), Some(&CoordinateInputResult::Cancelled));
+
+    // numbers too large for a coordinate do not panic
+    let (_, results) = type_keys(&[Key9; 12]);
+    assert!(results
+        .iter()
+        .all(|&result| result == CoordinateInputResult::Continue));
+    let mut input = CoordinateInput::default();
+    for key in [Key9; 12].into_iter().chain([Comma, Key1]) {
+        input.press(key);
+    }
+    assert_eq!(input.press(Return), CoordinateInputResult::Invalid);
+}
@@ -0,0 +1,122 @@
+use crate::Automaton;
+
+/// A callback invoked after each time step.
+type StepHook = Box<dyn FnMut(&mut Automaton, u64)>;
+/// A callback invoked when a cell is painted.
+type EditHook = Box<dyn FnMut(u32, u32, u8)>;
+/// A callback invoked when the window is closed.
+type ExitHook = Box<dyn FnMut(&Automaton)>;
+
+/// A collection of callbacks invoked by the live view, created by [Automaton::run_live_with_hooks].
+///
+/// All callbacks run on the thread of the event loop, between the time step of the automaton and the update of the display,
+/// so changes made to the automaton are visible in the same frame.
+/// ```no_run
+/// # use cellumina::LiveHooks;
+/// let hooks = LiveHooks::new()
+///     .on_step(|automaton, generation| {
+///         if generation % 10 == 0 {
+///             automaton.set_cell(0, 0, 1).ok();
+///         }
+///     })
+///     .on_exit(|automaton| println!("Stopped after {} steps.", automaton.generation()));
+/// ```
+#[derive(Default)]
+pub struct LiveHooks {
+    /// Called after each time step with the automaton and its new generation.
+    step: Option<StepHook>,
+    /// Called when a cell is painted by the user, with its row, column and new value.
+    edit: Option<EditHook>,
+    /// Called with the final state of the automaton when the window is closed.
+    exit: Option<ExitHook>,
+}
+
+impl LiveHooks {
+    /// Creates a new collection of hooks that does nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function called after each time step with the automaton and its new generation.
+    pub fn on_step(mut self, hook: impl FnMut(&mut Automaton, u64) + 'static) -> Self {
+        self.step = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets the function called when a cell is painted by the user, with its row, column and new value.
+    pub fn on_edit(mut self, hook: impl FnMut(u32, u32, u8) + 'static) -> Self {
+        self.edit = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets the function called with the final state of the automaton when the window is closed.
+    pub fn on_exit(mut self, hook: impl FnMut(&Automaton) + 'static) -> Self {
+        self.exit = Some(Box::new(hook));
+        self
+    }
+
+    /// Invokes the step hook, if any.
+    pub(super) fn step(&mut self, automaton: &mut Automaton) {
+        if let Some(hook) = &mut self.step {
+            let generation = automaton.generation();
+            hook(automaton, generation);
+        }
+    }
+
+    /// Invokes the edit hook, if any.
+    pub(super) fn edit(&mut self, row: u32, col: u32, cell: u8) {
+        if let Some(hook) = &mut self.edit {
+            hook(row, col, cell);
+        }
+    }
+
+    /// Invokes the exit hook, if any.
+    pub(super) fn exit(&mut self, automaton: &Automaton) {
+        if let Some(hook) = &mut self.exit {
+            hook(automaton);
+        }
+    }
+}
+
+impl std::fmt::Debug for LiveHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LiveHooks")
+            .field("step", &self.step.is_some())
+            .field("edit", &self.edit.is_some())
+            .field("exit", &self.exit.is_some())
+            .finish()
+    }
+}
+
+#[test]
+fn hooks_test() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (log1, log2, log3) = (log.clone(), log.clone(), log.clone());
+    let mut hooks = LiveHooks::new()
+        .on_step(move |automaton, generation| {
+            automaton.set_cell(0, 0, 5).unwrap();
+            log1.borrow_mut().push(format!("step {generation}"));
+        })
+        .on_edit(move |row, col, cell| log2.borrow_mut().push(format!("edit {row} {col} {cell}")))
+        .on_exit(move |automaton| {
+            log3.borrow_mut()
+                .push(format!("exit {}", automaton.get_cell(0, 0).unwrap()))
+        });
+
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .build();
+    automaton.next_step();
+    hooks.step(&mut automaton);
+    hooks.edit(1, 0, 3);
+    hooks.exit(&automaton);
+
+    assert_eq!(*log.borrow(), vec!["step 1", "edit 1 0 3", "exit 5"]);
+
+    // empty hooks do nothing
+    let mut hooks = LiveHooks::new();
+    hooks.step(&mut automaton);
+    hooks.exit(&automaton);
+}
@@ -0,0 +1,166 @@
+use winit::event::VirtualKeyCode;
+
+/// The characters typed with the keys of the live view, selecting the symbol painted with the mouse, see [LiveViewConfig::key_bindings](crate::LiveViewConfig::key_bindings).
+///
+/// By default, letters, digits (of both the number row and the numpad), space and the usual punctuation keys type their character, see [KeyBindings::default_char_for_key].
+/// Only characters with an associated cell value (see [try_char_to_id](crate::try_char_to_id)) select a symbol, so keys typing other characters
+/// can be bound to any character to make symbols without a letter or digit reachable.
+/// ```no_run
+/// # use cellumina::{KeyBindings, LiveViewConfig, VirtualKeyCode};
+/// let config = LiveViewConfig {
+///     // minus paints cell 100, F1 paints the border symbol
+///     key_bindings: KeyBindings::default()
+///         .with_key(VirtualKeyCode::Minus, Some(cellumina::id_to_char(100)))
+///         .with_key(VirtualKeyCode::F1, Some('_')),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// Keys typing a different character than by default, or none at all.
+    overrides: std::collections::HashMap<VirtualKeyCode, Option<char>>,
+}
+
+impl KeyBindings {
+    /// Binds the passed key to the passed character, replacing its default binding. Binding a key to [None] unbinds it.
+    ///
+    /// Keys with other functions in the live view, such as ```Enter``` pausing the simulation, keep them.
+    pub fn with_key(mut self, key: VirtualKeyCode, character: Option<char>) -> Self {
+        self.overrides.insert(key, character);
+        self
+    }
+
+    /// Returns the character typed with the passed key, if any.
+    pub fn char_for_key(&self, key: VirtualKeyCode) -> Option<char> {
+        match self.overrides.get(&key) {
+            Some(&character) => character,
+            None => Self::default_char_for_key(key),
+        }
+    }
+
+    /// Returns the character typed with the passed key without any changed bindings, if any.
+    ///
+    /// Letters always type their upper case character.
+    pub fn default_char_for_key(key: VirtualKeyCode) -> Option<char> {
+        use VirtualKeyCode::*;
+        Some(match key {
+            A => 'A',
+            B => 'B',
+            C => 'C',
+            D => 'D',
+            E => 'E',
+            F => 'F',
+            G => 'G',
+            H => 'H',
+            I => 'I',
+            J => 'J',
+            K => 'K',
+            L => 'L',
+            M => 'M',
+            N => 'N',
+            O => 'O',
+            P => 'P',
+            Q => 'Q',
+            R => 'R',
+            S => 'S',
+            T => 'T',
+            U => 'U',
+            V => 'V',
+            W => 'W',
+            X => 'X',
+            Y => 'Y',
+            Z => 'Z',
+            Key0 | Numpad0 => '0',
+            Key1 | Numpad1 => '1',
+            Key2 | Numpad2 => '2',
+            Key3 | Numpad3 => '3',
+            Key4 | Numpad4 => '4',
+            Key5 | Numpad5 => '5',
+            Key6 | Numpad6 => '6',
+            Key7 | Numpad7 => '7',
+            Key8 | Numpad8 => '8',
+            Key9 | Numpad9 => '9',
+            Space => ' ',
+            Asterisk | NumpadMultiply => '*',
+            Plus | NumpadAdd => '+',
+            Minus | NumpadSubtract => '-',
+            Slash | NumpadDivide => '/',
+            Period | NumpadDecimal => '.',
+            Comma | NumpadComma => ',',
+            Equals | NumpadEquals => '=',
+            Underline => '_',
+            Apostrophe => '\'',
+            At => '@',
+            Backslash => '\\',
+            Caret => '^',
+            Colon => ':',
+            Semicolon => ';',
+            Grave => '`',
+            LBracket => '[',
+            RBracket => ']',
+            _ => return None,
+        })
+    }
+}
+
+/// Returns wether the passed key is a modifier, which is pressed as part of shortcuts and not expected to type anything.
+pub(super) fn is_modifier(key: VirtualKeyCode) -> bool {
+    use VirtualKeyCode::*;
+    matches!(
+        key,
+        LControl | RControl | LShift | RShift | LAlt | RAlt | LWin | RWin
+    )
+}
+
+#[test]
+fn key_bindings_test() {
+    use VirtualKeyCode::*;
+
+    let bindings = KeyBindings::default();
+    for (key, character) in [
+        (A, 'A'),
+        (M, 'M'),
+        (Z, 'Z'),
+        (Key0, '0'),
+        (Key7, '7'),
+        (Numpad0, '0'),
+        (Numpad3, '3'),
+        (Numpad9, '9'),
+        (Space, ' '),
+        (Minus, '-'),
+        (NumpadSubtract, '-'),
+        (Period, '.'),
+        (NumpadDecimal, '.'),
+        (Comma, ','),
+        (Slash, '/'),
+        (NumpadDivide, '/'),
+        (NumpadMultiply, '*'),
+        (Asterisk, '*'),
+        (Underline, '_'),
+        (LBracket, '['),
+    ] {
+        assert_eq!(bindings.char_for_key(key), Some(character), "{key:?}");
+    }
+    for key in [Escape, Return, F1, Up, LControl, Tab] {
+        assert_eq!(bindings.char_for_key(key), None, "{key:?}");
+    }
+
+    // the numpad reaches the same symbols as the number row
+    assert_eq!(
+        bindings.char_for_key(Numpad5).map(crate::char_to_id),
+        Some(5)
+    );
+
+    let bindings = bindings
+        .with_key(Minus, Some('\u{164}'))
+        .with_key(F1, Some('_'))
+        .with_key(A, None);
+    assert_eq!(
+        bindings.char_for_key(Minus).map(crate::char_to_id),
+        Some(100)
+    );
+    assert_eq!(bindings.char_for_key(F1), Some('_'));
+    assert_eq!(bindings.char_for_key(A), None);
+    assert_eq!(bindings.char_for_key(B), Some('B'));
+    assert!(is_modifier(RShift) && !is_modifier(A));
+}
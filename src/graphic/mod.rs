@@ -1,3 +1,5 @@
+/// Contains the [camera::Camera] struct describing the visible part of the automaton.
+mod camera;
 /// Contains the [vertex::Vertex] struct and some related const objects.
 mod vertex;
 /// Contains the [displayer::AutomatonDisplayer] struct.
@@ -10,19 +12,52 @@ use controller::AutomatonController;
 mod model;
 use model::AutomatonModel;
 
+/// Contains the [LiveViewConfig] struct and related enums.
+mod config;
+pub use config::{ColorMode, LiveStepping, LiveViewConfig, PresentMode};
+
+/// Contains the [LiveHooks] struct for callbacks from the live view.
+mod hooks;
+pub use hooks::LiveHooks;
+
+/// Contains the [KeyBindings] struct mapping keys to the characters they type.
+mod keys;
+pub use keys::KeyBindings;
+pub use winit::event::VirtualKeyCode;
+
+/// Contains the [goto::CoordinateInput] struct for typing the coordinates of a cell to jump to.
+mod goto;
+
+/// Contains the [timer::FrameTimer] struct limiting the frame rate of the live view.
+mod timer;
+
+/// Contains the [texture_cache::TextureCache] struct keeping the pixel data of the cell state texture between frames.
+mod texture_cache;
+
+/// Contains the [LiveSession] struct for showing multiple automata one after another.
+mod session;
+pub use session::{LiveResult, LiveSession};
+
 use winit::{
-    dpi::PhysicalSize,
     event::*,
     event_loop::{ControlFlow, EventLoop},
+    platform::run_return::EventLoopExtRunReturn,
     window::WindowBuilder,
 };
 
 use crate::automaton;
 
 /// Creates an [AutomatonDisplayer] for the passed [automaton::Automaton], creates a window
-pub(crate) async fn run_live(automaton: automaton::Automaton) {
-    let event_loop = EventLoop::new();
-
+/// The automaton is shared with the host, see [AutomatonModel] for how it is locked.
+/// Returns once the window is closed, dropping the window and all GPU resources, so the event loop can be used again, see [LiveSession].
+/// ## Error
+/// A [GraphicsInitError](crate::CelluminaError::GraphicsInitError) if the window could not be set up, in which case the event loop is not run.
+async fn run_live(
+    event_loop: &mut EventLoop<()>,
+    automaton: automaton::SharedAutomaton,
+    config: LiveViewConfig,
+    hooks: LiveHooks,
+) -> Result<LiveResult, crate::CelluminaError> {
     log::info!("Starting window initialization.");
 
     let window = WindowBuilder::new()
@@ -33,61 +68,119 @@ pub(crate) async fn run_live(automaton: automaton::Automaton) {
         // for now
         .with_resizable(true)
         .with_title("Cellumina")
-        .build(&event_loop)
-        .expect("Could not init window.");
+        .build(event_loop)
+        .map_err(|err| crate::GraphicsInitError::Setup(err.into()))?;
 
     log::info!("Created window.");
 
-    let (mut view, mut model) = AutomatonView::create_view_model(window, automaton).await;
+    let (mut view, mut model) =
+        AutomatonView::create_view_model(window, automaton, &config).await?;
+    model.hooks = hooks;
+    model.auto_pause = config.auto_pause;
+    model.stepping = config.stepping;
 
     log::info!("Created view and model.");
+
+    // upload the initial state, as the first step might still be a while away
+    model.write_texture(&view.queue);
     log::debug!("Model: {:?}", model);
     log::debug!("View: {:?}", view);
 
-    let mut controller = AutomatonController::new();
+    let mut controller = AutomatonController::new()
+        .with_stamps(config.stamps.clone())
+        .with_key_bindings(config.key_bindings.clone());
+    // the last title set on the window, to avoid unneccessary updates
+    let mut title = String::new();
+
+    let mut timer = timer::FrameTimer::new(config.max_frame_rate);
 
     log::info!("Created controller.");
     log::debug!("Controller: {:?}", controller);
 
     log::info!("Initializing event loop. Starting simulation.");
 
-    event_loop.run(move |event, _event_loop_window_target, control_flow| {
+    // the automaton is handed back once the loop is destroyed
+    let mut finished = None;
+    let finished_ref = &mut finished;
+
+    let exit_code = event_loop.run_return(move |event, _event_loop_window_target, control_flow| {
         match event {
             // Window events
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == view.window.id() => {
                 // first try to handle by the drawing state
-                if !controller.handle_event(&mut model, &view.config, event) {
-                    // then handle events concerning the actual window
-                    view.window_events(control_flow, event, model.cell_state.dimensions());
-                }
+            } if window_id == view.window.id()
+                && !controller.handle_event(&mut model, &mut view, event) =>
+            {
+                // then handle events concerning the actual window
+                let dimensions = model.automaton().dimensions();
+                view.window_events(control_flow, event, dimensions);
+                // window changes such as resizing need a redraw even if the model did not change
+                view.window.request_redraw();
             }
             Event::RedrawRequested(window_id) if window_id == view.window.id() => {
-                if model.update() || controller.modify(&mut model) {
-                    model.write_texture(&mut view.queue);
-                }
-
                 match view.render() {
                     Ok(_) => {}
-                    // Reconfigure the surface if lost
-                    Err(wgpu::SurfaceError::Lost) => view.resize(
-                        PhysicalSize::new(view.config.width, view.config.height),
-                        model.cell_state.dimensions(),
-                    ),
+                    // Reconfigure the surface if lost or outdated, e.g. after the window was restored
+                    Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                        let size = view.window.inner_size();
+                        let dimensions = model.automaton().dimensions();
+                        view.resize(size, dimensions);
+                        view.window.request_redraw();
+                    }
                     // The system is out of memory, we should probably quit
-                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
-                    // All other errors (Outdated, Timeout) should be resolved by the next frame
-                    Err(e) => log::error!("{:?}", e),
+                    Err(wgpu::SurfaceError::OutOfMemory) => {
+                        *control_flow = ControlFlow::ExitWithCode(1)
+                    }
+                    // Timeouts should be resolved by the next frame
+                    Err(e) => log::warn!("{:?}", e),
                 }
             }
+            // Give the host a last look at the automaton, and close the window right away instead of once it is dropped.
+            Event::LoopDestroyed => {
+                model.exit();
+                view.window.set_visible(false);
+                *finished_ref = Some(model.shared());
+            }
             Event::MainEventsCleared => {
-                // RedrawRequested will only trigger once, unless we manually
-                // request it.
-                view.window.request_redraw();
+                // Only check the model once per frame, and only redraw if anything changed.
+                if timer.poll(std::time::Instant::now()) {
+                    // the dimensions are checked every frame, so the texture never mismatches the automaton when written
+                    let changed = model.update()
+                        | controller.modify(&mut model)
+                        | view.adapt_to_dimensions(&mut model);
+                    if changed {
+                        model.write_texture(&view.queue);
+                        view.window.request_redraw();
+                    // other threads may have changed the automaton without the model knowing
+                    } else if model.is_shared() && model.write_texture(&view.queue) {
+                        view.window.request_redraw();
+                    }
+
+                    // show information about the hovered cell in the title
+                    let new_title = controller.hover_info(&model);
+                    if new_title != title {
+                        view.window.set_title(&new_title);
+                        title = new_title;
+                    }
+                }
+
+                // Sleep until the next frame is due, unless the event loop is already exiting.
+                if !matches!(*control_flow, ControlFlow::ExitWithCode(_)) {
+                    *control_flow = match timer.next_frame() {
+                        timer::NextFrame::At(instant) => ControlFlow::WaitUntil(instant),
+                        timer::NextFrame::Never => ControlFlow::Wait,
+                        timer::NextFrame::Unlimited => ControlFlow::Poll,
+                    };
+                }
             }
             _ => {}
         }
     });
+
+    Ok(LiveResult {
+        automaton: finished.expect("The event loop is destroyed before it returns."),
+        failed: exit_code != 0,
+    })
 }
@@ -1,14 +1,90 @@
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use wgpu::util::DeviceExt;
+
 use crate::automaton;
 
 /// A part of the MVC pattern, describing the underlying model/data of a live-run automaton.
+///
+/// The automaton may be shared with other threads of the host, see [Automaton::run_live_shared](crate::Automaton::run_live_shared).
+/// Therefore, it is only ever locked for one operation at a time, through [Self::automaton] and [Self::automaton_mut],
+/// and no lock is held while waiting for the next frame or while rendering.
 #[derive(Debug)]
 pub(super) struct AutomatonModel {
-    /// The contained automaton representing a cell state to draw.
-    pub(super) cell_state: automaton::Automaton,
+    /// The contained automaton representing a cell state to draw, possibly shared with other threads.
+    cell_state: automaton::SharedAutomaton,
     /// Wether the simulation is currently paused, so only drawn and not progressed.
     pub(super) paused: bool,
     /// The current texture updated to the state of the automaton.
     pub(super) cell_state_texture: wgpu::Texture,
+    /// How the cell state is converted to colors, and therefore what the texture contains.
+    pub(super) color_mode: super::ColorMode,
+    /// If the activity heatmap is shown, the map tracking the age of each cell.
+    pub(super) activity: Option<crate::ActivityMap>,
+    /// Callbacks supplied by the host, invoked on steps, edits and exit.
+    pub(super) hooks: super::LiveHooks,
+    /// The condition on which the simulation pauses by itself, turned off once it was met.
+    pub(super) auto_pause: crate::AutoPause,
+    /// The reason the simulation was paused by the auto pause condition, until it is resumed.
+    pub(super) pause_reason: Option<String>,
+    /// How many steps are performed each frame.
+    pub(super) stepping: super::LiveStepping,
+    /// The state after the last step, to detect steps that changed nothing, if the auto pause condition needs it.
+    last_state: Option<crate::CellGrid>,
+    /// The pixel data last written to the texture, used to only upload rows that changed.
+    texture_cache: super::texture_cache::TextureCache,
+}
+
+/// The number of generations after which the highlight of a changed cell in the activity heatmap has decayed by half.
+const ACTIVITY_HALF_LIFE: f32 = 8.;
+
+/// The color information uploaded to the GPU when drawing raw cell states.
+/// Mirrors the ```ColorInfo``` struct in ```shader_indexed.wgsl```.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ColorInfo {
+    /// The colors of all cells, in linear color space.
+    palette: [[f32; 4]; 256],
+    /// The color of cells with state 0 in gradient mode, in linear color space.
+    gradient_from: [f32; 4],
+    /// The color of cells with state ```gradient_max``` in gradient mode, in linear color space.
+    gradient_to: [f32; 4],
+    /// 0 for palette mode, 1 for gradient mode.
+    mode: u32,
+    /// The cell state receiving the color ```gradient_to```.
+    gradient_max: u32,
+    /// Padding to the alignment of a vec4.
+    _padding: [u32; 2],
+}
+
+impl ColorInfo {
+    /// Creates the color info for the passed color mode and the colors of all cells, as used for images of the state, so both agree on cells without a color of their own.
+    /// Returns [None] if the color mode does not need one.
+    fn new(color_mode: super::ColorMode, colors: &[[u8; 4]; 256]) -> Option<Self> {
+        let mut info = Self {
+            palette: [[0.; 4]; 256],
+            gradient_from: [0.; 4],
+            gradient_to: [0.; 4],
+            mode: 0,
+            gradient_max: 1,
+            _padding: [0; 2],
+        };
+        match color_mode {
+            super::ColorMode::Rgba => return None,
+            super::ColorMode::Palette => {
+                for (entry, &color) in info.palette.iter_mut().zip(colors) {
+                    *entry = crate::CellColor::from(color).to_linear_f32();
+                }
+            }
+            super::ColorMode::Gradient { from, to, max } => {
+                info.mode = 1;
+                info.gradient_from = crate::CellColor::from(from).to_linear_f32();
+                info.gradient_to = crate::CellColor::from(to).to_linear_f32();
+                info.gradient_max = max as u32;
+            }
+        }
+        Some(info)
+    }
 }
 
 impl AutomatonModel {
@@ -17,25 +93,148 @@ impl AutomatonModel {
     /// Mirrors [[AutomatonView::create_view_model]]
     pub(super) async fn create_view_model(
         window: winit::window::Window,
-        automaton: automaton::Automaton,
-    ) -> (super::AutomatonView, Self) {
-        super::AutomatonView::create_view_model(window, automaton).await
+        automaton: automaton::SharedAutomaton,
+        live_config: &super::LiveViewConfig,
+    ) -> Result<(super::AutomatonView, Self), crate::CelluminaError> {
+        super::AutomatonView::create_view_model(window, automaton, live_config).await
     }
 
     /// Creates a new AutomatonModel.
     ///
     /// Supposed to be used as part of creating an [[AutomatonView]], receiving its device and returning appropriate bind groups.
+    /// If the color mode requires color information on the GPU, a second bind group containing it is returned.
+    #[allow(clippy::type_complexity)]
     pub(super) fn new(
-        cell_state: automaton::Automaton,
+        cell_state: automaton::SharedAutomaton,
+        device: &wgpu::Device,
+        color_mode: super::ColorMode,
+    ) -> (
+        Self,
+        wgpu::BindGroupLayout,
+        wgpu::BindGroup,
+        Option<(wgpu::BindGroupLayout, wgpu::BindGroup)>,
+    ) {
+        let raw = color_mode != super::ColorMode::Rgba;
+
+        log::info!("Creating cell state bind group layout.");
+
+        let cell_state_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        // what shaders this is used in
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            // raw cell states are read as integers
+                            sample_type: if raw {
+                                wgpu::TextureSampleType::Uint
+                            } else {
+                                wgpu::TextureSampleType::Float { filterable: true }
+                            },
+                            // 2D
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            // wether to use multiple samples
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let automaton = read(&cell_state);
+        let (cell_state_texture, cell_state_bind_group) = Self::create_texture(
+            device,
+            automaton.dimensions(),
+            raw,
+            &cell_state_bind_group_layout,
+        );
+
+        let colors = automaton.image.lookup_colors();
+        let color_bind_group = ColorInfo::new(color_mode, colors).map(|info| {
+            log::info!("Creating color info bind group.");
+
+            let color_info_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Color Info Buffer"),
+                contents: bytemuck::cast_slice(&[info]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+            let color_info_bind_group_layout =
+                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Color Info Bind Group Layout"),
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+
+            let color_info_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Color Info Bind Group"),
+                layout: &color_info_bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: color_info_buffer.as_entire_binding(),
+                }],
+            });
+
+            (color_info_bind_group_layout, color_info_bind_group)
+        });
+        drop(automaton);
+
+        (
+            Self {
+                cell_state,
+                cell_state_texture,
+                paused: false,
+                color_mode,
+                activity: None,
+                hooks: Default::default(),
+                auto_pause: crate::AutoPause::Off,
+                pause_reason: None,
+                stepping: super::LiveStepping::WallClock,
+                last_state: None,
+                texture_cache: super::texture_cache::TextureCache::new(
+                    if color_mode == super::ColorMode::Rgba {
+                        4
+                    } else {
+                        1
+                    },
+                ),
+            },
+            cell_state_bind_group_layout,
+            cell_state_bind_group,
+            color_bind_group,
+        )
+    }
+
+    /// Creates a texture of the passed dimensions holding either raw cell states or RGBA colors, and a bind group with the passed layout to draw it.
+    fn create_texture(
         device: &wgpu::Device,
-    ) -> (Self, wgpu::BindGroupLayout, wgpu::BindGroup) {
+        dimensions: (u32, u32),
+        raw: bool,
+        layout: &wgpu::BindGroupLayout,
+    ) -> (wgpu::Texture, wgpu::BindGroup) {
         log::info!("Creating cell state texture.");
 
         let cell_state_texture = device.create_texture(&wgpu::TextureDescriptor {
             // the size of the texture
             size: wgpu::Extent3d {
-                width: cell_state.dimensions().1,
-                height: cell_state.dimensions().0,
+                width: dimensions.1,
+                height: dimensions.0,
                 // ??
                 depth_or_array_layers: 1,
             },
@@ -45,8 +244,12 @@ impl AutomatonModel {
             sample_count: 1,
             // not a 3D-object
             dimension: wgpu::TextureDimension::D2,
-            // we converted to rgba8 above
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            // either a converted rgba8 image, whose sRGB colors are converted to linear values when sampled, or the raw cell states
+            format: if raw {
+                wgpu::TextureFormat::R8Uint
+            } else {
+                wgpu::TextureFormat::Rgba8UnormSrgb
+            },
             // TEXTURE_BINDING = use in shaders, COPY_DST: data will be copied here
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             label: Some("diffuse texture"),
@@ -71,36 +274,9 @@ impl AutomatonModel {
 
         log::info!("Creating cell state bind group.");
 
-        let cell_state_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some("Texture Bind Group Layout"),
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        // what shaders this is used in
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            // ??
-                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                            // 2D
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            // wether to use multiple samples
-                            multisampled: false,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                        count: None,
-                    },
-                ],
-            });
-
         let cell_state_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Texture Bind Group"),
-            layout: &cell_state_bind_group_layout,
+            layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -113,48 +289,365 @@ impl AutomatonModel {
             ],
         });
 
-        (
-            Self {
-                cell_state,
-                cell_state_texture,
-                paused: false,
-            },
-            cell_state_bind_group_layout,
-            cell_state_bind_group,
-        )
+        (cell_state_texture, cell_state_bind_group)
+    }
+
+    /// Locks the automaton for reading, waiting for writes of other threads to complete.
+    /// The guard should be dropped as soon as possible, as other threads can not change the automaton while it is held.
+    pub(super) fn automaton(&self) -> RwLockReadGuard<'_, automaton::Automaton> {
+        read(&self.cell_state)
+    }
+
+    /// Locks the automaton for writing, waiting for all other accesses to complete.
+    /// The guard should be dropped as soon as possible, as other threads can not access the automaton while it is held.
+    pub(super) fn automaton_mut(&self) -> RwLockWriteGuard<'_, automaton::Automaton> {
+        write(&self.cell_state)
+    }
+
+    /// Gives the exit hook a last look at the automaton.
+    pub(super) fn exit(&mut self) {
+        let automaton = read(&self.cell_state);
+        self.hooks.exit(&automaton);
+    }
+
+    /// Returns another reference to the automaton, for example to return it once the window is closed.
+    pub(super) fn shared(&self) -> automaton::SharedAutomaton {
+        std::sync::Arc::clone(&self.cell_state)
+    }
+
+    /// Returns wether other threads hold a reference to the automaton, so it may change without the live view knowing.
+    pub(super) fn is_shared(&self) -> bool {
+        std::sync::Arc::strong_count(&self.cell_state) > 1
+    }
+
+    /// Returns the dimensions of the texture as (rows, columns).
+    fn texture_dimensions(&self) -> (u32, u32) {
+        let size = self.cell_state_texture.size();
+        (size.height, size.width)
+    }
+
+    /// If the dimensions of the automaton changed since the texture was created, e.g. because it was rotated or resized,
+    /// replaces the texture, its view and sampler with ones matching the new dimensions and returns the bind group to draw them, which the view has to bind instead of the old one.
+    /// The new texture is filled on the next call to [Self::write_texture].
+    /// Returns [None] if the texture still matches, which is a cheap check done every frame.
+    pub(super) fn rebuild_texture(
+        &mut self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+    ) -> Option<wgpu::BindGroup> {
+        let dimensions = self.automaton().dimensions();
+        if texture_matches(self.cell_state_texture.size(), dimensions) {
+            return None;
+        }
+        log::info!(
+            "Automaton dimensions changed to {dimensions:?}, recreating cell state texture."
+        );
+        let (texture, bind_group) = Self::create_texture(
+            device,
+            dimensions,
+            self.color_mode != super::ColorMode::Rgba,
+            layout,
+        );
+        self.cell_state_texture = texture;
+        self.texture_cache.invalidate();
+        Some(bind_group)
     }
 
     /// Turns the cell state of this model's automaton to a texture and writes it to the queue of the passed view.
     /// This queue must be the one created by the shared creater of Model and View.
-    pub(super) fn write_texture(&self, queue: &mut wgpu::Queue) {
+    /// Only rows containing cells that changed since the last call are uploaded, unless more than half of all rows changed.
+    /// If another thread changed the dimensions of the automaton since the texture was created, nothing is written until the texture is recreated.
+    /// Returns wether anything was written.
+    pub(super) fn write_texture(&mut self, queue: &wgpu::Queue) -> bool {
+        // hold the lock only while copying the state to the cache
+        let automaton = read(&self.cell_state);
+        if !texture_matches(self.cell_state_texture.size(), automaton.dimensions()) {
+            return false;
+        }
+        let (rows, cols) = automaton.state.size();
+
+        // the tint of every cell changes each generation, so the whole texture is rebuilt
+        if let Some(activity) = &self.activity {
+            let mut image = automaton.create_image_buffer();
+            drop(automaton);
+            activity.tint(&mut image, ACTIVITY_HALF_LIFE);
+            self.texture_cache.invalidate();
+            self.upload_rows(queue, &image.into_raw(), 4, 0..rows);
+            return true;
+        }
+
+        let dirty_rows = if self.color_mode == super::ColorMode::Rgba {
+            self.texture_cache
+                .update(&automaton.state, |cell| automaton.image_color(cell))
+        } else {
+            // raw cell states are uploaded as they are, one byte per cell
+            self.texture_cache
+                .update(&automaton.state, |cell| [cell, 0, 0, 0])
+        };
+        drop(automaton);
+
+        if let Some(mut dirty_rows) = dirty_rows {
+            // for large changes, one upload of everything is cheaper than a partial one
+            if dirty_rows.len() * 2 > rows {
+                dirty_rows = 0..rows;
+            }
+            let bytes_per_cell = self.texture_cache.bytes_per_cell();
+            let data = &self.texture_cache.data()
+                [dirty_rows.start * cols * bytes_per_cell..dirty_rows.end * cols * bytes_per_cell];
+            self.upload_rows(queue, data, bytes_per_cell, dirty_rows);
+            return true;
+        }
+        false
+    }
+
+    /// Writes the passed pixel data, containing exactly the passed range of rows, to the texture.
+    fn upload_rows(
+        &self,
+        queue: &wgpu::Queue,
+        data: &[u8],
+        bytes_per_cell: usize,
+        rows: std::ops::Range<usize>,
+    ) {
         queue.write_texture(
-            // copy destination
+            // copy destination, starting at the first changed row
             wgpu::ImageCopyTextureBase {
                 texture: &self.cell_state_texture,
                 mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: rows.start as u32,
+                    z: 0,
+                },
                 aspect: wgpu::TextureAspect::All,
             },
             // actual pixel data
-            &self.cell_state.create_image_buffer(),
+            data,
             // internal layout
             wgpu::ImageDataLayout {
                 offset: 0,
-                bytes_per_row: Some(4 * self.cell_state.dimensions().1),
-                rows_per_image: Some(self.cell_state.dimensions().0),
+                bytes_per_row: Some(bytes_per_cell as u32 * self.texture_dimensions().1),
+                rows_per_image: Some(rows.len() as u32),
             },
-            // size as above
+            // size of the changed region
             wgpu::Extent3d {
-                width: self.cell_state.dimensions().1,
-                height: self.cell_state.dimensions().0,
-                // ??
+                width: self.texture_dimensions().1,
+                height: rows.len() as u32,
                 depth_or_array_layers: 1,
             },
         );
     }
 
-    /// Attempts to perform a time step of the underlying cell state. Returns wether a time step was performed.
+    /// Attempts to perform the time steps of one frame of the underlying cell state, see [LiveStepping](super::LiveStepping).
+    /// Returns wether a time step was performed.
     pub(super) fn update(&mut self) -> bool {
-        !self.paused && self.cell_state.next_step()
+        if self.paused {
+            return false;
+        }
+        // the steps, the hooks and the analysis of the new states form one write access
+        let cell_state = self.cell_state.clone();
+        let mut automaton = write(&cell_state);
+        advance(&mut automaton, self.stepping, |automaton| {
+            self.hooks.step(automaton);
+            if let Some(activity) = &mut self.activity {
+                activity.update(automaton.state());
+            }
+            self.check_auto_pause(automaton);
+            !self.paused
+        })
+    }
+
+    /// Pauses the simulation if the auto pause condition is met after a step of the passed automaton, turning the condition off.
+    fn check_auto_pause(&mut self, automaton: &automaton::Automaton) {
+        let state = automaton.state();
+        let met = match (self.auto_pause, &self.last_state) {
+            (crate::AutoPause::Off, _) => return,
+            // the first step has nothing to compare to
+            (crate::AutoPause::OnStable, None) => false,
+            (condition, previous) => condition.is_met(
+                previous.as_ref().unwrap_or(state),
+                state,
+                automaton.generation(),
+            ),
+        };
+        if met {
+            let reason = self.auto_pause.reason();
+            log::info!("Model simulation paused, as it is {reason}.");
+            self.paused = true;
+            self.pause_reason = Some(reason);
+            self.auto_pause = crate::AutoPause::Off;
+            self.last_state = None;
+        } else if self.auto_pause == crate::AutoPause::OnStable {
+            self.last_state
+                .get_or_insert_with(|| crate::CellGrid::new(0, 0))
+                .clone_from(state);
+        }
+    }
+
+    /// Shows or hides the activity heatmap. Returns wether the texture needs to be rewritten.
+    pub(super) fn toggle_activity(&mut self) -> bool {
+        if self.color_mode != super::ColorMode::Rgba {
+            log::warn!("The activity heatmap is only available in the RGBA color mode.");
+            return false;
+        }
+        self.activity = match self.activity {
+            Some(_) => None,
+            None => Some(crate::ActivityMap::new(self.automaton().state())),
+        };
+        log::info!(
+            "Activity heatmap {}.",
+            if self.activity.is_some() {
+                "shown"
+            } else {
+                "hidden"
+            }
+        );
+        true
+    }
+}
+
+/// Returns wether a texture of the passed size can hold a grid of the passed dimensions (rows, columns), with one texel per cell.
+fn texture_matches(size: wgpu::Extent3d, (rows, cols): (u32, u32)) -> bool {
+    size.height == rows && size.width == cols
+}
+
+/// Locks the passed automaton for reading, ignoring poisoning as the automaton remains consistent between steps.
+fn read(automaton: &RwLock<automaton::Automaton>) -> RwLockReadGuard<'_, automaton::Automaton> {
+    automaton.read().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Performs the time steps of one frame on the passed automaton, calling ```after_step``` after each step until it returns false.
+/// Returns wether a time step was performed.
+fn advance(
+    automaton: &mut automaton::Automaton,
+    stepping: super::LiveStepping,
+    mut after_step: impl FnMut(&mut automaton::Automaton) -> bool,
+) -> bool {
+    match stepping {
+        super::LiveStepping::WallClock => {
+            let stepped = automaton.next_step();
+            if stepped {
+                after_step(automaton);
+            }
+            stepped
+        }
+        super::LiveStepping::Deterministic { steps_per_frame } => {
+            for _ in 0..steps_per_frame {
+                automaton.perform_step();
+                if !after_step(automaton) {
+                    break;
+                }
+            }
+            steps_per_frame > 0
+        }
+    }
+}
+
+/// Locks the passed automaton for writing, ignoring poisoning as the automaton remains consistent between steps.
+fn write(automaton: &RwLock<automaton::Automaton>) -> RwLockWriteGuard<'_, automaton::Automaton> {
+    automaton.write().unwrap_or_else(|err| err.into_inner())
+}
+
+#[test]
+fn color_info_test() {
+    let colors = std::collections::HashMap::from([
+        (0, [0, 0, 0, 255]),
+        (5, [255, 255, 255, 0]),
+        (200, [255, 0, 0, 255]),
+    ]);
+    let image = automaton::ImageCache::new(&colors, [0, 0, 255, 255]);
+    assert!(ColorInfo::new(super::ColorMode::Rgba, image.lookup_colors()).is_none());
+
+    let palette = ColorInfo::new(super::ColorMode::Palette, image.lookup_colors()).unwrap();
+    assert_eq!(palette.mode, 0);
+    assert_eq!(palette.palette[0], [0., 0., 0., 1.]);
+    assert_eq!(palette.palette[5], [1., 1., 1., 0.]);
+    // cells without a color, and cells above 127, are drawn as in images of the state
+    let cells = [0, 1, 5, 127, 128, 200, 255];
+    let state = grid::Grid::from_vec(cells.to_vec(), cells.len());
+    for (pixel, cell) in image.bytes(&state).chunks(4).zip(cells) {
+        assert_eq!(
+            palette.palette[cell as usize],
+            crate::CellColor::from(<[u8; 4]>::try_from(pixel).unwrap()).to_linear_f32()
+        );
     }
+    assert_eq!(palette.palette[1], [0., 0., 1., 1.]);
+    assert_eq!(palette.palette[200], [1., 0., 0., 1.]);
+
+    let gradient = ColorInfo::new(
+        super::ColorMode::Gradient {
+            from: [0, 0, 0, 0],
+            to: [255, 255, 255, 255],
+            max: 63,
+        },
+        image.lookup_colors(),
+    )
+    .unwrap();
+    assert_eq!(gradient.mode, 1);
+    assert_eq!(gradient.gradient_max, 63);
+    assert_eq!(gradient.gradient_to, [1., 1., 1., 1.]);
+
+    // the size must be a multiple of 16 to be usable as a uniform
+    assert_eq!(std::mem::size_of::<ColorInfo>() % 16, 0);
+}
+
+#[test]
+fn texture_matches_test() {
+    let size = |width, height| wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    // textures are as wide as the grid has columns
+    assert!(texture_matches(size(5, 3), (3, 5)));
+    assert!(!texture_matches(size(3, 5), (3, 5)));
+    assert!(!texture_matches(size(5, 3), (3, 6)));
+
+    // rotating an automaton invalidates its texture, unless it is square
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 15], 5)
+        .build();
+    let texture = size(5, 3);
+    assert!(texture_matches(texture, automaton.dimensions()));
+    automaton.rotate_cw();
+    assert!(!texture_matches(texture, automaton.dimensions()));
+    assert!(texture_matches(size(3, 5), automaton.dimensions()));
+}
+
+#[test]
+fn deterministic_stepping_test() {
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![0; 4], 2)
+        .with_min_time_step(std::time::Duration::from_secs(3600))
+        .build();
+    let deterministic = super::LiveStepping::Deterministic { steps_per_frame: 3 };
+
+    // every frame performs exactly the requested steps, although the time step is far from over
+    let mut generations = Vec::new();
+    for _ in 0..4 {
+        assert!(advance(&mut automaton, deterministic, |automaton| {
+            generations.push(automaton.generation());
+            true
+        }));
+    }
+    assert_eq!(automaton.generation(), 12);
+    assert_eq!(generations, (1..=12).collect::<Vec<_>>());
+
+    // pausing after a step ends the frame early
+    assert!(advance(&mut automaton, deterministic, |automaton| {
+        automaton.generation() < 13
+    }));
+    assert_eq!(automaton.generation(), 13);
+
+    // by wall clock, the time step has not passed yet
+    assert!(!advance(
+        &mut automaton,
+        super::LiveStepping::WallClock,
+        |_| true
+    ));
+    assert_eq!(automaton.generation(), 13);
+    assert!(!advance(
+        &mut automaton,
+        super::LiveStepping::Deterministic { steps_per_frame: 0 },
+        |_| true
+    ));
 }
@@ -0,0 +1,176 @@
+use std::cell::{Cell, RefCell};
+
+use winit::event_loop::{EventLoop, EventLoopBuilder};
+
+use crate::automaton;
+
+thread_local! {
+    /// The event loop of this thread while no session uses it, so later sessions reuse it instead of creating another one, which some platforms forbid.
+    static EVENT_LOOP: RefCell<Option<EventLoop<()>>> = const { RefCell::new(None) };
+    /// Wether a session currently exists on this thread.
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Shows automata in windows one after another, for example to return to a menu after each simulation and start the next one picked there.
+///
+/// All sessions of a thread share a single event loop, which is created by the first run and then kept until the thread ends, as some platforms allow only one per process.
+/// Each run creates its own window, surface and GPU resources, which are dropped again when the window is closed.
+/// [Automaton::run_live](crate::Automaton::run_live) and its variants use a session as well, so they can also be called multiple times.
+///
+/// Only one session may exist on a thread at a time, and, as some platforms only support windows on the main thread, sessions should be created there.
+/// Running a session requires a platform on which the event loop can return, which includes Windows, macOS, Linux and Android.
+/// ```no_run
+/// use cellumina::{rule::presets, AutomatonBuilder, LiveSession};
+///
+/// let mut session = LiveSession::new();
+/// let result = session.run(
+///     AutomatonBuilder::new()
+///         .from_text_file("glider.txt")
+///         .with_preset(presets::game_of_life())
+///         .build(),
+///     Default::default(),
+/// )?;
+/// println!("stopped at generation {}", result.automaton.read().unwrap().generation());
+///
+/// // after the first window was closed, the next one opens
+/// session.run(
+///     AutomatonBuilder::new()
+///         .from_text_file("sand.txt")
+///         .with_preset(presets::falling_sand())
+///         .build(),
+///     Default::default(),
+/// )?;
+/// # Ok::<(), cellumina::CelluminaError>(())
+/// ```
+#[derive(Debug)]
+pub struct LiveSession {
+    /// The event loop of the thread, held by the session while it exists, or none if it was not created yet.
+    event_loop: Option<EventLoop<()>>,
+}
+
+/// The outcome of showing an automaton with a [LiveSession], returned when its window was closed.
+#[derive(Debug)]
+pub struct LiveResult {
+    /// The automaton shown, in the state it had when the window was closed.
+    pub automaton: automaton::SharedAutomaton,
+    /// Wether the window was closed because drawing failed, as the GPU ran out of memory, instead of by the user.
+    pub failed: bool,
+}
+
+impl LiveSession {
+    /// Creates a session, taking the event loop of this thread if an earlier session created it.
+    /// Otherwise, it is created by the first run, so a missing display is reported as an error there.
+    /// ## Panics
+    /// If another session exists on this thread.
+    pub fn new() -> Self {
+        assert!(
+            !ACTIVE.with(|active| active.replace(true)),
+            "Only one live session may exist on a thread at a time."
+        );
+        Self {
+            event_loop: EVENT_LOOP.with(|slot| slot.borrow_mut().take()),
+        }
+    }
+
+    /// Shows the passed automaton in a new window configured by the passed [LiveViewConfig](crate::LiveViewConfig) and returns once the window is closed.
+    /// ## Error
+    /// A [GraphicsInitError](crate::CelluminaError::GraphicsInitError) if the window could not be set up:
+    ///  - [NoDisplay](crate::GraphicsInitError::NoDisplay) if there is no windowing system to connect to, for example on a headless machine, or the platform does not allow creating the event loop on this thread.
+    ///  - [Setup](crate::GraphicsInitError::Setup) if the window or its GPU resources could not be created, for example because there is no GPU adapter, not even a fallback one.
+    ///
+    /// The session can still be used afterwards, for example to try again with a different configuration.
+    pub fn run(
+        &mut self,
+        automaton: automaton::Automaton,
+        config: crate::LiveViewConfig,
+    ) -> Result<LiveResult, crate::CelluminaError> {
+        self.run_shared(automaton.into_shared(), config, Default::default())
+    }
+
+    /// Shows the passed shared automaton in a new window like [Self::run], calling the passed [LiveHooks](crate::LiveHooks).
+    /// Other threads holding a clone of the automaton may read or change it while it is shown, see [Automaton::run_live_shared](crate::Automaton::run_live_shared).
+    /// ## Error
+    /// Like [Self::run].
+    pub fn run_shared(
+        &mut self,
+        automaton: automaton::SharedAutomaton,
+        config: crate::LiveViewConfig,
+        hooks: crate::LiveHooks,
+    ) -> Result<LiveResult, crate::CelluminaError> {
+        let event_loop = match &mut self.event_loop {
+            Some(event_loop) => event_loop,
+            None => self
+                .event_loop
+                .insert(catch_no_display(|| EventLoopBuilder::new().build())?),
+        };
+        pollster::block_on(super::run_live(event_loop, automaton, config, hooks))
+    }
+}
+
+impl Default for LiveSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for LiveSession {
+    /// Returns the event loop to the thread for the next session.
+    fn drop(&mut self) {
+        let _ = ACTIVE.try_with(|active| active.set(false));
+        if let Some(event_loop) = self.event_loop.take() {
+            // if the thread is already shutting down, the event loop is dropped instead
+            let _ = EVENT_LOOP.try_with(|slot| *slot.borrow_mut() = Some(event_loop));
+        }
+    }
+}
+
+impl LiveResult {
+    /// Returns the automaton, unless other threads still hold a clone of it.
+    pub fn into_automaton(self) -> Option<automaton::Automaton> {
+        std::sync::Arc::try_unwrap(self.automaton)
+            .ok()
+            .map(|lock| lock.into_inner().unwrap_or_else(|err| err.into_inner()))
+    }
+}
+
+/// Runs the passed initialization of the windowing library, which panics instead of returning an error if no windowing system is available,
+/// and turns such a panic into a [NoDisplay](crate::GraphicsInitError::NoDisplay) error with the message of the panic.
+fn catch_no_display<T>(init: impl FnOnce() -> T) -> Result<T, crate::GraphicsInitError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(init)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "the windowing system could not be initialized".to_string());
+        crate::GraphicsInitError::NoDisplay(message)
+    })
+}
+
+#[test]
+fn no_display_test() {
+    // the panics of the windowing library become errors, keeping their message
+    let error = catch_no_display(|| -> u32 {
+        panic!("Failed to initialize any backend! Wayland status: NoCompositorListening");
+    })
+    .unwrap_err();
+    assert!(matches!(
+        &error,
+        crate::GraphicsInitError::NoDisplay(message) if message.ends_with("NoCompositorListening")
+    ));
+    assert_eq!(
+        crate::CelluminaError::from(error).to_string(),
+        "could not initialize the live view: no display available: Failed to initialize any backend! Wayland status: NoCompositorListening"
+    );
+    assert!(matches!(
+        catch_no_display(|| -> u32 { std::panic::panic_any(17) }),
+        Err(crate::GraphicsInitError::NoDisplay(_))
+    ));
+    assert_eq!(catch_no_display(|| 17).unwrap(), 17);
+
+    // creating a session does not need a display, and sessions can follow each other
+    drop(LiveSession::new());
+    let session = LiveSession::new();
+    assert!(std::panic::catch_unwind(LiveSession::new).is_err());
+    drop(session);
+    drop(LiveSession::new());
+}
@@ -0,0 +1,55 @@
+// Vertex shader
+
+@group(0) @binding(0)
+var t_cells: texture_2d<u32>;
+
+struct ColorInfo {
+    palette: array<vec4<f32>, 256>,
+    gradient_from: vec4<f32>,
+    gradient_to: vec4<f32>,
+    // 0 = palette, 1 = gradient
+    mode: u32,
+    gradient_max: u32,
+}
+
+@group(1) @binding(0)
+var<uniform> color_info: ColorInfo;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) vert_pos: vec2<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(vertex.position.xyz, 1.0);
+    out.tex_coords = vertex.tex_coords;
+    out.vert_pos = vertex.position.xy;
+    return out;
+}
+
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // integer textures can not be sampled, so find the texel by hand
+    let dimensions = vec2<f32>(textureDimensions(t_cells));
+    let texel = min(vec2<u32>(in.tex_coords * dimensions), vec2<u32>(dimensions) - vec2<u32>(1u, 1u));
+    let cell = textureLoad(t_cells, texel, 0).r;
+
+    if color_info.mode == 0u {
+        return color_info.palette[min(cell, 255u)];
+    } else {
+        let t = clamp(f32(cell) / f32(max(color_info.gradient_max, 1u)), 0.0, 1.0);
+        return mix(color_info.gradient_from, color_info.gradient_to, t);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{CellGrid, CellGridExt};
+
+/// Keeps the pixel data last uploaded to the cell state texture, so that only cells that changed since have to be converted and uploaded again.
+#[derive(Debug, Clone)]
+pub(super) struct TextureCache {
+    /// The state the pixel data was last computed from, or [None] if the data needs to be fully rebuilt.
+    previous: Option<CellGrid>,
+    /// The pixel data, row by row.
+    data: Vec<u8>,
+    /// The number of bytes per cell, e.g. 4 for RGBA data.
+    bytes_per_cell: usize,
+}
+
+impl TextureCache {
+    /// Creates a new, empty cache for pixels consisting of ```bytes_per_cell``` bytes (at most 4).
+    pub(super) fn new(bytes_per_cell: usize) -> Self {
+        Self {
+            previous: None,
+            data: Vec::new(),
+            bytes_per_cell: bytes_per_cell.min(4),
+        }
+    }
+
+    /// Forces the next update to rebuild all pixel data, for example because the color mapping changed.
+    pub(super) fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    /// Returns the cached pixel data.
+    pub(super) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the number of bytes per cached cell.
+    pub(super) fn bytes_per_cell(&self) -> usize {
+        self.bytes_per_cell
+    }
+
+    /// Updates the pixel data of all cells that changed since the last update, using ```pixel``` to convert a cell to its pixel data.
+    /// Only the first ```bytes_per_cell``` bytes returned by ```pixel``` are used.
+    ///
+    /// Returns the range of rows that contain changed cells, or [None] if nothing changed.
+    pub(super) fn update(
+        &mut self,
+        state: &CellGrid,
+        pixel: impl Fn(u8) -> [u8; 4],
+    ) -> Option<std::ops::Range<usize>> {
+        let (rows, cols) = state.size();
+        let bpc = self.bytes_per_cell;
+
+        match &mut self.previous {
+            // same dimensions as before: only update changed cells
+            Some(previous) if previous.size() == state.size() => {
+                let mut dirty: Option<std::ops::Range<usize>> = None;
+                for (((row, col), &cell), &old) in state.indexed_iter().zip(previous.iter()) {
+                    if old != cell {
+                        let offset = (row * cols + col) * bpc;
+                        self.data[offset..offset + bpc].copy_from_slice(&pixel(cell)[..bpc]);
+                        dirty = Some(match dirty {
+                            Some(range) => range.start..row + 1,
+                            None => row..row + 1,
+                        });
+                    }
+                }
+                previous.clone_from(state);
+                dirty
+            }
+            // else, rebuild everything
+            _ => {
+                self.data.clear();
+                self.data.reserve(rows * cols * bpc);
+                for &cell in state.iter() {
+                    self.data.extend_from_slice(&pixel(cell)[..bpc]);
+                }
+                self.previous = Some(state.clone());
+                Some(0..rows)
+            }
+        }
+    }
+}
+
+#[test]
+fn texture_cache_test() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec((0..24 * 16).map(|_| rng.gen_range(0..4)).collect(), 16)
+        .with_color(0, [0, 0, 0, 255])
+        .with_color(1, [255, 0, 0, 255])
+        .with_color(2, [0, 255, 0, 255])
+        .with_color(3, [0, 0, 255, 255])
+        .build();
+    fn pixel(cell: u8) -> [u8; 4] {
+        match cell {
+            0 => [0, 0, 0, 255],
+            1 => [255, 0, 0, 255],
+            2 => [0, 255, 0, 255],
+            3 => [0, 0, 255, 255],
+            _ => [0; 4],
+        }
+    }
+
+    let mut cache = TextureCache::new(4);
+    // first update is a full rebuild
+    assert_eq!(cache.update(automaton.state(), pixel), Some(0..24));
+    assert_eq!(
+        cache.data(),
+        automaton.create_image_buffer().as_raw().as_slice()
+    );
+    // no changes, nothing to do
+    assert_eq!(cache.update(automaton.state(), pixel), None);
+
+    for _ in 0..20 {
+        // random edits within a band of rows
+        let band = rng.gen_range(0..20);
+        for _ in 0..5 {
+            automaton
+                .set_cell(
+                    band + rng.gen_range(0..4),
+                    rng.gen_range(0..16),
+                    rng.gen_range(0..4),
+                )
+                .unwrap();
+        }
+        if let Some(range) = cache.update(automaton.state(), pixel) {
+            assert!(range.start >= band as usize && range.end <= band as usize + 4);
+        }
+        // incremental data must always equal a full rebuild
+        assert_eq!(
+            cache.data(),
+            automaton.create_image_buffer().as_raw().as_slice()
+        );
+    }
+
+    // single byte cells store the raw state
+    let mut cache = TextureCache::new(1);
+    cache.update(automaton.state(), |cell| [cell, 0, 0, 0]);
+    assert_eq!(cache.data(), automaton.state().flatten().as_slice());
+    cache.invalidate();
+    assert_eq!(
+        cache.update(automaton.state(), |cell| [cell, 0, 0, 0]),
+        Some(0..24)
+    );
+}
@@ -0,0 +1,98 @@
+use std::time;
+
+/// Decides when the live view should next check its model for changes, limiting this to a maximum frame rate.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FrameTimer {
+    /// The minimum duration between two frames, or [None] for no limit.
+    interval: Option<time::Duration>,
+    /// The time at which the last frame was started.
+    last_frame: Option<time::Instant>,
+}
+
+impl FrameTimer {
+    /// Creates a new timer permitting at most ```max_frame_rate``` frames per second.
+    /// Non-positive or non-finite rates are treated as no limit, while rates too low to fit their interval into a duration never permit another frame.
+    pub(super) fn new(max_frame_rate: Option<f32>) -> Self {
+        Self {
+            interval: max_frame_rate
+                .filter(|rate| rate.is_finite() && *rate > 0.)
+                .map(|rate| {
+                    time::Duration::try_from_secs_f32(1. / rate).unwrap_or(time::Duration::MAX)
+                }),
+            last_frame: None,
+        }
+    }
+
+    /// Checks if a new frame is due at time ```now```. If so, the frame is counted as started.
+    pub(super) fn poll(&mut self, now: time::Instant) -> bool {
+        let due = match (self.interval, self.last_frame) {
+            (Some(interval), Some(last)) => now.duration_since(last) >= interval,
+            _ => true,
+        };
+        if due {
+            self.last_frame = Some(now);
+        }
+        due
+    }
+
+    /// Returns when the next frame is due.
+    pub(super) fn next_frame(&self) -> NextFrame {
+        match (self.interval, self.last_frame) {
+            (Some(interval), Some(last)) => match last.checked_add(interval) {
+                Some(instant) => NextFrame::At(instant),
+                None => NextFrame::Never,
+            },
+            (Some(_), None) => NextFrame::At(time::Instant::now()),
+            _ => NextFrame::Unlimited,
+        }
+    }
+}
+
+/// When the next frame of a [FrameTimer] is due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum NextFrame {
+    /// Frames are not limited.
+    Unlimited,
+    /// The next frame is due at the contained point in time.
+    At(time::Instant),
+    /// The next frame is too far in the future to be represented, so only events should wake the live view.
+    Never,
+}
+
+#[test]
+fn frame_timer_test() {
+    let start = time::Instant::now();
+    let mut timer = FrameTimer::new(Some(10.));
+
+    // first frame is always due
+    assert!(timer.poll(start));
+    assert_eq!(
+        timer.next_frame(),
+        NextFrame::At(start + time::Duration::from_secs_f32(1. / 10.))
+    );
+    // not due within the interval
+    assert!(!timer.poll(start + time::Duration::from_millis(50)));
+    // due after the interval, restarting it from then on
+    assert!(timer.poll(start + time::Duration::from_millis(120)));
+    assert!(!timer.poll(start + time::Duration::from_millis(200)));
+    assert!(timer.poll(start + time::Duration::from_millis(230)));
+
+    // unlimited timers are always due
+    let mut timer = FrameTimer::new(None);
+    assert!(timer.poll(start));
+    assert!(timer.poll(start));
+    assert_eq!(timer.next_frame(), NextFrame::Unlimited);
+
+    // nonsensical rates mean unlimited
+    assert_eq!(FrameTimer::new(Some(0.)).next_frame(), NextFrame::Unlimited);
+    assert_eq!(
+        FrameTimer::new(Some(f32::NAN)).next_frame(),
+        NextFrame::Unlimited
+    );
+
+    // rates too low for a duration never permit a second frame
+    let mut timer = FrameTimer::new(Some(f32::MIN_POSITIVE));
+    assert!(timer.poll(start));
+    assert!(!timer.poll(start + time::Duration::from_secs(1_000_000)));
+    assert_eq!(timer.next_frame(), NextFrame::Never);
+}
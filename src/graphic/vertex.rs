@@ -51,3 +51,100 @@ impl Vertex {
         }
     }
 }
+
+/// Creates the 4 vertices of a rectangle covering the ```area``` of the screen given in normalized device coordinates ```[left, bottom, right, top]```,
+/// showing the ```region``` of the texture given as ```[left, top, right, bottom]```.
+pub(super) fn rectangle(area: [f32; 4], region: [f32; 4]) -> [Vertex; VERTICES_COUNT] {
+    let mut vertices = VERTICES;
+    for v in vertices.iter_mut() {
+        let (left, bottom) = (v.position[0] < 0., v.position[1] < 0.);
+        v.position[0] = if left { area[0] } else { area[2] };
+        v.position[1] = if bottom { area[1] } else { area[3] };
+        v.tex_coords[0] = if left { region[0] } else { region[2] };
+        v.tex_coords[1] = if bottom { region[3] } else { region[1] };
+    }
+    vertices
+}
+
+/// The number of vertices of a frame drawn with [OverlayVertex]s.
+pub(super) const FRAME_VERTICES_COUNT: usize = 16;
+
+/// Indices to draw a frame of four rectangles, each made of two triangles.
+pub(super) const FRAME_INDICES: &[u16] = &[
+    0, 1, 2, 1, 3, 2, //
+    4, 5, 6, 5, 7, 6, //
+    8, 9, 10, 9, 11, 10, //
+    12, 13, 14, 13, 15, 14,
+];
+
+/// A vertex of a single-colored overlay, such as the frame marking the visible region on the minimap.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(super) struct OverlayVertex {
+    /// The vertex position in screen coordinates.
+    pub(super) position: [f32; 3],
+    /// The color of this vertex.
+    pub(super) color: [f32; 4],
+}
+
+impl OverlayVertex {
+    /// A representation of this struct's layout.
+    pub(super) const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    /// A representation of a buffer containing multiple overlay vertices.
+    pub(super) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<OverlayVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Creates the vertices of a frame along the inside of the passed ```area``` (in normalized device coordinates ```[left, bottom, right, top]```)
+/// with the passed thickness (horizontal, vertical) and color.
+pub(super) fn frame(
+    area: [f32; 4],
+    thickness: [f32; 2],
+    color: [f32; 4],
+) -> [OverlayVertex; FRAME_VERTICES_COUNT] {
+    let [left, bottom, right, top] = area;
+    let bars = [
+        // left, bottom, right, top
+        [left, bottom, left + thickness[0], top],
+        [left, bottom, right, bottom + thickness[1]],
+        [right - thickness[0], bottom, right, top],
+        [left, top - thickness[1], right, top],
+    ];
+    let mut vertices = [OverlayVertex {
+        position: [0.; 3],
+        color,
+    }; FRAME_VERTICES_COUNT];
+    for (bar, chunk) in bars.iter().zip(vertices.chunks_mut(4)) {
+        for (v, corner) in chunk.iter_mut().zip(VERTICES) {
+            v.position[0] = if corner.position[0] < 0. {
+                bar[0]
+            } else {
+                bar[2]
+            };
+            v.position[1] = if corner.position[1] < 0. {
+                bar[1]
+            } else {
+                bar[3]
+            };
+        }
+    }
+    vertices
+}
+
+#[test]
+fn rectangle_test() {
+    let vertices = rectangle([-0.5, -1., 0.5, 1.], [0.25, 0., 0.75, 0.5]);
+    // bottom left corner of the screen area shows the bottom left of the region
+    assert_eq!(vertices[0].position, [-0.5, -1., 0.]);
+    assert_eq!(vertices[0].tex_coords, [0.25, 0.5]);
+    // top right corner of the screen area shows the top right of the region
+    assert_eq!(vertices[3].position, [0.5, 1., 0.]);
+    assert_eq!(vertices[3].tex_coords, [0.75, 0.]);
+}
@@ -2,19 +2,22 @@ use wgpu::util::DeviceExt;
 
 use winit::{event::*, event_loop::ControlFlow, window::Window};
 
-use super::vertex;
+use super::{camera, vertex};
 use crate::automaton;
 
+/// Grids with at least this many rows or columns show the minimap by default.
+const MINIMAP_THRESHOLD: u32 = 512;
+
 /// A part of the MVC pattern, describing the OpenGL state and windoww of the view of a live-run automaton.
 #[derive(Debug)]
 pub(super) struct AutomatonView {
     // ----- VIEW -----
     /// The WebGL Surface.
     surface: wgpu::Surface,
-    /// The WebGL Device.
-    device: wgpu::Device,
-    /// The WebGL Queue.
-    pub(super) queue: wgpu::Queue,
+    /// The WebGL Device, possibly shared with the automaton's rule.
+    device: std::sync::Arc<wgpu::Device>,
+    /// The WebGL Queue, possibly shared with the automaton's rule.
+    pub(super) queue: std::sync::Arc<wgpu::Queue>,
     /// The WebGL Config.
     pub(super) config: wgpu::SurfaceConfiguration,
     /// The winit-window being drawn to.
@@ -28,14 +31,38 @@ pub(super) struct AutomatonView {
     index_buffer: wgpu::Buffer,
     /// The bind group used to draw the automaton's cells to the image.
     cell_state_bind_group: wgpu::BindGroup,
+    /// The layout of the cell state bind group, kept to recreate it when the dimensions of the automaton change.
+    cell_state_bind_group_layout: wgpu::BindGroupLayout,
+    /// The bind group containing palette or gradient information, if the cells are colored on the GPU.
+    color_bind_group: Option<wgpu::BindGroup>,
+    /// The rectangle the automaton is currently drawn to, in normalized device coordinates ```[left, bottom, right, top]```.
+    pub(super) quad: [f32; 4],
+    /// The part of the automaton currently visible.
+    pub(super) camera: camera::Camera,
+
+    /// Wether the minimap is currently drawn.
+    minimap_visible: bool,
+    /// The vertex buffer containing the rectangle the minimap is drawn to.
+    minimap_vertex_buffer: wgpu::Buffer,
+    /// The Render Pipeline for single-colored overlays.
+    overlay_pipeline: wgpu::RenderPipeline,
+    /// The vertex buffer containing the frame marking the visible region on the minimap.
+    frame_vertex_buffer: wgpu::Buffer,
+    /// The index buffer to draw frames.
+    frame_index_buffer: wgpu::Buffer,
+    /// Wether the window currently has no area, e.g. because it is minimized. Nothing is drawn in this case.
+    minimized: bool,
 }
 
 impl AutomatonView {
     /// Creates a new AutomatonDisplayer to draw the passed automaton to the passed window.
+    /// ## Error
+    /// A [GraphicsInitError::Setup](crate::GraphicsInitError::Setup) if no surface, adapter or device could be created for the window.
     pub(super) async fn create_view_model(
         window: Window,
-        automaton: automaton::Automaton,
-    ) -> (Self, super::AutomatonModel) {
+        automaton: automaton::SharedAutomaton,
+        live_config: &super::LiveViewConfig,
+    ) -> Result<(Self, super::AutomatonModel), crate::CelluminaError> {
         // +-------------------------------------------------------------+
         // |                                                             |
         // |                   GENERAL SETUP                             |
@@ -48,56 +75,75 @@ impl AutomatonView {
         // steal window size
         let size = window.inner_size();
 
+        // reuse the GPU context of the rule, if it has one
+        let shared_context = automaton
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .rule
+            .gpu_context()
+            .cloned();
+
         // create the instance
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            dx12_shader_compiler: Default::default(),
-        });
+        let instance = match &shared_context {
+            Some(context) => context.instance.clone(),
+            None => std::sync::Arc::new(wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends: wgpu::Backends::all(),
+                dx12_shader_compiler: Default::default(),
+            })),
+        };
 
         // create the surface unsafely
-        let surface =
-            unsafe { instance.create_surface(&window) }.expect("Could not create surface.");
-
-        // create adapter
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .expect("Could not create adapter.");
-
-        // create device & queue
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::default(),
-                },
-                None, // Trace path
-            )
-            .await
-            .expect("Could not create device/queue.");
+        let surface = unsafe { instance.create_surface(&window) }
+            .map_err(|err| crate::GraphicsInitError::Setup(err.into()))?;
+
+        // create adapter, device & queue, unless the shared ones can draw to the surface
+        let context = match shared_context {
+            Some(context) if context.adapter.is_surface_supported(&surface) => context,
+            shared_context => {
+                if shared_context.is_some() {
+                    log::warn!("The GPU context of the rule cannot draw to the window, creating a second device.");
+                }
+                crate::GpuContext::request(instance, Some(&surface))
+                    .await
+                    .map_err(|err| crate::GraphicsInitError::Setup(err.into()))?
+            }
+        };
+        let adapter = &context.adapter;
+        let device = context.device.clone();
+        let queue = context.queue.clone();
 
         // get capabilities of surface
-        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_caps = surface.get_capabilities(adapter);
         // find an srgb surface format
         let surface_format = surface_caps
             .formats
             .iter()
             .copied()
             .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats.first().copied().unwrap());
+            .or(surface_caps.formats.first().copied())
+            .ok_or_else(|| {
+                crate::GraphicsInitError::Setup("the adapter can not draw to the window".into())
+            })?;
 
         // create surface config
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes.first().copied().unwrap(),
+            // surfaces can not be configured with a size of zero
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: {
+                let requested = live_config.present_mode.into();
+                if surface_caps.present_modes.contains(&requested) {
+                    requested
+                } else {
+                    log::warn!(
+                        "Present mode {:?} is not supported by the surface, falling back to Fifo.",
+                        requested
+                    );
+                    wgpu::PresentMode::Fifo
+                }
+            },
             alpha_mode: surface_caps.alpha_modes.first().copied().unwrap(),
             view_formats: vec![],
         };
@@ -109,8 +155,8 @@ impl AutomatonView {
         // |                                                             |
         // +-------------------------------------------------------------+
 
-        let (model, cell_state_bind_group_layout, cell_state_bind_group) =
-            super::AutomatonModel::new(automaton, &device);
+        let (model, cell_state_bind_group_layout, cell_state_bind_group, color_bind_group) =
+            super::AutomatonModel::new(automaton, &device, live_config.color_mode);
 
         // +-------------------------------------------------------------+
         // |                                                             |
@@ -120,8 +166,17 @@ impl AutomatonView {
 
         log::info!("Creating shader.");
 
-        // create & compile the shaders
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shader.wgsl"));
+        // create & compile the shaders, coloring on the GPU requires a different fragment shader
+        let shader = if color_bind_group.is_some() {
+            device.create_shader_module(wgpu::include_wgsl!("shader_indexed.wgsl"))
+        } else {
+            device.create_shader_module(wgpu::include_wgsl!("shader.wgsl"))
+        };
+
+        let mut bind_group_layouts = vec![&cell_state_bind_group_layout];
+        if let Some((color_bind_group_layout, _)) = &color_bind_group {
+            bind_group_layouts.push(color_bind_group_layout);
+        }
 
         log::info!("Creating render pipeline.");
 
@@ -131,7 +186,7 @@ impl AutomatonView {
             layout: Some(
                 &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some("Render Pipeline Layout"),
-                    bind_group_layouts: &[&cell_state_bind_group_layout], //&[&info_bind_group_layout],
+                    bind_group_layouts: &bind_group_layouts,
                     push_constant_ranges: &[],
                 }),
             ),
@@ -170,6 +225,44 @@ impl AutomatonView {
             multiview: None,
         });
 
+        log::info!("Creating overlay pipeline.");
+
+        let overlay_shader = device.create_shader_module(wgpu::include_wgsl!("overlay.wgsl"));
+
+        let overlay_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Overlay Pipeline"),
+            layout: Some(
+                &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Overlay Pipeline Layout"),
+                    bind_group_layouts: &[],
+                    push_constant_ranges: &[],
+                }),
+            ),
+            vertex: wgpu::VertexState {
+                module: &overlay_shader,
+                entry_point: "vs_main",
+                buffers: &[vertex::OverlayVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &overlay_shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                // overlays are flat, so there is nothing to cull
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
         log::info!("Creating vertex & index buffers.");
 
         // create the buffer
@@ -188,20 +281,53 @@ impl AutomatonView {
             usage: wgpu::BufferUsages::INDEX,
         });
 
-        (
-            Self {
-                surface,
-                device,
-                queue,
-                config,
-                window,
-                render_pipeline,
-                vertex_buffer,
-                index_buffer,
-                cell_state_bind_group,
-            },
-            model,
-        )
+        let minimap_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Minimap Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertex::VERTICES),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let frame_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Frame Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertex::frame([0.; 4], [0.; 2], [0.; 4])),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let frame_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Frame Index Buffer"),
+            contents: bytemuck::cast_slice(vertex::FRAME_INDICES),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let (rows, cols) = model.automaton().dimensions();
+
+        let mut view = Self {
+            surface,
+            device,
+            queue,
+            config,
+            window,
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+            cell_state_bind_group,
+            cell_state_bind_group_layout,
+            color_bind_group: color_bind_group.map(|(_, bind_group)| bind_group),
+            quad: [-1., -1., 1., 1.],
+            camera: camera::Camera::new(),
+            minimap_visible: rows.max(cols) >= MINIMAP_THRESHOLD,
+            minimap_vertex_buffer,
+            overlay_pipeline,
+            frame_vertex_buffer,
+            frame_index_buffer,
+            minimized: false,
+        };
+
+        // make sure the quad is letterboxed from the very first frame on
+        let dimensions = model.automaton().dimensions();
+        view.resize(size, dimensions);
+
+        Ok((view, model))
     }
 
     /// Sets the physical window size whereever needed and also calculates the maximum rectangle with the same side length ratio as the contained automaton
@@ -211,31 +337,110 @@ impl AutomatonView {
         new_size: winit::dpi::PhysicalSize<u32>,
         model_dimensions: (u32, u32),
     ) {
+        // a surface can not be configured with a size of zero, so keep the old configuration until the window is restored
+        self.minimized = new_size.width == 0 || new_size.height == 0;
+        if self.minimized {
+            return;
+        }
+
         // update a lot of stuff
         self.config.width = new_size.width;
         self.config.height = new_size.height;
         self.surface.configure(&self.device, &self.config);
 
-        // get new vertex positions to keep ratio of display consistent
-        let mut vertices = vertex::VERTICES;
-        // Calculate ratios
-        let cell_ratio = model_dimensions.1 as f32 / model_dimensions.0 as f32;
-        let win_ratio = new_size.width as f32 / new_size.height as f32;
-
-        // Based on the larger ratio, make the rectangle thinner or lower.
-        if cell_ratio > win_ratio {
-            for v in vertices.iter_mut() {
-                v.position[1] *= win_ratio / cell_ratio;
-            }
-        } else {
-            for v in vertices.iter_mut() {
-                v.position[0] *= cell_ratio / win_ratio;
+        self.update_vertices(model_dimensions);
+    }
+
+    /// Replaces the texture of the model if the dimensions of its automaton changed, e.g. because it was rotated, binds the new texture and fits the drawn rectangle to the new dimensions.
+    /// Returns wether the texture was replaced, in which case it needs to be written and drawn again.
+    pub(super) fn adapt_to_dimensions(&mut self, model: &mut super::AutomatonModel) -> bool {
+        match model.rebuild_texture(&self.device, &self.cell_state_bind_group_layout) {
+            Some(bind_group) => {
+                self.cell_state_bind_group = bind_group;
+                self.camera = camera::Camera::new();
+                self.update_vertices(model.automaton().dimensions());
+                true
             }
+            None => false,
         }
+    }
+
+    /// Recalculates the positions of all drawn rectangles from the window size, model dimensions and camera and writes them to the vertex buffers.
+    fn update_vertices(&mut self, model_dimensions: (u32, u32)) {
+        let window_size = (self.config.width, self.config.height);
+
+        // get new vertex positions to keep ratio of display consistent
+        self.quad = letterbox(window_size, model_dimensions);
+        let region = self.camera.region();
+        self.queue.write_buffer(
+            &self.vertex_buffer,
+            0,
+            bytemuck::cast_slice(&vertex::rectangle(self.quad, region)),
+        );
+
+        // the minimap always shows the whole state
+        let map = camera::minimap_rect(window_size, model_dimensions);
+        self.queue.write_buffer(
+            &self.minimap_vertex_buffer,
+            0,
+            bytemuck::cast_slice(&vertex::rectangle(map, [0., 0., 1., 1.])),
+        );
+
+        // the frame on the minimap marks the visible region
+        let (map_width, map_height) = (map[2] - map[0], map[3] - map[1]);
+        self.queue.write_buffer(
+            &self.frame_vertex_buffer,
+            0,
+            bytemuck::cast_slice(&vertex::frame(
+                [
+                    map[0] + region[0] * map_width,
+                    map[3] - region[3] * map_height,
+                    map[0] + region[2] * map_width,
+                    map[3] - region[1] * map_height,
+                ],
+                // two pixels thick
+                [
+                    4. / window_size.0.max(1) as f32,
+                    4. / window_size.1.max(1) as f32,
+                ],
+                [1., 1., 1., 1.],
+            )),
+        );
+    }
 
-        // update the vertex buffer
-        self.queue
-            .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+    /// Returns the rectangle the minimap is drawn to, in normalized device coordinates ```[left, bottom, right, top]```, or [None] if the minimap is hidden.
+    pub(super) fn minimap(&self, model_dimensions: (u32, u32)) -> Option<[f32; 4]> {
+        self.minimap_visible.then(|| {
+            camera::minimap_rect((self.config.width, self.config.height), model_dimensions)
+        })
+    }
+
+    /// Shows or hides the minimap.
+    pub(super) fn toggle_minimap(&mut self) {
+        self.minimap_visible = !self.minimap_visible;
+        log::info!(
+            "Minimap {}.",
+            if self.minimap_visible {
+                "shown"
+            } else {
+                "hidden"
+            }
+        );
+        self.window.request_redraw();
+    }
+
+    /// Applies the passed modification to the camera and updates the display accordingly.
+    pub(super) fn move_camera(
+        &mut self,
+        model_dimensions: (u32, u32),
+        modification: impl FnOnce(&mut camera::Camera),
+    ) {
+        let before = self.camera;
+        modification(&mut self.camera);
+        if before != self.camera {
+            self.update_vertices(model_dimensions);
+            self.window.request_redraw();
+        }
     }
 
     /// Handles all sorts of window events that are not related to input affecting the model (these are handled by the controller)
@@ -296,7 +501,11 @@ impl AutomatonView {
     }
 
     /// Renders the currently stored automaton state to the window.
+    /// Does nothing while the window has no area.
     pub(super) fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        if self.minimized {
+            return Ok(());
+        }
         // get the current 'framebuffer'
         let output = self.surface.get_current_texture()?;
         // create a 'view' = definition how render code interacts with this texture
@@ -338,6 +547,9 @@ impl AutomatonView {
             render_pass.set_pipeline(&self.render_pipeline);
             //render_pass.set_bind_group(0, &self.info_bind_group, &[]);
             render_pass.set_bind_group(0, &self.cell_state_bind_group, &[]);
+            if let Some(color_bind_group) = &self.color_bind_group {
+                render_pass.set_bind_group(1, color_bind_group, &[]);
+            }
             render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
             render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
             //render_pass.draw(0..3, 0..1);
@@ -349,6 +561,19 @@ impl AutomatonView {
                 // how many instances?
                 0..1,
             );
+
+            if self.minimap_visible {
+                // draw the whole state again, but smaller
+                render_pass.set_vertex_buffer(0, self.minimap_vertex_buffer.slice(..));
+                render_pass.draw_indexed(0..vertex::INDICES.len() as u32, 0, 0..1);
+
+                // then mark the visible region
+                render_pass.set_pipeline(&self.overlay_pipeline);
+                render_pass.set_vertex_buffer(0, self.frame_vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(self.frame_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..vertex::FRAME_INDICES.len() as u32, 0, 0..1);
+            }
         }
 
         // submit this pass to the command queue
@@ -358,3 +583,45 @@ impl AutomatonView {
         Ok(())
     }
 }
+
+/// Calculates the largest rectangle with the same side length ratio as an automaton of the passed dimensions (rows, columns) that fits into a window of the passed size (width, height).
+/// The rectangle is centered and returned in normalized device coordinates as ```[left, bottom, right, top]```.
+/// If either has a size of zero, the whole window is returned.
+pub(super) fn letterbox(window_size: (u32, u32), model_dimensions: (u32, u32)) -> [f32; 4] {
+    if window_size.0 == 0
+        || window_size.1 == 0
+        || model_dimensions.0 == 0
+        || model_dimensions.1 == 0
+    {
+        return [-1., -1., 1., 1.];
+    }
+
+    // Calculate ratios
+    let cell_ratio = model_dimensions.1 as f32 / model_dimensions.0 as f32;
+    let win_ratio = window_size.0 as f32 / window_size.1 as f32;
+
+    // Based on the larger ratio, make the rectangle thinner or lower.
+    if cell_ratio > win_ratio {
+        let height = win_ratio / cell_ratio;
+        [-1., -height, 1., height]
+    } else {
+        let width = cell_ratio / win_ratio;
+        [-width, -1., width, 1.]
+    }
+}
+
+#[test]
+fn letterbox_test() {
+    // same ratio => whole window
+    assert_eq!(letterbox((200, 100), (50, 100)), [-1., -1., 1., 1.]);
+    // wide window => bars left and right
+    assert_eq!(letterbox((400, 100), (50, 100)), [-0.5, -1., 0.5, 1.]);
+    // tall window => bars top and bottom
+    assert_eq!(letterbox((200, 400), (10, 10)), [-1., -0.5, 1., 0.5]);
+    // degenerate sizes => whole window instead of NaNs or infinities
+    assert_eq!(letterbox((0, 0), (10, 10)), [-1., -1., 1., 1.]);
+    assert_eq!(letterbox((200, 0), (10, 10)), [-1., -1., 1., 1.]);
+    assert_eq!(letterbox((0, 200), (10, 10)), [-1., -1., 1., 1.]);
+    assert_eq!(letterbox((200, 100), (0, 10)), [-1., -1., 1., 1.]);
+    assert_eq!(letterbox((200, 100), (10, 0)), [-1., -1., 1., 1.]);
+}
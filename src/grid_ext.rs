@@ -0,0 +1,112 @@
+/// Iterates over and fills grids with the coordinates of their cells, so loops need no manual index arithmetic.
+///
+/// Coordinates are always ```(row, col)``` and cells are visited in row-major order, as stored by [grid::Grid].
+/// ```
+/// use cellumina::CellGridExt;
+/// let mut grid = grid::Grid::new(2, 3);
+/// grid.fill_from_fn(|row, col| (row * 10 + col) as u8);
+/// assert_eq!(grid, grid::grid![[0, 1, 2][10, 11, 12]]);
+///
+/// assert_eq!(grid.indexed_iter().nth(4), Some(((1, 1), &11)));
+/// for ((row, col), cell) in grid.indexed_iter_mut() {
+///     *cell += (row + col) as u8;
+/// }
+/// assert_eq!(grid.row_slices().last(), Some([11, 13, 15].as_slice()));
+/// ```
+pub trait CellGridExt<T> {
+    /// Iterates over all cells with their coordinates.
+    fn indexed_iter<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a T)>
+    where
+        T: 'a;
+
+    /// Iterates mutably over all cells with their coordinates.
+    fn indexed_iter_mut<'a>(&'a mut self) -> impl Iterator<Item = ((usize, usize), &'a mut T)>
+    where
+        T: 'a;
+
+    /// Iterates over the rows as slices, from top to bottom.
+    fn row_slices<'a>(&'a self) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a;
+
+    /// Sets every cell to the value the passed function returns for its coordinates, calling it in row-major order.
+    fn fill_from_fn(&mut self, f: impl FnMut(usize, usize) -> T);
+}
+
+impl<T> CellGridExt<T> for grid::Grid<T> {
+    fn indexed_iter<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a T)>
+    where
+        T: 'a,
+    {
+        let cols = self.cols();
+        self.iter()
+            .enumerate()
+            .map(move |(index, cell)| ((index / cols, index % cols), cell))
+    }
+
+    fn indexed_iter_mut<'a>(&'a mut self) -> impl Iterator<Item = ((usize, usize), &'a mut T)>
+    where
+        T: 'a,
+    {
+        let cols = self.cols();
+        self.iter_mut()
+            .enumerate()
+            .map(move |(index, cell)| ((index / cols, index % cols), cell))
+    }
+
+    fn row_slices<'a>(&'a self) -> impl Iterator<Item = &'a [T]>
+    where
+        T: 'a,
+    {
+        // grids without columns have no cells, so the chunk size does not matter for them
+        self.flatten().chunks(self.cols().max(1))
+    }
+
+    fn fill_from_fn(&mut self, mut f: impl FnMut(usize, usize) -> T) {
+        for ((row, col), cell) in self.indexed_iter_mut() {
+            *cell = f(row, col);
+        }
+    }
+}
+
+#[test]
+fn grid_ext_test() {
+    let grid = crate::testing::parse_grid(
+        "0123
+         4567
+         89ab",
+    );
+
+    // the coordinates match direct indexing, in row-major order
+    let indexed = grid.indexed_iter().collect::<Vec<_>>();
+    assert_eq!(indexed.len(), 12);
+    for (index, &((row, col), &cell)) in indexed.iter().enumerate() {
+        assert_eq!((row, col), (index / 4, index % 4));
+        assert_eq!(cell, grid[row][col]);
+    }
+
+    let rows = grid.row_slices().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 3);
+    for (row, slice) in rows.iter().enumerate() {
+        assert_eq!(
+            slice.to_vec(),
+            grid.iter_row(row).copied().collect::<Vec<_>>()
+        );
+    }
+
+    let mut copy = grid::Grid::new(3, 4);
+    copy.fill_from_fn(|row, col| grid[row][col]);
+    assert_eq!(copy, grid);
+    for ((row, col), cell) in copy.indexed_iter_mut() {
+        *cell = (row * 4 + col) as u8;
+    }
+    assert_eq!(copy, grid);
+
+    // grids without cells have nothing to iterate over
+    let empty = grid::Grid::<u8>::new(0, 0);
+    assert_eq!(empty.indexed_iter().count(), 0);
+    assert_eq!(empty.row_slices().count(), 0);
+    let mut wide = grid::Grid::<u8>::new(0, 3);
+    wide.fill_from_fn(|_, _| unreachable!());
+    assert_eq!(wide.row_slices().count(), 0);
+}
@@ -0,0 +1,219 @@
+//! Operations rearranging the cells of grids, such as rotating initial states that were authored in the wrong orientation, or drawing on them.
+//!
+//! Automata offer the same operations for their state, see [AutomatonGeneric::rotate_cw](crate::AutomatonGeneric::rotate_cw),
+//! and the builder can apply them to the initial state, see [AutomatonBuilderGeneric::with_transform](crate::AutomatonBuilderGeneric::with_transform).
+
+use crate::CellGridExt;
+
+/// An operation rearranging the cells of a grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Transform {
+    /// Rotates the grid by 90 degrees clockwise, see [rotate_cw].
+    RotateCw,
+    /// Rotates the grid by 90 degrees counterclockwise, see [rotate_ccw].
+    RotateCcw,
+    /// Mirrors the grid left to right, see [flip_horizontal].
+    FlipHorizontal,
+    /// Mirrors the grid top to bottom, see [flip_vertical].
+    FlipVertical,
+    /// Swaps the rows and columns of the grid, see [transpose].
+    Transpose,
+}
+
+impl Transform {
+    /// Returns the grid resulting from applying this operation to the passed grid.
+    pub fn apply<T: Clone>(self, grid: &grid::Grid<T>) -> grid::Grid<T> {
+        match self {
+            Self::RotateCw => rotate_cw(grid),
+            Self::RotateCcw => rotate_ccw(grid),
+            Self::FlipHorizontal => flip_horizontal(grid),
+            Self::FlipVertical => flip_vertical(grid),
+            Self::Transpose => transpose(grid),
+        }
+    }
+
+    /// Returns wether this operation swaps the number of rows and columns of a grid.
+    pub fn swaps_dimensions(self) -> bool {
+        matches!(self, Self::RotateCw | Self::RotateCcw | Self::Transpose)
+    }
+}
+
+/// Creates a grid of the passed size, taking each cell from the passed position of ```grid```.
+fn rearrange<T: Clone>(
+    grid: &grid::Grid<T>,
+    rows: usize,
+    cols: usize,
+    source: impl Fn(usize, usize) -> (usize, usize),
+) -> grid::Grid<T> {
+    if rows == 0 || cols == 0 {
+        return grid::Grid::from_vec(Vec::new(), 0);
+    }
+    grid::Grid::from_vec(
+        (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let (row, col) = source(row, col);
+                grid[row][col].clone()
+            })
+            .collect(),
+        cols,
+    )
+}
+
+/// Rotates the grid by 90 degrees clockwise, so the first column, read bottom to top, becomes the first row.
+/// ```
+/// assert_eq!(
+///     cellumina::gridops::rotate_cw(&grid::grid![[1, 2, 3][4, 5, 6]]),
+///     grid::grid![[4, 1][5, 2][6, 3]]
+/// );
+/// ```
+pub fn rotate_cw<T: Clone>(grid: &grid::Grid<T>) -> grid::Grid<T> {
+    let (rows, cols) = grid.size();
+    rearrange(grid, cols, rows, |row, col| (rows - 1 - col, row))
+}
+
+/// Rotates the grid by 90 degrees counterclockwise, so the last column, read top to bottom, becomes the first row.
+/// ```
+/// assert_eq!(
+///     cellumina::gridops::rotate_ccw(&grid::grid![[1, 2, 3][4, 5, 6]]),
+///     grid::grid![[3, 6][2, 5][1, 4]]
+/// );
+/// ```
+pub fn rotate_ccw<T: Clone>(grid: &grid::Grid<T>) -> grid::Grid<T> {
+    let (rows, cols) = grid.size();
+    rearrange(grid, cols, rows, |row, col| (col, cols - 1 - row))
+}
+
+/// Mirrors the grid left to right, reversing each row.
+/// ```
+/// assert_eq!(
+///     cellumina::gridops::flip_horizontal(&grid::grid![[1, 2, 3][4, 5, 6]]),
+///     grid::grid![[3, 2, 1][6, 5, 4]]
+/// );
+/// ```
+pub fn flip_horizontal<T: Clone>(grid: &grid::Grid<T>) -> grid::Grid<T> {
+    let (rows, cols) = grid.size();
+    rearrange(grid, rows, cols, |row, col| (row, cols - 1 - col))
+}
+
+/// Mirrors the grid top to bottom, reversing the order of the rows.
+/// ```
+/// assert_eq!(
+///     cellumina::gridops::flip_vertical(&grid::grid![[1, 2, 3][4, 5, 6]]),
+///     grid::grid![[4, 5, 6][1, 2, 3]]
+/// );
+/// ```
+pub fn flip_vertical<T: Clone>(grid: &grid::Grid<T>) -> grid::Grid<T> {
+    let (rows, cols) = grid.size();
+    rearrange(grid, rows, cols, |row, col| (rows - 1 - row, col))
+}
+
+/// Swaps the rows and columns of the grid, mirroring it along its main diagonal.
+/// ```
+/// assert_eq!(
+///     cellumina::gridops::transpose(&grid::grid![[1, 2, 3][4, 5, 6]]),
+///     grid::grid![[1, 4][2, 5][3, 6]]
+/// );
+/// ```
+pub fn transpose<T: Clone>(grid: &grid::Grid<T>) -> grid::Grid<T> {
+    let (rows, cols) = grid.size();
+    rearrange(grid, cols, rows, |row, col| (col, row))
+}
+
+/// Sets all cells within ```thickness``` cells of an edge of the grid to ```symbol```, such as a visible wall around the playfield.
+/// A border at least half as thick as the grid is wide or high covers the entire grid.
+/// Returns wether any cell was changed.
+/// ```
+/// let mut grid = grid::Grid::new(3, 4);
+/// cellumina::gridops::draw_border(&mut grid, 1, 126);
+/// assert_eq!(grid, grid::grid![[126, 126, 126, 126][126, 0, 0, 126][126, 126, 126, 126]]);
+/// ```
+pub fn draw_border<T: PartialEq + Clone>(
+    grid: &mut grid::Grid<T>,
+    thickness: usize,
+    symbol: T,
+) -> bool {
+    let (rows, cols) = grid.size();
+    let mut res = false;
+    for ((row, col), cell) in grid.indexed_iter_mut() {
+        let distance = row.min(rows - 1 - row).min(col).min(cols - 1 - col);
+        if distance < thickness && *cell != symbol {
+            *cell = symbol.clone();
+            res = true;
+        }
+    }
+    res
+}
+
+#[test]
+fn gridops_test() {
+    use Transform::*;
+
+    let grid = grid::grid![[1, 2, 3][4, 5, 6]];
+    let apply = |transforms: &[Transform]| {
+        transforms
+            .iter()
+            .fold(grid.clone(), |grid, transform| transform.apply(&grid))
+    };
+
+    assert_eq!(apply(&[RotateCw]), grid::grid![[4, 1][5, 2][6, 3]]);
+    assert_eq!(apply(&[RotateCcw]), grid::grid![[3, 6][2, 5][1, 4]]);
+    assert_eq!(apply(&[FlipHorizontal]), grid::grid![[3, 2, 1][6, 5, 4]]);
+    assert_eq!(apply(&[FlipVertical]), grid::grid![[4, 5, 6][1, 2, 3]]);
+    assert_eq!(apply(&[Transpose]), grid::grid![[1, 4][2, 5][3, 6]]);
+
+    // compositions
+    assert_eq!(apply(&[RotateCw, RotateCcw]), grid);
+    assert_eq!(apply(&[RotateCw; 4]), grid);
+    assert_eq!(
+        apply(&[RotateCw, RotateCw]),
+        apply(&[FlipHorizontal, FlipVertical])
+    );
+    assert_eq!(apply(&[RotateCw, RotateCw, RotateCw]), apply(&[RotateCcw]));
+    assert_eq!(apply(&[Transpose, FlipHorizontal]), apply(&[RotateCw]));
+    assert_eq!(apply(&[FlipHorizontal, Transpose]), apply(&[RotateCcw]));
+    for transform in [FlipHorizontal, FlipVertical, Transpose] {
+        assert_eq!(apply(&[transform, transform]), grid);
+    }
+    for transform in [RotateCw, RotateCcw, FlipHorizontal, FlipVertical, Transpose] {
+        let size = transform.apply(&grid).size();
+        assert_eq!(transform.swaps_dimensions(), size == (3, 2));
+    }
+
+    // single rows and empty grids
+    assert_eq!(rotate_cw(&grid::grid![[1, 2]]), grid::grid![[1][2]]);
+    assert_eq!(rotate_ccw(&grid::grid![[1, 2]]), grid::grid![[2][1]]);
+    let empty = grid::Grid::<u8>::new(0, 0);
+    for transform in [RotateCw, RotateCcw, FlipHorizontal, FlipVertical, Transpose] {
+        assert!(transform.apply(&empty).is_empty());
+    }
+}
+
+#[test]
+fn border_test() {
+    let mut grid = grid::Grid::<u8>::new(5, 6);
+    assert!(!draw_border(&mut grid, 0, 126));
+    assert!(!grid.iter().any(|&cell| cell == 126));
+
+    // corners are part of the border
+    assert!(draw_border(&mut grid, 2, 126));
+    assert_eq!(
+        grid,
+        grid::grid![
+            [126, 126, 126, 126, 126, 126]
+            [126, 126, 126, 126, 126, 126]
+            [126, 126, 0, 0, 126, 126]
+            [126, 126, 126, 126, 126, 126]
+            [126, 126, 126, 126, 126, 126]
+        ]
+    );
+    assert!(!draw_border(&mut grid, 1, 126));
+
+    // borders thicker than half the grid cover all of it
+    draw_border(&mut grid, 3, 1);
+    assert!(grid.iter().all(|&cell| cell == 1));
+    draw_border(&mut grid, usize::MAX, 2);
+    assert!(grid.iter().all(|&cell| cell == 2));
+    let mut empty = grid::Grid::<u8>::new(0, 0);
+    assert!(!draw_border(&mut empty, 1, 1));
+}
@@ -8,9 +8,22 @@
 //! ## Features
 //!
 //! Cellumina provides an ```Automaton``` struct that represents a 2-dimensional grid of characters.
-//! This grid can be initialized from a vector, a file or an image.
+//! This grid can be initialized from a vector, a text, CSV or image file or any reader such as standard input, or an image, and replaced with a new image at any time using ```Automaton::load_state_from_image```; the ```convert``` module offers the underlying conversions between grids and images.
 //! Additionally, the user can configure the ```Rule``` the automaton uses to transform itself into the next step.
 //! The transformation to the next state can be initiated manually or on a fixed time step, for example when using Cellumina as part of a larger graphical application.
+//! Rules that need several iterations per visible step can be applied multiple times per time step with ```AutomatonBuilder::with_substeps```, hiding the intermediate states.
+//! To keep an automaton running in the background, for example in a server, ```AutomatonHandle::spawn``` moves it to its own thread and ```AutomatonHandle::subscribe``` notifies other threads of each completed step.
+//! To plot population curves, ```AutomatonBuilder::with_stats_recorder``` counts chosen symbols in every generation, which ```Automaton::stats_recorder``` then returns as series or CSV.
+//! Likewise, ```AutomatonBuilder::with_transition_tracker``` counts how many cells turned from which symbol into which other symbol.
+//! For analysis scripts, ```Automaton::states``` turns an automaton into an iterator over its successive states, and ```Automaton::states_hashed``` yields cheap hashes instead, for example to look for cycles.
+//! States authored in the wrong orientation can be rotated, flipped or transposed with the functions of the ```gridops``` module, the corresponding methods of ```Automaton``` or ```AutomatonBuilder::with_transform```. Likewise, ```AutomatonBuilder::with_border``` surrounds the playfield with a visible wall of a symbol.
+//! Reversible rules, such as wrapping ```ShiftRule```s, provide an inverse, allowing ```Automaton::step_back``` to undo time steps.
+//! For symbols representing magnitudes, such as a heat scale, ```AutomatonBuilder::with_color_gradient``` colors a whole range of symbols at once.
+//! All colors are 8-bit sRGB, like the colors of image files and design tools, and can be passed either as arrays or as a ```CellColor```, which also reads hexadecimal codes such as ```#FF8000``` and converts from and to linear color space.
+//! Colors can also be loaded from a GIMP palette file with ```AutomatonBuilder::with_colors_from_gpl```. Saving a state as an image only keeps symbols with a color of their own, unless ```AutomatonBuilder::with_alpha_encoding``` stores the symbols in the alpha channel.
+//! Symbols passed to ```AutomatonBuilder::with_frozen_symbols```, such as walls, are never changed by rules, only by ```Automaton::set_cell```.
+//! To show an evolution in documentation, ```Automaton::render_filmstrip``` lays out consecutive states in a single image and ```Automaton::export_html_player``` writes a self-contained HTML page to play and scrub through them.
+//! For simulations spanning multiple grids, such as predators and their prey, ```CoupledAutomata``` steps several named automata together, with each one's ```CoupledRule``` reading the previous states of the others. For worlds wider than the grid, such as the landscape of a falling sand game, ```ScrollingAutomaton``` shifts the state sideways and generates the columns scrolling into view with a callback.
 //!
 //! ### Rules
 //!
@@ -21,13 +34,25 @@
 //!   * The next state of a cell is fully determined by its environment in the step before.
 //!   * Example: [Rule 90](https://en.wikipedia.org/wiki/Rule_90).
 //!   * Example: [Game Of Life](https://en.wikipedia.org/wiki/Conway%27s_Game_of_Life).
+//!   * Rules that only depend on the sum of a cell's neighborhood, such as Game of Life, can use the considerably faster totalistic rules.
+//!   * The ```rule::helpers``` module provides common computations on environments, such as counting neighbors without overflowing for large cell values.
+//! * Decay Rules
+//!   * Cells turn into another symbol once they held their symbol for a number of steps, added with ```AutomatonBuilder::with_decay```.
+//!   * Example: fire burning out to ash after exactly ten steps, no matter which other rules run alongside.
 //!
+//! Different rules can govern different parts of the grid, such as water and land zones, by adding them as named regions with ```AutomatonBuilder::with_region```, with masks given as rectangles or drawn in a second image.
 //! These rules can be added by creating these struct using normal Rust code.
+//! For well-known automata such as Game of Life, Brian's Brain, Wireworld or elementary automata, the ```rule::presets``` module provides ready-made rules with matching colors, added with ```AutomatonBuilder::with_preset```.
 //!
 //! The Patter Replacement Rules can also (de-)serialized by using ```serde``` or loaded from (and saved to) a custom file type.
 //! This representation is more humanly readable than the serde version and can easily be created by hand if you do not want your rust files to contain large amounts of grid initializations for the patterns.
+//! Single patterns can also be drawn as pairs of tiny images and loaded with ```Pattern::from_image_files``` or ```AutomatonBuilder::with_pattern_images```, with a chosen color standing for wildcards.
 //!
 //! Additionally, the public trait [```Rule```](https://docs.rs/cellumina/latest/cellumina/rule/trait.Rule.html) can be overwritten to implement completely custom rules.
+//! The ```testing``` module helps testing such rules, comparing their results with grids written as text and printing the mismatching cells on failure.
+//! Rules using randomness draw from a generator seeded with ```AutomatonBuilder::with_seed``` or from entropy. Any other source of random numbers, for example one recording them to replay a run, can be supplied by implementing the ```CellRng``` trait and passing it to ```AutomatonBuilder::with_rng```.
+//! To score how close two end states are, for example across a parameter sweep, the ```analysis``` module offers the Hamming distance, a similarity between 0 and 1 and a count of which symbols turned into which.
+//! To find the cells holding a symbol, for example to spawn effects there, ```Automaton::positions_of```, ```Automaton::first_cell_of``` and ```Automaton::random_cell_of``` search the state, while the ```CellQueries``` trait offers the same for any grid, such as the environment buffer of a rule. The ```CellGridExt``` trait iterates over grids with the ```(row, col)``` coordinates of each cell and fills them from a function of those coordinates, and ```Automaton::dimensions_wh``` and ```Automaton::dimensions_rc``` spell out the order of the dimensions.
 //!
 //! ### Live View
 //!
@@ -36,8 +61,23 @@
 //! This is useful when just playing around with cellular automata.
 //!
 //! The user can also directly change the state of cells. Press any (character or space) button, and then mouse clicks will replace the currently hovered cell with the pressed character.
-//! The automaton can also be paused and resumed with ```Enter```.
-//! The current state of the automaton can be saved to a file with ```Ctrl + S```, currently the following formats are supported: ```txt``` (with one row of chararcters per line) as well as ```png, jpeg, ico, bmp```. Normal restrictions of those files apply, e.g. saving to jpeg may result in compression, so ```.jpeg```-files are not suited for saving and reloading automata.
+//! The numpad types digits as well, and other keys can be bound to any character with the ```key_bindings``` of the ```LiveViewConfig```, to reach symbols without a letter or digit.
+//! Stamps, such as a glider or a fire source, can be loaded from text files into the ```stamps``` of the ```LiveViewConfig```. The keys ```1``` to ```9``` select a stamp, which is then placed centered on the clicked cell, with wildcards ```*``` leaving the cells below unchanged.
+//! The automaton can also be paused and resumed with ```Enter```, and ```Ctrl + Up``` and ```Ctrl + Down``` double and halve the speed of automata with a time step, see ```Automaton::set_speed_multiplier```.
+//! ```Ctrl + D``` enters a debug mode in which the keys ```1``` to ```9``` disable and enable the rules of a ```MultiRule``` or the patterns of a ```PatternRule``` while the automaton runs, logging the list of rules. The same is possible in code through ```Automaton::rule_mut``` and ```Rule::set_enabled```.
+//! The window title always shows the position, character and display color of the cell currently hovered by the mouse.
+//! To find a cell by its coordinates, press ```Ctrl + G```, type its row and column separated by a comma and press ```Enter```. The view then zooms in on the cell and the title shows it as if it was hovered. ```Escape``` cancels the input.
+//! For recordings that should be identical on every machine, setting the ```stepping``` of the ```LiveViewConfig``` to ```LiveStepping::Deterministic``` performs a fixed number of steps each frame instead of following the clock.
+//! Use the mouse wheel to zoom in and out and drag with the right mouse button to move the view.
+//! For large automata, a minimap in the bottom right corner shows the whole state and can be clicked to jump to a location. It can be shown and hidden with ```Ctrl + M```.
+//! ```Ctrl + T``` toggles an activity heatmap that dims cells depending on how long ago they last changed, making it easy to see where the automaton is still active.
+//! The current state of the automaton can be saved to a file with ```Ctrl + S```, currently the following formats are supported: ```txt``` (with one row of chararcters per line), ```cels``` (storing one byte per cell) as well as ```png, jpeg, ico, bmp```. Normal restrictions of those files apply, e.g. saving to jpeg may result in compression, so ```.jpeg```-files are not suited for saving and reloading automata.
+//!
+//! The live view can be configured by passing a ```LiveViewConfig``` to ```run_live_with```, for example to choose a present mode or a maximum frame rate, or to pause the simulation by itself once it has stabilized or died out with an ```AutoPause``` condition, which ```Automaton::run_until``` also accepts to run automata headlessly.
+//! By default, the window checks for new steps at most 60 times per second and is only redrawn when the state changes.
+//! To read or change the automaton from other threads while the window runs, wrap it with ```Automaton::into_shared``` and pass a clone to ```Automaton::run_live_shared```.
+//! Running live returns once the window is closed, so multiple automata can be shown one after another, for example from a menu. A ```LiveSession``` does the same and additionally returns each automaton in the state it had when its window was closed.
+//! On machines without a GPU, such as CI runners or virtual machines, the live view falls back to a software adapter if one is available. If the window can not be set up at all, running live returns a ```CelluminaError::GraphicsInitError``` describing the cause, for example ```GraphicsInitError::NoDisplay``` on a machine without a display, instead of panicking, so host applications can carry on without a window.
 //!
 //! The live view functionality is not included in the library by default and must be enabled via the ```display``` feature.
 //!
@@ -55,6 +95,8 @@
 //! ```
 //! if you want to enable [live view](#live-view).
 //!
+//! Then, ```use cellumina::prelude::*;``` imports the most common types, along with the ```grid!``` macro and the ```grid``` crate in the version Cellumina uses, so no direct dependency on ```grid``` is needed.
+//!
 //! ### Examples
 //!
 //! The [examples folder](https://github.com/Linus-Mussmaecher/cellumina/tree/master/examples) contains the following examples:
@@ -63,6 +105,9 @@
 //! * ```sand```: A small falling sand simulation using pattern replacement rules to simulate falling sand, fire and ash.
 //! * ```rule90```: A implementation of the [Rule 90](https://en.wikipedia.org/wiki/Rule_90) 1-dimensional cellular automaton that demonstrates how to use Cellumina's 2D-grid to display multiple successive states of a 1-dimensional automaton.
 //! * ```to_string```: An example that shows how to convert rules to and from the different string/file types.
+//! * ```hooks```: An example that shows how to interact with the automaton from code while the live view is running.
+//! * ```sequential```: An example that shows the game of life and, once its window is closed, the falling sand simulation in the same process using a ```LiveSession```.
+//! * ```headless```: An example that runs the game of life without a window, printing population statistics and saving the final state as an image and text. It needs no features.
 //!
 //!  All examples can be run by cloning this repository with
 //!  ```bash
@@ -73,6 +118,18 @@
 //!     cargo run --examples sand --features="display"
 //!   ```
 //!
+//! ### Command Line
+//!
+//! With the ```cli``` feature, cellumina also builds a ```cellumina``` binary that runs automata from files without writing any code. Its ```live``` subcommand additionally requires the ```display``` feature:
+//! ```bash
+//!   cellumina run --init init.txt --rules sand.cel --colors palette.toml --steps 500 --seed 1 --out out.png
+//!   cellumina live --init init.txt --preset game-of-life
+//!   cellumina convert state.txt state.png --colors palette.toml
+//! ```
+//! Palettes are TOML files mapping cell characters to RGBA colors, such as ```X = [224, 210, 159, 255]```.
+//! Cells can also be given a name shown in messages and the live view, such as ```F = { color = [255, 80, 0, 255], name = "fire" }```.
+//! Without ```--out```, ```run``` prints the final state as text. Errors exit with code 1, invalid arguments with code 2.
+//!
 //! ### Logging
 //!
 //! Cellumina supports logging via the [log](https://docs.rs/log/latest/log/) crate.
@@ -81,37 +138,160 @@
 //! ### Performance
 //!
 //! Since pattern replacement can be a rather costly operation, cellumina runs these in parallel using the [rayon](https://github.com/rayon-rs/rayon) crate.
+//! The grid is split into bands of rows that are scanned in parallel, so even rules with only a few patterns make use of all cores on large grids.
 //! Small patterns (as they may appear when e.g. using a falling sand simulation to create a death animation or similar) have negligible runtime.
 //! Larger patters, especially with many patters or rules, may require more calculation time but can still be viewed in high FPS when running on their own.
 //! Note that the runtime differs considerably between compilation in debug and release configuration.
+//! To measure the effect of changes, run the benchmarks with ```cargo bench``` or the headless ```stress``` example with ```cargo run --release --example stress -- [steps] [size]```.
+//!
+//! To control the number of threads, use ```AutomatonBuilder::with_parallelism```: ```Parallelism::Threads``` gives the automaton its own thread pool, while ```Parallelism::Sequential``` avoids the parallelism overhead for small grids.
+//! Parallelism can also be removed entirely by disabling the default ```parallel``` feature.
+//!
+//! If only a small part of the grid is active at a time, ```AutomatonBuilder::with_dirty_tracking``` restricts each step to the area around cells that changed during the previous step.
+//! This is only valid for local, deterministic rules, see its documentation for details.
+//!
+//! For large grids running life-like rules, the ```display``` feature additionally provides ```rule::GpuEnvironmentRule```, which applies the rule in a compute pass on the GPU and keeps the state there between steps, reading it back only when it is accessed.
+//! Create it from a ```GpuContext```; when running live, the window reuses that context instead of creating a second device.
+//!
+//! To find out where the time of a step goes, enable the ```tracing``` feature.
+//! Each step, each rule within a ```MultiRule``` and the scan, sort and commit phases of pattern rules then emit [tracing](https://github.com/tokio-rs/tracing) spans with the grid size, number of patterns and number of replacements.
+//! Without the feature, the same information is logged at trace level.
+//! To find out which rule changed what, ```AutomatonBuilder::with_step_reports``` records the number of cells each rule, including each rule within a ```MultiRule```, changed during the last step, readable with ```Automaton::last_step_report```.
+//! Hosts with a fixed time budget per frame can use ```Automaton::next_step_with_budget``` instead of ```next_step```, which spreads steps of environment rules over multiple calls and cuts the scan of pattern rules short, changing the semantics of a step.
+//!
+//! Static information per cell that rules consult but never change, such as terrain or materials, can be kept in an auxiliary layer added with ```AutomatonBuilder::with_aux_layer```.
+//! ```rule::AuxEnvironmentRule``` then receives the environment of each cell in both the state and the auxiliary layer, see the ```sticky_sand``` example.
+//!
+//! Cells are ```u8``` values throughout this documentation, but automata can also use other cell types, for example ```u16``` for more states or a small struct of multiple values.
+//! Create them with ```AutomatonBuilderGeneric::<T>::new()``` for a type implementing ```Cell```, and supply rules implementing ```Rule<T>```.
+//! The text format, patterns, the built-in rules and the live view remain limited to ```u8``` cells.
+//!
+//! Large states are saved most compactly in the binary ```cels``` format with ```Automaton::save_binary``` and loaded with ```AutomatonBuilder::from_binary_file```. The ```compression``` feature additionally allows compressing them, for example by saving to a ```celz``` file, which is recognized automatically when loading. For read-only analysis of very large states, the ```mmap``` feature adds ```analysis::open_binary```, which maps an uncompressed ```cels``` file into memory instead of reading it and allows accessing its cells by row and column. It is ```unsafe```, as the file must not be changed while it is mapped.
+//! For further processing of states, the ```ndarray``` feature adds ```Automaton::to_ndarray```, ```AutomatonBuilder::from_ndarray``` and ```Automaton::load_state_from_ndarray```, converting states to and from [ndarray](https://github.com/rust-ndarray/ndarray) arrays with the rows of the state along axis 0.
+//!
+//! To run automata from other languages, the ```ffi``` feature adds a minimal C interface in the ```ffi``` module, declared in ```include/cellumina.h```. The C dynamic library is only built on request, with ```cargo rustc --release --lib --features ffi --crate-type cdylib```. The module documentation includes an example of using it from Python.
+//!
+//! To author rules without recompiling, the ```scripting``` feature adds ```rule::ScriptedEnvironmentRule```, which calculates the next value of each cell with a function written in [Lua](https://www.lua.org). Lua is compiled from source with the feature and runs a lot slower than native rules.
+
+/// Enters a span with the passed name and fields until the end of the enclosing block, if the ```tracing``` feature is enabled.
+/// Otherwise, the name and fields are logged at trace level.
+macro_rules! trace_span {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($name $(, $field = $value)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        log::trace!(concat!($name $(, " ", stringify!($field), "={}")*) $(, $value)*);
+    };
+}
 
 mod automaton;
-pub use automaton::Automaton;
+pub use automaton::{Automaton, AutomatonGeneric, BudgetOutcome, LegendEntry, SharedAutomaton};
 
 mod builder;
-pub use builder::AutomatonBuilder;
+pub use builder::{AutomatonBuilder, AutomatonBuilderGeneric};
+
+mod cell;
+pub use cell::Cell;
+
+mod color;
+pub use color::CellColor;
 
 mod error;
-pub use error::CelluminaError;
+pub use error::{CelluminaError, GraphicsInitError, SourceKind, ValidationIssue};
+
+mod activity;
+pub use activity::ActivityMap;
+
+mod stats;
+pub use stats::{StatsRecorder, DEFAULT_STATS_CAPACITY};
+
+mod transitions;
+pub use transitions::TransitionTracker;
+
+mod queries;
+pub use queries::CellQueries;
+
+mod grid_ext;
+pub use grid_ext::CellGridExt;
+
+mod binary;
+
+mod rng;
+pub use rng::CellRng;
+
+mod parallelism;
+pub use parallelism::Parallelism;
+
+mod player;
+
+mod coupled;
+pub use coupled::{CoupledAutomata, CoupledRule};
+
+mod scrolling;
+pub use scrolling::ScrollingAutomaton;
+
+mod auto_pause;
+pub use auto_pause::AutoPause;
+
+mod runner;
+pub use runner::{AutomatonEvent, AutomatonHandle, EventReceiver, DEFAULT_EVENT_CAPACITY};
+
+/// Contains the [GpuContext] struct shared by GPU rules and the live view.
+#[cfg(feature = "display")]
+mod gpu;
+#[cfg(feature = "display")]
+pub use gpu::GpuContext;
+/// Contains the placeholder for the [GpuContext] struct without the ```display``` feature.
+#[cfg(not(feature = "display"))]
+mod no_gpu;
+#[cfg(not(feature = "display"))]
+pub use no_gpu::GpuContext;
 
 /// Contains the model, view and controller for diplaying automata.
 #[cfg(feature = "display")]
 pub(crate) mod graphic;
+#[cfg(feature = "display")]
+pub use graphic::{
+    ColorMode, KeyBindings, LiveHooks, LiveResult, LiveSession, LiveStepping, LiveViewConfig,
+    PresentMode, VirtualKeyCode,
+};
 /// Contains structs and traits for the definition of the transformations rules of cellular automata.
 pub mod rule;
 
+pub mod convert;
+
+pub mod gridops;
+
+pub mod analysis;
+
+pub mod prelude;
+
+pub mod testing;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// The [grid] crate in the version used by cellumina, so downstream code needs no dependency of its own, see [prelude].
+pub use ::grid;
+/// Creates a [Grid](grid::Grid) from rows of values, see [grid](grid::grid).
+pub use ::grid::grid;
+
 /// A type for the underlying state of a cellular automaton.
 /// Each cell always has a character as a state in cellumina.
 pub type CellGrid = grid::Grid<u8>;
 
 /// Converts each character to its associated u8 value.
 ///
+/// Digits, lower case and upper case letters map to ```0..62```.
+/// All other values, except for the reserved ones, are represented by the characters ```U+0100``` to ```U+01FF``` (Latin Extended-A and B), offset by the value, so every cell value has a character and survives conversion to text and back.
+/// Unknown characters map to 0, see [try_char_to_id] for a fallible version.
 /// ```
 ///     # use cellumina::char_to_id;
 ///     assert_eq!(char_to_id('0'), 0);
 ///     assert_eq!(char_to_id('5'), 5);
 ///     assert_eq!(char_to_id('b'), 11);
 ///     assert_eq!(char_to_id('A'), 36);
+///     assert_eq!(char_to_id('\u{164}'), 100);
 /// ```
 /// PREFERABLY RESERVED:
 ///     0 - Used for space
@@ -132,17 +312,67 @@ pub const fn char_to_id(symbol: char) -> u8 {
         '_' => 126,
         '*' => 127,
         ' ' => 0,
+        '\u{13E}'..='\u{17D}' | '\u{180}'..='\u{1FF}' => (symbol as u32 - 0x100) as u8,
         _ => 0,
     }
 }
 
-/// Converts an u8 value to its associated character.
+/// Converts each character to its associated u8 value, like [char_to_id], but fails for characters that have no associated value instead of mapping them to 0.
+/// ```
+///     # use cellumina::try_char_to_id;
+///     assert_eq!(try_char_to_id('b').unwrap(), 11);
+///     assert_eq!(try_char_to_id(' ').unwrap(), 0);
+///     assert!(try_char_to_id('#').is_err());
+/// ```
+pub fn try_char_to_id(symbol: char) -> Result<u8, CelluminaError> {
+    match symbol {
+        '0'..='9'
+        | 'a'..='z'
+        | 'A'..='Z'
+        | '_'
+        | '*'
+        | ' '
+        | '\u{13E}'..='\u{17D}'
+        | '\u{180}'..='\u{1FF}' => Ok(char_to_id(symbol)),
+        _ => Err(CelluminaError::UnknownCharacterError(symbol)),
+    }
+}
+
+/// Converts a line of text of the passed kind to cell values.
+/// In strict mode, unknown characters cause an error pointing to their position, otherwise they are counted in ```substituted``` and become 0.
+pub(crate) fn parse_line(
+    line: &str,
+    source_kind: SourceKind,
+    line_number: usize,
+    strict: bool,
+    substituted: &mut usize,
+) -> Result<Vec<u8>, CelluminaError> {
+    line.chars()
+        .enumerate()
+        .map(|(col, symbol)| match try_char_to_id(symbol) {
+            Ok(id) => Ok(id),
+            Err(err) if strict => Err(CelluminaError::ParseError {
+                source_kind,
+                line: line_number,
+                column: col + 1,
+                message: err.to_string(),
+            }),
+            Err(_) => {
+                *substituted += 1;
+                Ok(0)
+            }
+        })
+        .collect()
+}
+
+/// Converts an u8 value to its associated character, the inverse of [char_to_id].
 /// ```
 ///     # use cellumina::id_to_char;
 ///     assert_eq!(id_to_char(0), ' ');
 ///     assert_eq!(id_to_char(5), '5');
 ///     assert_eq!(id_to_char(11), 'b');
 ///     assert_eq!(id_to_char(36), 'A');
+///     assert_eq!(id_to_char(100), '\u{164}');
 /// ```
 ///
 /// PREFERABLY RESERVED:
@@ -162,10 +392,14 @@ pub const fn id_to_char(id: u8) -> char {
         0 => ' ',
         1..=9 => (id + 48) as char,
         10..=35 => (id + 97 - 10) as char,
-        36..=63 => (id + 65 - 10 - 26) as char,
+        36..=61 => (id + 65 - 10 - 26) as char,
         126 => '_',
         127 => '*',
-        _ => ' ',
+        // all remaining values are offset into the Latin Extended blocks
+        _ => match char::from_u32(0x100 + id as u32) {
+            Some(symbol) => symbol,
+            None => ' ',
+        },
     }
 }
 
@@ -195,4 +429,9 @@ fn conversion_test() {
         (36..62).map(id_to_char).collect::<Vec<char>>(),
         ('A'..='Z').collect::<Vec<char>>()
     );
+    // every value survives the conversion to a character and back
+    for id in 0..=255 {
+        assert_eq!(char_to_id(id_to_char(id)), id);
+        assert_eq!(try_char_to_id(id_to_char(id)).unwrap(), id);
+    }
 }
@@ -0,0 +1,237 @@
+//! Command line interface for running cellumina automata from files.
+//!
+//! ```text
+//! cellumina run --init init.txt --rules sand.cel --colors palette.toml --steps 500 --out out.png
+//! cellumina live --init init.txt --preset game-of-life
+//! cellumina convert state.txt state.png --colors palette.toml
+//! ```
+//!
+//! The ```live``` subcommand is only available with the ```display``` feature.
+//!
+//! Palettes are TOML files mapping the characters of cells (see [cellumina::char_to_id]) to RGBA colors, such as ```X = [224, 210, 159, 255]```.
+//! Cells can also be given a name shown in messages and the live view, such as ```F = { color = [255, 80, 0, 255], name = "fire" }```.
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use cellumina::{rule, AutomatonBuilder, CelluminaError};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Run cellular automata from files.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Runs an automaton for a number of steps without a window and saves or prints the result.
+    Run {
+        #[command(flatten)]
+        automaton: AutomatonArgs,
+        /// The number of steps to perform.
+        #[arg(long, default_value_t = 100)]
+        steps: u64,
+        /// The file to save the final state to, as text, image or binary state depending on its extension. Prints the state as text if omitted.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Runs an automaton in a window.
+    #[cfg(feature = "display")]
+    Live {
+        #[command(flatten)]
+        automaton: AutomatonArgs,
+        /// The minimum time between two steps, in seconds.
+        #[arg(long, default_value_t = 0.1)]
+        interval: f32,
+    },
+    /// Converts a state between the text, image and binary formats.
+    Convert {
+        /// The state to read, as text, image or binary state depending on its extension.
+        input: PathBuf,
+        /// The file to write, as text, image or binary state depending on its extension.
+        output: PathBuf,
+        /// A TOML file mapping cell characters to colors, required for images.
+        #[arg(long)]
+        colors: Option<PathBuf>,
+    },
+}
+
+/// The arguments describing an automaton.
+#[derive(Debug, Args)]
+struct AutomatonArgs {
+    /// The initial state, as text, image or binary state depending on its extension.
+    #[arg(long)]
+    init: PathBuf,
+    /// A pattern rule in the .cel format.
+    #[arg(long, conflicts_with = "preset", required_unless_present = "preset")]
+    rules: Option<PathBuf>,
+    /// A prebuilt rule, bringing its own colors.
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+    /// A TOML file mapping cell characters to colors, overriding the colors of a preset.
+    #[arg(long)]
+    colors: Option<PathBuf>,
+    /// Seeds the randomness of the rules, making runs reproducible.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Rejects unknown characters in text files instead of reading them as empty cells.
+    #[arg(long)]
+    strict: bool,
+}
+
+/// The presets of [rule::presets].
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Preset {
+    GameOfLife,
+    Highlife,
+    Seeds,
+    BriansBrain,
+    Wireworld,
+    Rule90,
+    FallingSand,
+}
+
+impl Preset {
+    /// Creates the preset of this name.
+    fn create(self) -> rule::presets::Preset {
+        match self {
+            Preset::GameOfLife => rule::presets::game_of_life(),
+            Preset::Highlife => rule::presets::highlife(),
+            Preset::Seeds => rule::presets::seeds(),
+            Preset::BriansBrain => rule::presets::brians_brain(),
+            Preset::Wireworld => rule::presets::wireworld(),
+            Preset::Rule90 => rule::presets::rule90(),
+            Preset::FallingSand => rule::presets::falling_sand(),
+        }
+    }
+}
+
+/// An entry of a palette, either just a color or a table with a color and a name.
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum PaletteEntry {
+    Color([u8; 4]),
+    Named {
+        color: Option<[u8; 4]>,
+        name: Option<String>,
+    },
+}
+
+/// Reads a palette mapping cell characters to colors and names and adds them to the builder.
+fn with_palette(
+    mut builder: AutomatonBuilder,
+    path: &Path,
+) -> Result<AutomatonBuilder, CelluminaError> {
+    let content = std::fs::read_to_string(path)?;
+    let palette = toml::from_str::<HashMap<char, PaletteEntry>>(&content).map_err(|err| {
+        CelluminaError::CustomError(format!("invalid palette {}: {err}", path.display()))
+    })?;
+    for (symbol, entry) in palette {
+        let symbol = cellumina::try_char_to_id(symbol)?;
+        let (color, name) = match entry {
+            PaletteEntry::Color(color) => (Some(color), None),
+            PaletteEntry::Named { color, name } => (color, name),
+        };
+        if let Some(color) = color {
+            builder = builder.with_color(symbol, color);
+        }
+        if let Some(name) = name {
+            builder = builder.with_symbol_name(symbol, &name);
+        }
+    }
+    Ok(builder)
+}
+
+/// Creates a builder with the passed initial state, read as text, image or binary state depending on its extension.
+fn builder_from_file(path: &Path) -> AutomatonBuilder {
+    let path = path.to_path_buf();
+    match path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some("txt") | None => AutomatonBuilder::new().from_text_file(path),
+        Some("cels" | "celz") => AutomatonBuilder::new().from_binary_file(path),
+        Some(_) => AutomatonBuilder::new().from_image_file(path),
+    }
+}
+
+impl AutomatonArgs {
+    /// Creates a builder for the automaton described by these arguments.
+    fn builder(&self) -> Result<AutomatonBuilder, CelluminaError> {
+        let mut builder = builder_from_file(&self.init);
+        if let Some(preset) = self.preset {
+            builder = builder.with_preset(preset.create());
+        }
+        if let Some(rules) = &self.rules {
+            let rules = rule::PatternRule::parse(&std::fs::read_to_string(rules)?, self.strict)?;
+            builder = builder.with_rule(rules);
+        }
+        if let Some(colors) = &self.colors {
+            builder = with_palette(builder, colors)?;
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.with_seed(seed);
+        }
+        if self.strict {
+            builder = builder.with_strict_parsing();
+        }
+        Ok(builder)
+    }
+}
+
+/// Executes the passed command.
+fn execute(command: Command) -> Result<(), CelluminaError> {
+    match command {
+        Command::Run {
+            automaton,
+            steps,
+            out,
+        } => {
+            let mut automaton = automaton.builder()?.build_checked()?;
+            for _ in 0..steps {
+                automaton.next_step();
+            }
+            match out {
+                Some(out) => automaton.save(out),
+                None => {
+                    print!("{}", automaton.to_text_string());
+                    Ok(())
+                }
+            }
+        }
+        #[cfg(feature = "display")]
+        Command::Live {
+            automaton,
+            interval,
+        } => automaton
+            .builder()?
+            .with_min_time_step(std::time::Duration::try_from_secs_f32(interval).map_err(
+                |err| CelluminaError::CustomError(format!("invalid interval {interval}: {err}")),
+            )?)
+            .build_checked()?
+            .run_live(),
+        Command::Convert {
+            input,
+            output,
+            colors,
+        } => {
+            let mut builder = builder_from_file(&input);
+            if let Some(colors) = &colors {
+                builder = with_palette(builder, colors)?;
+            }
+            builder.build_checked()?.save(output)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match execute(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
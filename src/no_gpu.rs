@@ -0,0 +1,9 @@
+/// A handle to a GPU device and queue, which rules running on the GPU are created from.
+///
+/// Without the ```display``` feature, no context can be created, so [Rule::gpu_context](crate::rule::Rule::gpu_context) always returns [None].
+/// The type still exists so that the [Rule](crate::rule::Rule) trait is the same with and without the feature.
+#[derive(Debug, Clone)]
+pub struct GpuContext {
+    /// Prevents creating a context.
+    _private: (),
+}
@@ -0,0 +1,88 @@
+/// Describes how an [Automaton](crate::Automaton) distributes the work of applying its rules across threads.
+///
+/// Only rules that make use of parallelism themselves, such as [PatternRule](crate::rule::PatternRule), are affected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parallelism {
+    /// Rules run on the global thread pool of [rayon](https://github.com/rayon-rs/rayon), using all available cores.
+    #[default]
+    Default,
+    /// Rules run on a dedicated thread pool of the automaton with the passed number of threads.
+    Threads(usize),
+    /// Rules run on the calling thread only, avoiding the overhead of parallelism for small grids.
+    Sequential,
+}
+
+thread_local! {
+    /// Wether rules applied on this thread should currently avoid parallelism.
+    static SEQUENTIAL: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Returns wether rules may currently use parallelism.
+#[cfg(feature = "parallel")]
+pub(crate) fn is_parallel() -> bool {
+    !SEQUENTIAL.with(|sequential| sequential.get())
+}
+
+/// The runtime representation of a [Parallelism] setting, owning the thread pool if necessary.
+#[derive(Debug)]
+pub(crate) enum Executor {
+    /// Run on the global thread pool.
+    Global,
+    /// Run on a dedicated thread pool.
+    #[cfg(feature = "parallel")]
+    Pool(rayon::ThreadPool),
+    /// Run on the calling thread only.
+    Sequential,
+}
+
+impl Executor {
+    /// Creates an executor implementing the passed setting.
+    pub(crate) fn new(parallelism: Parallelism) -> Self {
+        match parallelism {
+            Parallelism::Default => Self::Global,
+            Parallelism::Sequential => Self::Sequential,
+            #[cfg(feature = "parallel")]
+            Parallelism::Threads(threads) => {
+                match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                    Ok(pool) => Self::Pool(pool),
+                    Err(err) => {
+                        log::error!(
+                            "Could not create thread pool, falling back to the global pool: {err}"
+                        );
+                        Self::Global
+                    }
+                }
+            }
+            #[cfg(not(feature = "parallel"))]
+            Parallelism::Threads(_) => {
+                log::warn!("Thread pools require the 'parallel' feature, running sequentially.");
+                Self::Sequential
+            }
+        }
+    }
+
+    /// Returns the setting this executor implements.
+    pub(crate) fn parallelism(&self) -> Parallelism {
+        match self {
+            Self::Global => Parallelism::Default,
+            #[cfg(feature = "parallel")]
+            Self::Pool(pool) => Parallelism::Threads(pool.current_num_threads()),
+            Self::Sequential => Parallelism::Sequential,
+        }
+    }
+
+    /// Runs the passed function according to this executor's setting.
+    pub(crate) fn run<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        match self {
+            Self::Global => f(),
+            #[cfg(feature = "parallel")]
+            Self::Pool(pool) => pool.install(f),
+            Self::Sequential => {
+                let previous = SEQUENTIAL.with(|sequential| sequential.replace(true));
+                let res = f();
+                SEQUENTIAL.with(|sequential| sequential.set(previous));
+                res
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+/// The page written by [FrameEncoder::to_html], with ```{{TITLE}}```, ```{{SCALE}}``` and ```{{DATA}}``` replaced by the title, the size of a cell in pixels and the encoded frames.
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{TITLE}}</title>
+<style>
+body { font-family: sans-serif; background: #202020; color: #e0e0e0; }
+canvas { image-rendering: pixelated; display: block; margin-bottom: 8px; }
+#slider { width: 400px; vertical-align: middle; }
+</style>
+</head>
+<body>
+<canvas id="canvas"></canvas>
+<button id="play">Play</button>
+<input id="slider" type="range" min="0" value="0">
+<span id="label"></span>
+<script>
+const data = {{DATA}};
+const scale = {{SCALE}};
+const canvas = document.getElementById("canvas");
+const context = canvas.getContext("2d");
+const slider = document.getElementById("slider");
+const label = document.getElementById("label");
+const button = document.getElementById("play");
+canvas.width = data.width * scale;
+canvas.height = data.height * scale;
+slider.max = Math.max(data.frames.length - 1, 0);
+const image = context.createImageData(data.width, data.height);
+const buffer = document.createElement("canvas");
+buffer.width = data.width;
+buffer.height = data.height;
+context.imageSmoothingEnabled = false;
+
+function show(frame) {
+  let pixel = 0;
+  const runs = data.frames[frame] || [];
+  for (let i = 0; i < runs.length; i += 2) {
+    const color = data.palette[runs[i]];
+    for (let n = 0; n < runs[i + 1]; n++, pixel++) {
+      image.data.set(color, pixel * 4);
+    }
+  }
+  buffer.getContext("2d").putImageData(image, 0, 0);
+  context.clearRect(0, 0, canvas.width, canvas.height);
+  context.drawImage(buffer, 0, 0, canvas.width, canvas.height);
+  slider.value = frame;
+  label.textContent = "Frame " + frame + " / " + (data.frames.length - 1);
+}
+
+let timer = null;
+button.onclick = () => {
+  if (timer) {
+    clearInterval(timer);
+    timer = null;
+    button.textContent = "Play";
+  } else {
+    timer = setInterval(() => show((Number(slider.value) + 1) % data.frames.length), 100);
+    button.textContent = "Pause";
+  }
+};
+slider.oninput = () => show(Number(slider.value));
+show(0);
+</script>
+</body>
+</html>
+"#;
+
+/// Collects frames of RGBA pixel data and encodes them compactly for the HTML player, see [AutomatonGeneric::export_html_player](crate::AutomatonGeneric::export_html_player).
+///
+/// Each distinct color is stored once in a palette, and each frame as a list of runs of equally colored pixels,
+/// alternating the palette index and the length of the run, in row-major order.
+#[derive(Debug, Clone)]
+pub(crate) struct FrameEncoder {
+    /// The number of pixels per row.
+    width: usize,
+    /// The number of rows.
+    height: usize,
+    /// All distinct colors of the frames, in order of appearance.
+    palette: Vec<[u8; 4]>,
+    /// The palette index of each color.
+    indices: HashMap<[u8; 4], usize>,
+    /// The runs of each frame, alternating palette indices and lengths.
+    frames: Vec<Vec<usize>>,
+}
+
+impl FrameEncoder {
+    /// Creates an encoder for frames of the passed size.
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            palette: Vec::new(),
+            indices: HashMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Adds a frame of RGBA pixel data, with four bytes per pixel.
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        let mut runs: Vec<usize> = Vec::new();
+        for color in bytes.chunks_exact(4) {
+            let color = [color[0], color[1], color[2], color[3]];
+            let index = *self.indices.entry(color).or_insert_with(|| {
+                self.palette.push(color);
+                self.palette.len() - 1
+            });
+            match runs.as_mut_slice() {
+                [.., last, count] if *last == index => *count += 1,
+                _ => runs.extend([index, 1]),
+            }
+        }
+        self.frames.push(runs);
+    }
+
+    /// Encodes the frames as a JSON object with the fields ```width```, ```height```, ```palette``` and ```frames```.
+    pub(crate) fn to_json(&self) -> String {
+        let list = |values: &mut dyn Iterator<Item = String>| {
+            format!("[{}]", values.collect::<Vec<_>>().join(","))
+        };
+        format!(
+            "{{\"width\":{},\"height\":{},\"palette\":{},\"frames\":{}}}",
+            self.width,
+            self.height,
+            list(
+                &mut self
+                    .palette
+                    .iter()
+                    .map(|color| list(&mut color.iter().map(ToString::to_string)))
+            ),
+            list(
+                &mut self
+                    .frames
+                    .iter()
+                    .map(|runs| list(&mut runs.iter().map(ToString::to_string)))
+            ),
+        )
+    }
+
+    /// Creates a self-contained HTML page playing the frames, drawing each cell as a square of ```scale``` pixels.
+    pub(crate) fn to_html(&self, title: &str, scale: u32) -> String {
+        TEMPLATE
+            .replace("{{TITLE}}", title)
+            .replace("{{SCALE}}", &scale.max(1).to_string())
+            .replace("{{DATA}}", &self.to_json())
+    }
+}
+
+#[test]
+fn frame_encoder_test() {
+    let (black, white) = ([0, 0, 0, 255], [255, 255, 255, 255]);
+    let mut encoder = FrameEncoder::new(3, 2);
+    encoder.push(&[black, black, white, white, white, white].concat());
+    encoder.push(&[white; 6].concat());
+    encoder.push(&[black, white, black, white, black, [255, 0, 0, 0]].concat());
+    assert_eq!(encoder.frames.len(), 3);
+    assert_eq!(
+        encoder.to_json(),
+        "{\"width\":3,\"height\":2,\
+         \"palette\":[[0,0,0,255],[255,255,255,255],[255,0,0,0]],\
+         \"frames\":[[0,2,1,4],[1,6],[0,1,1,1,0,1,1,1,0,1,2,1]]}"
+    );
+
+    // no frames at all
+    assert_eq!(
+        FrameEncoder::new(0, 0).to_json(),
+        "{\"width\":0,\"height\":0,\"palette\":[],\"frames\":[]}"
+    );
+
+    let html = encoder.to_html("test", 0);
+    assert!(html.contains("const data = {\"width\":3,"));
+    assert!(html.contains("const scale = 1;"));
+    assert!(html.contains("<title>test</title>"));
+}
@@ -0,0 +1,24 @@
+//! Re-exports the types most programs working with cellumina need, so a single ```use cellumina::prelude::*;``` suffices.
+//!
+//! This includes the [grid] crate and its [grid!] macro in the version cellumina uses, so no direct dependency on it is needed.
+//! ```
+//! use cellumina::prelude::*;
+//!
+//! let mut automaton = AutomatonBuilder::new()
+//!     .from_grid(grid![[0, 1, 0][0, 1, 0][0, 1, 0]])
+//!     .with_rule(LifeLikeRule::parse("B3/S23").unwrap())
+//!     .build();
+//! automaton.next_step();
+//! let expected: CellGrid = grid![[0, 0, 0][1, 1, 1][0, 0, 0]];
+//! assert_eq!(automaton.state(), &expected);
+//! ```
+
+pub use crate::rule::{
+    AuxEnvironmentRule, BoundaryBehaviour, ConvolutionRule, DecayRule, EnvironmentRule, FnRule,
+    LifeLikeRule, MultiRule, Pattern, PatternRule, Rule, ShiftRule, TotalisticRule,
+};
+pub use crate::{
+    char_to_id, id_to_char, Automaton, AutomatonBuilder, CellColor, CellGrid, CellGridExt,
+    CellQueries, CelluminaError,
+};
+pub use ::grid::{self, grid, Grid};
@@ -0,0 +1,106 @@
+use crate::{Cell, CellGridExt};
+
+/// Finds the cells holding a symbol in a grid, such as the state of an automaton or the environment buffer of a rule.
+///
+/// Positions are returned as ```(row, col)```, in row-major order where an order applies.
+/// [Automaton](crate::AutomatonGeneric) offers the same methods for its state.
+/// ```
+/// use cellumina::CellQueries;
+/// let grid = grid::grid![[0, 1, 0][1, 0, 0]];
+/// assert_eq!(grid.positions_of(1), vec![(0, 1), (1, 0)]);
+/// assert_eq!(grid.first_cell_of(1), Some((0, 1)));
+/// assert_eq!(grid.first_cell_of(2), None);
+///
+/// let cell = grid.random_cell_of(1, &mut rand::thread_rng()).unwrap();
+/// assert!(cell == (0, 1) || cell == (1, 0));
+/// ```
+pub trait CellQueries<T: Cell> {
+    /// Returns the positions of all cells holding the passed symbol.
+    fn positions_of(&self, symbol: T) -> Vec<(usize, usize)>;
+
+    /// Returns the position of the first cell holding the passed symbol, if any.
+    fn first_cell_of(&self, symbol: T) -> Option<(usize, usize)>;
+
+    /// Returns the position of a cell holding the passed symbol, chosen uniformly at random, if any.
+    ///
+    /// The grid is only traversed once, without collecting the positions of all candidates.
+    fn random_cell_of<R: rand::Rng + ?Sized>(
+        &self,
+        symbol: T,
+        rng: &mut R,
+    ) -> Option<(usize, usize)>;
+}
+
+impl<T: Cell> CellQueries<T> for grid::Grid<T> {
+    fn positions_of(&self, symbol: T) -> Vec<(usize, usize)> {
+        matching_cells(self, symbol).collect()
+    }
+
+    fn first_cell_of(&self, symbol: T) -> Option<(usize, usize)> {
+        matching_cells(self, symbol).next()
+    }
+
+    fn random_cell_of<R: rand::Rng + ?Sized>(
+        &self,
+        symbol: T,
+        rng: &mut R,
+    ) -> Option<(usize, usize)> {
+        // reservoir sampling, the k-th candidate replaces the choice with a chance of 1/k
+        let mut res = None;
+        for (seen, position) in matching_cells(self, symbol).enumerate() {
+            if rng.gen_range(0..=seen) == 0 {
+                res = Some(position);
+            }
+        }
+        res
+    }
+}
+
+/// Iterates over the positions of all cells holding the passed symbol, in row-major order.
+fn matching_cells<T: Cell>(
+    grid: &grid::Grid<T>,
+    symbol: T,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    grid.indexed_iter()
+        .filter(move |&(_, &cell)| cell == symbol)
+        .map(|(position, _)| position)
+}
+
+#[test]
+fn queries_test() {
+    use rand::SeedableRng;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(4);
+    let grid = crate::testing::parse_grid(
+        "0120
+         1001
+         0001",
+    );
+
+    // no occurrences
+    assert!(grid.positions_of(5).is_empty());
+    assert_eq!(grid.first_cell_of(5), None);
+    assert_eq!(grid.random_cell_of(5, &mut rng), None);
+    assert_eq!(grid::Grid::<u8>::new(0, 0).first_cell_of(0), None);
+
+    // a single occurrence
+    assert_eq!(grid.positions_of(2), vec![(0, 2)]);
+    assert_eq!(grid.first_cell_of(2), Some((0, 2)));
+    for _ in 0..10 {
+        assert_eq!(grid.random_cell_of(2, &mut rng), Some((0, 2)));
+    }
+
+    // many occurrences, all of which are chosen about equally often
+    let ones = vec![(0, 1), (1, 0), (1, 3), (2, 3)];
+    assert_eq!(grid.positions_of(1), ones);
+    assert_eq!(grid.first_cell_of(1), Some((0, 1)));
+    let mut counts = std::collections::HashMap::new();
+    for _ in 0..4000 {
+        *counts
+            .entry(grid.random_cell_of(1, &mut rng).unwrap())
+            .or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), 4);
+    assert!(counts.values().all(|&count| (800..1200).contains(&count)));
+    assert_eq!(grid.positions_of(0).len(), 7);
+}
@@ -0,0 +1,185 @@
+/// A source of random numbers for rules using randomness, such as the chances of [Patterns](crate::rule::Pattern), see [AutomatonBuilder::with_rng](crate::AutomatonBuilder::with_rng).
+///
+/// By default, rules use a [rand::rngs::StdRng], seeded from entropy or with [AutomatonBuilder::with_seed](crate::AutomatonBuilder::with_seed).
+/// Implementing this trait allows supplying other generators instead, for example a counter-based generator matching a GPU implementation, or one recording its numbers to replay a run.
+///
+/// Rules consisting of multiple rules give each of them its own generator by [forking](Self::fork) the passed one with the index of the rule,
+/// and a [PatternRule](crate::rule::PatternRule) forks one generator per band of rows it scans, so the numbers drawn never depend on the number of threads or their timing.
+/// ```
+/// # use cellumina::CellRng;
+/// /// Returns the numbers of a linear congruential generator.
+/// #[derive(Debug)]
+/// struct Lcg(u64);
+///
+/// impl CellRng for Lcg {
+///     fn next_u64(&mut self) -> u64 {
+///         self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///         self.0
+///     }
+///
+///     fn fork(&mut self, stream: u64) -> Box<dyn CellRng> {
+///         Box::new(Lcg(self.next_u64() ^ stream))
+///     }
+/// }
+///
+/// let mut rng = Lcg(1);
+/// assert!((0. ..1.).contains(&rng.next_f32()));
+/// let auto = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![1, 0, 1, 0], 2)
+///     .with_pattern(cellumina::rule::Pattern {
+///         chance: 0.5,
+///         ..cellumina::rule::Pattern::default()
+///     })
+///     .with_rng(rng)
+///     .build();
+/// ```
+pub trait CellRng: std::fmt::Debug + Send {
+    /// Returns the next random number, uniformly distributed over all values of [u64].
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns the next random number, uniformly distributed over all values of [u32].
+    /// The default implementation uses the upper half of [Self::next_u64].
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns the next random number, uniformly distributed in ```[0, 1)```.
+    /// The default implementation uses the upper 24 bits of [Self::next_u64], as many as an [f32] can represent exactly.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns an independent generator for the passed stream, such as the index of a rule or of a band of rows scanned by one thread.
+    ///
+    /// The result has to only depend on the state of this generator and the stream, so it is the same no matter which thread uses it.
+    /// Forking may advance this generator, so forking the same stream again yields a different generator. Generators that do not advance have to make use of the stream instead.
+    fn fork(&mut self, stream: u64) -> Box<dyn CellRng>;
+
+    /// Returns an independent copy of this generator in the same state, if it can be copied, so copies of rules such as [AutomatonGeneric::sub_automaton](crate::AutomatonGeneric::sub_automaton) draw the same numbers.
+    ///
+    /// The default implementation returns [None], in which case copies of rules use a [fork](Self::fork) instead. Generators implementing [Clone] should return ```Some(Box::new(self.clone()))```.
+    fn clone_boxed(&self) -> Option<Box<dyn CellRng>> {
+        None
+    }
+}
+
+impl CellRng for rand::rngs::StdRng {
+    fn next_u64(&mut self) -> u64 {
+        rand::RngCore::next_u64(self)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        rand::RngCore::next_u32(self)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        rand::Rng::gen(self)
+    }
+
+    /// Returns a faster [rand::rngs::SmallRng], seeded with the next number of this generator.
+    fn fork(&mut self, _stream: u64) -> Box<dyn CellRng> {
+        Box::new(<rand::rngs::SmallRng as rand::SeedableRng>::seed_from_u64(
+            CellRng::next_u64(self),
+        ))
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn CellRng>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl CellRng for rand::rngs::SmallRng {
+    fn next_u64(&mut self) -> u64 {
+        rand::RngCore::next_u64(self)
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        rand::RngCore::next_u32(self)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        rand::Rng::gen(self)
+    }
+
+    /// Returns another [rand::rngs::SmallRng], seeded with the next number of this generator.
+    fn fork(&mut self, _stream: u64) -> Box<dyn CellRng> {
+        Box::new(<rand::rngs::SmallRng as rand::SeedableRng>::seed_from_u64(
+            CellRng::next_u64(self),
+        ))
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn CellRng>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+/// Allows using the functions of [rand::Rng], such as shuffling, with any [CellRng].
+impl rand::RngCore for dyn CellRng {
+    fn next_u32(&mut self) -> u32 {
+        CellRng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        CellRng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = CellRng::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[test]
+fn cell_rng_test() {
+    use rand::{Rng, SeedableRng};
+
+    // the standard generators draw the same numbers through both traits
+    let mut a = rand::rngs::StdRng::seed_from_u64(3);
+    let mut b = rand::rngs::StdRng::seed_from_u64(3);
+    for _ in 0..10 {
+        assert_eq!(CellRng::next_f32(&mut a), b.gen::<f32>());
+        assert_eq!(CellRng::next_u32(&mut a), b.gen::<u32>());
+    }
+
+    // forks only depend on the state of the parent
+    let mut a = rand::rngs::StdRng::seed_from_u64(3);
+    let mut b = rand::rngs::StdRng::seed_from_u64(3);
+    let (mut fork_a, mut fork_b) = (a.fork(0), b.fork(0));
+    for _ in 0..10 {
+        assert_eq!(fork_a.next_u64(), fork_b.next_u64());
+    }
+    // but differ from later forks
+    assert_ne!(a.fork(0).next_u64(), a.fork(0).next_u64());
+
+    // copies continue with the same numbers
+    let mut copy = a.clone_boxed().unwrap();
+    assert_eq!(copy.next_u64(), CellRng::next_u64(&mut a));
+
+    // the default conversions stay in range, even for the largest numbers
+    #[derive(Debug)]
+    struct Max;
+    impl CellRng for Max {
+        fn next_u64(&mut self) -> u64 {
+            u64::MAX
+        }
+        fn fork(&mut self, _stream: u64) -> Box<dyn CellRng> {
+            Box::new(Max)
+        }
+    }
+    assert!(Max.next_f32() < 1.);
+    assert_eq!(Max.next_u32(), u32::MAX);
+
+    // any generator can be used with the functions of rand
+    let mut boxed: Box<dyn CellRng> = Box::new(rand::rngs::StdRng::seed_from_u64(5));
+    let mut bytes = [0; 11];
+    rand::RngCore::fill_bytes(&mut boxed, &mut bytes);
+    assert!(bytes.iter().any(|&byte| byte != 0));
+    assert!((0..4).contains(&boxed.gen_range(0..4)));
+}
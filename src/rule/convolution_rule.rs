@@ -0,0 +1,225 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator, ParallelSliceMut};
+
+use super::BoundaryBehaviour;
+use crate::CellGrid;
+
+/// A convolution rule treats cells as numbers and replaces each cell with the sum of its neighborhood, weighted by a kernel.
+/// The sum is rounded to the nearest integer and clamped into the ```clamp``` range to get the new cell.
+///
+/// This makes numeric simulations such as heat diffusion, blurring or erosion straightforward to express.
+///
+/// The kernel is centered on the cell, so for a kernel of ```r``` rows and ```c``` columns the entry at ```[r / 2][c / 2]``` is the weight of the cell itself.
+/// ```
+/// # use cellumina::rule::{ConvolutionRule, Rule};
+/// // every cell spreads its value evenly among itself and its four direct neighbors
+/// let rule = ConvolutionRule {
+///     kernel: grid::grid![[0.0, 0.2, 0.0][0.2, 0.2, 0.2][0.0, 0.2, 0.0]],
+///     ..Default::default()
+/// };
+/// let mut grid = grid::grid![[0, 0, 0][0, 50, 0][0, 0, 0]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 10, 0][10, 10, 10][0, 10, 0]]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConvolutionRule {
+    /// The weights of the cells in the neighborhood of each cell, centered on the cell.
+    pub kernel: grid::Grid<f32>,
+    /// The smallest and largest value of the resulting cells.
+    pub clamp: (u8, u8),
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains rows that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    pub row_boundary: BoundaryBehaviour,
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains columns that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    /// Cells out of bounds in both directions use the row boundary, if it is a symbol.
+    pub col_boundary: BoundaryBehaviour,
+}
+
+impl Default for ConvolutionRule {
+    /// Creates a rule with a kernel that leaves all cells unchanged, the full range of cells and periodic boundaries.
+    fn default() -> Self {
+        Self {
+            kernel: grid::grid![[1.0]],
+            clamp: (0, u8::MAX),
+            row_boundary: Default::default(),
+            col_boundary: Default::default(),
+        }
+    }
+}
+
+impl ConvolutionRule {
+    /// Creates a rule replacing each cell with the average of the square of cells at most ```radius``` rows and columns away from it, including itself.
+    /// ```
+    /// # use cellumina::rule::{ConvolutionRule, Rule};
+    /// let mut grid = grid::grid![[0, 0, 0][0, 90, 0][0, 0, 0]];
+    /// ConvolutionRule::box_blur(1).transform(&mut grid);
+    /// assert_eq!(grid, grid::grid![[10, 10, 10][10, 10, 10][10, 10, 10]]);
+    /// ```
+    pub fn box_blur(radius: usize) -> Self {
+        let size = 2 * radius + 1;
+        Self {
+            kernel: grid::Grid::init(size, size, 1.0 / (size * size) as f32),
+            ..Default::default()
+        }
+    }
+
+    /// Writes the new cells of the passed row to ```res```.
+    fn convolve_row(&self, grid: &CellGrid, row: usize, res: &mut [u8]) {
+        let (rows, cols) = grid.size();
+        let (k_rows, k_cols) = self.kernel.size();
+        let (center_row, center_col) = (k_rows / 2, k_cols / 2);
+
+        for (col, res) in res.iter_mut().enumerate() {
+            let mut sum = 0.0;
+            for k_row in 0..k_rows {
+                let t_row = self
+                    .row_boundary
+                    .resolve(row as isize + k_row as isize - center_row as isize, rows);
+                for k_col in 0..k_cols {
+                    let weight = self.kernel[k_row][k_col];
+                    if weight == 0.0 {
+                        continue;
+                    }
+                    let t_col = self
+                        .col_boundary
+                        .resolve(col as isize + k_col as isize - center_col as isize, cols);
+                    let cell = match (t_row, t_col) {
+                        (Ok(t_row), Ok(t_col)) => grid[t_row][t_col],
+                        // The boundary symbol of rows takes precedence if need be.
+                        (Err(symbol), _) | (Ok(_), Err(symbol)) => symbol,
+                    };
+                    sum += weight * cell as f32;
+                }
+            }
+            *res = sum.round().clamp(self.clamp.0 as f32, self.clamp.1 as f32) as u8;
+        }
+    }
+}
+
+impl super::Rule for ConvolutionRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let (rows, cols) = grid.size();
+        if rows == 0 || cols == 0 {
+            *buffer = CellGrid::new(rows, cols);
+            return true;
+        }
+
+        // reuse the memory of the buffer
+        let mut data = std::mem::replace(buffer, CellGrid::new(0, 0)).into_vec();
+        data.resize(rows * cols, 0);
+
+        #[cfg(feature = "parallel")]
+        if crate::parallelism::is_parallel() {
+            data.par_chunks_mut(cols)
+                .enumerate()
+                .for_each(|(row, res)| self.convolve_row(grid, row, res));
+            *buffer = CellGrid::from_vec(data, cols);
+            return true;
+        }
+
+        for (row, res) in data.chunks_mut(cols).enumerate() {
+            self.convolve_row(grid, row, res);
+        }
+        *buffer = CellGrid::from_vec(data, cols);
+        true
+    }
+
+    fn reach(&self) -> Option<usize> {
+        // the kernel extends further above and to the left of the center for even sizes
+        let (rows, cols) = self.kernel.size();
+        Some((rows / 2).max(cols / 2))
+    }
+}
+
+#[test]
+fn box_blur_test() {
+    use super::Rule;
+
+    let blur = ConvolutionRule::box_blur(1);
+    let mut grid = CellGrid::new(9, 9);
+    grid[4][4] = 180;
+
+    // the heat spreads evenly to all neighbors
+    blur.transform(&mut grid);
+    let mut expected = CellGrid::new(9, 9);
+    for row in 3..6 {
+        for col in 3..6 {
+            expected[row][col] = 20;
+        }
+    }
+    assert_eq!(grid, expected);
+
+    for _ in 0..3 {
+        blur.transform(&mut grid);
+
+        // symmetric in all directions
+        for row in 0..9 {
+            for col in 0..9 {
+                assert_eq!(grid[row][col], grid[col][row]);
+                assert_eq!(grid[row][col], grid[8 - row][col]);
+                assert_eq!(grid[row][col], grid[row][8 - col]);
+            }
+        }
+
+        // the total amount of heat is conserved, up to rounding
+        let total = grid.iter().map(|&cell| cell as i32).sum::<i32>();
+        assert!((total - 180).abs() <= 81 / 2, "total heat {total}");
+    }
+}
+
+#[test]
+fn convolution_boundary_test() {
+    use super::Rule;
+
+    // moves all cells one column to the right
+    let shift = |boundary| ConvolutionRule {
+        kernel: grid::grid![[1.0, 0.0, 0.0]],
+        col_boundary: boundary,
+        ..Default::default()
+    };
+    for (boundary, expected) in [
+        (BoundaryBehaviour::Periodic, grid::grid![[3, 1, 2][6, 4, 5]]),
+        (
+            BoundaryBehaviour::Symbol(9),
+            grid::grid![[9, 1, 2][9, 4, 5]],
+        ),
+        (BoundaryBehaviour::Clamp, grid::grid![[1, 1, 2][4, 4, 5]]),
+    ] {
+        let mut grid = grid::grid![[1, 2, 3][4, 5, 6]];
+        shift(boundary).transform(&mut grid);
+        assert_eq!(grid, expected, "{boundary}");
+    }
+
+    // results are rounded and clamped
+    let rule = ConvolutionRule {
+        kernel: grid::grid![[-1.0, 2.5]],
+        clamp: (1, 100),
+        row_boundary: BoundaryBehaviour::Symbol(0),
+        col_boundary: BoundaryBehaviour::Symbol(0),
+    };
+    let mut grid = grid::grid![[0, 1, 10, 60]];
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1, 3, 24, 100]]);
+    assert_eq!(rule.reach(), Some(1));
+
+    // sequential and parallel application agree
+    let blur = ConvolutionRule::box_blur(2);
+    let grid = CellGrid::from_vec((0..40 * 30).map(|i| (i * 37 % 256) as u8).collect(), 30);
+    let mut parallel = grid.clone();
+    blur.transform(&mut parallel);
+    let mut sequential = grid.clone();
+    crate::parallelism::Executor::new(crate::Parallelism::Sequential)
+        .run(|| blur.transform(&mut sequential));
+    assert_eq!(parallel, sequential);
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+use crate::CellGrid;
+
+/// A rule letting symbols age, turning each cell into another symbol once it held its symbol for a number of steps, such as fire burning out to ash.
+///
+/// Each entry ```(from, to, steps)``` of the ```chain``` turns cells of symbol ```from``` into ```to``` once they held ```from``` for ```steps``` steps.
+/// If multiple entries start at the same symbol, the first one is used.
+/// Entries may continue each other, e.g. fire turning into ash and ash turning into an empty cell later on.
+///
+/// The rule remembers the age of each cell between steps. Whenever another rule, or a manual change, gives a cell a new symbol, its age starts over.
+/// Ages count the steps this rule was applied in, starting with the first step the rule sees the cell with its current symbol.
+/// ```
+/// # use cellumina::rule::{DecayRule, Rule};
+/// let rule = DecayRule::new(vec![(1, 2, 2)]);
+/// let mut grid = grid::grid![[1, 0]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[1, 0]]);
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[2, 0]]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecayRule {
+    /// The transitions of this rule, each turning a symbol into another symbol after a number of steps.
+    pub chain: Vec<(u8, u8, u32)>,
+    /// The ages of the cells of the last grid this rule was applied to.
+    #[serde(skip)]
+    ages: Ages,
+}
+
+impl DecayRule {
+    /// Creates a decay rule from the passed transitions, see [DecayRule].
+    pub fn new(chain: Vec<(u8, u8, u32)>) -> Self {
+        Self {
+            chain,
+            ages: Ages::default(),
+        }
+    }
+}
+
+/// The ages of the cells of the last grid a [DecayRule] was applied to, together with that grid as left by the rule, kept behind a lock like the [RuleRng](super::RuleRng).
+#[derive(Debug)]
+struct Ages(std::sync::Mutex<(grid::Grid<u32>, CellGrid)>);
+
+impl Default for Ages {
+    fn default() -> Self {
+        Self(std::sync::Mutex::new((
+            grid::Grid::new(0, 0),
+            grid::Grid::new(0, 0),
+        )))
+    }
+}
+
+impl Clone for Ages {
+    fn clone(&self) -> Self {
+        Self(std::sync::Mutex::new(
+            self.0.lock().unwrap_or_else(|err| err.into_inner()).clone(),
+        ))
+    }
+}
+
+impl super::Rule for DecayRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut guard = self.ages.0.lock().unwrap_or_else(|err| err.into_inner());
+        let (ages, previous) = &mut *guard;
+        if previous.size() != grid.size() {
+            // a new grid, so no cell has aged yet
+            *ages = grid::Grid::new(grid.rows(), grid.cols());
+            *previous = grid.clone();
+        }
+
+        for ((cell, age), before) in grid.iter_mut().zip(ages.iter_mut()).zip(previous.iter()) {
+            if cell != before {
+                *age = 0;
+            }
+            *age = age.saturating_add(1);
+            if let Some(&(_, to, steps)) = self.chain.iter().find(|(from, ..)| from == cell) {
+                if *age >= steps {
+                    *cell = to;
+                    *age = 0;
+                }
+            }
+        }
+        previous.clone_from(grid);
+    }
+
+    fn remap_symbols(&mut self, map: &std::collections::HashMap<u8, u8>) {
+        for (from, to, _) in self.chain.iter_mut() {
+            *from = map.get(from).copied().unwrap_or(*from);
+            *to = map.get(to).copied().unwrap_or(*to);
+        }
+    }
+
+    fn shift_columns(&self, dx: i64) {
+        // the remembered cells moving in do not matter, as their ages start over anyway
+        let mut guard = self.ages.0.lock().unwrap_or_else(|err| err.into_inner());
+        let (ages, previous) = &mut *guard;
+        super::shift_columns(ages, dx);
+        super::shift_columns(previous, dx);
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+#[test]
+fn decay_test() {
+    use super::Rule;
+
+    // fire (1) burns out to ash (2) after 3 steps, while wood (3) next to it catches fire
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 3, 0], 3)
+        .with_pattern(super::Pattern {
+            chance: 1.0,
+            before: grid::grid![[1, 3]],
+            after: grid::grid![[1, 1]],
+            ..Default::default()
+        })
+        .with_decay(&[(1, 2, 3), (2, 0, 2)])
+        .build();
+    let expected = [
+        [1, 1, 0],
+        [1, 1, 0],
+        // the initial fire burns out after 3 steps
+        [2, 1, 0],
+        // the new fire burns out 3 steps after it appeared
+        [2, 2, 0],
+        // ash vanishes after 2 steps
+        [0, 2, 0],
+        [0, 0, 0],
+    ];
+    for (step, cells) in expected.into_iter().enumerate() {
+        automaton.next_step();
+        assert_eq!(
+            automaton.state().iter().copied().collect::<Vec<_>>(),
+            cells,
+            "step {}",
+            step + 1
+        );
+    }
+
+    // changing a cell manually starts its age over
+    let rule = DecayRule::new(vec![(1, 2, 2)]);
+    let mut grid = grid::grid![[1, 1]];
+    rule.transform(&mut grid);
+    grid[(0, 0)] = 0;
+    rule.transform(&mut grid);
+    grid[(0, 0)] = 1;
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1, 2]]);
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[2, 2]]);
+
+    // the chain survives a roundtrip, the ages do not
+    let text = toml::to_string(&rule).unwrap();
+    let parsed = toml::from_str::<DecayRule>(&text).unwrap();
+    assert_eq!(parsed.chain, rule.chain);
+    let mut grid = grid::grid![[1]];
+    parsed.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1]]);
+}
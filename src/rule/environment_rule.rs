@@ -5,7 +5,12 @@ use crate::CellGrid;
 /// Applying this to each cell yields the entire transformation.
 ///
 /// Note that each application of the ```cell_transform``` function will read from the entire untransformed array.
-/// Also, the environment will wrap around the grid edges.
+///
+/// Parts of the environment outside the grid are filled according to ```row_boundary``` and ```col_boundary```, as described by [BoundaryBehaviour::resolve](super::BoundaryBehaviour::resolve).
+/// With [Periodic](super::BoundaryBehaviour::Periodic) boundaries, the environment wraps around the grid edges as often as necessary, so it may be larger than the grid itself.
+/// Cells out of bounds in both directions use the row boundary symbol, if there is one.
+///
+/// With the [Hex](GridTopology::Hex) topology, the environment consists of the six neighbors of the cell on a hexagonal grid instead, see [GridTopology].
 #[derive(Clone, Copy)]
 pub struct EnvironmentRule {
     /// The distance the considered environment extends from the cell to be set, in order ```[top, right, bottom, left]```.
@@ -24,13 +29,19 @@ pub struct EnvironmentRule {
     ///  +---------------+
     ///
     /// ```
+    ///
+    /// Ignored for the [Hex](GridTopology::Hex) topology.
     pub environment_size: [usize; 4],
     /// Behaviour of this rule when encountering cases in which the environment of a cell contains rows that go out of bounds of the state grid.
     pub row_boundary: super::BoundaryBehaviour,
     /// Behaviour of this rule when encountering cases in which the environment of a cell contains columns that go out of bounds of the state grid.
     pub col_boundary: super::BoundaryBehaviour,
+    /// The layout of the cells, deciding which cells make up the environment.
+    pub topology: GridTopology,
     /// The function that calculates the next state of a single cell based on its environment.
     ///
+    /// For the [Hex](GridTopology::Hex) topology, the received grid instead consists of a single row holding the cell and its six neighbors in the order described there.
+    ///
     /// Receives a grid of size ```(top + bottom + 1) * (left + right + 1)```, where ```[top, right, bottom, left]``` is the ```enviroment_size```.
     /// Must return a character.
     /// In the next iteration after applying this rule, the cell at position ```[top][left]```, with ```[0][0]``` being the top right, of the received grid will contain the return value of this function.
@@ -43,6 +54,7 @@ impl Default for EnvironmentRule {
             environment_size: [1, 1, 1, 1],
             row_boundary: Default::default(),
             col_boundary: Default::default(),
+            topology: Default::default(),
             cell_transform: |_| 0,
         }
     }
@@ -54,74 +66,400 @@ impl std::fmt::Debug for EnvironmentRule {
             .field("environment_size", &self.environment_size)
             .field("row_boundary", &self.row_boundary)
             .field("col_boundary", &self.col_boundary)
+            .field("topology", &self.topology)
             //.field("cell_transform", &self.cell_transform)
             .finish()
     }
 }
 
-impl super::Rule for EnvironmentRule {
-    fn transform(&self, grid: &mut CellGrid) {
-        let mut buffer = grid::Grid::new(
-            self.environment_size[0] + self.environment_size[2] + 1,
-            self.environment_size[1] + self.environment_size[3] + 1,
+impl EnvironmentRule {
+    /// Calculates the next state of the cell at the passed position, using ```buffer``` to hold its environment.
+    fn next_cell(&self, grid: &CellGrid, row: usize, col: usize, buffer: &mut CellGrid) -> u8 {
+        match self.topology {
+            GridTopology::Square => fill_environment(
+                self.environment_size,
+                self.row_boundary,
+                self.col_boundary,
+                grid,
+                (row, col),
+                buffer,
+            ),
+            GridTopology::Hex => fill_hex_environment(
+                self.row_boundary,
+                self.col_boundary,
+                grid,
+                (row, col),
+                buffer,
+            ),
+        }
+        (self.cell_transform)(buffer)
+    }
+
+    /// Returns the number of rows and columns of the environment passed to ```cell_transform```.
+    fn environment_shape(&self) -> (usize, usize) {
+        match self.topology {
+            GridTopology::Square => environment_shape(self.environment_size),
+            GridTopology::Hex => (1, 7),
+        }
+    }
+}
+
+/// The layout of the cells of a grid, deciding which cells neighbor each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum GridTopology {
+    /// Square cells, neighboring the cells in the rows and columns around them.
+    #[default]
+    Square,
+    /// Hexagonal cells in rows, with every odd row shifted right by half a cell, so each cell has six neighbors.
+    ///
+    /// The environment of a cell consists of a single row holding the cell itself followed by its neighbors counter-clockwise, starting to the right:
+    /// east, north-east, north-west, west, south-west and south-east.
+    /// In terms of rows and columns, the neighbors to the north and south of a cell in an even row are in its own and the previous column,
+    /// while those of a cell in an odd row are in its own and the next column.
+    ///
+    /// [Periodic](super::BoundaryBehaviour::Periodic) row boundaries only join up seamlessly on grids with an even number of rows.
+    /// Images of the state still show square cells, skewing the hexagonal layout, unless drawn with [create_image_buffer_scaled](crate::AutomatonGeneric::create_image_buffer_scaled).
+    /// ```
+    /// # use cellumina::rule::{EnvironmentRule, GridTopology, Rule};
+    /// // every cell becomes the sum of its neighbors
+    /// let rule = EnvironmentRule {
+    ///     topology: GridTopology::Hex,
+    ///     row_boundary: cellumina::rule::BoundaryBehaviour::Symbol(0),
+    ///     col_boundary: cellumina::rule::BoundaryBehaviour::Symbol(0),
+    ///     cell_transform: |env| env.iter().skip(1).sum(),
+    ///     ..Default::default()
+    /// };
+    /// let mut grid = grid::grid![[0, 0, 0][0, 1, 0][0, 0, 0]];
+    /// rule.transform(&mut grid);
+    /// assert_eq!(grid, grid::grid![[0, 1, 1][1, 0, 1][0, 1, 1]]);
+    /// ```
+    Hex,
+}
+
+/// Row and column offsets of the neighbors of a cell in an even and in an odd row of a hexagonal grid, in the order described for [GridTopology::Hex].
+const HEX_OFFSETS: [[(isize, isize); 6]; 2] = [
+    [(0, 1), (-1, 0), (-1, -1), (0, -1), (1, -1), (1, 0)],
+    [(0, 1), (-1, 1), (-1, 0), (0, -1), (1, 0), (1, 1)],
+];
+
+/// Copies the cell at ```position``` and its six neighbors on a hexagonal grid into the single row of ```buffer```, filling neighbors outside the grid according to the boundaries.
+fn fill_hex_environment(
+    row_boundary: super::BoundaryBehaviour,
+    col_boundary: super::BoundaryBehaviour,
+    grid: &CellGrid,
+    (row, col): (usize, usize),
+    buffer: &mut CellGrid,
+) {
+    let (rows, cols) = grid.size();
+    buffer[0][0] = grid[row][col];
+    for (index, (row_del, col_del)) in HEX_OFFSETS[row % 2].into_iter().enumerate() {
+        let t_row = row_boundary.resolve(row as isize + row_del, rows);
+        let t_col = col_boundary.resolve(col as isize + col_del, cols);
+        buffer[0][index + 1] = match (t_row, t_col) {
+            (Ok(t_row), Ok(t_col)) => grid[t_row][t_col],
+            // The boundary symbol of rows takes precedence if need be.
+            (Err(symbol), _) | (Ok(_), Err(symbol)) => symbol,
+        };
+    }
+}
+
+/// Copies the environment of the passed size around the cell at ```position``` into ```buffer```, filling parts outside the grid according to the boundaries.
+pub(super) fn fill_environment(
+    environment_size: [usize; 4],
+    row_boundary: super::BoundaryBehaviour,
+    col_boundary: super::BoundaryBehaviour,
+    grid: &CellGrid,
+    (row, col): (usize, usize),
+    buffer: &mut CellGrid,
+) {
+    let (rows, cols) = grid.size();
+
+    for row_del in 0..=(environment_size[0] + environment_size[2]) {
+        // Calculate the row we are interested in, which may be out of bounds in either direction.
+        let t_row = row_boundary.resolve(
+            row as isize + row_del as isize - environment_size[0] as isize,
+            rows,
         );
-        let (rows, cols) = grid.size();
+        for col_del in 0..=(environment_size[1] + environment_size[3]) {
+            let t_col = col_boundary.resolve(
+                col as isize + col_del as isize - environment_size[3] as isize,
+                cols,
+            );
 
-        // correction factor to make sure no overflowing subtractions happen
+            buffer[row_del][col_del] = match (t_row, t_col) {
+                (Ok(t_row), Ok(t_col)) => grid[t_row][t_col],
+                // The boundary symbol of rows takes precedence if need be.
+                (Err(symbol), _) | (Ok(_), Err(symbol)) => symbol,
+            };
+        }
+    }
+}
 
-        let mut res = CellGrid::new(rows, cols);
+thread_local! {
+    /// Holds pairs of grids for the environment of a single cell, one pair per environment size, reused between transformations to avoid allocations.
+    /// The second grid of each pair holds the environment in the auxiliary layer for [AuxEnvironmentRule]s.
+    static ENVIRONMENTS: std::cell::RefCell<Vec<[CellGrid; 2]>> = const { std::cell::RefCell::new(Vec::new()) };
+}
 
-        for row in 0..rows {
-            for col in 0..cols {
-                for row_del in 0..=(self.environment_size[0] + self.environment_size[2]) {
-                    for col_del in 0..=(self.environment_size[1] + self.environment_size[3]) {
-                        // Calculate the index we are interested in.
-                        let (mut t_row, mut t_col) = (
-                            (row + row_del).wrapping_sub(self.environment_size[0]),
-                            (col + col_del).wrapping_sub(self.environment_size[3]),
-                        );
+/// Returns the number of rows and columns of an environment of the passed size.
+pub(super) fn environment_shape(environment_size: [usize; 4]) -> (usize, usize) {
+    (
+        environment_size[0] + environment_size[2] + 1,
+        environment_size[1] + environment_size[3] + 1,
+    )
+}
 
-                        let mut done = false;
-
-                        // If it is too large check the boundary condition.
-                        // The < 0 case is handled because we are performing a wrapping sub.
-                        // This might be error-prone if rows is close to the maximum value of a usize.
-                        if t_col >= cols {
-                            match self.col_boundary {
-                                // Perdiodic: Take the modulus.
-                                super::BoundaryBehaviour::Periodic => t_col %= cols,
-                                // Symbol: Set the buffer to a fixed element.
-                                super::BoundaryBehaviour::Symbol(symbol) => {
-                                    buffer[row_del][col_del] = symbol;
-                                    done = true;
-                                }
-                            }
-                        }
+/// Runs the passed function with a pair of grids of the passed number of rows and columns.
+pub(super) fn with_environments<R>(
+    size: (usize, usize),
+    f: impl FnOnce(&mut [CellGrid; 2]) -> R,
+) -> R {
+    ENVIRONMENTS.with(|environments| {
+        let mut environments = environments.borrow_mut();
+        let index = match environments
+            .iter()
+            .position(|environments| environments[0].size() == size)
+        {
+            Some(index) => index,
+            None => {
+                environments.push([CellGrid::new(size.0, size.1), CellGrid::new(size.0, size.1)]);
+                environments.len() - 1
+            }
+        };
+        f(&mut environments[index])
+    })
+}
 
-                        // Do the same for rows. Doing rows later ensures the boundary symbol of rows takes precedence if need be.
-                        if t_row >= rows {
-                            match self.row_boundary {
-                                // Perdiodic: Take the modulus.
-                                super::BoundaryBehaviour::Periodic => t_row %= rows,
-                                // Symbol: Set the buffer to a fixed element.
-                                super::BoundaryBehaviour::Symbol(symbol) => {
-                                    buffer[row_del][col_del] = symbol;
-                                    done = true;
-                                }
-                            }
-                        }
+impl EnvironmentRule {
+    /// Runs the passed function with a grid of the size of this rule's environment.
+    fn with_environment<R>(&self, f: impl FnOnce(&mut CellGrid) -> R) -> R {
+        with_environments(self.environment_shape(), |[environment, _]| f(environment))
+    }
+}
 
-                        if !done {
-                            buffer[row_del][col_del] = grid[t_row][t_col]
-                        }
-                    }
+impl super::Rule for EnvironmentRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(*self))
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let (rows, cols) = grid.size();
+        if buffer.size() != (rows, cols) {
+            *buffer = CellGrid::new(rows, cols);
+        }
+
+        self.with_environment(|environment| {
+            for row in 0..rows {
+                for col in 0..cols {
+                    buffer[row][col] = self.next_cell(grid, row, col, environment);
+                }
+            }
+        });
+
+        true
+    }
+
+    fn transform_rows(
+        &self,
+        grid: &CellGrid,
+        buffer: &mut CellGrid,
+        rows: std::ops::Range<usize>,
+    ) -> bool {
+        self.with_environment(|environment| {
+            for row in rows {
+                for col in 0..grid.cols() {
+                    buffer[row][col] = self.next_cell(grid, row, col, environment);
                 }
-                res[row][col] = (self.cell_transform)(&buffer);
             }
+        });
+        true
+    }
+
+    fn reach(&self) -> Option<usize> {
+        match self.topology {
+            GridTopology::Square => self.environment_size.iter().copied().max(),
+            GridTopology::Hex => Some(1),
+        }
+    }
+
+    fn transform_region(&self, grid: &mut CellGrid, dirty: &super::Region) {
+        let (rows, cols) = grid.size();
+        // only cells with a dirty cell in their environment can change
+        let region = dirty.expand(self.reach().unwrap_or(0), rows, cols);
+
+        // calculate all new values first, as each calculation reads from the untransformed grid
+        let res = self.with_environment(|environment| {
+            region
+                .rows
+                .clone()
+                .flat_map(|row| region.cols.clone().map(move |col| (row, col)))
+                .map(|(row, col)| self.next_cell(grid, row, col, environment))
+                .collect::<Vec<u8>>()
+        });
+
+        for (index, cell) in res.into_iter().enumerate() {
+            grid[region.rows.start + index / region.cols.len()]
+                [region.cols.start + index % region.cols.len()] = cell;
+        }
+    }
+}
+
+/// An auxiliary environment rule works like an [EnvironmentRule], but its ```cell_transform``` function additionally receives the environment of the cell in the auxiliary layer of the automaton.
+///
+/// The auxiliary layer holds static information for each cell, such as terrain or materials, that the rule reads but never changes, see [AutomatonBuilder::with_aux_layer](crate::AutomatonBuilder::with_aux_layer).
+/// Parts of both environments outside the grid are filled according to the same boundaries.
+/// Applied to an automaton without an auxiliary layer, or through [Rule::transform](super::Rule::transform), the auxiliary layer reads as all zeros.
+/// ```
+/// # use cellumina::rule::{AuxEnvironmentRule, Rule};
+/// // Cells turn on if their right neighbor is on, unless the auxiliary layer blocks them.
+/// let rule = AuxEnvironmentRule {
+///     environment_size: [0, 1, 0, 0],
+///     cell_transform: |env, aux| if aux[0][0] == 0 { env[0][1] } else { env[0][0] },
+///     ..Default::default()
+/// };
+/// let mut grid = grid::grid![[0, 0, 0, 1]];
+/// let mut buffer = grid.clone();
+/// let aux = grid::grid![[0, 1, 0, 0]];
+/// if rule.transform_with_aux(&mut grid, &mut buffer, &aux) {
+///     std::mem::swap(&mut grid, &mut buffer);
+/// }
+/// assert_eq!(grid, grid::grid![[0, 0, 1, 0]]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct AuxEnvironmentRule {
+    /// The distance the considered environments extend from the cell to be set, in order ```[top, right, bottom, left]```, see [EnvironmentRule::environment_size].
+    pub environment_size: [usize; 4],
+    /// Behaviour of this rule when encountering cases in which the environment of a cell contains rows that go out of bounds of the state grid.
+    pub row_boundary: super::BoundaryBehaviour,
+    /// Behaviour of this rule when encountering cases in which the environment of a cell contains columns that go out of bounds of the state grid.
+    pub col_boundary: super::BoundaryBehaviour,
+    /// The function that calculates the next state of a single cell based on its environment in the state and in the auxiliary layer, in this order.
+    ///
+    /// Both grids have the same size and center as described for [EnvironmentRule::cell_transform].
+    pub cell_transform: fn(&CellGrid, &CellGrid) -> u8,
+}
+
+impl Default for AuxEnvironmentRule {
+    fn default() -> Self {
+        Self {
+            environment_size: [1, 1, 1, 1],
+            row_boundary: Default::default(),
+            col_boundary: Default::default(),
+            cell_transform: |_, _| 0,
         }
+    }
+}
 
+impl std::fmt::Debug for AuxEnvironmentRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuxEnvironmentRule")
+            .field("environment_size", &self.environment_size)
+            .field("row_boundary", &self.row_boundary)
+            .field("col_boundary", &self.col_boundary)
+            .finish()
+    }
+}
+
+impl AuxEnvironmentRule {
+    /// Calculates the next state of the cell at the passed position, using ```buffers``` to hold its environments.
+    fn next_cell(
+        &self,
+        grid: &CellGrid,
+        aux: &CellGrid,
+        position: (usize, usize),
+        [environment, aux_environment]: &mut [CellGrid; 2],
+    ) -> u8 {
+        for (grid, buffer) in [(grid, &mut *environment), (aux, &mut *aux_environment)] {
+            fill_environment(
+                self.environment_size,
+                self.row_boundary,
+                self.col_boundary,
+                grid,
+                position,
+                buffer,
+            );
+        }
+        (self.cell_transform)(environment, aux_environment)
+    }
+}
+
+impl super::Rule for AuxEnvironmentRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
         *grid = res;
     }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(*self))
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let aux = CellGrid::new(grid.rows(), grid.cols());
+        self.transform_with_aux(grid, buffer, &aux)
+    }
+
+    fn reach(&self) -> Option<usize> {
+        self.environment_size.iter().copied().max()
+    }
+
+    fn transform_region(&self, grid: &mut CellGrid, dirty: &super::Region) {
+        let aux = CellGrid::new(grid.rows(), grid.cols());
+        self.transform_region_with_aux(grid, dirty, &aux);
+    }
+
+    fn transform_with_aux(
+        &self,
+        grid: &mut CellGrid,
+        buffer: &mut CellGrid,
+        aux: &CellGrid,
+    ) -> bool {
+        let (rows, cols) = grid.size();
+        if buffer.size() != (rows, cols) {
+            *buffer = CellGrid::new(rows, cols);
+        }
+
+        with_environments(environment_shape(self.environment_size), |environments| {
+            for row in 0..rows {
+                for col in 0..cols {
+                    buffer[row][col] = self.next_cell(grid, aux, (row, col), environments);
+                }
+            }
+        });
+
+        true
+    }
+
+    fn transform_region_with_aux(
+        &self,
+        grid: &mut CellGrid,
+        dirty: &super::Region,
+        aux: &CellGrid,
+    ) {
+        let (rows, cols) = grid.size();
+        // only cells with a dirty cell in their environment can change, as the auxiliary layer never does
+        let region = dirty.expand(self.reach().unwrap_or(0), rows, cols);
+
+        let res = with_environments(environment_shape(self.environment_size), |environments| {
+            region
+                .rows
+                .clone()
+                .flat_map(|row| region.cols.clone().map(move |col| (row, col)))
+                .map(|position| self.next_cell(grid, aux, position, environments))
+                .collect::<Vec<u8>>()
+        });
+
+        for (index, cell) in res.into_iter().enumerate() {
+            grid[region.rows.start + index / region.cols.len()]
+                [region.cols.start + index % region.cols.len()] = cell;
+        }
+    }
 }
 
 #[test]
@@ -155,6 +493,7 @@ fn environment_test() {
          // 0, 1 or more than 3 neighbors: The cell dies.
          _ => 0,
      },
+        ..Default::default()
     };
     let mut grid =
         grid::grid![[0, 0, 1, 0, 0][0, 0, 1,0, 0][0, 0, 0, 0, 0][0, 0, 1, 0, 0][0, 0, 1, 0, 0]];
@@ -171,3 +510,249 @@ fn environment_test() {
         grid::grid![[0, 1, 0, 1, 0][0, 0, 1,0, 0][0, 0, 0, 0, 0][0, 0, 1, 0, 0][0, 1, 0, 1, 0]]
     );
 }
+
+#[test]
+fn boundary_test() {
+    use super::{BoundaryBehaviour, Rule};
+
+    // Hashes the entire environment, so every cell of it influences the result.
+    fn hash(env: &CellGrid) -> u8 {
+        env.iter().enumerate().fold(0u8, |acc, (i, &c)| {
+            acc.wrapping_mul(31).wrapping_add(c ^ i as u8)
+        })
+    }
+
+    // Calculates the same environment by repeatedly moving out-of-bounds indices back into the grid.
+    fn reference(
+        grid: &CellGrid,
+        row: usize,
+        col: usize,
+        size: [usize; 4],
+        row_boundary: BoundaryBehaviour,
+        col_boundary: BoundaryBehaviour,
+    ) -> u8 {
+        let (rows, cols) = (grid.rows() as i64, grid.cols() as i64);
+        let mut env = CellGrid::new(size[0] + size[2] + 1, size[1] + size[3] + 1);
+        for env_row in 0..env.rows() {
+            for env_col in 0..env.cols() {
+                let mut t_row = row as i64 + env_row as i64 - size[0] as i64;
+                let mut t_col = col as i64 + env_col as i64 - size[3] as i64;
+                let row_out = !(0..rows).contains(&t_row);
+                let col_out = !(0..cols).contains(&t_col);
+                while t_row < 0 {
+                    t_row += rows;
+                }
+                while t_row >= rows {
+                    t_row -= rows;
+                }
+                while t_col < 0 {
+                    t_col += cols;
+                }
+                while t_col >= cols {
+                    t_col -= cols;
+                }
+                env[env_row][env_col] = match (row_out, row_boundary, col_out, col_boundary) {
+                    (true, BoundaryBehaviour::Symbol(symbol), _, _) => symbol,
+                    (_, _, true, BoundaryBehaviour::Symbol(symbol)) => symbol,
+                    _ => grid[t_row as usize][t_col as usize],
+                };
+            }
+        }
+        hash(&env)
+    }
+
+    let boundaries = [
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Symbol(0),
+        BoundaryBehaviour::Symbol(3),
+    ];
+    let grid = grid::grid![[1, 2, 4, 5][6, 7, 8, 9][10, 11, 12, 13]];
+
+    for environment_size in [
+        [1, 1, 1, 1],
+        [0, 0, 0, 0],
+        [5, 1, 0, 7],
+        [0, 6, 4, 2],
+        [3, 4, 3, 4],
+    ] {
+        for row_boundary in boundaries {
+            for col_boundary in boundaries {
+                let rule = EnvironmentRule {
+                    environment_size,
+                    row_boundary,
+                    col_boundary,
+                    cell_transform: hash,
+                    ..Default::default()
+                };
+                let mut res = grid.clone();
+                rule.transform(&mut res);
+                for row in 0..grid.rows() {
+                    for col in 0..grid.cols() {
+                        assert_eq!(
+                            res[row][col],
+                            reference(&grid, row, col, environment_size, row_boundary, col_boundary),
+                            "cell ({row}, {col}), environment {environment_size:?}, boundaries {row_boundary}, {col_boundary}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn large_environment_test() {
+    use super::{BoundaryBehaviour, Rule};
+
+    // An environment of six rows on a grid of three rows, summing up its column.
+    let rule = EnvironmentRule {
+        environment_size: [3, 0, 2, 0],
+        row_boundary: BoundaryBehaviour::Periodic,
+        col_boundary: BoundaryBehaviour::Periodic,
+        cell_transform: |env| env.iter().sum(),
+        ..Default::default()
+    };
+    let grid = grid::grid![[1, 2][3, 4][5, 6]];
+
+    // Periodic boundaries wrap around twice, so every row is counted twice.
+    let mut res = grid.clone();
+    rule.transform(&mut res);
+    assert_eq!(res, grid::grid![[18, 24][18, 24][18, 24]]);
+
+    // Symbol boundaries count every row once and the remaining three cells with the symbol.
+    for (symbol, expected) in [
+        (0, grid::grid![[9, 12][9, 12][9, 12]]),
+        (1, grid::grid![[12, 15][12, 15][12, 15]]),
+    ] {
+        let rule = EnvironmentRule {
+            row_boundary: BoundaryBehaviour::Symbol(symbol),
+            ..rule
+        };
+        let mut res = grid.clone();
+        rule.transform(&mut res);
+        assert_eq!(res, expected);
+    }
+}
+
+#[test]
+fn aux_environment_test() {
+    use super::{BoundaryBehaviour, Rule};
+
+    // every cell becomes the sum of its environment in both layers
+    let rule = AuxEnvironmentRule {
+        environment_size: [1, 1, 1, 1],
+        row_boundary: BoundaryBehaviour::Symbol(1),
+        col_boundary: BoundaryBehaviour::Periodic,
+        cell_transform: |env, aux| env.iter().chain(aux.iter()).sum(),
+    };
+    let grid = grid::grid![[1, 2, 3][4, 5, 6]];
+    let aux = grid::grid![[0, 10, 0][0, 0, 20]];
+
+    let mut res = grid.clone();
+    let mut buffer = CellGrid::new(0, 0);
+    assert!(rule.transform_with_aux(&mut res, &mut buffer, &aux));
+    // each environment contains the entire grid and three boundary cells, in both layers
+    assert_eq!(buffer, grid::grid![[57, 57, 57][57, 57, 57]]);
+    // the grid was left as it is
+    assert_eq!(res, grid);
+
+    // without an auxiliary layer, it reads as zeros, but still has boundaries
+    rule.transform(&mut res);
+    assert_eq!(res, grid::grid![[27, 27, 27][27, 27, 27]]);
+
+    // regions agree with transforming everything
+    let mut res = grid.clone();
+    rule.transform_region_with_aux(&mut res, &super::Region::full(2, 3), &aux);
+    assert_eq!(res, buffer);
+}
+
+#[test]
+fn hex_neighbors_test() {
+    use super::BoundaryBehaviour;
+
+    let grid = CellGrid::from_vec((1..=20).collect(), 5);
+    let mut buffer = CellGrid::new(1, 7);
+
+    // a cell in an even and in an odd row
+    fill_hex_environment(
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
+        &grid,
+        (2, 2),
+        &mut buffer,
+    );
+    assert_eq!(buffer, grid::grid![[13, 14, 8, 7, 12, 17, 18]]);
+    fill_hex_environment(
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
+        &grid,
+        (1, 2),
+        &mut buffer,
+    );
+    assert_eq!(buffer, grid::grid![[8, 9, 4, 3, 7, 13, 14]]);
+
+    // all cells match the neighbors in axial coordinates, where odd rows are shifted right by half a cell
+    let axial_directions = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+    for boundary in [BoundaryBehaviour::Periodic, BoundaryBehaviour::Symbol(99)] {
+        for row in 0..4 {
+            for col in 0..5 {
+                fill_hex_environment(boundary, boundary, &grid, (row, col), &mut buffer);
+                let (q, r) = (
+                    col as isize - (row as isize - (row as isize & 1)) / 2,
+                    row as isize,
+                );
+                let mut expected = vec![grid[row][col]];
+                for (dq, dr) in axial_directions {
+                    let (n_row, n_col) = (r + dr, q + dq + (r + dr - ((r + dr) & 1)) / 2);
+                    expected.push(match boundary {
+                        BoundaryBehaviour::Symbol(symbol)
+                            if !(0..4).contains(&n_row) || !(0..5).contains(&n_col) =>
+                        {
+                            symbol
+                        }
+                        _ => grid[n_row.rem_euclid(4) as usize][n_col.rem_euclid(5) as usize],
+                    });
+                }
+                assert_eq!(
+                    buffer.iter().copied().collect::<Vec<_>>(),
+                    expected,
+                    "cell ({row}, {col}), boundary {boundary}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn hex_majority_test() {
+    use super::{BoundaryBehaviour, Rule};
+
+    // cells take the state of the majority of themselves and their six neighbors
+    let rule = EnvironmentRule {
+        row_boundary: BoundaryBehaviour::Symbol(0),
+        col_boundary: BoundaryBehaviour::Symbol(0),
+        topology: GridTopology::Hex,
+        cell_transform: |env| u8::from(env.iter().filter(|&&cell| cell == 1).count() >= 4),
+        ..Default::default()
+    };
+    assert_eq!(rule.reach(), Some(1));
+    let mut grid = grid::grid![[0, 1, 1, 0][1, 1, 0, 0][0, 1, 0, 1][0, 0, 1, 1]];
+    rule.transform(&mut grid);
+    assert_eq!(
+        grid,
+        grid::grid![[0, 1, 0, 0][1, 1, 0, 0][0, 0, 1, 0][0, 0, 0, 0]]
+    );
+    rule.transform(&mut grid);
+    assert_eq!(
+        grid,
+        grid::grid![[0, 0, 0, 0][0, 1, 0, 0][0, 0, 0, 0][0, 0, 0, 0]]
+    );
+
+    // regions agree with transforming everything
+    let start = grid::grid![[0, 1, 1, 0][1, 1, 0, 0][0, 1, 0, 1][0, 0, 1, 1]];
+    let mut res = start.clone();
+    rule.transform_region(&mut res, &super::Region::full(4, 4));
+    let mut expected = start;
+    rule.transform(&mut expected);
+    assert_eq!(res, expected);
+}
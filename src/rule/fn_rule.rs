@@ -0,0 +1,200 @@
+use crate::{Cell, CellGrid, CellGridExt};
+
+/// The function of a [FnRule].
+type GridFunction<T> = std::sync::Arc<dyn Fn(&mut grid::Grid<T>) + Send + Sync>;
+
+/// A rule applying a function to the entire grid each step, for transformations that are easiest to express on the grid as a whole, such as moving or swapping regions.
+///
+/// The name of the rule is shown in its [Debug] output in place of the function.
+/// Clones of the rule share the same function.
+/// ```
+/// # use cellumina::rule::{FnRule, Rule};
+/// let rule = FnRule::new("clear first row", |grid: &mut cellumina::CellGrid| {
+///     grid.iter_row_mut(0).for_each(|cell| *cell = 0)
+/// });
+/// let mut grid = grid::grid![[1, 1][1, 1]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 0][1, 1]]);
+/// assert_eq!(format!("{rule:?}"), "FnRule { name: \"clear first row\" }");
+/// ```
+#[derive(Clone)]
+pub struct FnRule<T: Cell = u8> {
+    /// The name of the rule.
+    name: String,
+    /// The function transforming the grid.
+    function: GridFunction<T>,
+}
+
+impl<T: Cell> FnRule<T> {
+    /// Creates a rule with the passed name that transforms the grid using the passed function.
+    pub fn new(name: &str, function: impl Fn(&mut grid::Grid<T>) + Send + Sync + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            function: std::sync::Arc::new(function),
+        }
+    }
+
+    /// Returns the name of this rule.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: Cell> std::fmt::Debug for FnRule<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnRule").field("name", &self.name).finish()
+    }
+}
+
+impl<T: Cell> super::Rule<T> for FnRule<T> {
+    fn transform(&self, grid: &mut grid::Grid<T>) {
+        (self.function)(grid);
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule<T>>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// A rule moving the entire grid by ```dy``` rows and ```dx``` columns each step, with positive values moving the cells down and to the right.
+///
+/// Without a ```fill``` symbol, cells moved out of the grid on one side reappear on the opposite side.
+/// Otherwise, they are dropped and the cells left empty on the other side are set to the symbol.
+/// ```
+/// # use cellumina::rule::{Rule, ShiftRule};
+/// let mut grid = grid::grid![[1, 2, 3][4, 5, 6]];
+/// ShiftRule { dy: 0, dx: 1, fill: None }.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[3, 1, 2][6, 4, 5]]);
+/// ShiftRule { dy: -1, dx: 0, fill: Some(0) }.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[6, 4, 5][0, 0, 0]]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ShiftRule {
+    /// The number of rows to move the grid down by, moving it up if negative.
+    pub dy: isize,
+    /// The number of columns to move the grid right by, moving it left if negative.
+    pub dx: isize,
+    /// The symbol to fill cells left empty with, or [None] to wrap cells around the grid edges.
+    pub fill: Option<u8>,
+}
+
+impl super::Rule for ShiftRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(*self))
+    }
+
+    fn inverse(&self) -> Option<Box<dyn super::Rule>> {
+        // cells moved out of the grid are lost when filling
+        match self.fill {
+            None => Some(Box::new(ShiftRule {
+                dy: -self.dy,
+                dx: -self.dx,
+                fill: None,
+            })),
+            Some(_) => None,
+        }
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let (rows, cols) = grid.size();
+        if buffer.size() != (rows, cols) {
+            *buffer = CellGrid::new(rows, cols);
+        }
+
+        // returns the index a cell moved to the passed index came from, if it came from within the grid
+        let source = |index: usize, shift: isize, len: usize| {
+            let index = index as isize - shift;
+            match self.fill {
+                None => Some(index.rem_euclid(len as isize) as usize),
+                Some(_) => usize::try_from(index).ok().filter(|&index| index < len),
+            }
+        };
+
+        buffer.fill_from_fn(|row, col| {
+            match (source(row, self.dy, rows), source(col, self.dx, cols)) {
+                (Some(row), Some(col)) => grid[row][col],
+                _ => self.fill.unwrap_or_default(),
+            }
+        });
+        true
+    }
+}
+
+#[test]
+fn shift_test() {
+    use super::Rule;
+
+    let grid = grid::grid![[1, 2, 3, 4][5, 6, 7, 8][9, 10, 11, 12]];
+
+    // wrapping around the edges, in both directions and by more than the grid size
+    for (dy, dx, expected) in [
+        (0, 0, grid.clone()),
+        (1, 0, grid::grid![[9, 10, 11, 12][1, 2, 3, 4][5, 6, 7, 8]]),
+        (0, -1, grid::grid![[2, 3, 4, 1][6, 7, 8, 5][10, 11, 12, 9]]),
+        (-4, 6, grid::grid![[7, 8, 5, 6][11, 12, 9, 10][3, 4, 1, 2]]),
+    ] {
+        let mut res = grid.clone();
+        ShiftRule { dy, dx, fill: None }.transform(&mut res);
+        assert_eq!(res, expected, "shift by ({dy}, {dx})");
+    }
+
+    // filling vacated cells
+    for (dy, dx, expected) in [
+        (1, 1, grid::grid![[0, 0, 0, 0][0, 1, 2, 3][0, 5, 6, 7]]),
+        (-2, -3, grid::grid![[12, 0, 0, 0][0, 0, 0, 0][0, 0, 0, 0]]),
+        (3, 0, CellGrid::new(3, 4)),
+    ] {
+        let mut res = grid.clone();
+        ShiftRule {
+            dy,
+            dx,
+            fill: Some(0),
+        }
+        .transform(&mut res);
+        assert_eq!(res, expected, "shift by ({dy}, {dx})");
+    }
+
+    // repeated shifts with wrapping return to the start
+    let mut res = grid.clone();
+    let rule = ShiftRule {
+        dy: 1,
+        dx: 0,
+        fill: None,
+    };
+    for _ in 0..3 {
+        rule.transform(&mut res);
+    }
+    assert_eq!(res, grid);
+
+    // serializable
+    let rule = ShiftRule {
+        dy: -1,
+        dx: 2,
+        fill: Some(59),
+    };
+    let text = toml::to_string(&rule).unwrap();
+    assert_eq!(toml::from_str::<ShiftRule>(&text).unwrap(), rule);
+
+    // only wrapping shifts can be undone
+    assert!(rule.inverse().is_none());
+    let rule = ShiftRule {
+        dy: 2,
+        dx: -1,
+        fill: None,
+    };
+    let mut res = grid.clone();
+    rule.transform(&mut res);
+    assert_ne!(res, grid);
+    rule.inverse().unwrap().transform(&mut res);
+    assert_eq!(res, grid);
+}
@@ -0,0 +1,73 @@
+// Applies a life-like rule (birth and survival by neighbor count) to every cell.
+
+struct Params {
+    width: u32,
+    height: u32,
+    // bit n is set if a dead cell with n living neighbors is born
+    birth: u32,
+    // bit n is set if a living cell with n living neighbors survives
+    survival: u32,
+    // 1 if rows wrap around, 2 if they are clamped to the grid, 0 if out-of-bounds rows contain row_symbol
+    row_periodic: u32,
+    row_symbol: u32,
+    // 1 if columns wrap around, 2 if they are clamped to the grid, 0 if out-of-bounds columns contain col_symbol
+    col_periodic: u32,
+    col_symbol: u32,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var<storage, read> cells_in: array<u32>;
+@group(0) @binding(2)
+var<storage, read_write> cells_out: array<u32>;
+
+// Returns 1 if the cell at the passed position is alive, 0 otherwise.
+fn alive(row: i32, col: i32) -> u32 {
+    let height = i32(params.height);
+    let width = i32(params.width);
+    var r = row;
+    var c = col;
+    if (r < 0 || r >= height) {
+        if (params.row_periodic == 0u) {
+            return select(0u, 1u, params.row_symbol != 0u);
+        }
+        if (params.row_periodic == 2u) {
+            r = clamp(r, 0, height - 1);
+        } else {
+            r = (r + height) % height;
+        }
+    }
+    if (c < 0 || c >= width) {
+        if (params.col_periodic == 0u) {
+            return select(0u, 1u, params.col_symbol != 0u);
+        }
+        if (params.col_periodic == 2u) {
+            c = clamp(c, 0, width - 1);
+        } else {
+            c = (c + width) % width;
+        }
+    }
+    return select(0u, 1u, cells_in[u32(r) * params.width + u32(c)] != 0u);
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let row = i32(id.y);
+    let col = i32(id.x);
+
+    var neighbors = 0u;
+    for (var dr = -1; dr <= 1; dr = dr + 1) {
+        for (var dc = -1; dc <= 1; dc = dc + 1) {
+            if (dr != 0 || dc != 0) {
+                neighbors = neighbors + alive(row + dr, col + dc);
+            }
+        }
+    }
+
+    let mask = select(params.birth, params.survival, alive(row, col) == 1u);
+    cells_out[id.y * params.width + id.x] = (mask >> neighbors) & 1u;
+}
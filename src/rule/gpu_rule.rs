@@ -0,0 +1,414 @@
+use std::sync::{Arc, Mutex};
+
+use wgpu::util::DeviceExt;
+
+use super::BoundaryBehaviour;
+use crate::{CellGrid, GpuContext};
+
+/// The edge length of the square workgroups of the compute shader.
+const WORKGROUP_SIZE: u32 = 8;
+
+/// An environment rule that runs as a compute pass on the GPU.
+///
+/// Currently, only life-like rules are supported: Every cell is either dead (```0```) or alive (any other character), and the next state of a cell depends on the number of living cells in its Moore neighborhood.
+/// A dead cell becomes alive (```1```) if its number of living neighbors is contained in ```birth```, a living cell stays alive if its number of living neighbors is contained in ```survival```.
+/// All other cells die.
+///
+/// The state is kept on the GPU in a ping-pong pair of storage buffers.
+/// When used by an automaton, it is only uploaded when the cells were changed on the CPU, and only read back when the CPU accesses them, for example to draw, edit or save the state,
+/// see [Rule::transform_resident](super::Rule::transform_resident).
+/// Called directly, [transform](super::Rule::transform) and [transform_steps](GpuEnvironmentRule::transform_steps) upload the grid once and read it back once afterwards.
+pub struct GpuEnvironmentRule {
+    /// The context holding the device this rule runs on.
+    context: GpuContext,
+    /// Bit n is set if a dead cell with n living neighbors is born.
+    birth: u32,
+    /// Bit n is set if a living cell with n living neighbors survives.
+    survival: u32,
+    /// Behaviour of this rule when the neighborhood of a cell leaves the state grid vertically.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count as alive if the symbol is not ```0```.
+    pub row_boundary: BoundaryBehaviour,
+    /// Behaviour of this rule when the neighborhood of a cell leaves the state grid horizontally.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count as alive if the symbol is not ```0```.
+    pub col_boundary: BoundaryBehaviour,
+    /// The compute pipeline applying the rule.
+    pipeline: wgpu::ComputePipeline,
+    /// The buffers of the last grid size used, recreated when the grid size changes, and the index of the cell buffer holding the last result.
+    last: Mutex<Option<GpuState>>,
+}
+
+/// The result of a [GpuEnvironmentRule] kept in one of its cell buffers on the GPU.
+#[derive(Clone)]
+struct GpuState {
+    /// The context the buffers were created in.
+    context: GpuContext,
+    /// The buffers of the rule.
+    buffers: Arc<GpuBuffers>,
+    /// The index of the cell buffer holding the state.
+    index: usize,
+}
+
+/// The buffers and bind groups needed to transform a grid of a certain size.
+struct GpuBuffers {
+    /// The size of the grid these buffers fit, in rows and columns.
+    size: (usize, usize),
+    /// The uniform buffer containing the rule parameters.
+    params: wgpu::Buffer,
+    /// The ping-pong pair of cell buffers.
+    cells: [wgpu::Buffer; 2],
+    /// The buffer the result is copied to for reading it back.
+    staging: wgpu::Buffer,
+    /// Bind groups reading from the first and writing to the second cell buffer, and the other way around.
+    bind_groups: [wgpu::BindGroup; 2],
+}
+
+/// The rule parameters passed to the compute shader.
+/// Mirrors the ```Params``` struct in ```gpu_life.wgsl```.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    width: u32,
+    height: u32,
+    birth: u32,
+    survival: u32,
+    row_periodic: u32,
+    row_symbol: u32,
+    col_periodic: u32,
+    col_symbol: u32,
+}
+
+impl GpuEnvironmentRule {
+    /// Creates a new life-like rule in the passed context.
+    /// A dead cell is born if its number of living neighbors is contained in ```birth```, and a living cell survives if its number of living neighbors is contained in ```survival```.
+    /// Both boundaries are periodic by default.
+    pub fn life_like(context: &GpuContext, birth: &[u8], survival: &[u8]) -> Self {
+        let shader = context
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Life-Like Rule Shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("gpu_life.wgsl").into()),
+            });
+
+        let pipeline = context
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Life-Like Rule Pipeline"),
+                layout: None,
+                module: &shader,
+                entry_point: "main",
+            });
+
+        Self {
+            context: context.clone(),
+            birth: neighbor_mask(birth),
+            survival: neighbor_mask(survival),
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
+            pipeline,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Creates a new rule running Conway's Game of Life (B3/S23) in the passed context.
+    pub fn game_of_life(context: &GpuContext) -> Self {
+        Self::life_like(context, &[3], &[2, 3])
+    }
+
+    /// Performs the passed number of steps on the GPU, reading the grid back only after the last one.
+    pub fn transform_steps(&self, grid: &mut CellGrid, steps: u32) {
+        if steps == 0 || grid.is_empty() {
+            return;
+        }
+        self.run_steps(Some(grid), steps).read_into(grid);
+    }
+
+    /// Performs the passed number of steps on the GPU, starting from the passed non-empty grid or, if [None], from the result of the last call, and returns where the result is kept.
+    fn run_steps(&self, grid: Option<&CellGrid>, steps: u32) -> GpuState {
+        let device = &self.context.device;
+        let queue = &self.context.queue;
+
+        let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+        let (buffers, index) = match (grid, last.as_ref()) {
+            (None, Some(last)) => (last.buffers.clone(), last.index),
+            (None, None) => panic!("The state is only kept on the GPU after a step."),
+            (Some(grid), last) => {
+                let (rows, cols) = grid.size();
+                let buffers = match last {
+                    Some(last) if last.buffers.size == (rows, cols) => last.buffers.clone(),
+                    _ => Arc::new(self.create_buffers(rows, cols)),
+                };
+                let cells = grid.iter().map(|&cell| cell as u32).collect::<Vec<u32>>();
+                queue.write_buffer(&buffers.cells[0], 0, bytemuck::cast_slice(&cells));
+                (buffers, 0)
+            }
+        };
+        let (rows, cols) = buffers.size;
+
+        // the boundaries may have changed since the last step
+        let (row_periodic, row_symbol) = boundary_params(self.row_boundary);
+        let (col_periodic, col_symbol) = boundary_params(self.col_boundary);
+        queue.write_buffer(
+            &buffers.params,
+            0,
+            bytemuck::bytes_of(&Params {
+                width: cols as u32,
+                height: rows as u32,
+                birth: self.birth,
+                survival: self.survival,
+                row_periodic,
+                row_symbol,
+                col_periodic,
+                col_symbol,
+            }),
+        );
+
+        // run all steps, alternating between the buffers
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Life-Like Rule Encoder"),
+        });
+        for step in 0..steps as usize {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Life-Like Rule Pass"),
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &buffers.bind_groups[(index + step) % 2], &[]);
+            pass.dispatch_workgroups(
+                (cols as u32).div_ceil(WORKGROUP_SIZE),
+                (rows as u32).div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        queue.submit(Some(encoder.finish()));
+
+        // the result is in the buffer the last step wrote to
+        let state = GpuState {
+            context: self.context.clone(),
+            buffers,
+            index: (index + steps as usize) % 2,
+        };
+        *last = Some(state.clone());
+        state
+    }
+
+    /// Creates the buffers and bind groups for a grid of the passed size.
+    fn create_buffers(&self, rows: usize, cols: usize) -> GpuBuffers {
+        let device = &self.context.device;
+        let cell_bytes = (rows * cols * std::mem::size_of::<u32>()) as u64;
+
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Life-Like Rule Params"),
+            contents: bytemuck::bytes_of(&<Params as bytemuck::Zeroable>::zeroed()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let cells = [0, 1].map(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Life-Like Rule Cells"),
+                size: cell_bytes,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            })
+        });
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Life-Like Rule Staging"),
+            size: cell_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let layout = self.pipeline.get_bind_group_layout(0);
+        let bind_groups = [(0, 1), (1, 0)].map(|(from, to)| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Life-Like Rule Bind Group"),
+                layout: &layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: cells[from].as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: cells[to].as_entire_binding(),
+                    },
+                ],
+            })
+        });
+
+        GpuBuffers {
+            size: (rows, cols),
+            params,
+            cells,
+            staging,
+            bind_groups,
+        }
+    }
+}
+
+impl GpuState {
+    /// Copies the state into the passed grid, which must have the dimensions of the buffers.
+    fn read_into(&self, grid: &mut CellGrid) {
+        let device = &self.context.device;
+        let buffers = &self.buffers;
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Life-Like Rule Read Back Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(
+            &buffers.cells[self.index],
+            0,
+            &buffers.staging,
+            0,
+            buffers.staging.size(),
+        );
+        self.context.queue.submit(Some(encoder.finish()));
+
+        let slice = buffers.staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        {
+            let data = slice.get_mapped_range();
+            for (cell, &value) in grid.iter_mut().zip(bytemuck::cast_slice::<u8, u32>(&data)) {
+                *cell = value as u8;
+            }
+        }
+        buffers.staging.unmap();
+    }
+}
+
+impl super::ResidentState for GpuState {
+    fn size(&self) -> (usize, usize) {
+        self.buffers.size
+    }
+
+    fn read_back(&self) -> CellGrid {
+        let (rows, cols) = self.buffers.size;
+        let mut grid = CellGrid::new(rows, cols);
+        self.read_into(&mut grid);
+        grid
+    }
+}
+
+impl super::Rule for GpuEnvironmentRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        self.transform_steps(grid, 1);
+    }
+
+    fn transform_resident(&self, grid: Option<&CellGrid>) -> Option<Arc<dyn super::ResidentState>> {
+        if grid.is_some_and(|grid| grid.is_empty()) {
+            return None;
+        }
+        Some(Arc::new(self.run_steps(grid, 1)))
+    }
+
+    fn gpu_context(&self) -> Option<&GpuContext> {
+        Some(&self.context)
+    }
+}
+
+impl std::fmt::Debug for GpuEnvironmentRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GpuEnvironmentRule")
+            .field("birth", &format!("{:09b}", self.birth))
+            .field("survival", &format!("{:09b}", self.survival))
+            .field("row_boundary", &self.row_boundary)
+            .field("col_boundary", &self.col_boundary)
+            .finish()
+    }
+}
+
+/// Converts a list of neighbor counts to a bit mask, ignoring counts above 8.
+fn neighbor_mask(counts: &[u8]) -> u32 {
+    counts
+        .iter()
+        .filter(|&&count| count <= 8)
+        .fold(0, |mask, &count| mask | 1 << count)
+}
+
+/// Converts a boundary behaviour to the pair ```(periodic, symbol)``` passed to the shader.
+fn boundary_params(boundary: BoundaryBehaviour) -> (u32, u32) {
+    match boundary {
+        BoundaryBehaviour::Periodic => (1, 0),
+        BoundaryBehaviour::Symbol(symbol) | BoundaryBehaviour::Sink(symbol) => (0, symbol as u32),
+        BoundaryBehaviour::Clamp => (2, 0),
+    }
+}
+
+#[test]
+fn gpu_environment_rule_test() {
+    use super::Rule;
+    use rand::Rng;
+
+    assert_eq!(neighbor_mask(&[3]), 0b1000);
+    assert_eq!(neighbor_mask(&[2, 3, 9]), 0b1100);
+
+    // skip on machines without a usable GPU
+    let context = match GpuContext::new() {
+        Ok(context) => context,
+        Err(err) => {
+            println!("Skipping GPU test: {}", err);
+            return;
+        }
+    };
+
+    let cpu_rule = super::EnvironmentRule {
+        environment_size: [1, 1, 1, 1],
+        row_boundary: BoundaryBehaviour::Periodic,
+        col_boundary: BoundaryBehaviour::Symbol(0),
+        cell_transform: |env| match env.iter().filter(|&&cell| cell != 0).count()
+            - (env[1][1] != 0) as usize
+        {
+            2 => env[1][1],
+            3 => 1,
+            _ => 0,
+        },
+        ..Default::default()
+    };
+    let mut gpu_rule = GpuEnvironmentRule::game_of_life(&context);
+    gpu_rule.col_boundary = BoundaryBehaviour::Symbol(0);
+
+    let mut rng = rand::thread_rng();
+    let mut cpu_grid = CellGrid::from_vec((0..32 * 29).map(|_| rng.gen_range(0..2)).collect(), 29);
+    let mut gpu_grid = cpu_grid.clone();
+    let mut gpu_multi_grid = cpu_grid.clone();
+
+    for _ in 0..10 {
+        cpu_rule.transform(&mut cpu_grid);
+        gpu_rule.transform(&mut gpu_grid);
+        assert_eq!(cpu_grid, gpu_grid);
+    }
+
+    gpu_rule.transform_steps(&mut gpu_multi_grid, 10);
+    assert_eq!(cpu_grid, gpu_multi_grid);
+
+    // automata keep the state on the GPU between steps, reading it back when accessed
+    let mut cpu_auto = crate::AutomatonBuilder::new()
+        .from_grid(cpu_grid.clone())
+        .with_rule(cpu_rule)
+        .build();
+    let mut gpu_auto = crate::AutomatonBuilder::new()
+        .from_grid(cpu_grid)
+        .with_rule(gpu_rule)
+        .build();
+    for step in 0..10 {
+        cpu_auto.next_step();
+        gpu_auto.next_step();
+        assert!(gpu_auto.state.is_resident());
+        if step % 3 == 0 {
+            assert_eq!(cpu_auto.state(), gpu_auto.state());
+        }
+        if step == 5 {
+            // edits are uploaded before the next step
+            cpu_auto.set_cell(3, 4, 1).unwrap();
+            gpu_auto.set_cell(3, 4, 1).unwrap();
+            assert!(!gpu_auto.state.is_resident());
+        }
+    }
+    assert_eq!(cpu_auto.state(), gpu_auto.state());
+}
@@ -0,0 +1,187 @@
+//! Functions for common computations on the environments an [EnvironmentRule](super::EnvironmentRule) passes to its ```cell_transform``` function.
+//!
+//! The center of an environment is its middle cell, so these functions assume an environment that extends equally far in opposing directions, such as ```[1, 1, 1, 1]```.
+use crate::CellGrid;
+
+/// Returns the position (row, column) of the center of the passed environment.
+///
+/// ## Panics
+/// If the environment has less than ```min``` rows or columns, naming the ```helper``` that required them.
+fn center_index(env: &CellGrid, min: usize, helper: &str) -> (usize, usize) {
+    assert!(
+        env.rows() >= min && env.cols() >= min,
+        "helpers::{helper} requires an environment of at least {min}x{min} cells, got {}x{}. Check the environment_size of your rule.",
+        env.rows(),
+        env.cols()
+    );
+    (env.rows() / 2, env.cols() / 2)
+}
+
+/// Returns the center cell of the environment, i.e. the cell whose next state is being calculated.
+///
+/// ```
+/// # use cellumina::rule::helpers::center;
+/// let env = grid::grid![[1, 0, 1][0, 59, 0][1, 0, 1]];
+/// assert_eq!(center(&env), 59);
+/// ```
+/// ## Panics
+/// If the environment is empty.
+pub fn center(env: &CellGrid) -> u8 {
+    let (row, col) = center_index(env, 1, "center");
+    env[row][col]
+}
+
+/// Counts the cells of the environment, including the center, that contain the passed symbol.
+///
+/// ```
+/// # use cellumina::rule::helpers::count;
+/// let env = grid::grid![[1, 0, 1][0, 1, 0][59, 0, 1]];
+/// assert_eq!(count(&env, 1), 4);
+/// assert_eq!(count(&env, 0), 4);
+/// ```
+pub fn count(env: &CellGrid, symbol: u8) -> usize {
+    env.iter().filter(|&&cell| cell == symbol).count()
+}
+
+/// Counts the cells of the environment, excluding the center, that contain the passed symbol.
+///
+/// ```
+/// # use cellumina::rule::helpers::count_excluding_center;
+/// let env = grid::grid![[1, 0, 1][0, 1, 0][59, 0, 1]];
+/// assert_eq!(count_excluding_center(&env, 1), 3);
+/// assert_eq!(count_excluding_center(&env, 59), 1);
+/// ```
+/// ## Panics
+/// If the environment is empty.
+pub fn count_excluding_center(env: &CellGrid, symbol: u8) -> usize {
+    let (row, col) = center_index(env, 1, "count_excluding_center");
+    count(env, symbol) - usize::from(env[row][col] == symbol)
+}
+
+/// Counts the cells of the environment, excluding the center, that contain the passed symbol.
+/// This is the same as [count_excluding_center].
+///
+/// ```
+/// # use cellumina::rule::helpers::count_neighbors;
+/// let env = grid::grid![[1, 0, 1][0, 1, 0][59, 0, 1]];
+/// assert_eq!(count_neighbors(&env, 1), 3);
+/// assert_eq!(count_neighbors(&env, 59), 1);
+/// ```
+/// ## Panics
+/// If the environment is empty.
+pub fn count_neighbors(env: &CellGrid, symbol: u8) -> usize {
+    count_excluding_center(env, symbol)
+}
+
+/// Returns wether any cell of the environment, including the center, fulfills the passed predicate.
+///
+/// ```
+/// # use cellumina::rule::helpers::any;
+/// let env = grid::grid![[1, 0, 1][0, 1, 0][59, 0, 1]];
+/// assert!(any(&env, |cell| cell == 59));
+/// assert!(!any(&env, |cell| cell > 59));
+/// ```
+pub fn any(env: &CellGrid, pred: impl Fn(u8) -> bool) -> bool {
+    env.iter().any(|&cell| pred(cell))
+}
+
+/// Returns the four cells orthogonally adjacent to the center, in order ```[top, right, bottom, left]``` like the ```environment_size``` of a rule.
+///
+/// ```
+/// # use cellumina::rule::helpers::orthogonal;
+/// let env = grid::grid![[0, 1, 0][4, 9, 2][0, 3, 0]];
+/// assert_eq!(orthogonal(&env), [1, 2, 3, 4]);
+/// ```
+/// ## Panics
+/// If the environment has less than 3 rows or columns.
+pub fn orthogonal(env: &CellGrid) -> [u8; 4] {
+    let (row, col) = center_index(env, 3, "orthogonal");
+    [
+        env[row - 1][col],
+        env[row][col + 1],
+        env[row + 1][col],
+        env[row][col - 1],
+    ]
+}
+
+/// Returns the four cells diagonally adjacent to the center, clockwise from the top left, in order ```[top left, top right, bottom right, bottom left]```.
+///
+/// ```
+/// # use cellumina::rule::helpers::diagonal;
+/// let env = grid::grid![[1, 0, 2][0, 9, 0][4, 0, 3]];
+/// assert_eq!(diagonal(&env), [1, 2, 3, 4]);
+/// ```
+/// ## Panics
+/// If the environment has less than 3 rows or columns.
+pub fn diagonal(env: &CellGrid) -> [u8; 4] {
+    let (row, col) = center_index(env, 3, "diagonal");
+    [
+        env[row - 1][col - 1],
+        env[row - 1][col + 1],
+        env[row + 1][col + 1],
+        env[row + 1][col - 1],
+    ]
+}
+
+/// Returns the sum of the values of all cells of the environment, excluding the center.
+/// The sum is calculated as an ```u32```, so unlike summing up ```u8```s directly, it can not overflow for environments with large values.
+///
+/// ```
+/// # use cellumina::rule::helpers::neighbor_sum;
+/// let env = grid::grid![[1, 0, 1][0, 1, 0][59, 0, 127]];
+/// assert_eq!(neighbor_sum(&env), 188);
+/// ```
+/// ## Panics
+/// If the environment is empty.
+pub fn neighbor_sum(env: &CellGrid) -> u32 {
+    env.iter().map(|&cell| cell as u32).sum::<u32>() - center(env) as u32
+}
+
+#[test]
+fn foreign_symbol_test() {
+    use super::Rule;
+
+    let life = super::EnvironmentRule {
+        environment_size: [1, 1, 1, 1],
+        row_boundary: super::BoundaryBehaviour::Symbol(0),
+        col_boundary: super::BoundaryBehaviour::Symbol(0),
+        cell_transform: |env| match count_neighbors(env, 1) {
+            2 => env[1][1],
+            3 => 1,
+            _ => 0,
+        },
+        ..Default::default()
+    };
+
+    // painted 'X's next to a blinker must not cause an overflow and are not counted as alive
+    let mut grid = grid::grid![[0, 59, 59, 0][0, 1, 59, 0][0, 1, 59, 0][0, 1, 0, 0]];
+    life.transform(&mut grid);
+    assert_eq!(
+        grid,
+        grid::grid![[0, 0, 0, 0][0, 0, 59, 0][1, 1, 1, 0][0, 0, 0, 0]]
+    );
+
+    // the sum of a neighborhood of large values does not fit an u8
+    let env = grid::grid![[127, 127, 127][127, 0, 127][127, 127, 127]];
+    assert_eq!(neighbor_sum(&env), 8 * 127);
+    assert_eq!(count_neighbors(&env, 127), 8);
+}
+
+#[test]
+fn small_environment_test() {
+    // larger environments use the cells around their center
+    let env =
+        grid::grid![[0, 0, 0, 0, 0][0, 5, 1, 6, 0][0, 4, 9, 2, 0][0, 8, 3, 7, 0][0, 0, 0, 0, 0]];
+    assert_eq!(center(&env), 9);
+    assert_eq!(orthogonal(&env), [1, 2, 3, 4]);
+    assert_eq!(diagonal(&env), [5, 6, 7, 8]);
+
+    // environments without neighbors in every direction are rejected
+    let message = std::panic::catch_unwind(|| orthogonal(&grid::grid![[1, 2, 3]]))
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(message.contains("helpers::orthogonal") && message.contains("1x3"));
+    assert!(std::panic::catch_unwind(|| diagonal(&grid::grid![[1][2][3]])).is_err());
+    assert!(std::panic::catch_unwind(|| center(&CellGrid::new(0, 0))).is_err());
+}
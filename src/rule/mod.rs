@@ -1,51 +1,898 @@
+mod convolution_rule;
+mod decay_rule;
 mod environment_rule;
+mod fn_rule;
+#[cfg(feature = "display")]
+mod gpu_rule;
+pub mod helpers;
 mod pattern_rule;
+pub mod presets;
+mod region_rule;
+#[cfg(feature = "scripting")]
+mod scripted_rule;
+mod source_rule;
+mod totalistic_rule;
 
 use std::fmt::Debug;
 use std::fmt::Display;
 
-use super::CellGrid;
+use super::Cell;
+pub use convolution_rule::ConvolutionRule;
+pub use decay_rule::DecayRule;
+pub use environment_rule::AuxEnvironmentRule;
 pub use environment_rule::EnvironmentRule;
+pub use environment_rule::GridTopology;
+pub use fn_rule::FnRule;
+pub use fn_rule::ShiftRule;
+#[cfg(feature = "display")]
+pub use gpu_rule::GpuEnvironmentRule;
+pub use pattern_rule::Explanation;
 pub use pattern_rule::Pattern;
+pub use pattern_rule::PatternBoundaryMode;
+pub use pattern_rule::PatternOrdering;
 pub use pattern_rule::PatternRule;
+pub use pattern_rule::PriorityMode;
+pub use pattern_rule::Verdict;
+pub use pattern_rule::WriteBoundary;
+pub use region_rule::RegionMask;
+pub use region_rule::RegionRules;
+#[cfg(feature = "scripting")]
+pub use scripted_rule::ScriptedEnvironmentRule;
+pub use source_rule::Edge;
+pub use source_rule::EdgeSourceRule;
+pub use totalistic_rule::LifeLikeRule;
+pub use totalistic_rule::TotalisticRule;
 
 /// A rule describes a transition from one state of a cellular automaton to the next.
-pub trait Rule: Debug {
+///
+/// Rules need to be [Send] and [Sync], as automata may apply them on a thread pool.
+///
+/// Rules work on grids of [u8] cells unless another [Cell] type is specified.
+/// The rules provided by this module only support [u8] cells, rules for automata of other cell types have to implement this trait themselves.
+///
+/// This trait is object safe and will stay so, as automata store their rule as a ```Box<dyn Rule<T>>```.
+/// Boxed rules implement the trait themselves, so rules chosen at runtime can be passed wherever a rule is expected,
+/// though [AutomatonBuilderGeneric::with_boxed_rule](crate::AutomatonBuilderGeneric::with_boxed_rule) avoids boxing them twice.
+/// Closures can not implement this trait directly, as rules need to implement [Debug], so they are wrapped in a named [FnRule] instead.
+/// ```
+/// # use cellumina::rule::{FnRule, LifeLikeRule, Rule};
+/// let rule: Box<dyn Rule> = match "clear" {
+///     "life" => Box::new(LifeLikeRule::parse("B3/S23").unwrap()),
+///     _ => Box::new(FnRule::new("clear", |grid: &mut cellumina::CellGrid| grid.fill(0))),
+/// };
+/// let mut grid = grid::grid![[1, 1]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 0]]);
+/// ```
+pub trait Rule<T: Cell = u8>: Debug + Send + Sync {
     /// Transforms the passed cell grid according to this transformation rule.
     /// Transformation happens in-place.
-    fn transform(&self, grid: &mut CellGrid);
+    fn transform(&self, grid: &mut grid::Grid<T>);
+
+    /// Transforms the passed cell grid, possibly writing the result to ```buffer``` instead of transforming in-place.
+    /// Returns wether the result was written to ```buffer```, in which case the caller is expected to swap both grids.
+    /// The buffer may be of any size and contain anything, rules writing to it have to resize it as needed.
+    ///
+    /// Rules that can not transform in-place, such as [EnvironmentRule], use this to avoid allocating a new grid each step.
+    /// The default implementation transforms in-place.
+    fn transform_buffered(&self, grid: &mut grid::Grid<T>, buffer: &mut grid::Grid<T>) -> bool {
+        let _ = buffer;
+        self.transform(grid);
+        false
+    }
+
+    /// Seeds the random number generator of this rule, if it has one, making its results reproducible.
+    /// The default implementation does nothing, which is correct for deterministic rules.
+    fn reseed(&self, seed: u64) {
+        let _ = seed;
+    }
+
+    /// Replaces the random number generator of this rule, if it has one, see [CellRng](crate::CellRng).
+    /// Rules consisting of other rules pass a [fork](crate::CellRng::fork) of it to each of them.
+    /// The default implementation drops the generator, which is correct for deterministic rules.
+    fn set_rng(&self, rng: Box<dyn crate::CellRng>) {
+        let _ = rng;
+    }
+
+    /// Returns how far the effect of a changed cell can reach when this rule is applied, in cells.
+    /// Returns [None] if this rule is not local, i.e. if cells may change even though nothing in their vicinity did.
+    ///
+    /// Only rules returning some reach benefit from dirty tracking, see [AutomatonBuilder::with_dirty_tracking](crate::AutomatonBuilder::with_dirty_tracking).
+    fn reach(&self) -> Option<usize> {
+        None
+    }
+
+    /// Transforms the passed cell grid, assuming that only cells within the ```dirty``` region changed since the last transformation.
+    /// Local rules may use this to skip cells that can not change, all other rules should keep the default implementation, which transforms the entire grid.
+    fn transform_region(&self, grid: &mut grid::Grid<T>, dirty: &Region) {
+        let _ = dirty;
+        self.transform(grid);
+    }
+
+    /// Transforms the passed cell grid like [Self::transform_buffered], with read access to the auxiliary layer of the automaton.
+    /// The auxiliary layer has the same dimensions as the grid and is never changed by rules, see [AutomatonBuilder::with_aux_layer](crate::AutomatonBuilder::with_aux_layer).
+    ///
+    /// Rules that do not use an auxiliary layer, which is all rules except [AuxEnvironmentRule], should keep the default implementation, which ignores it.
+    fn transform_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: &crate::CellGrid,
+    ) -> bool {
+        let _ = aux;
+        self.transform_buffered(grid, buffer)
+    }
+
+    /// Transforms the passed cell grid like [Self::transform_region], with read access to the auxiliary layer of the automaton.
+    /// The default implementation ignores the auxiliary layer.
+    fn transform_region_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        dirty: &Region,
+        aux: &crate::CellGrid,
+    ) {
+        let _ = aux;
+        self.transform_region(grid, dirty);
+    }
+
+    /// Returns the GPU context this rule runs in, if any.
+    /// Running an automaton live reuses this context for drawing.
+    ///
+    /// Without the ```display``` feature, no [GpuContext](crate::GpuContext) can be created, so all rules return [None].
+    fn gpu_context(&self) -> Option<&crate::GpuContext> {
+        None
+    }
+
+    /// Transforms the state like [Self::transform], but keeps the result in memory of its own, such as on the GPU, instead of writing it to a grid,
+    /// so consecutive steps do not copy the state back and forth.
+    /// The passed grid is the current state, or [None] if the state has not been changed since the last call of this method, in which case the rule continues from its last result.
+    /// The automaton reads the result back with [ResidentState::read_back] only once the state is accessed, for example to draw, edit or save it.
+    ///
+    /// Returns [None], without transforming anything, if this rule keeps no state of its own, which is what the default implementation does.
+    /// The automaton then applies the rule with [Self::transform_buffered] instead.
+    fn transform_resident(
+        &self,
+        grid: Option<&grid::Grid<T>>,
+    ) -> Option<std::sync::Arc<dyn ResidentState<T>>> {
+        let _ = grid;
+        None
+    }
+
+    /// Replaces the symbols this rule refers to with the symbol they are mapped to, as done by [AutomatonGeneric::remap_symbols](crate::AutomatonGeneric::remap_symbols).
+    ///
+    /// The default implementation does nothing, which is correct for rules that do not refer to specific symbols.
+    fn remap_symbols(&mut self, map: &std::collections::HashMap<T, T>) {
+        let _ = map;
+    }
+
+    /// Informs this rule about the symbols no rule may change, see [AutomatonBuilder::with_frozen_symbols](crate::AutomatonBuilder::with_frozen_symbols).
+    /// Rules moving cells around, such as [PatternRule], should not apply changes that would overwrite these symbols, as the automaton restores them afterwards, destroying whatever was moved there.
+    ///
+    /// The default implementation does nothing, which is correct for rules that compute each cell on its own.
+    fn set_frozen_symbols(&mut self, symbols: &[T]) {
+        let _ = symbols;
+    }
+
+    /// Moves the state this rule keeps for each cell, such as the cooldowns of a [PatternRule], ```dx``` columns to the right, or to the left for negative values,
+    /// as [ScrollingAutomaton::scroll](crate::ScrollingAutomaton::scroll) does with the cells. Cells moving in start without any state.
+    ///
+    /// The default implementation does nothing, which is correct for rules that keep no state between steps.
+    fn shift_columns(&self, dx: i64) {
+        let _ = dx;
+    }
+
+    /// Enables or disables the part of this rule with the passed index, such as a rule of a [MultiRule] or a pattern of a [PatternRule].
+    /// Disabled parts are skipped when transforming, as if they were not part of the rule, until they are enabled again.
+    /// Returns wether this rule has a part with the passed index.
+    ///
+    /// The default implementation returns false, which is correct for rules without parts that can be toggled.
+    fn set_enabled(&mut self, index: usize, enabled: bool) -> bool {
+        let _ = (index, enabled);
+        false
+    }
+
+    /// Returns a name and wether it is enabled for each part of this rule that can be toggled with [Self::set_enabled], in the order of their indices.
+    ///
+    /// The default implementation returns no parts.
+    fn toggles(&self) -> Vec<(String, bool)> {
+        Vec::new()
+    }
+
+    /// Returns an independent copy of this rule, if it can be copied, as needed by [AutomatonGeneric::sub_automaton](crate::AutomatonGeneric::sub_automaton).
+    ///
+    /// The default implementation returns [None]. Rules implementing [Clone] should return ```Some(Box::new(self.clone()))```.
+    fn clone_boxed(&self) -> Option<Box<dyn Rule<T>>> {
+        None
+    }
+
+    /// Returns a rule undoing one application of this rule, if this rule is reversible, as needed by [AutomatonGeneric::step_back](crate::AutomatonGeneric::step_back).
+    /// Applying this rule and then its inverse has to restore the original grid exactly.
+    ///
+    /// The default implementation returns [None], which is correct for all rules that may map different grids to the same result.
+    fn inverse(&self) -> Option<Box<dyn Rule<T>>> {
+        None
+    }
+
+    /// Returns a name describing this rule in [step reports](RuleReport).
+    /// The default implementation returns the [Debug] output of the rule.
+    fn name(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Transforms the passed cell grid like [Self::transform_with_aux], or like [Self::transform_buffered] without an auxiliary layer,
+    /// adding a [RuleReport] about the changes made to ```report```, as done by automata [recording step reports](crate::AutomatonBuilder::with_step_reports).
+    ///
+    /// Rules consisting of other rules, such as [MultiRule], add one report per contained rule instead. All other rules should keep the default implementation.
+    fn transform_reported(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        report: &mut Vec<RuleReport>,
+    ) -> bool {
+        let before = grid.clone();
+        let swapped = match aux {
+            Some(aux) => self.transform_with_aux(grid, buffer, aux),
+            None => self.transform_buffered(grid, buffer),
+        };
+        report.push(RuleReport::new(
+            self.name(),
+            &before,
+            if swapped { buffer } else { grid },
+        ));
+        swapped
+    }
+
+    /// Transforms the passed cell grid like [Self::transform_with_aux], or like [Self::transform_buffered] without an auxiliary layer,
+    /// but may stop early once the passed deadline has passed, only considering part of the grid, as done by [AutomatonGeneric::next_step_with_budget](crate::AutomatonGeneric::next_step_with_budget).
+    /// Unlike those functions, the result is always left in ```grid```.
+    /// Returns wether the entire grid was considered.
+    ///
+    /// The default implementation ignores the deadline and always transforms the entire grid.
+    fn transform_until(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        deadline: std::time::Instant,
+    ) -> bool {
+        let _ = deadline;
+        let swapped = match aux {
+            Some(aux) => self.transform_with_aux(grid, buffer, aux),
+            None => self.transform_buffered(grid, buffer),
+        };
+        if swapped {
+            std::mem::swap(grid, buffer);
+        }
+        true
+    }
+
+    /// Writes the next values of the cells in the passed rows of ```grid``` to the same rows of ```buffer```, which has the same dimensions as ```grid```, leaving all other rows alone.
+    /// Transforming all rows this way, in any number of calls, has to yield the same result as [Self::transform_buffered],
+    /// so a step can be spread over multiple calls of [AutomatonGeneric::next_step_with_budget](crate::AutomatonGeneric::next_step_with_budget).
+    /// Returns wether this rule supports transforming single rows, which is checked by calling it with an empty range of rows.
+    ///
+    /// The default implementation returns ```false```, which is correct for all rules that do not only read from the untransformed grid, such as rules changing the grid in-place.
+    fn transform_rows(
+        &self,
+        grid: &grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        rows: std::ops::Range<usize>,
+    ) -> bool {
+        let _ = (grid, buffer, rows);
+        false
+    }
+}
+
+impl<T: Cell> Rule<T> for Box<dyn Rule<T>> {
+    fn transform(&self, grid: &mut grid::Grid<T>) {
+        (**self).transform(grid);
+    }
+
+    fn transform_buffered(&self, grid: &mut grid::Grid<T>, buffer: &mut grid::Grid<T>) -> bool {
+        (**self).transform_buffered(grid, buffer)
+    }
+
+    fn reseed(&self, seed: u64) {
+        (**self).reseed(seed);
+    }
+
+    fn set_rng(&self, rng: Box<dyn crate::CellRng>) {
+        (**self).set_rng(rng);
+    }
+
+    fn reach(&self) -> Option<usize> {
+        (**self).reach()
+    }
+
+    fn transform_region(&self, grid: &mut grid::Grid<T>, dirty: &Region) {
+        (**self).transform_region(grid, dirty);
+    }
+
+    fn transform_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: &crate::CellGrid,
+    ) -> bool {
+        (**self).transform_with_aux(grid, buffer, aux)
+    }
+
+    fn transform_region_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        dirty: &Region,
+        aux: &crate::CellGrid,
+    ) {
+        (**self).transform_region_with_aux(grid, dirty, aux);
+    }
+
+    fn gpu_context(&self) -> Option<&crate::GpuContext> {
+        (**self).gpu_context()
+    }
+
+    fn transform_resident(
+        &self,
+        grid: Option<&grid::Grid<T>>,
+    ) -> Option<std::sync::Arc<dyn ResidentState<T>>> {
+        (**self).transform_resident(grid)
+    }
+
+    fn remap_symbols(&mut self, map: &std::collections::HashMap<T, T>) {
+        (**self).remap_symbols(map);
+    }
+
+    fn set_frozen_symbols(&mut self, symbols: &[T]) {
+        (**self).set_frozen_symbols(symbols);
+    }
+
+    fn shift_columns(&self, dx: i64) {
+        (**self).shift_columns(dx);
+    }
+
+    fn set_enabled(&mut self, index: usize, enabled: bool) -> bool {
+        (**self).set_enabled(index, enabled)
+    }
+
+    fn toggles(&self) -> Vec<(String, bool)> {
+        (**self).toggles()
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn Rule<T>>> {
+        (**self).clone_boxed()
+    }
+
+    fn inverse(&self) -> Option<Box<dyn Rule<T>>> {
+        (**self).inverse()
+    }
+
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn transform_reported(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        report: &mut Vec<RuleReport>,
+    ) -> bool {
+        (**self).transform_reported(grid, buffer, aux, report)
+    }
+
+    fn transform_until(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        deadline: std::time::Instant,
+    ) -> bool {
+        (**self).transform_until(grid, buffer, aux, deadline)
+    }
+
+    fn transform_rows(
+        &self,
+        grid: &grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        rows: std::ops::Range<usize>,
+    ) -> bool {
+        (**self).transform_rows(grid, buffer, rows)
+    }
 }
 
 /// A multi rule consists of multiple rules. Each rule will be applied in order, and the result of the final application is the result of the multi rule.
+///
+/// Single rules can be disabled and enabled again with [Rule::set_enabled], for example to find out what a rule contributes while the automaton runs.
 #[derive(Debug)]
-pub struct MultiRule {
+pub struct MultiRule<T: Cell = u8> {
     /// The collection of rules to be applied in order.
-    pub(crate) rules: Vec<Box<dyn Rule>>,
+    pub(crate) rules: Vec<Box<dyn Rule<T>>>,
+    /// Wether each rule is applied, in the same order.
+    enabled: Vec<bool>,
 }
 
-impl Rule for MultiRule {
-    fn transform(&self, grid: &mut CellGrid) {
-        for rule in &self.rules {
+impl<T: Cell> Rule<T> for MultiRule<T> {
+    fn transform(&self, grid: &mut grid::Grid<T>) {
+        for (index, rule) in self.enabled_rules() {
+            trace_span!("rule", index = index);
             rule.transform(grid);
         }
     }
+
+    fn transform_buffered(&self, grid: &mut grid::Grid<T>, buffer: &mut grid::Grid<T>) -> bool {
+        for (index, rule) in self.enabled_rules() {
+            trace_span!("rule", index = index);
+            // keep the current result in grid, so the next rule reads from it
+            if rule.transform_buffered(grid, buffer) {
+                std::mem::swap(grid, buffer);
+            }
+        }
+        false
+    }
+
+    fn transform_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: &crate::CellGrid,
+    ) -> bool {
+        for (index, rule) in self.enabled_rules() {
+            trace_span!("rule", index = index);
+            if rule.transform_with_aux(grid, buffer, aux) {
+                std::mem::swap(grid, buffer);
+            }
+        }
+        false
+    }
+
+    fn reseed(&self, seed: u64) {
+        // derive a different seed for each rule, so equal rules do not behave identically
+        for (index, rule) in self.rules.iter().enumerate() {
+            rule.reseed(seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        }
+    }
+
+    fn set_rng(&self, mut rng: Box<dyn crate::CellRng>) {
+        for (index, rule) in self.rules.iter().enumerate() {
+            rule.set_rng(rng.fork(index as u64));
+        }
+    }
+
+    fn reach(&self) -> Option<usize> {
+        self.enabled_rules()
+            .map(|(_, rule)| rule.reach())
+            .try_fold(0, |total, reach| Some(total + reach?))
+    }
+
+    fn transform_region(&self, grid: &mut grid::Grid<T>, dirty: &Region) {
+        self.transform_regions(grid, dirty, |rule, grid, dirty| {
+            rule.transform_region(grid, dirty)
+        });
+    }
+
+    fn transform_region_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        dirty: &Region,
+        aux: &crate::CellGrid,
+    ) {
+        self.transform_regions(grid, dirty, |rule, grid, dirty| {
+            rule.transform_region_with_aux(grid, dirty, aux)
+        });
+    }
+
+    fn gpu_context(&self) -> Option<&crate::GpuContext> {
+        self.rules.iter().find_map(|rule| rule.gpu_context())
+    }
+
+    fn remap_symbols(&mut self, map: &std::collections::HashMap<T, T>) {
+        for rule in self.rules.iter_mut() {
+            rule.remap_symbols(map);
+        }
+    }
+
+    fn set_frozen_symbols(&mut self, symbols: &[T]) {
+        for rule in self.rules.iter_mut() {
+            rule.set_frozen_symbols(symbols);
+        }
+    }
+
+    fn shift_columns(&self, dx: i64) {
+        for rule in self.rules.iter() {
+            rule.shift_columns(dx);
+        }
+    }
+
+    fn set_enabled(&mut self, index: usize, enabled: bool) -> bool {
+        match self.enabled.get_mut(index) {
+            Some(state) => {
+                *state = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn toggles(&self) -> Vec<(String, bool)> {
+        self.rules
+            .iter()
+            .zip(&self.enabled)
+            .map(|(rule, &enabled)| (rule.name(), enabled))
+            .collect()
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn Rule<T>>> {
+        Some(Box::new(MultiRule {
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| rule.clone_boxed())
+                .collect::<Option<_>>()?,
+            enabled: self.enabled.clone(),
+        }))
+    }
+
+    fn name(&self) -> String {
+        format!("MultiRule of {} rules", self.rules.len())
+    }
+
+    fn transform_reported(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        report: &mut Vec<RuleReport>,
+    ) -> bool {
+        for (_, rule) in self.enabled_rules() {
+            if rule.transform_reported(grid, buffer, aux, report) {
+                std::mem::swap(grid, buffer);
+            }
+        }
+        false
+    }
+
+    fn transform_until(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: Option<&crate::CellGrid>,
+        deadline: std::time::Instant,
+    ) -> bool {
+        // all rules are applied, each deciding on its own how much to skip
+        let mut complete = true;
+        for (_, rule) in self.enabled_rules() {
+            complete &= rule.transform_until(grid, buffer, aux, deadline);
+        }
+        complete
+    }
+
+    fn inverse(&self) -> Option<Box<dyn Rule<T>>> {
+        // undo the last rule first, disabled rules did nothing to undo
+        Some(Box::new(MultiRule::new(
+            self.enabled_rules()
+                .rev()
+                .map(|(_, rule)| rule.inverse())
+                .collect::<Option<_>>()?,
+        )))
+    }
+}
+
+impl<T: Cell> MultiRule<T> {
+    /// Creates a multi rule applying the passed rules in order, all of them enabled.
+    pub fn new(rules: Vec<Box<dyn Rule<T>>>) -> Self {
+        Self {
+            enabled: vec![true; rules.len()],
+            rules,
+        }
+    }
+
+    /// Returns the enabled rules with their indices, in order.
+    fn enabled_rules(&self) -> impl DoubleEndedIterator<Item = (usize, &dyn Rule<T>)> {
+        self.rules
+            .iter()
+            .zip(&self.enabled)
+            .enumerate()
+            .filter(|(_, (_, &enabled))| enabled)
+            .map(|(index, (rule, _))| (index, rule.as_ref()))
+    }
+
+    /// Applies all rules in order using the passed function, growing the dirty region by the reach of each rule.
+    fn transform_regions(
+        &self,
+        grid: &mut grid::Grid<T>,
+        dirty: &Region,
+        transform: impl Fn(&dyn Rule<T>, &mut grid::Grid<T>, &Region),
+    ) {
+        let (rows, cols) = grid.size();
+        let mut dirty = dirty.clone();
+        for (index, rule) in self.enabled_rules() {
+            trace_span!("rule", index = index);
+            transform(rule, grid, &dirty);
+            // the next rule has to consider everything this rule may have changed
+            dirty = match rule.reach() {
+                Some(reach) => dirty.expand(reach, rows, cols),
+                None => Region::full(rows, cols),
+            };
+        }
+    }
+}
+
+/// The result of a step kept by a rule in memory of its own, such as a buffer on the GPU, instead of in the state grid, see [Rule::transform_resident].
+pub trait ResidentState<T: Cell = u8>: Send + Sync {
+    /// Returns the dimensions of the state as ```(rows, cols)```, without reading it back.
+    fn size(&self) -> (usize, usize);
+
+    /// Reads the state back into a new grid, as done once the state is accessed.
+    fn read_back(&self) -> grid::Grid<T>;
+}
+
+/// The random number generator of a rule, seeded from entropy unless a seed or another [CellRng](crate::CellRng) is set explicitly.
+///
+/// As rules are applied through shared references, the generator is kept behind a lock.
+/// Cloning a generator yields an independent generator in the same state, or a [fork](crate::CellRng::fork) if the generator can not be copied.
+#[derive(Debug)]
+pub(crate) struct RuleRng(std::sync::Mutex<Box<dyn crate::CellRng>>);
+
+impl RuleRng {
+    /// Resets this generator to a [rand::rngs::StdRng] in a state determined by the passed seed.
+    pub(crate) fn reseed(&self, seed: u64) {
+        self.set(Box::new(
+            <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(seed),
+        ));
+    }
+
+    /// Replaces this generator with the passed one.
+    pub(crate) fn set(&self, rng: Box<dyn crate::CellRng>) {
+        *self.lock() = rng;
+    }
+
+    /// Locks this generator for use.
+    pub(crate) fn lock(&self) -> std::sync::MutexGuard<'_, Box<dyn crate::CellRng>> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Default for RuleRng {
+    fn default() -> Self {
+        Self(std::sync::Mutex::new(Box::new(
+            <rand::rngs::StdRng as rand::SeedableRng>::from_entropy(),
+        )))
+    }
+}
+
+impl Clone for RuleRng {
+    fn clone(&self) -> Self {
+        let mut rng = self.lock();
+        let copy = rng.clone_boxed().unwrap_or_else(|| rng.fork(u64::MAX));
+        Self(std::sync::Mutex::new(copy))
+    }
+}
+
+/// Describes the changes a single rule made during a step, see [AutomatonBuilder::with_step_reports](crate::AutomatonBuilder::with_step_reports).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleReport {
+    /// The [name](Rule::name) of the rule.
+    pub name: String,
+    /// Wether the rule changed the grid at all, including its dimensions.
+    pub changed: bool,
+    /// The number of cells the rule changed. If it changed the dimensions of the grid, all cells count as changed.
+    pub cells_changed: usize,
+}
+
+impl RuleReport {
+    /// Creates a report for the rule of the passed name, comparing the grid before and after its application.
+    fn new<T: Cell>(name: String, before: &grid::Grid<T>, after: &grid::Grid<T>) -> Self {
+        let cells_changed = count_changed(before, after);
+        Self {
+            name,
+            changed: cells_changed > 0 || before.size() != after.size(),
+            cells_changed,
+        }
+    }
+}
+
+/// Returns the number of cells that differ between the two passed grids. If the grids differ in size, all cells of the ```after``` grid count as changed.
+pub(crate) fn count_changed<T: PartialEq>(before: &grid::Grid<T>, after: &grid::Grid<T>) -> usize {
+    if before.size() == after.size() {
+        before
+            .iter()
+            .zip(after.iter())
+            .filter(|(before, after)| before != after)
+            .count()
+    } else {
+        after.rows() * after.cols()
+    }
+}
+
+/// Moves the cells of the passed grid ```dx``` columns to the right, or to the left for negative values, filling the columns moving in with the default value, see [Rule::shift_columns].
+pub(crate) fn shift_columns<C: Clone + Default>(grid: &mut grid::Grid<C>, dx: i64) {
+    let cols = grid.cols();
+    for row in 0..grid.rows() {
+        let line = &mut grid[row];
+        let distance = dx.unsigned_abs().min(cols as u64) as usize;
+        if dx > 0 {
+            line.rotate_right(distance);
+            line[..distance].fill(C::default());
+        } else {
+            line.rotate_left(distance);
+            line[cols - distance..].fill(C::default());
+        }
+    }
+}
+
+/// A rectangular area of a state grid, described by a range of rows and a range of columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// The rows contained in the region.
+    pub rows: std::ops::Range<usize>,
+    /// The columns contained in the region.
+    pub cols: std::ops::Range<usize>,
+}
+
+impl Region {
+    /// Creates a region covering an entire grid of the passed size.
+    pub fn full(rows: usize, cols: usize) -> Self {
+        Self {
+            rows: 0..rows,
+            cols: 0..cols,
+        }
+    }
+
+    /// Returns the smallest region containing both this and the other region.
+    pub fn union(&self, other: &Region) -> Self {
+        Self {
+            rows: self.rows.start.min(other.rows.start)..self.rows.end.max(other.rows.end),
+            cols: self.cols.start.min(other.cols.start)..self.cols.end.max(other.cols.end),
+        }
+    }
+
+    /// Returns this region grown by ```reach``` cells in every direction, within a grid of the passed size.
+    /// As the grid may wrap around, a region that would be grown beyond an edge instead spans that entire dimension.
+    pub fn expand(&self, reach: usize, rows: usize, cols: usize) -> Self {
+        fn expand_range(
+            range: &std::ops::Range<usize>,
+            reach: usize,
+            len: usize,
+        ) -> std::ops::Range<usize> {
+            if range.start < reach || range.end + reach > len {
+                0..len
+            } else {
+                range.start - reach..range.end + reach
+            }
+        }
+        Self {
+            rows: expand_range(&self.rows, reach, rows),
+            cols: expand_range(&self.cols, reach, cols),
+        }
+    }
+
+    /// Returns the smallest region containing all cells that differ between the two passed grids, or [None] if they are equal.
+    /// If the grids differ in size, the region covers the entire ```after``` grid.
+    pub fn changed<T: PartialEq>(before: &grid::Grid<T>, after: &grid::Grid<T>) -> Option<Self> {
+        let (rows, cols) = after.size();
+        if before.size() != after.size() {
+            return Some(Self::full(rows, cols));
+        }
+        Self::full(rows, cols).changed_cells(before.flatten(), after)
+    }
+
+    /// Returns the values of the cells of this region in the passed grid, row by row.
+    pub(crate) fn cells<T: Clone>(&self, grid: &grid::Grid<T>) -> Vec<T> {
+        self.rows
+            .clone()
+            .flat_map(|row| grid[row][self.cols.clone()].iter().cloned())
+            .collect()
+    }
+
+    /// Returns the smallest region containing all cells of this region whose values in the passed grid differ from the passed values, as returned by [Self::cells], or [None] if all are equal.
+    /// Only the cells of this region are compared.
+    pub(crate) fn changed_cells<T: PartialEq>(
+        &self,
+        before: &[T],
+        after: &grid::Grid<T>,
+    ) -> Option<Self> {
+        let positions = self
+            .rows
+            .clone()
+            .flat_map(|row| self.cols.clone().map(move |col| (row, col)));
+        let mut res: Option<Self> = None;
+        for ((row, col), before) in positions.zip(before) {
+            if *before != after[row][col] {
+                let cell = Self {
+                    rows: row..row + 1,
+                    cols: col..col + 1,
+                };
+                res = Some(match res {
+                    Some(region) => region.union(&cell),
+                    None => cell,
+                });
+            }
+        }
+        res
+    }
+
+    /// Returns the number of cells of this region whose values in the passed grid differ from the passed values, as returned by [Self::cells].
+    pub(crate) fn count_changed_cells<T: PartialEq>(
+        &self,
+        before: &[T],
+        after: &grid::Grid<T>,
+    ) -> usize {
+        self.rows
+            .clone()
+            .flat_map(|row| after[row][self.cols.clone()].iter())
+            .zip(before)
+            .filter(|(after, before)| after != before)
+            .count()
+    }
+
+    /// Returns wether this region contains no cells.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() || self.cols.is_empty()
+    }
 }
 
 /// Describes how Rules, specifically [EnvironmentRule] and [PatternRule], deal with the boundaries of the state grid.
-#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+///
+/// The default is [BoundaryBehaviour::Periodic], which [EnvironmentRule] and [TotalisticRule] use unless specified otherwise.
+/// [PatternRule]s however use [BoundaryBehaviour::blocking_boundary()] by default, including those created from the patterns added to an [AutomatonBuilder](crate::AutomatonBuilder).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum BoundaryBehaviour {
     #[default]
     /// When trying to get a cell from an index outside of the state grid, wrap around
     Periodic,
     /// When trying to get a cell from outside the state grid, return '_' to indicate a wall.
-    /// [PatternRule] will simply not check subareas that leave the state grid.
+    /// [PatternRule] will simply not check subareas that leave the state grid, unless set to match the symbol with [PatternBoundaryMode::MatchSymbol].
     Symbol(u8),
+    /// When trying to get a cell from outside the state grid, return the closest cell at the edge of the grid instead.
+    /// [PatternRule] will simply not check subareas that leave the state grid, like for [BoundaryBehaviour::Symbol].
+    Clamp,
+    /// When trying to get a cell from outside the state grid, return the passed symbol, like [BoundaryBehaviour::Symbol].
+    /// [PatternRule] however also checks subareas that leave the state grid and discards replacements of cells outside it,
+    /// so cells moved across the boundary by a pattern vanish, for example sand falling off the bottom edge.
+    Sink(u8),
 }
 
 impl BoundaryBehaviour {
+    /// Returns a boundary acting as a wall, which no pattern can match and that is distinct from all cells with a character.
+    /// This is the default boundary of [PatternRule]s.
     pub fn blocking_boundary() -> Self {
         Self::Symbol(126)
     }
+
+    /// Resolves a possibly out-of-bounds index along a dimension of the passed length.
+    ///
+    /// Indices within ```0..len``` are returned unchanged.
+    /// For other indices, [BoundaryBehaviour::Periodic] wraps around as often as necessary, so even indices more than ```len``` out of bounds are mapped correctly,
+    /// [BoundaryBehaviour::Clamp] returns the closest index within bounds,
+    /// while [BoundaryBehaviour::Symbol] and [BoundaryBehaviour::Sink] return their symbol as the error value, to be used instead of a cell of the grid.
+    /// ```
+    /// # use cellumina::rule::BoundaryBehaviour;
+    /// assert_eq!(BoundaryBehaviour::Periodic.resolve(-1, 3), Ok(2));
+    /// assert_eq!(BoundaryBehaviour::Periodic.resolve(-7, 3), Ok(2));
+    /// assert_eq!(BoundaryBehaviour::Periodic.resolve(4, 3), Ok(1));
+    /// assert_eq!(BoundaryBehaviour::Symbol(9).resolve(1, 3), Ok(1));
+    /// assert_eq!(BoundaryBehaviour::Symbol(9).resolve(-1, 3), Err(9));
+    /// assert_eq!(BoundaryBehaviour::Symbol(9).resolve(3, 3), Err(9));
+    /// assert_eq!(BoundaryBehaviour::Clamp.resolve(-7, 3), Ok(0));
+    /// assert_eq!(BoundaryBehaviour::Clamp.resolve(4, 3), Ok(2));
+    /// assert_eq!(BoundaryBehaviour::Sink(0).resolve(3, 3), Err(0));
+    /// ```
+    /// ## Panics
+    /// If the index is out of bounds, the boundary periodic or clamping and ```len``` is zero.
+    pub fn resolve(self, index: isize, len: usize) -> Result<usize, u8> {
+        if (0..len as isize).contains(&index) {
+            Ok(index as usize)
+        } else {
+            match self {
+                BoundaryBehaviour::Periodic => Ok(index.rem_euclid(len as isize) as usize),
+                BoundaryBehaviour::Symbol(symbol) | BoundaryBehaviour::Sink(symbol) => Err(symbol),
+                BoundaryBehaviour::Clamp => {
+                    assert!(len > 0, "Can not clamp an index to an empty dimension.");
+                    Ok(index.clamp(0, len as isize - 1) as usize)
+                }
+            }
+        }
+    }
 }
 
 impl Display for BoundaryBehaviour {
@@ -53,6 +900,8 @@ impl Display for BoundaryBehaviour {
         match self {
             BoundaryBehaviour::Periodic => write!(f, "Periodic"),
             BoundaryBehaviour::Symbol(symbol) => write!(f, "Symbol:{}", crate::id_to_char(*symbol)),
+            BoundaryBehaviour::Clamp => write!(f, "Clamp"),
+            BoundaryBehaviour::Sink(symbol) => write!(f, "Sink:{}", crate::id_to_char(*symbol)),
         }
     }
 }
@@ -61,14 +910,81 @@ impl From<&str> for BoundaryBehaviour {
     fn from(value: &str) -> Self {
         match value {
             "Periodic" => Self::Periodic,
+            "Clamp" => Self::Clamp,
             value => {
                 let parts = value.split(':').collect::<Vec<&str>>();
-                if parts[0] == "Symbol" {
-                    Self::Symbol(crate::char_to_id(parts[1].as_bytes()[0] as char))
-                } else {
-                    Self::Symbol(126)
+                let symbol = || crate::char_to_id(parts[1].chars().next().unwrap_or_default());
+                match parts[0] {
+                    "Symbol" => Self::Symbol(symbol()),
+                    "Sink" => Self::Sink(symbol()),
+                    _ => Self::Symbol(126),
                 }
             }
         }
     }
 }
+
+#[test]
+fn region_test() {
+    let region = Region {
+        rows: 4..6,
+        cols: 1..2,
+    };
+    assert_eq!(
+        region.expand(1, 10, 10),
+        Region {
+            rows: 3..7,
+            cols: 0..3
+        }
+    );
+    // growing beyond an edge spans the entire dimension
+    assert_eq!(
+        region.expand(2, 10, 10),
+        Region {
+            rows: 2..8,
+            cols: 0..10
+        }
+    );
+    assert_eq!(region.expand(4, 9, 10), Region::full(9, 10));
+
+    assert_eq!(
+        region.union(&Region {
+            rows: 8..9,
+            cols: 0..1
+        }),
+        Region {
+            rows: 4..9,
+            cols: 0..2
+        }
+    );
+
+    let before = crate::CellGrid::from_vec(vec![0; 12], 4);
+    let mut after = before.clone();
+    assert_eq!(Region::changed(&before, &after), None);
+    after[0][3] = 1;
+    after[2][1] = 1;
+    assert_eq!(
+        Region::changed(&before, &after),
+        Some(Region {
+            rows: 0..3,
+            cols: 1..4
+        })
+    );
+
+    // comparing only part of the grid ignores changes elsewhere
+    let part = Region {
+        rows: 1..3,
+        cols: 0..2,
+    };
+    let cells = part.cells(&before);
+    assert_eq!(cells, vec![0; 4]);
+    assert_eq!(
+        part.changed_cells(&cells, &after),
+        Some(Region {
+            rows: 2..3,
+            cols: 1..2
+        })
+    );
+    after[2][1] = 0;
+    assert_eq!(part.changed_cells(&cells, &after), None);
+}
@@ -1,13 +1,18 @@
 use super::{BoundaryBehaviour, Rule};
 use crate::CellGrid;
-use rand::seq::SliceRandom;
-use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use rand::{seq::SliceRandom, Rng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
 /// A Pattern Rule works by looping over the current state and replacing every occurence of one or more certain patterns with another, equally sized pattern of characters.
 ///
 /// For more information about how [Pattern]s are processed, see [Pattern].
+///
+/// Unless specified otherwise, pattern rules treat the grid boundaries as walls of [BoundaryBehaviour::blocking_boundary()] in both directions,
+/// so patterns never wrap around the grid edges.
+/// Note that this differs from the [Default] of [BoundaryBehaviour] itself, which is periodic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternRule {
     /// The replacment patterns of this rule.
@@ -16,6 +21,141 @@ pub struct PatternRule {
     pub(crate) row_boundary: BoundaryBehaviour,
     /// Describes the way the rule deals with attempts to match patterns that overlap columns out of bounds of the state grid.
     pub(crate) col_boundary: BoundaryBehaviour,
+    /// The order in which equally prioritized replacements are applied.
+    #[serde(default)]
+    pub(crate) ordering: PatternOrdering,
+    /// What happens to replacement cells of a match that fall outside the grid.
+    #[serde(default)]
+    pub(crate) write_boundary: WriteBoundary,
+    /// How the priorities of the patterns decide the order in which their replacements are applied.
+    #[serde(default)]
+    pub(crate) priority_mode: PriorityMode,
+    /// Wether patterns may match against the symbols of [BoundaryBehaviour::Symbol] boundaries.
+    #[serde(default)]
+    pub(crate) boundary_mode: PatternBoundaryMode,
+    /// The indices of the patterns that are currently disabled, see [Rule::set_enabled].
+    #[serde(skip)]
+    disabled: std::collections::BTreeSet<usize>,
+    /// The random number generator deciding pattern chances and the order of equally prioritized replacements.
+    #[serde(skip)]
+    pub(crate) rng: super::RuleRng,
+    /// The number of steps each cell still has to wait until patterns with a cooldown may match at it again.
+    #[serde(skip)]
+    cooldowns: Cooldowns,
+    /// The symbols replacements may not overwrite, see [Rule::set_frozen_symbols].
+    #[serde(skip)]
+    frozen: Vec<u8>,
+}
+
+/// Describes the order in which a [PatternRule] applies the replacements of equally prioritized patterns when they compete for the same cells.
+///
+/// Replacements of different priorities are always applied from the highest priority to the lowest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternOrdering {
+    /// All replacements of the same priority are applied in a random order.
+    /// Patterns with many matches are likely to win most conflicts, possibly leaving another pattern without any applications in a step.
+    #[default]
+    Shuffle,
+    /// Replacements of the same priority are applied in rounds, each round applying one replacement of every pattern that still has some, in a random order.
+    /// The replacements of each pattern are still applied in a random order, but every pattern gets its turn, so patterns competing for the same cells win equally often.
+    RoundRobin,
+}
+
+/// Describes how a [PatternRule] uses the priorities of its patterns to decide which replacements are applied first when they compete for the same cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PriorityMode {
+    /// Replacements are applied from the highest priority to the lowest, so a pattern always beats patterns of lower priority, no matter how small the difference.
+    /// Equal priorities are ordered as described by the [PatternOrdering].
+    #[default]
+    Strict,
+    /// Priorities are soft preferences: the order of the replacements is sampled without replacement,
+    /// each next replacement being picked with a probability proportional to ```exp(priority / temperature)```.
+    ///
+    /// With two competing replacements of priority ```a``` and ```b```, the first wins with probability ```1 / (1 + exp((b - a) / temperature))```,
+    /// so low temperatures approach [PriorityMode::Strict] and high temperatures approach a random order.
+    /// The [PatternOrdering] is ignored in this mode.
+    Softmax {
+        /// The temperature, a positive finite number.
+        temperature: f32,
+    },
+}
+
+/// Describes what a [PatternRule] does with the replacement cells of a match that fall outside the grid.
+///
+/// Under a [BoundaryBehaviour::Periodic] boundary, patterns match across the grid edges, so their replacements can reach beyond them.
+/// Under a [BoundaryBehaviour::Sink], they reach into the sink.
+/// Other boundaries never let patterns overlap the grid edges, so this setting has no effect for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteBoundary {
+    /// Replacements beyond a periodic boundary wrap around to the opposite edge, replacements beyond a sink are dropped.
+    #[default]
+    Wrap,
+    /// Replacements outside the grid are dropped, while the rest of the match is still applied.
+    Clip,
+    /// Matches with any replacement outside the grid are discarded completely.
+    /// Wildcards in the ```after``` grid do not count, as they replace nothing.
+    Reject,
+}
+
+/// Describes how a [PatternRule] treats [BoundaryBehaviour::Symbol] boundaries, which [EnvironmentRule](super::EnvironmentRule)s see as cells holding the symbol.
+///
+/// Other boundaries are not affected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternBoundaryMode {
+    /// Patterns are only placed where they lie entirely within the grid, so they never see the boundary symbol.
+    #[default]
+    SkipOutOfBounds,
+    /// Patterns may also be placed where they reach beyond the grid, with the cells outside matching the boundary symbol,
+    /// for example to keep sand resting on the bottom row by matching the wall below it.
+    ///
+    /// Cells outside the grid can not be replaced, so [validation](PatternRule::validate) requires the ```after``` grid to hold a wildcard wherever the ```before``` grid holds the boundary symbol.
+    /// Patterns should therefore use the boundary symbol only to match the boundary, as the default [blocking boundary](BoundaryBehaviour::blocking_boundary) is meant to be.
+    /// Matches that would replace a cell outside the grid anyway, for example one matched by a wildcard, are discarded.
+    MatchSymbol,
+}
+
+/// Describes a single placement of a pattern checked by [PatternRule::explain].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    /// The index of the pattern within the rule.
+    pub pattern: usize,
+    /// The row of the top left corner of the placement. May lie outside the grid for patterns reaching beyond a sink boundary.
+    pub row: isize,
+    /// The column of the top left corner of the placement. May lie outside the grid for patterns reaching beyond a sink boundary.
+    pub col: isize,
+    /// What would happen to the placement.
+    pub verdict: Verdict,
+}
+
+/// The outcome of checking a placement of a pattern, see [Explanation].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    /// The ```before``` pattern does not match, the first failing cell being at the passed position within the pattern.
+    Mismatch {
+        /// The row of the failing cell within the pattern.
+        row: usize,
+        /// The column of the failing cell within the pattern.
+        col: usize,
+        /// The symbol the pattern expects.
+        expected: u8,
+        /// The symbol found in the grid, or of the sink boundary the cell lies beyond.
+        found: u8,
+    },
+    /// The pattern was disabled with [Rule::set_enabled], so it is not checked.
+    Disabled,
+    /// The pattern matches, but was applied at this position too recently to match again, see [Pattern].
+    CoolingDown,
+    /// The pattern matches, but would replace a cell beyond a boundary it matched against with [PatternBoundaryMode::MatchSymbol], so it is discarded.
+    ReplacesBoundary,
+    /// The pattern matches.
+    Match {
+        /// The chance of the pattern at this position, including ```chance_per_match```.
+        chance: f32,
+        /// Wether a random roll decides if the match is applied, i.e. wether ```chance``` is below ```1```.
+        rolls_chance: bool,
+        /// Pattern index, row and column of all matches of patterns with a higher priority that would replace some of the same cells, and thus prevent this match from being applied if they were applied themselves.
+        conflicts: Vec<(usize, isize, isize)>,
+    },
 }
 
 impl Display for PatternRule {
@@ -30,18 +170,11 @@ impl Display for PatternRule {
 }
 
 impl From<&str> for PatternRule {
+    /// Parses a pattern rule from its [Display] output, replacing unknown characters with empty cells.
+    /// Panics if the text is malformed, see [PatternRule::parse] for a fallible version.
     fn from(value: &str) -> Self {
-        let mut vals = value.split(";\n\n");
-
-        PatternRule {
-            row_boundary: 
-                BoundaryBehaviour::from(vals.next().unwrap()),
-                col_boundary: BoundaryBehaviour::from(vals.next().unwrap()),
-            patterns: vals
-                .filter(|val| !val.is_empty())
-                .map(Pattern::from)
-                .collect(),
-        }
+        Self::parse(value, false)
+            .unwrap_or_else(|err| panic!("Could not parse pattern rule: {err}"))
     }
 }
 
@@ -51,11 +184,22 @@ impl From<&str> for PatternRule {
 /// The ```after``` pattern may contain wildcards ```*``` to not mutate that cell and simply keep its previous value.
 ///
 /// Whenever a pattern matches, the attribute might randomly be discarded instead of being applied.
-/// The ```chance``` attribute describes the likelihood of the pattern being applied without discard, i.e. any value over ```1.0``` means the pattern will always be applied when it matches.
+/// The ```chance``` attribute describes the likelihood of the pattern being applied without discard, i.e. ```1.0``` means the pattern will always be applied when it matches.
+/// Chances outside of ```[0, 1]``` are clamped into that range with a warning when the pattern is added to a [PatternRule], parsed or deserialized.
+///
+/// With ```chance_per_match``` set to a symbol and a factor, the chance grows with the number of cells of that symbol in the area matched by ```before```, including cells matched by wildcards.
+/// The chance at each position is then ```chance + count * factor```, clamped into ```[0, 1]```, so for example fire can spread faster to cells with more burning neighbors.
+/// Factors must be finite numbers, just like priorities.
 ///
 /// If multiple patterns are applicable within a time step, the one with higher priority will always be applied first.
 /// Only if no cell concerning the second pattern has been mutated, the second pattern will apply also.
+/// Priorities must be finite numbers, other values are rejected by [PatternRule::validate] and when parsing or deserializing.
+///
+/// A pattern with a ```cooldown``` above ```0``` can not match at the same position again for that many steps after being applied, for example to keep explosions from chain-reacting forever.
+/// The position of a match is that of its first cell that is not a wildcard in ```before```, and all patterns with a cooldown of a rule share the countdown of each cell.
+/// As the countdowns change even where the grid does not, rules with such patterns are always applied to the entire grid, even with [dirty tracking](crate::AutomatonBuilder::with_dirty_tracking).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "PatternData")]
 pub struct Pattern {
     /// The chance for the pattern to apply on a match.
     pub chance: f32,
@@ -67,6 +211,10 @@ pub struct Pattern {
     /// The cell pattern it should be replaced with.
     #[serde(with = "SerdeGrid")]
     pub after: CellGrid,
+    /// The number of steps after being applied during which the pattern can not match at the same position again.
+    pub cooldown: u32,
+    /// A symbol and the amount the chance of the pattern grows by for each cell of that symbol within the matched area.
+    pub chance_per_match: Option<(u8, f32)>,
 }
 
 impl Default for Pattern {
@@ -76,6 +224,8 @@ impl Default for Pattern {
             priority: 0.,
             before: grid::grid![[127]],
             after: grid::grid![[127]],
+            cooldown: 0,
+            chance_per_match: None,
         }
     }
 }
@@ -97,40 +247,294 @@ impl Display for Pattern {
                 write!(f, "{}", crate::id_to_char(a_cell))?;
             }
         }
+        // the cooldown and chance factor are only written if set, so older files stay valid
+        if self.cooldown > 0 || self.chance_per_match.is_some() {
+            write!(f, ";\n{}", self.cooldown)?;
+        }
+        if let Some((symbol, factor)) = self.chance_per_match {
+            write!(f, ";\n{}:{factor}", crate::id_to_char(symbol))?;
+        }
         writeln!(f, ";")
     }
 }
 
 impl From<&str> for Pattern {
+    /// Parses a pattern from its [Display] output, replacing unknown characters with empty cells.
+    /// Panics if the text is malformed.
     fn from(value: &str) -> Self {
+        let mut substituted = 0;
+        let pattern = Self::parse(value, 1, false, &mut substituted)
+            .unwrap_or_else(|err| panic!("Could not parse pattern: {err}"));
+        if substituted > 0 {
+            log::warn!("Replaced {substituted} unknown characters with empty cells.");
+        }
+        pattern
+    }
+}
+
+/// The serialized form of a [Pattern], which is checked before being turned into one.
+#[derive(Deserialize)]
+struct PatternData {
+    chance: f32,
+    priority: f32,
+    #[serde(with = "SerdeGrid")]
+    before: CellGrid,
+    #[serde(with = "SerdeGrid")]
+    after: CellGrid,
+    #[serde(default)]
+    cooldown: u32,
+    #[serde(default)]
+    chance_per_match: Option<(u8, f32)>,
+}
+
+impl TryFrom<PatternData> for Pattern {
+    type Error = crate::CelluminaError;
+
+    fn try_from(value: PatternData) -> Result<Self, Self::Error> {
+        if !value.priority.is_finite() {
+            return Err(crate::CelluminaError::CustomError(format!(
+                "pattern priority {} is not a finite number",
+                value.priority
+            )));
+        }
+        if let Some((_, factor)) = value.chance_per_match {
+            if !factor.is_finite() {
+                return Err(crate::CelluminaError::CustomError(format!(
+                    "pattern chance factor {factor} is not a finite number"
+                )));
+            }
+        }
+        let mut pattern = Pattern {
+            chance: value.chance,
+            priority: value.priority,
+            before: value.before,
+            after: value.after,
+            cooldown: value.cooldown,
+            chance_per_match: value.chance_per_match,
+        };
+        pattern.clamp_chance();
+        Ok(pattern)
+    }
+}
+
+impl Pattern {
+    /// Loads a pattern from two image files of the same size, such as tiny PNGs drawn in an image editor, see [Self::from_images].
+    pub fn from_image_files(
+        before: impl AsRef<std::path::Path>,
+        after: impl AsRef<std::path::Path>,
+        colors: &std::collections::HashMap<u8, [u8; 4]>,
+        wildcard_color: Option<[u8; 4]>,
+    ) -> Result<Self, crate::CelluminaError> {
+        Self::from_images(
+            &image::open(before)?.into_rgba8(),
+            &image::open(after)?.into_rgba8(),
+            colors,
+            wildcard_color,
+        )
+    }
+
+    /// Creates a pattern from two images of the same size, with one pixel per cell, turning each pixel into the symbol of its color in ```colors```.
+    /// Pixels of the ```wildcard_color```, if any, become wildcards ```*``` (```127```), taking precedence over symbols of the same color.
+    /// All other values of the pattern are the [Default].
+    /// ## Error
+    /// When the images differ in size, or when a pixel matches no color exactly, naming its position.
+    /// ```
+    /// # use std::collections::HashMap;
+    /// let colors = HashMap::from([(0, [0, 0, 0, 255]), (1, [255, 255, 255, 255])]);
+    /// let image = |pixels: Vec<u8>| image::RgbaImage::from_raw(1, 2, pixels).unwrap();
+    /// let pattern = cellumina::rule::Pattern::from_images(
+    ///     &image([255, 255, 255, 255, 0, 0, 0, 255].to_vec()),
+    ///     &image([0, 0, 0, 255, 255, 0, 255, 255].to_vec()),
+    ///     &colors,
+    ///     Some([255, 0, 255, 255]),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(pattern.before, grid::grid![[1][0]]);
+    /// assert_eq!(pattern.after, grid::grid![[0][127]]);
+    /// ```
+    pub fn from_images(
+        before: &image::RgbaImage,
+        after: &image::RgbaImage,
+        colors: &std::collections::HashMap<u8, [u8; 4]>,
+        wildcard_color: Option<[u8; 4]>,
+    ) -> Result<Self, crate::CelluminaError> {
+        let size = |image: &image::RgbaImage| (image.height() as usize, image.width() as usize);
+        if size(before) != size(after) {
+            return Err(crate::CelluminaError::DimensionMismatch {
+                expected: size(before),
+                got: size(after),
+            });
+        }
+        let mut symbols = colors
+            .iter()
+            .map(|(&symbol, &color)| (color, symbol))
+            .collect::<std::collections::HashMap<_, _>>();
+        if let Some(color) = wildcard_color {
+            symbols.insert(color, 127);
+        }
+        let grid = |image: &image::RgbaImage| {
+            image
+                .enumerate_pixels()
+                .map(|(x, y, pixel)| {
+                    symbols
+                        .get(&pixel.0)
+                        .copied()
+                        .ok_or(crate::CelluminaError::UnmatchedPixel {
+                            x,
+                            y,
+                            color: pixel.0,
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|cells| grid::Grid::from_vec(cells, image.width() as usize))
+        };
+        Ok(Self {
+            before: grid(before)?,
+            after: grid(after)?,
+            ..Default::default()
+        })
+    }
+
+    /// Clamps the chance of this pattern into ```[0, 1]```, logging a warning if it was outside of that range.
+    /// A chance that is not a number is set to ```0```.
+    pub(crate) fn clamp_chance(&mut self) {
+        let chance = if self.chance.is_nan() {
+            0.
+        } else {
+            self.chance.clamp(0., 1.)
+        };
+        if chance != self.chance {
+            log::warn!(
+                "Pattern chance {} is outside of [0, 1], using {chance} instead.",
+                self.chance
+            );
+            self.chance = chance;
+        }
+    }
+
+    /// Parses a pattern from its [Display] output, which starts at line ```first_line``` of the parsed text.
+    /// Unknown characters cause an error in ```strict``` mode and are replaced with zeros (and counted in ```substituted```) otherwise.
+    fn parse(
+        value: &str,
+        first_line: usize,
+        strict: bool,
+        substituted: &mut usize,
+    ) -> Result<Self, crate::CelluminaError> {
         let parts = value.split(";\n").collect::<Vec<&str>>();
+        // the line each part starts in
+        let lines = parts
+            .iter()
+            .scan(first_line, |line, part| {
+                let start = *line;
+                *line += part.matches('\n').count() + 1;
+                Some(start)
+            })
+            .collect::<Vec<usize>>();
 
-        Pattern {
-            chance: parts[0].parse().unwrap_or(1.),
-            priority: parts[1].parse().unwrap_or(0.),
-            before: {
-                let lines = parts[2].split('\n').collect::<Vec<&str>>();
-                grid::Grid::from_vec(
-                    lines
-                        .iter()
-                        .flat_map(|line| line.chars())
-                        .map(crate::char_to_id)
-                        .collect::<Vec<u8>>(),
-                    lines[0].len(),
-                )
-            },
-            after: {
-                let lines = parts[3].split('\n').collect::<Vec<&str>>();
-                grid::Grid::from_vec(
-                    lines
-                        .iter()
-                        .flat_map(|line| line.chars())
-                        .map(crate::char_to_id)
-                        .collect::<Vec<u8>>(),
-                    lines[0].len(),
-                )
-            },
+        let error =
+            |line: usize, column: usize, message: String| crate::CelluminaError::ParseError {
+                source_kind: crate::SourceKind::PatternRule,
+                line,
+                column,
+                message,
+            };
+
+        if parts.len() < 4 {
+            return Err(error(
+                lines[parts.len() - 1],
+                1,
+                "expected chance, priority, before and after grid, each terminated by ';'"
+                    .to_string(),
+            ));
         }
+
+        let parse_grid = |index: usize, substituted: &mut usize| {
+            let rows = parts[index].split('\n').collect::<Vec<&str>>();
+            let cols = rows[0].chars().count();
+            let mut cells = Vec::new();
+            for (row, line) in rows.iter().enumerate() {
+                let line_number = lines[index] + row;
+                let row_cells = crate::parse_line(
+                    line,
+                    crate::SourceKind::PatternRule,
+                    line_number,
+                    strict,
+                    substituted,
+                )?;
+                if row_cells.len() != cols {
+                    return Err(error(
+                        line_number,
+                        1,
+                        format!("row has {} cells, expected {cols}", row_cells.len()),
+                    ));
+                }
+                cells.extend(row_cells);
+            }
+            Ok(grid::Grid::from_vec(cells, cols))
+        };
+
+        let before = parse_grid(2, substituted)?;
+        let after = parse_grid(3, substituted)?;
+        if before.size() != after.size() {
+            return Err(crate::CelluminaError::DimensionMismatch {
+                expected: before.size(),
+                got: after.size(),
+            });
+        }
+
+        let priority = parts[1].parse().unwrap_or(0.);
+        if !f32::is_finite(priority) {
+            return Err(error(
+                lines[1],
+                1,
+                format!("priority {priority} is not a finite number"),
+            ));
+        }
+
+        let cooldown = match parts.get(4).map(|part| part.trim()) {
+            None | Some("") => 0,
+            Some(part) => part.parse().map_err(|_| {
+                error(
+                    lines[4],
+                    1,
+                    format!("cooldown {part} is not a number of steps"),
+                )
+            })?,
+        };
+
+        let chance_per_match = match parts.get(5).map(|part| part.trim()) {
+            None | Some("") => None,
+            Some(part) => {
+                let mut chars = part.chars();
+                let symbol = chars.next().map(crate::try_char_to_id);
+                let factor = chars
+                    .as_str()
+                    .strip_prefix(':')
+                    .and_then(|factor| factor.parse::<f32>().ok())
+                    .filter(|factor| factor.is_finite());
+                match (symbol, factor) {
+                    (Some(Ok(symbol)), Some(factor)) => Some((symbol, factor)),
+                    _ => {
+                        return Err(error(
+                            lines[5],
+                            1,
+                            format!("chance factor {part} is not a symbol followed by ':' and a finite number"),
+                        ))
+                    }
+                }
+            }
+        };
+
+        let mut pattern = Pattern {
+            chance: parts[0].parse().unwrap_or(1.),
+            priority,
+            before,
+            after,
+            cooldown,
+            chance_per_match,
+        };
+        pattern.clamp_chance();
+        Ok(pattern)
     }
 }
 
@@ -153,207 +557,2240 @@ impl<T> From<SerdeGrid<T>> for grid::Grid<T> {
     }
 }
 
+impl Default for PatternRule {
+    /// Creates an empty pattern rule, see [PatternRule::new_empty].
+    fn default() -> Self {
+        Self::new_empty()
+    }
+}
+
 impl PatternRule {
-    /// Create a new (empty) pattern rule.
+    /// Create a new (empty) pattern rule, with [BoundaryBehaviour::blocking_boundary()] walls at all grid edges.
     pub fn new_empty() -> Self {
         Self {
             patterns: Vec::new(),
-            row_boundary: 
-                BoundaryBehaviour::Symbol(126),
-                col_boundary: BoundaryBehaviour::Symbol(126),
+            row_boundary: BoundaryBehaviour::blocking_boundary(),
+            col_boundary: BoundaryBehaviour::blocking_boundary(),
+            ordering: PatternOrdering::default(),
+            write_boundary: WriteBoundary::default(),
+            priority_mode: PriorityMode::default(),
+            boundary_mode: PatternBoundaryMode::default(),
+            disabled: Default::default(),
+            rng: Default::default(),
+            cooldowns: Default::default(),
+            frozen: Vec::new(),
+        }
+    }
+
+    /// Sets the order in which replacements of equally prioritized patterns are applied, see [PatternOrdering].
+    /// The ordering is not part of the [Display] output.
+    pub fn with_ordering(mut self, ordering: PatternOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Sets what happens to replacement cells that fall outside the grid, see [WriteBoundary].
+    /// The setting is not part of the [Display] output.
+    pub fn with_write_boundary(mut self, write_boundary: WriteBoundary) -> Self {
+        self.write_boundary = write_boundary;
+        self
+    }
+
+    /// Sets how the priorities of the patterns order their replacements, see [PriorityMode].
+    /// The mode is not part of the [Display] output.
+    pub fn with_priority_mode(mut self, priority_mode: PriorityMode) -> Self {
+        self.priority_mode = priority_mode;
+        self
+    }
+
+    /// Sets wether patterns may match against the symbols of [BoundaryBehaviour::Symbol] boundaries, see [PatternBoundaryMode].
+    /// The mode is not part of the [Display] output.
+    pub fn with_boundary_mode(mut self, boundary_mode: PatternBoundaryMode) -> Self {
+        self.boundary_mode = boundary_mode;
+        self
+    }
+
+    /// Parses a pattern rule from its [Display] output, as saved in ```.cel``` files.
+    ///
+    /// In ```strict``` mode, characters without an associated cell value (see [char_to_id](crate::char_to_id)) cause an error naming the character and its position.
+    /// Otherwise, they are read as empty cells and a warning is logged, as when using [From<&str>].
+    pub fn parse(value: &str, strict: bool) -> Result<Self, crate::CelluminaError> {
+        let mut vals = value.split(";\n\n");
+        let row_boundary = BoundaryBehaviour::from(vals.next().unwrap_or_default());
+        let col_boundary = BoundaryBehaviour::from(vals.next().unwrap_or_default());
+
+        // patterns start after the two boundaries, each followed by an empty line
+        let mut line = 5;
+        let mut substituted = 0;
+        let mut patterns = Vec::new();
+        for val in vals {
+            if !val.is_empty() {
+                patterns.push(Pattern::parse(val, line, strict, &mut substituted)?);
+            }
+            line += val.matches('\n').count() + 2;
+        }
+
+        if substituted > 0 {
+            log::warn!("Replaced {substituted} unknown characters with empty cells.");
+        }
+
+        let rule = PatternRule {
+            row_boundary,
+            col_boundary,
+            patterns,
+            ordering: PatternOrdering::default(),
+            write_boundary: WriteBoundary::default(),
+            priority_mode: PriorityMode::default(),
+            boundary_mode: PatternBoundaryMode::default(),
+            disabled: Default::default(),
+            rng: Default::default(),
+            cooldowns: Default::default(),
+            frozen: Vec::new(),
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+
+    /// Checks that all patterns of this rule can be applied, i.e. that they are not empty, replace their ```before``` grid with an equally sized ```after``` grid and have a finite priority,
+    /// that the temperature of a [PriorityMode::Softmax] is a positive finite number
+    /// and that, with [PatternBoundaryMode::MatchSymbol], no pattern replaces a cell matching the symbol of a boundary.
+    /// Returns all issues found as a [ValidationError](crate::CelluminaError::ValidationError).
+    pub fn validate(&self) -> Result<(), crate::CelluminaError> {
+        let mut issues = Vec::new();
+        // the symbols of boundaries patterns match against
+        let walls = [self.row_boundary, self.col_boundary]
+            .into_iter()
+            .filter_map(|boundary| match (boundary, self.boundary_mode) {
+                (BoundaryBehaviour::Symbol(symbol), PatternBoundaryMode::MatchSymbol) => {
+                    Some(symbol)
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if let PriorityMode::Softmax { temperature } = self.priority_mode {
+            if !(temperature.is_finite() && temperature > 0.) {
+                issues.push(crate::ValidationIssue::InvalidTemperature);
+            }
+        }
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            if !pattern.priority.is_finite() {
+                issues.push(crate::ValidationIssue::InvalidPriority { pattern: index });
+            }
+            if let Some((_, factor)) = pattern.chance_per_match {
+                if !factor.is_finite() {
+                    issues.push(crate::ValidationIssue::InvalidChanceFactor { pattern: index });
+                }
+            }
+            if pattern.before.is_empty() {
+                issues.push(crate::ValidationIssue::EmptyPattern { pattern: index });
+            } else if pattern.before.size() != pattern.after.size() {
+                issues.push(crate::ValidationIssue::PatternSizeMismatch {
+                    pattern: index,
+                    before: pattern.before.size(),
+                    after: pattern.after.size(),
+                });
+            } else if pattern
+                .before
+                .iter()
+                .zip(pattern.after.iter())
+                .any(|(before, &after)| walls.contains(before) && after != 127)
+            {
+                issues.push(crate::ValidationIssue::BoundaryReplacement { pattern: index });
+            }
+        }
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::CelluminaError::ValidationError(issues))
         }
     }
 
     /// Create a new pattern rule from a set of patterns.
+    /// Unlike the rest of this type, the boundaries are passed explicitly, so ```Default::default()``` creates a periodic rule here.
+    ///
+    /// Chances outside of ```[0, 1]``` are clamped into that range.
+    /// Patterns with a priority that is not a finite number are kept, but logged as an error, use [Self::validate] to reject them.
     pub fn from_patterns(
         rules: &[Pattern],
         row_boundary: BoundaryBehaviour,
         col_boundary: BoundaryBehaviour,
     ) -> Self {
-        Self {
-            patterns: rules.to_vec(),
+        let rule = Self {
+            patterns: rules
+                .iter()
+                .cloned()
+                .map(|mut pattern| {
+                    pattern.clamp_chance();
+                    pattern
+                })
+                .collect(),
             row_boundary,
             col_boundary,
+            ordering: PatternOrdering::default(),
+            write_boundary: WriteBoundary::default(),
+            priority_mode: PriorityMode::default(),
+            boundary_mode: PatternBoundaryMode::default(),
+            disabled: Default::default(),
+            rng: Default::default(),
+            cooldowns: Default::default(),
+            frozen: Vec::new(),
+        };
+        if let Err(err) = rule.validate() {
+            log::error!("Created pattern rule with invalid patterns: {err}");
+        }
+        rule
+    }
+
+    /// Replaces all symbols in the ```before``` and ```after``` grids and chance factors of the patterns and in symbol boundaries with the symbol they are mapped to, leaving symbols that are not in the map unchanged.
+    /// Wildcards keep their meaning, even if the map contains their symbol.
+    pub fn remap_symbols(&mut self, map: &std::collections::HashMap<u8, u8>) {
+        let remap = |cell: &mut u8| {
+            if *cell != crate::char_to_id('*') {
+                *cell = map.get(cell).copied().unwrap_or(*cell);
+            }
+        };
+        for pattern in self.patterns.iter_mut() {
+            pattern.before.iter_mut().for_each(remap);
+            pattern.after.iter_mut().for_each(remap);
+            if let Some((symbol, _)) = &mut pattern.chance_per_match {
+                remap(symbol);
+            }
+        }
+        for boundary in [&mut self.row_boundary, &mut self.col_boundary] {
+            if let BoundaryBehaviour::Symbol(symbol) | BoundaryBehaviour::Sink(symbol) = boundary {
+                remap(symbol);
+            }
         }
     }
 }
 
-/// A collection of replacement actions, containing a priority, a position (row/column) and a placement character.
-/// A pattern will always produce such a collection of replacements belonging together.
-type ReplacementCollection = Vec<Vec<(f32, usize, usize, u8)>>;
+/// The number of rows scanned together by a single task of a [PatternRule].
+const BAND_HEIGHT: usize = 16;
 
-impl Rule for PatternRule {
-    fn transform(&self, grid: &mut CellGrid) {
-        let (rows, cols) = grid.size();
+/// The replacements produced by a single occurence of a pattern, which are only applied together.
+struct ReplacementGroup {
+    /// The index of the pattern that produced the replacements.
+    pattern: usize,
+    /// The replacement actions, each containing a priority, a position (row/column) and a placement character.
+    replacements: Vec<(f32, usize, usize, u8)>,
+    /// For patterns with a cooldown, the position of the match and the countdown to set there when the group is applied.
+    cooldown: Option<(usize, usize, u32)>,
+}
+
+/// A collection of the replacement groups of all pattern occurences found in one step.
+type ReplacementCollection = Vec<ReplacementGroup>;
+
+/// The countdowns of the cells of the last grid a [PatternRule] was applied to, kept behind a lock like the [RuleRng](super::RuleRng).
+#[derive(Debug)]
+struct Cooldowns(std::sync::Mutex<grid::Grid<u32>>);
+
+impl Default for Cooldowns {
+    fn default() -> Self {
+        Self(std::sync::Mutex::new(grid::Grid::new(0, 0)))
+    }
+}
 
-        let mut replacements: ReplacementCollection = self
+impl Cooldowns {
+    /// Counts down all cells for a new step and locks the countdowns for use.
+    /// If the grid changed its size, all countdowns are reset instead.
+    fn tick(&self, rows: usize, cols: usize) -> std::sync::MutexGuard<'_, grid::Grid<u32>> {
+        let mut cooldowns = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        if cooldowns.size() != (rows, cols) {
+            *cooldowns = grid::Grid::new(rows, cols);
+        } else {
+            cooldowns
+                .iter_mut()
+                .for_each(|cooldown| *cooldown = cooldown.saturating_sub(1));
+        }
+        cooldowns
+    }
+}
+
+impl Clone for Cooldowns {
+    fn clone(&self) -> Self {
+        Self(std::sync::Mutex::new(
+            self.0.lock().unwrap_or_else(|err| err.into_inner()).clone(),
+        ))
+    }
+}
+
+impl PatternRule {
+    /// Explains what this rule would do to the passed cell if it was applied to ```grid``` next, for debugging a cell that behaves unexpectedly.
+    ///
+    /// Returns an [Explanation] of every placement of every pattern that covers the cell, in the order of the patterns.
+    /// Conflicts are only searched among matches of patterns with a higher priority, as the order of equally prioritized matches is random.
+    /// As chances are not rolled, conflicting matches might not actually be applied.
+    ///
+    /// This does not change the random number generator or the cooldowns of the rule, so explaining has no effect on the next step.
+    /// ```
+    /// # use cellumina::rule::{PatternRule, Verdict};
+    /// let rule = PatternRule::from_patterns(
+    ///     &[cellumina::rule::Pattern {
+    ///         before: grid::grid![[1][0]],
+    ///         after: grid::grid![[0][1]],
+    ///         ..Default::default()
+    ///     }],
+    ///     cellumina::rule::BoundaryBehaviour::Symbol(2),
+    ///     cellumina::rule::BoundaryBehaviour::Symbol(2),
+    /// );
+    /// let explanations = rule.explain(&grid::grid![[1, 1][0, 1]], 0, 1);
+    /// assert_eq!(explanations.len(), 1);
+    /// assert!(matches!(
+    ///     explanations[0].verdict,
+    ///     Verdict::Mismatch { row: 1, col: 0, expected: 0, found: 1 }
+    /// ));
+    /// ```
+    pub fn explain(&self, grid: &CellGrid, row: usize, col: usize) -> Vec<Explanation> {
+        let (rows, cols) = grid.size();
+        if row >= rows || col >= cols {
+            return Vec::new();
+        }
+        let cooldowns = self
+            .cooldowns
+            .0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let cooldowns = (cooldowns.size() == (rows, cols)).then_some(&*cooldowns);
+        let bounds = self
             .patterns
-            .par_iter()
-            .filter_map(|pattern| {
-                let mut partial_res = Vec::new();
-
-                let row_stop = match self.row_boundary {
-                    BoundaryBehaviour::Periodic => rows,
-                    BoundaryBehaviour::Symbol(_)=> 
-                        rows - pattern.before.rows() + 1
-                    ,
-                };
-
-                let col_stop = match self.col_boundary {
-                    BoundaryBehaviour::Periodic => cols,
-                    BoundaryBehaviour::Symbol(_)=> 
-                        cols - pattern.before.cols() + 1,
-                };
-
-                for row in 0..row_stop {
-                    'inner_loop: for col in 0..col_stop {
-                        let (p_rows, p_cols) = pattern.after.size();
-
-                        // possibly immediately randomly stop to adhere to pattern chance
-                        if rand::random::<f32>() > pattern.chance {
-                            continue 'inner_loop;
-                        }
+            .iter()
+            .map(|pattern| PatternBounds::new(pattern, rows, cols, self, None))
+            .collect::<Vec<_>>();
+        let position = |index: usize, o_row: usize, o_col: usize| {
+            let offset = |sink: Option<(usize, u8)>| sink.map_or(0, |(offset, _)| offset as isize);
+            (
+                index,
+                o_row as isize - offset(bounds[index].row_sink),
+                o_col as isize - offset(bounds[index].col_sink),
+            )
+        };
 
-                        // check if pattern is applicable
-                        for row_del in 0..p_rows {
-                            for col_del in 0..p_cols {
-                                if pattern.before[row_del][col_del] != 127
-                                // do modulo in case we are wrapping - if edge behaviour is set to stop, this will never change anything
-                                    && grid
-                                        .get(row + row_del, col + col_del)
-                                        .copied()
-                                        .unwrap_or_else(|| grid[(row + row_del)%rows][(col + col_del) % cols])
-                                    != pattern.before[row_del][col_del]
-                                {
-                                    continue 'inner_loop;
-                                }
+        let mut res = Vec::new();
+        for (index, pattern) in self.patterns.iter().enumerate() {
+            for (o_row, o_col) in bounds[index].placements(pattern, grid.size(), row, col) {
+                let (mut verdict, targets) =
+                    self.check(grid, index, &bounds[index], cooldowns, o_row, o_col);
+                if let Verdict::Match { conflicts, .. } = &mut verdict {
+                    // re-run the matching of higher priority patterns around the cells this match would replace
+                    for &(t_row, t_col) in targets.iter() {
+                        for (other, other_pattern) in self.patterns.iter().enumerate() {
+                            if other_pattern.priority.total_cmp(&pattern.priority)
+                                != std::cmp::Ordering::Greater
+                            {
+                                continue;
                             }
-                        }
-
-                        // if we arrive here, the pattern fits
-                        let mut rep_group = Vec::new();
-                        // push replacements as dictated by the pattern
-                        for row_del in 0..p_rows {
-                            for col_del in 0..p_cols {
-                                let rep = pattern.after[row_del][col_del];
-                                // make sure to not replace wild cards, and check edge behaviour
-                                if rep != 127 {
-                                    // apply modulus to replacement coordinates to be sure
-                                    rep_group.push((
-                                        pattern.priority,
-                                        (row + row_del) % rows,
-                                        (col + col_del) % cols,
-                                        rep,
-                                    ));
+                            for (p_row, p_col) in
+                                bounds[other].placements(other_pattern, grid.size(), t_row, t_col)
+                            {
+                                let conflict = position(other, p_row, p_col);
+                                if conflicts.contains(&conflict) {
+                                    continue;
+                                }
+                                if let (Verdict::Match { .. }, other_targets) =
+                                    self.check(grid, other, &bounds[other], cooldowns, p_row, p_col)
+                                {
+                                    if other_targets.contains(&(t_row, t_col)) {
+                                        conflicts.push(conflict);
+                                    }
                                 }
                             }
                         }
-                        partial_res.push(rep_group);
                     }
                 }
-                // only return partial result if it contains any elements
-                if partial_res.is_empty() {
-                    None
-                } else {
-                    Some(partial_res)
+                let (pattern, row, col) = position(index, o_row, o_col);
+                res.push(Explanation {
+                    pattern,
+                    row,
+                    col,
+                    verdict,
+                });
+            }
+        }
+        res
+    }
+
+    /// Checks a single placement of a pattern like a step would, but without rolling any chances, and returns the verdict without conflicts and the cells the match would replace.
+    fn check(
+        &self,
+        grid: &CellGrid,
+        index: usize,
+        bounds: &PatternBounds,
+        cooldowns: Option<&grid::Grid<u32>>,
+        row: usize,
+        col: usize,
+    ) -> (Verdict, Vec<(usize, usize)>) {
+        if self.disabled.contains(&index) {
+            return (Verdict::Disabled, Vec::new());
+        }
+        let pattern = &self.patterns[index];
+        let (rows, cols) = grid.size();
+        let (p_rows, p_cols) = pattern.before.size();
+        let cells =
+            (0..p_rows).flat_map(|row_del| (0..p_cols).map(move |col_del| (row_del, col_del)));
+
+        for (row_del, col_del) in cells.clone() {
+            let expected = pattern.before[row_del][col_del];
+            let found = bounds.cell(grid, row + row_del, col + col_del);
+            if expected != 127 && found != expected {
+                return (
+                    Verdict::Mismatch {
+                        row: row_del,
+                        col: col_del,
+                        expected,
+                        found,
+                    },
+                    Vec::new(),
+                );
+            }
+        }
+
+        // the countdowns are counted down at the start of the next step
+        if let (Some(cooldowns), true) = (cooldowns, pattern.cooldown > 0) {
+            let (a_row, a_col) = bounds
+                .anchor
+                .map_or((0, 0), |(a_row, a_col, _)| (a_row, a_col));
+            if let (Ok(c_row), Ok(c_col)) = (
+                locate(row + a_row, rows, bounds.row_sink),
+                locate(col + a_col, cols, bounds.col_sink),
+            ) {
+                if cooldowns[c_row][c_col] > 1 {
+                    return (Verdict::CoolingDown, Vec::new());
                 }
+            }
+        }
+
+        if cells.clone().any(|(row_del, col_del)| {
+            pattern.after[row_del][col_del] != 127
+                && bounds.beyond_wall(row + row_del, col + col_del, grid.size())
+        }) {
+            return (Verdict::ReplacesBoundary, Vec::new());
+        }
+
+        let chance = match pattern.chance_per_match {
+            Some((symbol, factor)) => {
+                let count = cells
+                    .clone()
+                    .filter(|&(row_del, col_del)| {
+                        bounds.cell(grid, row + row_del, col + col_del) == symbol
+                    })
+                    .count();
+                (pattern.chance + count as f32 * factor).clamp(0., 1.)
+            }
+            None => pattern.chance,
+        };
+        let targets = cells
+            .filter(|&(row_del, col_del)| pattern.after[row_del][col_del] != 127)
+            .filter_map(|(row_del, col_del)| {
+                Some((
+                    locate(row + row_del, rows, bounds.row_sink).ok()?,
+                    locate(col + col_del, cols, bounds.col_sink).ok()?,
+                ))
             })
-            .flatten()
             .collect();
+        (
+            Verdict::Match {
+                chance,
+                rolls_chance: chance < 1.0,
+                conflicts: Vec::new(),
+            },
+            targets,
+        )
+    }
+}
+
+impl Rule for PatternRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        self.apply(grid, None, None);
+    }
+
+    fn transform_until(
+        &self,
+        grid: &mut CellGrid,
+        buffer: &mut CellGrid,
+        aux: Option<&CellGrid>,
+        deadline: std::time::Instant,
+    ) -> bool {
+        let _ = (buffer, aux);
+        self.apply(grid, None, Some(deadline))
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn Rule>> {
+        Some(Box::new(self.clone()))
+    }
+
+    fn reach(&self) -> Option<usize> {
+        // countdowns change everywhere, so the whole grid has to be checked each step
+        if self.patterns.iter().any(|pattern| pattern.cooldown > 0) {
+            return None;
+        }
+        Some(
+            self.patterns
+                .iter()
+                .map(|pattern| {
+                    pattern
+                        .before
+                        .rows()
+                        .max(pattern.before.cols())
+                        .saturating_sub(1)
+                })
+                .max()
+                .unwrap_or(0),
+        )
+    }
+
+    fn transform_region(&self, grid: &mut CellGrid, dirty: &super::Region) {
+        self.apply(grid, Some(dirty), None);
+    }
+
+    fn reseed(&self, seed: u64) {
+        self.rng.reseed(seed);
+    }
+
+    fn set_rng(&self, rng: Box<dyn crate::CellRng>) {
+        self.rng.set(rng);
+    }
+
+    fn remap_symbols(&mut self, map: &std::collections::HashMap<u8, u8>) {
+        PatternRule::remap_symbols(self, map);
+    }
+
+    fn set_frozen_symbols(&mut self, symbols: &[u8]) {
+        self.frozen = symbols.to_vec();
+    }
+
+    fn shift_columns(&self, dx: i64) {
+        let mut cooldowns = self
+            .cooldowns
+            .0
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        super::shift_columns(&mut cooldowns, dx);
+    }
 
-        // shuffle the replacements
-        replacements.shuffle(&mut rand::thread_rng());
-        // then re-sort them by priority
-        replacements.sort_by(|rule1, rule2| {
-            if let Some(rep1) = rule1.first() {
-                if let Some(rep2) = rule2.first() {
-                    rep2.0
-                        .partial_cmp(&rep1.0)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                } else {
-                    std::cmp::Ordering::Equal
+    fn set_enabled(&mut self, index: usize, enabled: bool) -> bool {
+        if index >= self.patterns.len() {
+            return false;
+        }
+        if enabled {
+            self.disabled.remove(&index);
+        } else {
+            self.disabled.insert(index);
+        }
+        true
+    }
+
+    fn toggles(&self) -> Vec<(String, bool)> {
+        (0..self.patterns.len())
+            .map(|index| (format!("pattern {index}"), !self.disabled.contains(&index)))
+            .collect()
+    }
+}
+
+/// Returns the range of pattern origins (along one dimension) of which the pattern overlaps the dirty range, limited to ```stop```.
+/// If the pattern could overlap the dirty range by wrapping around the grid edge, all origins up to ```stop``` are returned.
+fn origin_range(
+    dirty: Option<&std::ops::Range<usize>>,
+    pattern_len: usize,
+    stop: usize,
+) -> std::ops::Range<usize> {
+    match dirty {
+        Some(dirty) if dirty.start + 1 >= pattern_len => {
+            (dirty.start + 1 - pattern_len)..dirty.end.min(stop)
+        }
+        _ => 0..stop,
+    }
+}
+
+impl PatternRule {
+    /// Applies this rule to the passed grid. If a dirty region is passed, only patterns overlapping this region are considered.
+    /// If a deadline is passed, the scan stops once it has passed, and only the replacements found so far are committed.
+    /// Returns wether the entire grid was scanned.
+    fn apply(
+        &self,
+        grid: &mut CellGrid,
+        dirty: Option<&super::Region>,
+        deadline: Option<std::time::Instant>,
+    ) -> bool {
+        let (rows, cols) = grid.size();
+        let mut cooldowns = self
+            .patterns
+            .iter()
+            .any(|pattern| pattern.cooldown > 0)
+            .then(|| self.cooldowns.tick(rows, cols));
+
+        let (mut replacements, complete) = {
+            trace_span!(
+                "scan",
+                rows = rows,
+                cols = cols,
+                patterns = self.patterns.len()
+            );
+            match deadline {
+                Some(deadline) => {
+                    self.collect_replacements_until(grid, cooldowns.as_deref(), deadline)
+                }
+                None => (
+                    self.collect_replacements(grid, dirty, cooldowns.as_deref()),
+                    true,
+                ),
+            }
+        };
+
+        trace_span!("sort", replacements = replacements.len());
+        match self.priority_mode {
+            PriorityMode::Strict => {
+                // shuffle the replacements
+                replacements.shuffle(&mut *self.rng.lock());
+                // then re-sort them by priority
+                replacements.sort_by(|rule1, rule2| {
+                    if let Some(rep1) = rule1.replacements.first() {
+                        if let Some(rep2) = rule2.replacements.first() {
+                            rep2.0.total_cmp(&rep1.0)
+                        } else {
+                            std::cmp::Ordering::Equal
+                        }
+                    } else {
+                        std::cmp::Ordering::Equal
+                    }
+                });
+                if self.ordering == PatternOrdering::RoundRobin {
+                    replacements = round_robin(replacements, &mut *self.rng.lock());
                 }
-            } else {
-                std::cmp::Ordering::Equal
             }
-        });
+            PriorityMode::Softmax { temperature } => {
+                replacements = softmax_order(
+                    replacements,
+                    |group| self.patterns[group.pattern].priority,
+                    temperature,
+                    &mut *self.rng.lock(),
+                );
+            }
+        }
 
+        trace_span!("commit", replacements = replacements.len());
         let mut mutated = grid::Grid::new(rows, cols);
         mutated.fill(false);
 
         for rep_group in replacements.iter() {
-            if rep_group
-                .iter()
-                .all(|(_, row, col, _)| !mutated[*row][*col])
-            {
-                for (_, row, col, rep) in rep_group.iter().copied() {
+            // replacements overwriting frozen symbols are skipped entirely, so nothing moves into a wall
+            if rep_group.replacements.iter().all(|&(_, row, col, rep)| {
+                !mutated[row][col]
+                    && (grid[row][col] == rep || !self.frozen.contains(&grid[row][col]))
+            }) {
+                for (_, row, col, rep) in rep_group.replacements.iter().copied() {
                     grid[row][col] = rep;
                     mutated[row][col] = true;
                 }
+                if let (Some(cooldowns), Some((row, col, cooldown))) =
+                    (cooldowns.as_mut(), rep_group.cooldown)
+                {
+                    cooldowns[row][col] = cooldown;
+                }
             }
         }
+        complete
     }
-}
 
+    /// Searches the grid like [Self::collect_replacements], but one band after another on the calling thread, stopping once the deadline has passed.
+    /// The scan starts at a random band and wraps around, so all parts of the grid get their turn even if the scan never completes.
+    /// Returns the replacements found so far and wether all bands were scanned.
+    fn collect_replacements_until(
+        &self,
+        grid: &CellGrid,
+        cooldowns: Option<&grid::Grid<u32>>,
+        deadline: std::time::Instant,
+    ) -> (ReplacementCollection, bool) {
+        let (rows, cols) = grid.size();
+        if rows == 0 || cols == 0 {
+            return (Vec::new(), true);
+        }
 
-#[test]
-fn to_from_string_test(){
-    use crate::rule;
-    let pattern = rule::Pattern{
-                chance: 1.0,
-                priority: 1.0,
-                before: grid::grid![[0, 0, 1][0, 1, 1]],
-                after: grid::grid![[127, 127, 0][1, 127, 127]],
-            };
-    let pattern2 = rule::Pattern::from(pattern.to_string().as_str());
-    assert_eq!(pattern.chance, pattern2.chance);
-    assert_eq!(pattern.priority, pattern2.priority);
-    assert_eq!(pattern.before.rows(), pattern2.before.rows());
-    assert_eq!(pattern.before.cols(), pattern2.before.cols());
-    assert_eq!(pattern.after.rows(), pattern2.after.rows());
-    assert_eq!(pattern.after.cols(), pattern2.after.cols());
-    for (c1, c2) in pattern.before.iter().zip(pattern2.before.iter()) {
-        assert_eq!(*c1, *c2);
+        let bounds = self
+            .patterns
+            .iter()
+            .map(|pattern| PatternBounds::new(pattern, rows, cols, self, None))
+            .collect::<Vec<_>>();
+
+        let bands = rows.div_ceil(BAND_HEIGHT);
+        let (start, mut band_rngs) = {
+            let mut rng = self.rng.lock();
+            (
+                rng.gen_range(0..bands),
+                (0..bands)
+                    .map(|band| Some(rng.fork(band as u64)))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let mut res = Vec::new();
+        for (scanned, band) in (start..bands).chain(0..start).enumerate() {
+            if scanned > 0 && std::time::Instant::now() >= deadline {
+                return (res, false);
+            }
+            // every band is only scanned once
+            if let Some(rng) = band_rngs[band].take() {
+                res.extend(self.scan_band(grid, &bounds, cooldowns, band, rng));
+            }
+        }
+        (res, true)
     }
-    for (c1, c2) in pattern.after.iter().zip(pattern2.after.iter()) {
-        assert_eq!(*c1, *c2);
+
+    /// Searches the grid for all pattern occurences that pass their chance roll and collects the resulting replacements.
+    /// If a dirty region is passed, only patterns overlapping this region are considered.
+    ///
+    /// The grid is split into bands of rows that are scanned in parallel, each band checking all patterns.
+    /// Each band uses its own random number generator [forked](crate::CellRng::fork) from the rule's generator, so seeded results do not depend on the number of threads.
+    /// The chance roll is only performed for positions that match the first non-wildcard cell of a pattern,
+    /// so the sequence of random numbers (and therefore the result for a certain seed) may change whenever the scan is changed.
+    ///
+    /// The countdowns of the cells have to be passed if any pattern has a cooldown.
+    fn collect_replacements(
+        &self,
+        grid: &CellGrid,
+        dirty: Option<&super::Region>,
+        cooldowns: Option<&grid::Grid<u32>>,
+    ) -> ReplacementCollection {
+        let (rows, cols) = grid.size();
+        if rows == 0 || cols == 0 {
+            return Vec::new();
+        }
+
+        // everything that only depends on the pattern and the grid size is calculated once per step
+        let bounds = self
+            .patterns
+            .iter()
+            .map(|pattern| PatternBounds::new(pattern, rows, cols, self, dirty))
+            .collect::<Vec<_>>();
+
+        let bands = rows.div_ceil(BAND_HEIGHT);
+        let band_rngs = {
+            let mut rng = self.rng.lock();
+            (0..bands)
+                .map(|band| rng.fork(band as u64))
+                .collect::<Vec<_>>()
+        };
+
+        #[cfg(feature = "parallel")]
+        if crate::parallelism::is_parallel() {
+            return band_rngs
+                .into_par_iter()
+                .enumerate()
+                .flat_map_iter(|(band, rng)| self.scan_band(grid, &bounds, cooldowns, band, rng))
+                .collect();
+        }
+
+        band_rngs
+            .into_iter()
+            .enumerate()
+            .flat_map(|(band, rng)| self.scan_band(grid, &bounds, cooldowns, band, rng))
+            .collect()
     }
-}
 
-#[test]
-fn pattern_rule_test(){
-    use crate::rule;
-    use rule::Rule;
-    let rule = rule::PatternRule::from_patterns(
-        &[
-            rule::Pattern{
+    /// Scans a single band of rows for occurences of all patterns, using the passed random number generator forked for this band.
+    fn scan_band(
+        &self,
+        grid: &CellGrid,
+        bounds: &[PatternBounds],
+        cooldowns: Option<&grid::Grid<u32>>,
+        band: usize,
+        mut rng: Box<dyn crate::CellRng>,
+    ) -> ReplacementCollection {
+        let (rows, cols) = grid.size();
+        // the last band also contains the origins beyond the last row, which only exist for sink boundaries
+        let band_rows = band * BAND_HEIGHT..if (band + 1) * BAND_HEIGHT >= rows {
+            usize::MAX
+        } else {
+            (band + 1) * BAND_HEIGHT
+        };
+        let mut partial_res = Vec::new();
+
+        for (index, (pattern, bounds)) in self.patterns.iter().zip(bounds).enumerate() {
+            if self.disabled.contains(&index) {
+                continue;
+            }
+            let (p_rows, p_cols) = pattern.after.size();
+            let cell = |row: usize, col: usize| bounds.cell(grid, row, col);
+
+            for row in bounds.rows.start.max(band_rows.start)..bounds.rows.end.min(band_rows.end) {
+                'inner_loop: for col in bounds.cols.clone() {
+                    // cheaply discard most positions by checking a single cell first
+                    if let Some((a_row, a_col, anchor)) = bounds.anchor {
+                        if cell(row + a_row, col + a_col) != anchor {
+                            continue 'inner_loop;
+                        }
+                    }
+
+                    // possibly randomly stop to adhere to pattern chance, unless it depends on the matched cells
+                    if pattern.chance_per_match.is_none()
+                        && pattern.chance < 1.0
+                        && rng.next_f32() > pattern.chance
+                    {
+                        continue 'inner_loop;
+                    }
+
+                    // check if pattern is applicable
+                    for row_del in 0..p_rows {
+                        for col_del in 0..p_cols {
+                            if pattern.before[row_del][col_del] != 127
+                                && cell(row + row_del, col + col_del)
+                                    != pattern.before[row_del][col_del]
+                            {
+                                continue 'inner_loop;
+                            }
+                        }
+                    }
+
+                    // roll for chances that grow with the number of matching cells
+                    if let Some((symbol, factor)) = pattern.chance_per_match {
+                        let count = (0..p_rows)
+                            .flat_map(|row_del| (0..p_cols).map(move |col_del| (row_del, col_del)))
+                            .filter(|&(row_del, col_del)| {
+                                cell(row + row_del, col + col_del) == symbol
+                            })
+                            .count();
+                        let chance = (pattern.chance + count as f32 * factor).clamp(0., 1.);
+                        if chance < 1.0 && rng.next_f32() > chance {
+                            continue 'inner_loop;
+                        }
+                    }
+
+                    // skip positions that are still cooling down from an earlier application
+                    let cooldown = match cooldowns {
+                        Some(cooldowns) if pattern.cooldown > 0 => {
+                            let (a_row, a_col) = bounds
+                                .anchor
+                                .map_or((0, 0), |(a_row, a_col, _)| (a_row, a_col));
+                            match (
+                                locate(row + a_row, rows, bounds.row_sink),
+                                locate(col + a_col, cols, bounds.col_sink),
+                            ) {
+                                (Ok(c_row), Ok(c_col)) if cooldowns[c_row][c_col] > 0 => {
+                                    continue 'inner_loop;
+                                }
+                                (Ok(c_row), Ok(c_col)) => Some((c_row, c_col, pattern.cooldown)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    };
+
+                    // if we arrive here, the pattern fits
+                    let mut rep_group = Vec::with_capacity(p_rows * p_cols);
+                    // push replacements as dictated by the pattern
+                    for row_del in 0..p_rows {
+                        for col_del in 0..p_cols {
+                            let rep = pattern.after[row_del][col_del];
+                            // make sure to not replace wild cards
+                            if rep == 127 {
+                                continue;
+                            }
+                            match (
+                                self.write_target(row + row_del, rows, bounds.row_sink),
+                                self.write_target(col + col_del, cols, bounds.col_sink),
+                            ) {
+                                (Some(t_row), Some(t_col)) => {
+                                    rep_group.push((pattern.priority, t_row, t_col, rep))
+                                }
+                                _ if self.write_boundary == WriteBoundary::Reject
+                                    || bounds.beyond_wall(
+                                        row + row_del,
+                                        col + col_del,
+                                        (rows, cols),
+                                    ) =>
+                                {
+                                    continue 'inner_loop;
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    partial_res.push(ReplacementGroup {
+                        pattern: index,
+                        replacements: rep_group,
+                        cooldown,
+                    });
+                }
+            }
+        }
+        partial_res
+    }
+
+    /// Resolves the index a replacement is written to along a dimension of the passed length, see [locate],
+    /// or returns [None] if it lies outside the grid and is not wrapped around according to the [WriteBoundary].
+    #[inline]
+    fn write_target(&self, index: usize, len: usize, sink: Option<(usize, u8)>) -> Option<usize> {
+        if sink.is_none() && index >= len && self.write_boundary != WriteBoundary::Wrap {
+            return None;
+        }
+        locate(index, len, sink).ok()
+    }
+}
+
+impl ReplacementGroup {
+    /// Returns the priority of the replacements, if there are any.
+    fn priority(&self) -> Option<f32> {
+        self.replacements.first().map(|(priority, ..)| *priority)
+    }
+}
+
+/// Reorders replacement groups that are sorted by priority so that within each priority, the patterns take turns, see [PatternOrdering::RoundRobin].
+/// The groups of each pattern keep their order, the order of the patterns in each round is random.
+fn round_robin(groups: ReplacementCollection, rng: &mut impl Rng) -> ReplacementCollection {
+    let mut res = Vec::with_capacity(groups.len());
+    let mut groups = groups.into_iter().peekable();
+    while let Some(first) = groups.next() {
+        let priority = first.priority().map(f32::to_bits);
+        // split the groups of this priority by pattern, reversed to take them from the back
+        let mut tier = vec![first];
+        while let Some(group) =
+            groups.next_if(|group| group.priority().map(f32::to_bits) == priority)
+        {
+            tier.push(group);
+        }
+        let mut by_pattern: Vec<(usize, Vec<ReplacementGroup>)> = Vec::new();
+        for group in tier.into_iter().rev() {
+            match by_pattern
+                .iter_mut()
+                .find(|(pattern, _)| *pattern == group.pattern)
+            {
+                Some((_, pattern_groups)) => pattern_groups.push(group),
+                None => by_pattern.push((group.pattern, vec![group])),
+            }
+        }
+        // each round takes one group of each pattern that has some left
+        while !by_pattern.is_empty() {
+            by_pattern.shuffle(rng);
+            res.extend(
+                by_pattern
+                    .iter_mut()
+                    .filter_map(|(_, pattern_groups)| pattern_groups.pop()),
+            );
+            by_pattern.retain(|(_, pattern_groups)| !pattern_groups.is_empty());
+        }
+    }
+    res
+}
+
+/// Orders replacement groups randomly, each next group being picked with a probability proportional to ```exp(priority / temperature)```, see [PriorityMode::Softmax].
+///
+/// Instead of drawing the groups one by one, each group gets the key ```priority / temperature``` plus Gumbel distributed noise, and the groups are sorted by descending key,
+/// which results in the same distribution of orders.
+fn softmax_order(
+    groups: ReplacementCollection,
+    priority: impl Fn(&ReplacementGroup) -> f32,
+    temperature: f32,
+    rng: &mut impl Rng,
+) -> ReplacementCollection {
+    let mut keyed = groups
+        .into_iter()
+        .map(|group| {
+            let noise = -(-rng.gen::<f64>().ln()).ln();
+            (priority(&group) as f64 / temperature as f64 + noise, group)
+        })
+        .collect::<Vec<_>>();
+    keyed.sort_by(|(key1, _), (key2, _)| key2.total_cmp(key1));
+    keyed.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Wraps the passed index around a dimension of the passed length.
+/// Cheaper than a plain modulo for the common case of indices that are already in bounds.
+#[inline]
+fn wrap(index: usize, len: usize) -> usize {
+    if index < len {
+        index
+    } else {
+        index % len
+    }
+}
+
+/// Resolves an index relative to the shifted pattern origins along a dimension of the passed length.
+///
+/// Without a sink boundary, origins are not shifted and the index is wrapped around the dimension.
+/// Otherwise, the index is shifted back by the offset of the sink and its symbol is returned as the error value if it lies outside the grid.
+#[inline]
+fn locate(index: usize, len: usize, sink: Option<(usize, u8)>) -> Result<usize, u8> {
+    match sink {
+        None => Ok(wrap(index, len)),
+        Some((offset, symbol)) => index
+            .checked_sub(offset)
+            .filter(|&index| index < len)
+            .ok_or(symbol),
+    }
+}
+
+/// The positions at which a pattern needs to be checked during one step, and its first non-wildcard cell.
+struct PatternBounds {
+    /// The rows the top left corner of the pattern may be placed in, shifted by the offset of a sink boundary.
+    rows: std::ops::Range<usize>,
+    /// The columns the top left corner of the pattern may be placed in, shifted by the offset of a sink boundary.
+    cols: std::ops::Range<usize>,
+    /// For a sink boundary, the number of rows the origins are shifted by so the pattern may start above the grid, and the symbol of the sink.
+    row_sink: Option<(usize, u8)>,
+    /// For a sink boundary, the number of columns the origins are shifted by so the pattern may start left of the grid, and the symbol of the sink.
+    col_sink: Option<(usize, u8)>,
+    /// Wether the row boundary is a symbol matched with [PatternBoundaryMode::MatchSymbol], which works like a sink that can not be written to.
+    row_wall: bool,
+    /// Wether the column boundary is a symbol matched with [PatternBoundaryMode::MatchSymbol], which works like a sink that can not be written to.
+    col_wall: bool,
+    /// Row, column and value of the first cell of the pattern that is not a wildcard, if any.
+    anchor: Option<(usize, usize, u8)>,
+}
+
+impl PatternBounds {
+    /// Calculates the bounds of the passed pattern of the passed rule in a grid of the passed size.
+    fn new(
+        pattern: &Pattern,
+        rows: usize,
+        cols: usize,
+        rule: &PatternRule,
+        dirty: Option<&super::Region>,
+    ) -> Self {
+        // returns the exclusive end of the (shifted) origins along one dimension and the sink, if any
+        let stop = |boundary: BoundaryBehaviour, len: usize, pattern_len: usize| match boundary {
+            BoundaryBehaviour::Periodic => (len, None),
+            BoundaryBehaviour::Symbol(_)
+                if rule.boundary_mode == PatternBoundaryMode::SkipOutOfBounds =>
+            {
+                ((len + 1).saturating_sub(pattern_len), None)
+            }
+            BoundaryBehaviour::Clamp => ((len + 1).saturating_sub(pattern_len), None),
+            BoundaryBehaviour::Symbol(symbol) | BoundaryBehaviour::Sink(symbol) => {
+                let offset = pattern_len.saturating_sub(1);
+                (len + offset, Some((offset, symbol)))
+            }
+        };
+        let (row_stop, row_sink) = stop(rule.row_boundary, rows, pattern.before.rows());
+        let (col_stop, col_sink) = stop(rule.col_boundary, cols, pattern.before.cols());
+
+        let wall = |boundary: BoundaryBehaviour| {
+            matches!(boundary, BoundaryBehaviour::Symbol(_))
+                && rule.boundary_mode == PatternBoundaryMode::MatchSymbol
+        };
+
+        // the dirty region has to be shifted along with the origins
+        let shift = |range: &std::ops::Range<usize>, sink: Option<(usize, u8)>| {
+            let offset = sink.map_or(0, |(offset, _)| offset);
+            range.start + offset..range.end + offset
+        };
+
+        Self {
+            rows: origin_range(
+                dirty.map(|d| shift(&d.rows, row_sink)).as_ref(),
+                pattern.before.rows(),
+                row_stop,
+            ),
+            cols: origin_range(
+                dirty.map(|d| shift(&d.cols, col_sink)).as_ref(),
+                pattern.before.cols(),
+                col_stop,
+            ),
+            row_sink,
+            col_sink,
+            row_wall: wall(rule.row_boundary),
+            col_wall: wall(rule.col_boundary),
+            anchor: (0..pattern.before.rows())
+                .flat_map(|row| (0..pattern.before.cols()).map(move |col| (row, col)))
+                .map(|(row, col)| (row, col, pattern.before[row][col]))
+                .find(|&(_, _, cell)| cell != 127),
+        }
+    }
+
+    /// Returns the cell at the passed position relative to the shifted origins, or the symbol of the sink boundary it lies beyond.
+    #[inline]
+    fn cell(&self, grid: &CellGrid, row: usize, col: usize) -> u8 {
+        match (
+            locate(row, grid.rows(), self.row_sink),
+            locate(col, grid.cols(), self.col_sink),
+        ) {
+            (Ok(row), Ok(col)) => grid[row][col],
+            // the symbol of the row boundary takes precedence if need be
+            (Err(symbol), _) | (Ok(_), Err(symbol)) => symbol,
+        }
+    }
+
+    /// Returns wether the passed position relative to the shifted origins lies beyond a boundary matched with [PatternBoundaryMode::MatchSymbol] in a grid of the passed size.
+    #[inline]
+    fn beyond_wall(&self, row: usize, col: usize, (rows, cols): (usize, usize)) -> bool {
+        (self.row_wall && locate(row, rows, self.row_sink).is_err())
+            || (self.col_wall && locate(col, cols, self.col_sink).is_err())
+    }
+
+    /// Returns the shifted origins of all placements of the passed pattern that cover the passed cell of a grid of the passed size.
+    fn placements(
+        &self,
+        pattern: &Pattern,
+        (rows, cols): (usize, usize),
+        row: usize,
+        col: usize,
+    ) -> Vec<(usize, usize)> {
+        // returns the origins along one dimension from which the pattern covers the passed index
+        let covering = |origins: std::ops::Range<usize>, len, pattern_len, sink, index| {
+            origins
+                .filter(|&origin| {
+                    (0..pattern_len).any(|del| locate(origin + del, len, sink) == Ok(index))
+                })
+                .collect::<Vec<_>>()
+        };
+        let p_rows = covering(
+            self.rows.clone(),
+            rows,
+            pattern.before.rows(),
+            self.row_sink,
+            row,
+        );
+        let p_cols = covering(
+            self.cols.clone(),
+            cols,
+            pattern.before.cols(),
+            self.col_sink,
+            col,
+        );
+        p_rows
+            .into_iter()
+            .flat_map(|p_row| p_cols.iter().map(move |&p_col| (p_row, p_col)))
+            .collect()
+    }
+}
+
+#[test]
+fn to_from_string_test() {
+    use crate::rule;
+    let pattern = rule::Pattern {
+        chance: 1.0,
+        priority: 1.0,
+        before: grid::grid![[0, 0, 1][0, 1, 1]],
+        after: grid::grid![[127, 127, 0][1, 127, 127]],
+        ..Default::default()
+    };
+    let pattern2 = rule::Pattern::from(pattern.to_string().as_str());
+    assert_eq!(pattern.chance, pattern2.chance);
+    assert_eq!(pattern.priority, pattern2.priority);
+    assert_eq!(pattern.before.rows(), pattern2.before.rows());
+    assert_eq!(pattern.before.cols(), pattern2.before.cols());
+    assert_eq!(pattern.after.rows(), pattern2.after.rows());
+    assert_eq!(pattern.after.cols(), pattern2.after.cols());
+    for (c1, c2) in pattern.before.iter().zip(pattern2.before.iter()) {
+        assert_eq!(*c1, *c2);
+    }
+    for (c1, c2) in pattern.after.iter().zip(pattern2.after.iter()) {
+        assert_eq!(*c1, *c2);
+    }
+}
+
+#[test]
+fn pattern_rule_test() {
+    use crate::{rule, testing};
+    let rule = rule::PatternRule::from_patterns(
+        &[
+            rule::Pattern {
                 chance: 1.0,
                 priority: 1.0,
                 before: grid::grid![[1][0]],
                 after: grid::grid![[0][1]],
+                ..Default::default()
             },
-            rule::Pattern{
+            rule::Pattern {
                 chance: 1.0,
                 priority: 0.5,
                 before: grid::grid![[0, 1][1, 0]],
                 after: grid::grid![[1, 1][0, 0]],
+                ..Default::default()
             },
         ],
         rule::BoundaryBehaviour::Symbol(126),
         rule::BoundaryBehaviour::Symbol(126),
     );
 
-    let mut grid = grid::grid![[0, 1][1, 0][0, 0]];
-    rule.transform(&mut grid);
-    assert_eq!(grid, grid::grid![[0, 0][0, 1][1, 0]]);
+    let rule2 = rule::PatternRule::from(rule.to_string().as_str());
+
+    for rule in [&rule, &rule2] {
+        testing::assert_rule_step(
+            rule,
+            "01
+             10
+             00",
+            "00
+             01
+             10",
+        );
+        testing::assert_rule_evolution(
+            rule,
+            "01
+             10
+             00",
+            "00
+             00
+             11",
+            2,
+            0,
+        );
+    }
+}
+
+#[test]
+fn band_scan_test() {
+    use crate::rule;
+    use rand::Rng;
+
+    let patterns = [
+        rule::Pattern {
+            chance: 1.0,
+            priority: 2.0,
+            before: grid::grid![[59][0]],
+            after: grid::grid![[0][59]],
+            ..Default::default()
+        },
+        rule::Pattern {
+            chance: 1.0,
+            priority: 1.0,
+            before: grid::grid![[59, 127][59, 0]],
+            after: grid::grid![[0, 127][127, 59]],
+            ..Default::default()
+        },
+    ];
+    let mut rng = rand::thread_rng();
+    let grid = CellGrid::from_vec(
+        (0..101 * 37)
+            .map(|_| if rng.gen_bool(0.3) { 59 } else { 0 })
+            .collect(),
+        37,
+    );
+
+    for boundary in [
+        BoundaryBehaviour::Symbol(126),
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Clamp,
+    ] {
+        let rule = rule::PatternRule::from_patterns(&patterns, boundary, boundary);
+
+        // reference: a simple sequential scan over all positions
+        let mut expected = Vec::new();
+        for pattern in &patterns {
+            for row in 0..grid.rows() {
+                for col in 0..grid.cols() {
+                    let fits = (0..pattern.before.rows()).all(|r| {
+                        (0..pattern.before.cols()).all(|c| {
+                            let cell = match boundary {
+                                BoundaryBehaviour::Periodic => {
+                                    grid.get((row + r) % grid.rows(), (col + c) % grid.cols())
+                                }
+                                BoundaryBehaviour::Symbol(_) | BoundaryBehaviour::Clamp => {
+                                    grid.get(row + r, col + c)
+                                }
+                                BoundaryBehaviour::Sink(_) => unreachable!(),
+                            };
+                            pattern.before[r][c] == 127 || cell == Some(&pattern.before[r][c])
+                        })
+                    });
+                    if fits {
+                        expected.push(
+                            (0..pattern.after.rows())
+                                .flat_map(|r| (0..pattern.after.cols()).map(move |c| (r, c)))
+                                .filter(|&(r, c)| pattern.after[r][c] != 127)
+                                .map(|(r, c)| {
+                                    (
+                                        pattern.priority,
+                                        (row + r) % grid.rows(),
+                                        (col + c) % grid.cols(),
+                                        pattern.after[r][c],
+                                    )
+                                })
+                                .collect::<Vec<_>>(),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut res = rule
+            .collect_replacements(&grid, None, None)
+            .into_iter()
+            .map(|group| group.replacements)
+            .collect::<Vec<_>>();
+        let key = |group: &Vec<(f32, usize, usize, u8)>| {
+            group
+                .iter()
+                .map(|&(_, r, c, s)| (r, c, s))
+                .collect::<Vec<_>>()
+        };
+        res.sort_by_key(key);
+        expected.sort_by_key(key);
+        assert_eq!(res, expected);
+    }
+
+    // seeded rules behave identically, even with random chances
+    let rule = rule::PatternRule::from_patterns(
+        &patterns.map(|pattern| rule::Pattern {
+            chance: 0.5,
+            ..pattern
+        }),
+        BoundaryBehaviour::Symbol(126),
+        BoundaryBehaviour::Periodic,
+    );
+    let rule2 = rule.clone();
+    rule.reseed(42);
+    rule2.reseed(42);
+    let (mut grid1, mut grid2) = (grid.clone(), grid);
+    for _ in 0..5 {
+        rule.transform(&mut grid1);
+        rule2.transform(&mut grid2);
+        assert_eq!(grid1, grid2);
+    }
+}
+
+#[test]
+fn strict_parsing_test() {
+    let text = "Periodic;\n\nPeriodic;\n\n1;\n1;\na;\nb;\n\n1;\n1;\nab\nc\u{1F980};\n**\n**;\n\n";
+
+    // lenient parsing replaces the emoji with an empty cell
+    let rule = PatternRule::parse(text, false).unwrap();
+    assert_eq!(rule.patterns.len(), 2);
+    assert_eq!(rule.patterns[1].before, grid::grid![[10, 11][12, 0]]);
+    assert_eq!(
+        PatternRule::from(text).patterns[1].before,
+        rule.patterns[1].before
+    );
+
+    // strict parsing reports its position
+    match PatternRule::parse(text, true) {
+        Err(crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::PatternRule,
+            line: 13,
+            column: 2,
+            ..
+        }) => {}
+        res => panic!("Expected parse error, got {res:?}"),
+    }
+}
+
+#[test]
+fn parse_error_test() {
+    let header = "Periodic;\n\nPeriodic;\n\n";
+
+    // rows of different length
+    assert!(matches!(
+        PatternRule::parse(&format!("{header}1;\n1;\nab\nc;\nab\ncd;\n\n"), true),
+        Err(crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::PatternRule,
+            line: 8,
+            ..
+        })
+    ));
+
+    // missing after grid
+    assert!(matches!(
+        PatternRule::parse(&format!("{header}1;\n1;\nab;\n\n"), true),
+        Err(crate::CelluminaError::ParseError { line: 7, .. })
+    ));
+
+    // after grid of a different size
+    assert!(matches!(
+        PatternRule::parse(&format!("{header}1;\n1;\nab;\na\nb;\n\n"), true),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (1, 2),
+            got: (2, 1)
+        })
+    ));
+
+    // patterns created in code are validated as a whole
+    let rule = PatternRule::from_patterns(
+        &[
+            Pattern {
+                before: grid::Grid::new(0, 0),
+                after: grid::Grid::new(0, 0),
+                ..Default::default()
+            },
+            Pattern::default(),
+            Pattern {
+                before: grid::grid![[1, 0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            },
+        ],
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
+    );
+    match rule.validate() {
+        Err(crate::CelluminaError::ValidationError(issues)) => assert_eq!(
+            issues,
+            [
+                crate::ValidationIssue::EmptyPattern { pattern: 0 },
+                crate::ValidationIssue::PatternSizeMismatch {
+                    pattern: 2,
+                    before: (1, 2),
+                    after: (2, 1)
+                }
+            ]
+        ),
+        res => panic!("Expected validation error, got {res:?}"),
+    }
+}
+
+#[test]
+fn pattern_values_test() {
+    let pattern = |chance: f32, priority: f32| Pattern {
+        chance,
+        priority,
+        before: grid::grid![[1]],
+        after: grid::grid![[0]],
+        ..Default::default()
+    };
+
+    // chances are clamped when creating a rule, invalid priorities are reported by validation
+    let rule = PatternRule::from_patterns(
+        &[
+            pattern(-3., 0.),
+            pattern(2.5, 1.),
+            pattern(f32::NAN, f32::NAN),
+            pattern(0.5, f32::INFINITY),
+        ],
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Periodic,
+    );
+    assert_eq!(
+        rule.patterns
+            .iter()
+            .map(|pattern| pattern.chance)
+            .collect::<Vec<f32>>(),
+        [0., 1., 0., 0.5]
+    );
+    match rule.validate() {
+        Err(crate::CelluminaError::ValidationError(issues)) => assert_eq!(
+            issues,
+            [
+                crate::ValidationIssue::InvalidPriority { pattern: 2 },
+                crate::ValidationIssue::InvalidPriority { pattern: 3 },
+            ]
+        ),
+        res => panic!("Expected validation error, got {res:?}"),
+    }
+
+    // the same holds for parsing
+    let header = "Periodic;\n\nPeriodic;\n\n";
+    let rule = PatternRule::parse(
+        &format!("{header}-3;\n1;\na;\nb;\n\n7;\n0;\na;\nb;\n\n"),
+        true,
+    )
+    .unwrap();
+    assert_eq!(rule.patterns[0].chance, 0.);
+    assert_eq!(rule.patterns[1].chance, 1.);
+    for priority in ["NaN", "inf", "-inf"] {
+        assert!(matches!(
+            PatternRule::parse(&format!("{header}1;\n{priority};\na;\nb;\n\n"), true),
+            Err(crate::CelluminaError::ParseError { line: 6, .. })
+        ));
+    }
+
+    // and deserializing
+    let toml = |chance: &str, priority: &str| {
+        format!("chance = {chance}\npriority = {priority}\nbefore = {{ data = [1], cols = 1 }}\nafter = {{ data = [0], cols = 1 }}\n")
+    };
+    assert_eq!(
+        toml::from_str::<Pattern>(&toml("-3.0", "1.0"))
+            .unwrap()
+            .chance,
+        0.
+    );
+    assert_eq!(
+        toml::from_str::<Pattern>(&toml("4.0", "1.0"))
+            .unwrap()
+            .chance,
+        1.
+    );
+    assert!(toml::from_str::<Pattern>(&toml("1.0", "nan")).is_err());
+    assert!(toml::from_str::<Pattern>(&toml("1.0", "-inf")).is_err());
+}
+
+#[test]
+fn sink_test() {
+    use crate::rule::{self, Rule};
+
+    // sand falls through the bottom edge and vanishes
+    let rule = rule::PatternRule::from_patterns(
+        &[Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0][1]],
+            ..Default::default()
+        }],
+        BoundaryBehaviour::Sink(0),
+        BoundaryBehaviour::blocking_boundary(),
+    );
+    let mut grid = grid::grid![[1, 0][1, 1][0, 0][1, 1]];
     rule.transform(&mut grid);
-    assert_eq!(grid, grid::grid![[0, 0][0, 0][1, 1]]);
+    assert_eq!(grid, grid::grid![[1, 0][0, 0][1, 1][0, 0]]);
+    let mut population = grid.iter().filter(|&&cell| cell == 1).count();
+    for _ in 0..4 {
+        rule.transform(&mut grid);
+        let next = grid.iter().filter(|&&cell| cell == 1).count();
+        assert!(next <= population);
+        population = next;
+    }
+    assert_eq!(population, 0);
 
-    let rule2 = rule::PatternRule::from(rule.to_string().as_str());
+    // the sink symbol does not match the pattern, so nothing enters through the top edge
+    let mut rising = rule::PatternRule::from_patterns(
+        &[Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[1][1]],
+            ..Default::default()
+        }],
+        BoundaryBehaviour::Sink(0),
+        BoundaryBehaviour::Sink(0),
+    );
+    let mut grid = CellGrid::new(3, 3);
+    rising.transform(&mut grid);
+    assert_eq!(grid, CellGrid::new(3, 3));
+    rising.remap_symbols(&std::collections::HashMap::from([(0, 1), (1, 2)]));
+    assert_eq!(rising.row_boundary, BoundaryBehaviour::Sink(1));
+
+    // the boundary survives the text format
+    let boundary = BoundaryBehaviour::Sink(59);
+    assert_eq!(
+        BoundaryBehaviour::from(boundary.to_string().as_str()),
+        boundary
+    );
+
+    // an open world with a source at the top, with and without dirty tracking
+    let world = |dirty_tracking: bool| {
+        let builder = crate::AutomatonBuilder::new()
+            .from_grid(CellGrid::new(20, 30))
+            .with_edge_source(rule::Edge::Top, 1, 0.2)
+            .with_pattern(Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            })
+            .with_pattern_edge_behaviour(
+                BoundaryBehaviour::Sink(0),
+                BoundaryBehaviour::blocking_boundary(),
+            )
+            .with_seed(3);
+        if dirty_tracking {
+            builder.with_dirty_tracking().build()
+        } else {
+            builder.build()
+        }
+    };
+    let (mut full, mut dirty) = (world(false), world(true));
+    for _ in 0..60 {
+        full.next_step();
+        dirty.next_step();
+        assert_eq!(full.state(), dirty.state());
+    }
+    // sand entering and leaving balances out, with about a fifth of the cells of each column filled
+    let population = full.state().iter().filter(|&&cell| cell == 1).count();
+    assert!((80..160).contains(&population), "{population} cells");
+}
+
+#[test]
+fn cooldown_test() {
+    use crate::rule::{self, Rule};
+
+    // fires into the right cell, which is cleared after each step
+    let rule = |chance: f32| {
+        rule::PatternRule::from_patterns(
+            &[Pattern {
+                before: grid::grid![[1, 127]],
+                after: grid::grid![[127, 2]],
+                chance,
+                cooldown: 3,
+                ..Default::default()
+            }],
+            BoundaryBehaviour::blocking_boundary(),
+            BoundaryBehaviour::blocking_boundary(),
+        )
+    };
+    let firing_steps = |rule: &rule::PatternRule| {
+        rule.reseed(5);
+        let mut grid = grid::grid![[1, 0]];
+        let mut steps = Vec::new();
+        for step in 0..30 {
+            rule.transform(&mut grid);
+            if grid[0][1] == 2 {
+                steps.push(step);
+                grid[0][1] = 0;
+            }
+        }
+        steps
+    };
+    assert_eq!(
+        firing_steps(&rule(1.0)),
+        (0..30).step_by(3).collect::<Vec<_>>()
+    );
+    let steps = firing_steps(&rule(0.5));
+    assert!(steps.len() > 2);
+    assert!(
+        steps.windows(2).all(|pair| pair[1] - pair[0] >= 3),
+        "{steps:?}"
+    );
+    assert_eq!(steps, firing_steps(&rule(0.5)));
+
+    // rules with cooldowns always check the entire grid
+    assert_eq!(rule(1.0).reach(), None);
+
+    // the cooldown survives both formats, and older text without it still parses
+    let pattern = &rule(1.0).patterns[0];
+    let text = pattern.to_string();
+    assert_eq!(text, "1;\n0;\n1*;\n*2;\n3;\n");
+    assert_eq!(Pattern::from(text.as_str()).cooldown, 3);
+    assert_eq!(Pattern::from("1;\n0;\n1*;\n*2;\n").cooldown, 0);
+    let parsed = rule::PatternRule::parse(&rule(1.0).to_string(), true).unwrap();
+    assert_eq!(parsed.patterns[0].cooldown, 3);
+    assert!(matches!(
+        Pattern::parse("1;\n0;\n1*;\n*2;\nsoon;\n", 1, true, &mut 0),
+        Err(crate::CelluminaError::ParseError { line: 5, .. })
+    ));
+    let text = toml::to_string(pattern).unwrap();
+    assert_eq!(toml::from_str::<Pattern>(&text).unwrap().cooldown, 3);
+}
+
+#[test]
+fn chance_per_match_test() {
+    use crate::rule::{self, Rule};
+
+    // trees (2) catch fire (41) more likely the more burning neighbors they have
+    let rule = rule::PatternRule::from_patterns(
+        &[Pattern {
+            before: grid::grid![[127, 127, 127][127, 2, 127][127, 127, 127]],
+            after: grid::grid![[127, 127, 127][127, 41, 127][127, 127, 127]],
+            chance: 0.0,
+            chance_per_match: Some((41, 0.1)),
+            ..Default::default()
+        }],
+        BoundaryBehaviour::blocking_boundary(),
+        BoundaryBehaviour::blocking_boundary(),
+    );
+    rule.reseed(3);
+    let ignitions = |neighbors: usize| {
+        (0..2000)
+            .filter(|_| {
+                let mut grid = CellGrid::new(3, 3);
+                grid[1][1] = 2;
+                for cell in [(0, 0), (0, 2), (2, 0), (2, 2)].into_iter().take(neighbors) {
+                    grid[cell.0][cell.1] = 41;
+                }
+                rule.transform(&mut grid);
+                grid[1][1] == 41
+            })
+            .count()
+    };
+    assert_eq!(ignitions(0), 0);
+    let (one, three) = (ignitions(1), ignitions(3));
+    assert!(
+        (150..250).contains(&one),
+        "{one} ignitions with one neighbor"
+    );
+    assert!(
+        (500..700).contains(&three),
+        "{three} ignitions with three neighbors"
+    );
+
+    // the chance is clamped
+    let mut grid = grid::grid![[41, 41, 41][41, 2, 41][41, 41, 41]];
+    rule::PatternRule {
+        patterns: vec![Pattern {
+            chance_per_match: Some((41, 0.5)),
+            ..rule.patterns[0].clone()
+        }],
+        ..rule.clone()
+    }
+    .transform(&mut grid);
+    assert_eq!(grid[1][1], 41);
+
+    // the factor survives both formats and symbol remapping
+    let pattern = &rule.patterns[0];
+    let text = pattern.to_string();
+    assert!(text.ends_with(";\n0;\nF:0.1;\n"), "{text}");
+    assert_eq!(
+        Pattern::from(text.as_str()).chance_per_match,
+        Some((41, 0.1))
+    );
+    assert!(Pattern::parse(&text.replace("F:0.1", "F0.1"), 1, true, &mut 0).is_err());
+    let toml_text = toml::to_string(pattern).unwrap();
+    assert_eq!(
+        toml::from_str::<Pattern>(&toml_text)
+            .unwrap()
+            .chance_per_match,
+        Some((41, 0.1))
+    );
+    let mut remapped = rule.clone();
+    remapped.remap_symbols(&std::collections::HashMap::from([(41, 42)]));
+    assert_eq!(remapped.patterns[0].chance_per_match, Some((42, 0.1)));
+
+    // factors have to be finite
+    let mut invalid = rule.clone();
+    invalid.patterns[0].chance_per_match = Some((41, f32::NAN));
+    assert!(matches!(
+        invalid.validate(),
+        Err(crate::CelluminaError::ValidationError(issues))
+            if issues == [crate::ValidationIssue::InvalidChanceFactor { pattern: 0 }]
+    ));
+}
+
+#[test]
+fn round_robin_test() {
+    use crate::rule::{self, Rule};
+
+    // piles of two grains, the top one sliding down either to the left or to the right
+    let piles = 50;
+    let rule = |ordering| {
+        rule::PatternRule::from_patterns(
+            &[
+                Pattern {
+                    before: grid::grid![[127, 1][0, 1]],
+                    after: grid::grid![[127, 0][1, 127]],
+                    ..Default::default()
+                },
+                Pattern {
+                    before: grid::grid![[1, 127][1, 0]],
+                    after: grid::grid![[0, 127][127, 1]],
+                    ..Default::default()
+                },
+            ],
+            BoundaryBehaviour::blocking_boundary(),
+            BoundaryBehaviour::blocking_boundary(),
+        )
+        .with_ordering(ordering)
+    };
+    let collapses = |rule: &rule::PatternRule| {
+        let (mut left, mut right) = (0, 0);
+        for seed in 0..20 {
+            rule.reseed(seed);
+            let mut grid = grid::Grid::from_vec([0, 1, 0].repeat(2 * piles), 3 * piles);
+            rule.transform(&mut grid);
+            for pile in 0..piles {
+                // exactly one grain slid down
+                assert_eq!(grid[0][3 * pile + 1], 0);
+                left += grid[1][3 * pile] as usize;
+                right += grid[1][3 * pile + 2] as usize;
+            }
+        }
+        (left, right)
+    };
+
+    let (left, right) = collapses(&rule(PatternOrdering::RoundRobin));
+    assert_eq!(left + right, 20 * piles);
+    assert!(left.abs_diff(right) < 100, "{left} left, {right} right");
+    // reproducible for a seed
+    assert_eq!(collapses(&rule(PatternOrdering::RoundRobin)), (left, right));
+    assert_eq!(
+        rule(PatternOrdering::Shuffle).ordering,
+        PatternOrdering::default()
+    );
+
+    // within a priority, the patterns take turns, even if one has many more replacements
+    let group = |pattern, priority| ReplacementGroup {
+        pattern,
+        replacements: vec![(priority, 0, 0, 1)],
+        cooldown: None,
+    };
+    let groups = vec![
+        group(0, 2.),
+        group(0, 1.),
+        group(0, 1.),
+        group(0, 1.),
+        group(1, 1.),
+        group(1, 1.),
+    ];
+    let order = round_robin(
+        groups,
+        &mut <rand::rngs::SmallRng as rand::SeedableRng>::seed_from_u64(0),
+    )
+    .iter()
+    .map(|group| (group.pattern, group.priority().unwrap()))
+    .collect::<Vec<_>>();
+    assert_eq!(order[0], (0, 2.));
+    let mut first_round = vec![order[1].0, order[2].0];
+    first_round.sort();
+    assert_eq!(first_round, [0, 1]);
+    let mut second_round = vec![order[3].0, order[4].0];
+    second_round.sort();
+    assert_eq!(second_round, [0, 1]);
+    assert_eq!(order[5], (0, 1.));
+}
+
+#[test]
+fn explain_test() {
+    use super::Region;
+
+    // sand falls down with priority, and a rarer pattern pushes it right
+    let rule = PatternRule::from_patterns(
+        &[
+            Pattern {
+                priority: 1.,
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            },
+            Pattern {
+                chance: 0.5,
+                before: grid::grid![[1, 0]],
+                after: grid::grid![[0, 1]],
+                ..Default::default()
+            },
+        ],
+        BoundaryBehaviour::Symbol(2),
+        BoundaryBehaviour::Symbol(2),
+    );
+    let grid = grid::grid![[1, 0][0, 0]];
+
+    // both patterns match at the sand, but the falling one takes precedence
+    assert_eq!(
+        rule.explain(&grid, 0, 0),
+        vec![
+            Explanation {
+                pattern: 0,
+                row: 0,
+                col: 0,
+                verdict: Verdict::Match {
+                    chance: 1.,
+                    rolls_chance: false,
+                    conflicts: vec![],
+                },
+            },
+            Explanation {
+                pattern: 1,
+                row: 0,
+                col: 0,
+                verdict: Verdict::Match {
+                    chance: 0.5,
+                    rolls_chance: true,
+                    conflicts: vec![(0, 0, 0)],
+                },
+            },
+        ]
+    );
+
+    // neither pattern matches in the bottom right corner
+    assert_eq!(
+        rule.explain(&grid, 1, 1)
+            .into_iter()
+            .map(|explanation| (
+                explanation.pattern,
+                explanation.row,
+                explanation.col,
+                explanation.verdict
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                0,
+                0,
+                1,
+                Verdict::Mismatch {
+                    row: 0,
+                    col: 0,
+                    expected: 1,
+                    found: 0
+                }
+            ),
+            (
+                1,
+                1,
+                0,
+                Verdict::Mismatch {
+                    row: 0,
+                    col: 0,
+                    expected: 1,
+                    found: 0
+                }
+            ),
+        ]
+    );
+    assert!(rule.explain(&grid, 2, 0).is_empty());
+
+    // sink boundaries place patterns beyond the grid
+    let sink = PatternRule::from_patterns(
+        &[Pattern {
+            before: grid::grid![[1][3]],
+            after: grid::grid![[0][127]],
+            ..Default::default()
+        }],
+        BoundaryBehaviour::Sink(3),
+        BoundaryBehaviour::Symbol(2),
+    );
+    let explanations = sink.explain(&grid::grid![[0][1]], 1, 0);
+    assert_eq!(
+        explanations
+            .iter()
+            .map(|e| (e.row, e.col))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (1, 0)]
+    );
+    assert!(matches!(
+        explanations[0].verdict,
+        Verdict::Mismatch { found: 0, .. }
+    ));
+    assert!(matches!(explanations[1].verdict, Verdict::Match { .. }));
+
+    // cooling down positions, without changing the countdowns or the random numbers
+    let cooling = PatternRule::from_patterns(
+        &[Pattern {
+            chance: 0.5,
+            before: grid::grid![[1]],
+            after: grid::grid![[1]],
+            cooldown: 2,
+            ..Default::default()
+        }],
+        BoundaryBehaviour::Symbol(2),
+        BoundaryBehaviour::Symbol(2),
+    );
+    let mut grid = grid::grid![[1, 1, 1, 1]];
+    cooling.reseed(7);
+    let copy = cooling.clone();
+    cooling.transform(&mut grid);
+    copy.transform(&mut grid.clone());
+    let cooled = (0..4)
+        .map(|col| cooling.explain(&grid, 0, col)[0].verdict == Verdict::CoolingDown)
+        .collect::<Vec<_>>();
+    assert!(cooled.contains(&true));
+    for _ in 0..3 {
+        let (mut a, mut b) = (grid.clone(), grid.clone());
+        cooling.explain(&grid, 0, 0);
+        cooling.transform_region(
+            &mut a,
+            &Region {
+                rows: 0..1,
+                cols: 0..4,
+            },
+        );
+        copy.transform(&mut b);
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn pattern_images_test() {
+    let colors =
+        std::collections::HashMap::from([(0, [255, 255, 255, 255]), (59, [224, 210, 159, 255])]);
+    let fixture = |name| format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let pattern = Pattern::from_image_files(
+        fixture("slide_before.png"),
+        fixture("slide_after.png"),
+        &colors,
+        Some([255, 0, 255, 255]),
+    )
+    .unwrap();
+    assert_eq!(pattern.before, grid::grid![[59, 0, 127][59, 0, 127]]);
+    assert_eq!(pattern.after, grid::grid![[0, 127, 127][127, 59, 127]]);
+    assert_eq!(pattern.chance, 1.);
+
+    // without a wildcard color, the wildcard pixels match nothing
+    match Pattern::from_image_files(
+        fixture("slide_before.png"),
+        fixture("slide_after.png"),
+        &colors,
+        None,
+    ) {
+        Err(crate::CelluminaError::UnmatchedPixel {
+            x: 2,
+            y: 0,
+            color: [255, 0, 255, 255],
+        }) => {}
+        res => panic!("Expected unmatched pixel, got {res:?}"),
+    }
+
+    // images of different sizes
+    let image = image::open(fixture("slide_before.png"))
+        .unwrap()
+        .into_rgba8();
+    let small = image::RgbaImage::from_pixel(2, 2, image::Rgba([255; 4]));
+    assert!(matches!(
+        Pattern::from_images(&image, &small, &colors, None),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (2, 3),
+            got: (2, 2)
+        })
+    ));
+    assert!(matches!(
+        Pattern::from_image_files(
+            fixture("missing.png"),
+            fixture("slide_after.png"),
+            &colors,
+            None
+        ),
+        Err(crate::CelluminaError::ImageError(_))
+    ));
+
+    // the builder adds the pattern like any other
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![59, 0, 0, 59, 0, 0], 3)
+        .with_pattern_images(
+            fixture("slide_before.png"),
+            fixture("slide_after.png"),
+            &colors,
+            Some([255, 0, 255, 255]),
+        )
+        .unwrap()
+        .build();
+    automaton.next_step();
+    assert_eq!(automaton.state(), &grid::grid![[0, 0, 0][59, 59, 0]]);
+}
+
+#[test]
+fn write_boundary_test() {
+    use crate::rule::Rule;
+
+    // a cell moving right across the periodic edge
+    let rule = |write_boundary| {
+        PatternRule::from_patterns(
+            &[Pattern {
+                before: grid::grid![[1, 0]],
+                after: grid::grid![[0, 1]],
+                ..Default::default()
+            }],
+            BoundaryBehaviour::Periodic,
+            BoundaryBehaviour::Periodic,
+        )
+        .with_write_boundary(write_boundary)
+    };
+    let step = |rule: &PatternRule| {
+        let mut grid = grid::grid![[0, 0, 1][0, 1, 0]];
+        rule.transform(&mut grid);
+        grid
+    };
+    assert_eq!(
+        step(&rule(WriteBoundary::Wrap)),
+        grid::grid![[1, 0, 0][0, 0, 1]]
+    );
+    assert_eq!(
+        step(&rule(WriteBoundary::Clip)),
+        grid::grid![[0, 0, 0][0, 0, 1]]
+    );
+    assert_eq!(
+        step(&rule(WriteBoundary::Reject)),
+        grid::grid![[0, 0, 1][0, 0, 1]]
+    );
+    assert_eq!(
+        rule(WriteBoundary::Wrap).write_boundary,
+        WriteBoundary::default()
+    );
+
+    // rejecting keeps sand from falling into a sink
+    let sand = PatternRule::from_patterns(
+        &[Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0][1]],
+            ..Default::default()
+        }],
+        BoundaryBehaviour::Sink(0),
+        BoundaryBehaviour::blocking_boundary(),
+    );
+    let mut grid = grid::grid![[1][1]];
+    sand.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1][0]]);
+    let sand = sand.with_write_boundary(WriteBoundary::Reject);
+    let mut grid = grid::grid![[1][1]];
+    sand.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1][1]]);
+
+    // the setting is serialized, and missing settings wrap
+    let text = toml::to_string(&rule(WriteBoundary::Clip)).unwrap();
+    let parsed = toml::from_str::<PatternRule>(&text).unwrap();
+    assert_eq!(step(&parsed), step(&rule(WriteBoundary::Clip)));
+    let text = text.replace("write_boundary = \"Clip\"", "");
+    assert_eq!(
+        toml::from_str::<PatternRule>(&text).unwrap().write_boundary,
+        WriteBoundary::Wrap
+    );
+}
+
+#[test]
+fn softmax_test() {
+    use crate::rule::Rule;
+
+    // two patterns competing for the middle cell
+    let rule = |priority_mode| {
+        PatternRule::from_patterns(
+            &[
+                Pattern {
+                    before: grid::grid![[1, 0]],
+                    after: grid::grid![[1, 1]],
+                    priority: 0.,
+                    ..Default::default()
+                },
+                Pattern {
+                    before: grid::grid![[0, 2]],
+                    after: grid::grid![[2, 2]],
+                    priority: 1.,
+                    ..Default::default()
+                },
+            ],
+            BoundaryBehaviour::blocking_boundary(),
+            BoundaryBehaviour::blocking_boundary(),
+        )
+        .with_priority_mode(priority_mode)
+    };
+    let wins = |rule: &PatternRule| {
+        rule.reseed(7);
+        (0..4000)
+            .filter(|_| {
+                let mut grid = grid::grid![[1, 0, 2]];
+                rule.transform(&mut grid);
+                grid[0][1] == 2
+            })
+            .count()
+    };
 
-    grid = grid::grid![[0, 1][1, 0][0, 0]];
-    rule2.transform(&mut grid);
-    assert_eq!(grid, grid::grid![[0, 0][0, 1][1, 0]]);
-    rule2.transform(&mut grid);
-    assert_eq!(grid, grid::grid![[0, 0][0, 0][1, 1]]);
-}
\ No newline at end of file
+    assert_eq!(wins(&rule(PriorityMode::Strict)), 4000);
+    // the higher priority wins with probability e / (1 + e), about 0.731
+    let softmax = rule(PriorityMode::Softmax { temperature: 1. });
+    let won = wins(&softmax);
+    assert!((2790..3060).contains(&won), "{won} wins");
+    // reproducible for a seed
+    assert_eq!(wins(&softmax), won);
+    // with a high temperature, both win about equally often
+    let won = wins(&rule(PriorityMode::Softmax { temperature: 100. }));
+    assert!((1860..2140).contains(&won), "{won} wins");
+
+    assert!(rule(PriorityMode::Softmax { temperature: 0. })
+        .validate()
+        .is_err());
+    assert!(rule(PriorityMode::Softmax { temperature: 0.5 })
+        .validate()
+        .is_ok());
+    let text = toml::to_string(&softmax).unwrap();
+    assert_eq!(
+        toml::from_str::<PatternRule>(&text).unwrap().priority_mode,
+        PriorityMode::Softmax { temperature: 1. }
+    );
+}
+
+#[test]
+fn boundary_mode_test() {
+    use crate::testing::parse_grid;
+
+    let pattern = |before, after| Pattern {
+        before,
+        after,
+        ..Default::default()
+    };
+
+    // sand falls and turns into stone once it rests on the wall below the last row
+    let rule = |boundary_mode| {
+        PatternRule::from_patterns(
+            &[
+                pattern(grid::grid![[1][0]], grid::grid![[0][1]]),
+                pattern(grid::grid![[1][126]], grid::grid![[2][127]]),
+            ],
+            BoundaryBehaviour::blocking_boundary(),
+            BoundaryBehaviour::blocking_boundary(),
+        )
+        .with_boundary_mode(boundary_mode)
+    };
+    let mut skipping = parse_grid("11\n01\n00");
+    let mut matching = skipping.clone();
+    for _ in 0..3 {
+        rule(PatternBoundaryMode::SkipOutOfBounds).transform(&mut skipping);
+        rule(PatternBoundaryMode::MatchSymbol).transform(&mut matching);
+    }
+    assert_eq!(skipping, parse_grid("00\n01\n11"));
+    assert_eq!(matching, parse_grid("00\n01\n22"));
+    assert!(rule(PatternBoundaryMode::MatchSymbol).validate().is_ok());
+
+    // the wall can only be matched, not replaced
+    let replacing = PatternRule::from_patterns(
+        &[
+            pattern(grid::grid![[1][0]], grid::grid![[0][1]]),
+            pattern(grid::grid![[1][126]], grid::grid![[0][1]]),
+        ],
+        BoundaryBehaviour::blocking_boundary(),
+        BoundaryBehaviour::Periodic,
+    );
+    assert!(replacing.validate().is_ok());
+    let replacing = replacing.with_boundary_mode(PatternBoundaryMode::MatchSymbol);
+    assert!(matches!(
+        replacing.validate(),
+        Err(crate::CelluminaError::ValidationError(issues))
+            if issues == vec![crate::ValidationIssue::BoundaryReplacement { pattern: 1 }]
+    ));
+
+    // wildcards may reach beyond the wall, but matches replacing cells there are discarded
+    let falling = PatternRule::from_patterns(
+        &[pattern(grid::grid![[1][127]], grid::grid![[0][1]])],
+        BoundaryBehaviour::blocking_boundary(),
+        BoundaryBehaviour::blocking_boundary(),
+    )
+    .with_boundary_mode(PatternBoundaryMode::MatchSymbol);
+    let mut grid = parse_grid("0\n1");
+    falling.transform(&mut grid);
+    assert_eq!(grid, parse_grid("0\n1"));
+    let explanations = falling.explain(&grid, 1, 0);
+    assert_eq!(explanations.len(), 2);
+    assert!(explanations
+        .iter()
+        .any(|explanation| explanation.verdict == Verdict::ReplacesBoundary));
+
+    let text = toml::to_string(&rule(PatternBoundaryMode::MatchSymbol)).unwrap();
+    assert_eq!(
+        toml::from_str::<PatternRule>(&text).unwrap().boundary_mode,
+        PatternBoundaryMode::MatchSymbol
+    );
+    let text = text.replace("boundary_mode = \"MatchSymbol\"", "");
+    assert_eq!(
+        toml::from_str::<PatternRule>(&text).unwrap().boundary_mode,
+        PatternBoundaryMode::SkipOutOfBounds
+    );
+}
+
+#[test]
+fn cell_rng_test() {
+    /// Returns the numbers of a linear congruential generator.
+    #[derive(Debug)]
+    struct Lcg(u64);
+
+    impl crate::CellRng for Lcg {
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0
+        }
+
+        fn fork(&mut self, stream: u64) -> Box<dyn crate::CellRng> {
+            Box::new(Lcg(self.next_u64() ^ stream))
+        }
+    }
+
+    let rule = || {
+        PatternRule::from_patterns(
+            &[Pattern {
+                before: grid::grid![[1]],
+                after: grid::grid![[2]],
+                chance: 0.5,
+                ..Default::default()
+            }],
+            BoundaryBehaviour::Periodic,
+            BoundaryBehaviour::Periodic,
+        )
+    };
+    let multi = || {
+        super::MultiRule::new(vec![
+            Box::new(rule()),
+            Box::new(super::EdgeSourceRule::new(super::Edge::Top, 1, 0.5)),
+        ])
+    };
+    // runs a few steps of the passed rule with a generator seeded with the passed number
+    let run = |rule: &dyn Rule<u8>, seed: u64| {
+        rule.set_rng(Box::new(Lcg(seed)));
+        let mut grid = grid::Grid::init(16, 16, 1);
+        let mut states = Vec::new();
+        for _ in 0..3 {
+            rule.transform(&mut grid);
+            states.push(grid.clone());
+        }
+        states
+    };
+
+    // the generator is actually used: some cells were replaced, others not
+    let states = run(&rule(), 1);
+    assert!(states[0].iter().any(|&cell| cell == 1));
+    assert!(states[0].iter().any(|&cell| cell == 2));
+
+    // the same generator results in the same evolution, a different one in a different evolution
+    assert_eq!(run(&rule(), 1), states);
+    assert_ne!(run(&rule(), 2), states);
+    assert_eq!(run(&multi(), 1), run(&multi(), 1));
+    assert_ne!(run(&multi(), 1), run(&multi(), 2));
+}
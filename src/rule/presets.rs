@@ -0,0 +1,297 @@
+//! Ready-made rules for well-known cellular automata, each with a suggested color for every cell it uses.
+//!
+//! Add a preset to an automaton with [AutomatonBuilder::with_preset](crate::AutomatonBuilder::with_preset), or take it apart to use its rule and colors separately.
+//! ```
+//! let automaton = cellumina::AutomatonBuilder::new()
+//!     .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+//!     .with_preset(cellumina::rule::presets::game_of_life())
+//!     .build();
+//! ```
+use std::collections::HashMap;
+
+use super::{
+    helpers, BoundaryBehaviour, EnvironmentRule, Pattern, PatternRule, Rule, TotalisticRule,
+};
+use crate::CellGrid;
+
+/// A rule together with suggested display colors for the cells it uses.
+#[derive(Debug)]
+pub struct Preset {
+    /// The rule describing the automaton.
+    pub rule: Box<dyn Rule>,
+    /// A color for every cell the rule uses.
+    pub colors: HashMap<u8, [u8; 4]>,
+}
+
+/// Black, used for empty or dead cells.
+const BLACK: [u8; 4] = [0, 0, 0, 255];
+/// White, used for live cells.
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// Creates a life-like rule with live cells (1) and dead cells (0), in which cells outside the grid are dead.
+fn life_like(cell_transform: fn(u8, u32) -> u8) -> Preset {
+    Preset {
+        rule: Box::new(TotalisticRule {
+            radius: 1,
+            row_boundary: BoundaryBehaviour::Symbol(0),
+            col_boundary: BoundaryBehaviour::Symbol(0),
+            cell_transform,
+        }),
+        colors: HashMap::from([(0, BLACK), (1, WHITE)]),
+    }
+}
+
+/// John Conway's Game of Life (B3/S23): Dead cells (0) with exactly three live neighbors are born, live cells (1) with two or three live neighbors survive.
+pub fn game_of_life() -> Preset {
+    life_like(|center, sum| match sum {
+        2 => center,
+        3 => 1,
+        _ => 0,
+    })
+}
+
+/// HighLife (B36/S23): Like the [Game of Life](game_of_life), but dead cells (0) are also born with six live neighbors.
+pub fn highlife() -> Preset {
+    life_like(|center, sum| match (center, sum) {
+        (1, 2 | 3) | (0, 3 | 6) => 1,
+        _ => 0,
+    })
+}
+
+/// Seeds (B2/S): Dead cells (0) with exactly two live neighbors are born, all live cells (1) die.
+pub fn seeds() -> Preset {
+    life_like(|center, sum| u8::from(center == 0 && sum == 2))
+}
+
+/// Brian's Brain, with three states: Off cells (0, black) with exactly two firing neighbors start firing (1, white).
+/// Firing cells start dying (2, blue) and dying cells turn off.
+pub fn brians_brain() -> Preset {
+    Preset {
+        rule: Box::new(EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            row_boundary: BoundaryBehaviour::Symbol(0),
+            col_boundary: BoundaryBehaviour::Symbol(0),
+            cell_transform: |env| match helpers::center(env) {
+                0 => u8::from(helpers::count_excluding_center(env, 1) == 2),
+                1 => 2,
+                _ => 0,
+            },
+            ..Default::default()
+        }),
+        colors: HashMap::from([(0, BLACK), (1, WHITE), (2, [40, 80, 200, 255])]),
+    }
+}
+
+/// Wireworld, with four states: Empty cells (0, black) never change.
+/// Electron heads (1, blue) become electron tails (2, red), which become conductors (3, yellow) again.
+/// Conductors become electron heads if one or two of their neighbors are electron heads.
+pub fn wireworld() -> Preset {
+    Preset {
+        rule: Box::new(EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            row_boundary: BoundaryBehaviour::Symbol(0),
+            col_boundary: BoundaryBehaviour::Symbol(0),
+            cell_transform: |env| match helpers::center(env) {
+                1 => 2,
+                2 => 3,
+                3 if matches!(helpers::count_excluding_center(env, 1), 1 | 2) => 1,
+                cell => cell,
+            },
+            ..Default::default()
+        }),
+        colors: HashMap::from([
+            (0, BLACK),
+            (1, [60, 120, 255, 255]),
+            (2, [255, 80, 40, 255]),
+            (3, [230, 190, 40, 255]),
+        ]),
+    }
+}
+
+/// The elementary cellular automaton with the passed [Wolfram code](https://en.wikipedia.org/wiki/Wolfram_code), on cells that are either on (1) or off (0).
+///
+/// Elementary automata are one-dimensional, so each row of the grid shows one generation, with the newest at the bottom.
+/// Each step, all rows move up by one and the bottom row is replaced by the next generation. Cells beyond the left and right edge are off.
+pub fn elementary(number: u8) -> Preset {
+    Preset {
+        rule: Box::new(ElementaryRule { number }),
+        colors: HashMap::from([(0, BLACK), (1, WHITE)]),
+    }
+}
+
+/// The elementary cellular automaton rule 90, in which each cell becomes the exclusive or of its two neighbors. See [elementary].
+pub fn rule90() -> Preset {
+    elementary(90)
+}
+
+/// A falling sand simulation: Sand (59) falls down and slides off piles steeper than 45 degrees, empty cells (0) are air.
+/// The edges of the grid are walls.
+pub fn falling_sand() -> Preset {
+    Preset {
+        rule: Box::new(PatternRule::from_patterns(
+            &[
+                // Sand falls down by one or even two spaces if possible.
+                Pattern {
+                    before: grid::grid![[59][0][0]],
+                    after: grid::grid![[0][0][59]],
+                    priority: 1.0,
+                    chance: 0.9,
+                    ..Default::default()
+                },
+                Pattern {
+                    before: grid::grid![[59][0]],
+                    after: grid::grid![[0][59]],
+                    priority: 0.5,
+                    ..Default::default()
+                },
+                // Stacks of sand collapse to the left or right.
+                Pattern {
+                    before: grid::grid![[59, 0][59, 0]],
+                    after: grid::grid![[0, 127][127, 59]],
+                    ..Default::default()
+                },
+                Pattern {
+                    before: grid::grid![[0, 59][0, 59]],
+                    after: grid::grid![[127, 0][59, 127]],
+                    ..Default::default()
+                },
+                // Even 45 degree slopes of sand collapse.
+                Pattern {
+                    before: grid::grid![[59, 0, 0][59, 59, 0]],
+                    after: grid::grid![[0, 127, 127][127, 127, 59]],
+                    ..Default::default()
+                },
+                Pattern {
+                    before: grid::grid![[0, 0, 59][0, 59, 59]],
+                    after: grid::grid![[127, 127, 0][59, 127, 127]],
+                    ..Default::default()
+                },
+            ],
+            BoundaryBehaviour::blocking_boundary(),
+            BoundaryBehaviour::blocking_boundary(),
+        )),
+        colors: HashMap::from([(0, [61, 159, 184, 255]), (59, [224, 210, 159, 255])]),
+    }
+}
+
+/// An elementary cellular automaton, see [elementary].
+#[derive(Debug, Clone, Copy)]
+struct ElementaryRule {
+    /// The Wolfram code of the automaton.
+    number: u8,
+}
+
+impl Rule for ElementaryRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let (rows, cols) = grid.size();
+        if rows == 0 {
+            return;
+        }
+        let on = |col: Option<usize>| {
+            col.and_then(|col| grid.get(rows - 1, col))
+                .is_some_and(|&cell| cell != 0)
+        };
+        let next = (0..cols)
+            .map(|col| {
+                let index = u8::from(on(col.checked_sub(1))) << 2
+                    | u8::from(on(Some(col))) << 1
+                    | u8::from(on(Some(col + 1)));
+                (self.number >> index) & 1
+            })
+            .collect::<Vec<u8>>();
+
+        // move all generations up by one row
+        for row in 1..rows {
+            for col in 0..cols {
+                grid[row - 1][col] = grid[row][col];
+            }
+        }
+        for (col, cell) in next.into_iter().enumerate() {
+            grid[rows - 1][col] = cell;
+        }
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn Rule>> {
+        Some(Box::new(*self))
+    }
+}
+
+#[test]
+fn presets_test() {
+    use rand::{Rng, SeedableRng};
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let mut random_grid = |states: u8| {
+        CellGrid::from_vec((0..32 * 32).map(|_| rng.gen_range(0..states)).collect(), 32)
+    };
+
+    // every preset has a color for every cell it creates
+    let run = |preset: Preset, mut grid: CellGrid, check: &dyn Fn(&CellGrid, &CellGrid)| {
+        preset.rule.reseed(0);
+        for _ in 0..10 {
+            let before = grid.clone();
+            preset.rule.transform(&mut grid);
+            assert!(grid.iter().all(|cell| preset.colors.contains_key(cell)));
+            check(&before, &grid);
+        }
+    };
+
+    // a blinker oscillates in the game of life
+    let blinker = grid::grid![[0, 0, 0][1, 1, 1][0, 0, 0]];
+    let mut grid = blinker.clone();
+    let life = game_of_life();
+    life.rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[0, 1, 0][0, 1, 0][0, 1, 0]]);
+    life.rule.transform(&mut grid);
+    assert_eq!(grid, blinker);
+    run(life, random_grid(2), &|_, _| {});
+
+    // highlife agrees with the game of life, except for births with six neighbors
+    let mut grid = grid::grid![[1, 1, 1][0, 0, 0][1, 1, 1]];
+    highlife().rule.transform(&mut grid);
+    assert_eq!(grid[1], [0, 1, 0]);
+    run(highlife(), random_grid(2), &|_, _| {});
+
+    // no cell survives in seeds
+    run(seeds(), random_grid(2), &|before, after| {
+        assert!(before
+            .iter()
+            .zip(after.iter())
+            .all(|(&before, &after)| before == 0 || after == 0));
+    });
+
+    // no cell fires two steps in a row in brian's brain
+    run(brians_brain(), random_grid(3), &|before, after| {
+        assert!(before
+            .iter()
+            .zip(after.iter())
+            .all(|(&before, &after)| before != 1 || after == 2));
+    });
+
+    // empty cells never change in wireworld, and nothing else becomes empty
+    run(wireworld(), random_grid(4), &|before, after| {
+        assert!(before
+            .iter()
+            .zip(after.iter())
+            .all(|(&before, &after)| (before == 0) == (after == 0)));
+    });
+
+    // rule 90 draws a Sierpinski triangle from a single cell
+    let mut grid = CellGrid::new(4, 7);
+    grid[3][3] = 1;
+    let rule90 = rule90();
+    for _ in 0..3 {
+        rule90.rule.transform(&mut grid);
+    }
+    assert_eq!(
+        grid,
+        grid::grid![[0, 0, 0, 1, 0, 0, 0][0, 0, 1, 0, 1, 0, 0][0, 1, 0, 0, 0, 1, 0][1, 0, 1, 0, 1, 0, 1]]
+    );
+
+    // sand is never created or destroyed
+    let mut grid = CellGrid::new(8, 8);
+    grid[0].fill(59);
+    run(falling_sand(), grid, &|before, after| {
+        assert_eq!(helpers::count(before, 59), helpers::count(after, 59));
+    });
+}
@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use super::{Region, Rule};
+use crate::{Cell, CellGrid, CellGridExt};
+
+/// The cells a region of [RegionRules] covers, either a rectangle or an arbitrary selection of cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegionMask {
+    /// All cells within the rectangle. Parts outside of the state grid are ignored.
+    Rect(Region),
+    /// All cells that are ```true``` in this grid, which should have the size of the state grid. Cells outside of it are not covered.
+    Cells(grid::Grid<bool>),
+}
+
+impl From<Region> for RegionMask {
+    fn from(region: Region) -> Self {
+        Self::Rect(region)
+    }
+}
+
+impl From<grid::Grid<bool>> for RegionMask {
+    fn from(cells: grid::Grid<bool>) -> Self {
+        Self::Cells(cells)
+    }
+}
+
+impl RegionMask {
+    /// Creates a mask covering all cells of the passed grid that contain ```symbol```, such as the water of a map.
+    pub fn from_symbol(grid: &CellGrid, symbol: u8) -> Self {
+        Self::Cells(grid::Grid::from_vec(
+            grid.iter().map(|&cell| cell == symbol).collect(),
+            grid.cols(),
+        ))
+    }
+
+    /// Creates a mask from an image, such as a hand-drawn map of zones, covering all pixels whose color is the color of ```symbol``` in ```colors```.
+    /// Pixels need to match the color exactly, and if ```symbol``` has no color, no cell is covered.
+    pub fn from_image(image: &image::RgbaImage, colors: &HashMap<u8, [u8; 4]>, symbol: u8) -> Self {
+        let colors = colors
+            .get(&symbol)
+            .map(|&color| HashMap::from([(true, color)]))
+            .unwrap_or_default();
+        Self::Cells(crate::convert::image_to_grid(image, &colors, 0))
+    }
+
+    /// Returns wether the mask covers the passed cell.
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        match self {
+            Self::Rect(region) => region.rows.contains(&row) && region.cols.contains(&col),
+            Self::Cells(cells) => cells.get(row, col).copied().unwrap_or(false),
+        }
+    }
+}
+
+/// A single named region of [RegionRules].
+#[derive(Debug)]
+struct NamedRegion<T: Cell> {
+    /// The name of the region, for logging.
+    name: String,
+    /// The cells the region covers.
+    mask: RegionMask,
+    /// The rule applied within the region.
+    rule: Box<dyn Rule<T>>,
+}
+
+/// A rule applying different rules in different named regions of the grid, such as water and land zones with their own physics,
+/// and optionally a global rule for the remaining cells covered by no region.
+///
+/// The rule of each region is applied to a copy of the entire grid, so it may read cells outside of its region, but only cells within its mask are taken over.
+/// Regions are applied in the order they were added, each reading the results of the ones before.
+/// Cells covered by multiple regions are thus transformed by all their rules in turn, with the region added last having the final say.
+/// The remainder rule is applied last, and only changes cells covered by no region at all. Without a remainder rule, these cells never change.
+/// ```
+/// # use cellumina::rule::{Region, RegionRules, ShiftRule};
+/// let rule = RegionRules::new().with_region(
+///     "left",
+///     Region { rows: 0..2, cols: 0..2 },
+///     ShiftRule { dy: 0, dx: 1, fill: Some(0) },
+/// );
+/// let mut grid = grid::grid![[1, 0, 1][2, 0, 0]];
+/// cellumina::rule::Rule::transform(&rule, &mut grid);
+/// assert_eq!(grid, grid::grid![[0, 1, 1][0, 2, 0]]);
+/// ```
+#[derive(Debug)]
+pub struct RegionRules<T: Cell = u8> {
+    /// The regions, in the order they were added.
+    regions: Vec<NamedRegion<T>>,
+    /// The rule for cells outside of all regions.
+    remainder: Option<Box<dyn Rule<T>>>,
+}
+
+impl<T: Cell> Default for RegionRules<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Cell> RegionRules<T> {
+    /// Creates a rule without any regions and without a remainder rule, which does not change any cells.
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+            remainder: None,
+        }
+    }
+
+    /// Adds a region with the passed name, applying ```rule``` within the cells of ```mask```.
+    /// A region already added under the same name is replaced, keeping its position in the order of application.
+    pub fn with_region(
+        mut self,
+        name: &str,
+        mask: impl Into<RegionMask>,
+        rule: impl Rule<T> + 'static,
+    ) -> Self {
+        self.add_region(name, mask.into(), Box::new(rule));
+        self
+    }
+
+    /// Sets the rule applied to all cells that are covered by no region.
+    pub fn with_remainder(mut self, rule: impl Rule<T> + 'static) -> Self {
+        self.remainder = Some(Box::new(rule));
+        self
+    }
+
+    /// Returns the names of the regions, in the order they are applied.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.regions.iter().map(|region| region.name.as_str())
+    }
+
+    /// Adds or replaces a region, see [Self::with_region].
+    pub(crate) fn add_region(&mut self, name: &str, mask: RegionMask, rule: Box<dyn Rule<T>>) {
+        let region = NamedRegion {
+            name: name.to_string(),
+            mask,
+            rule,
+        };
+        match self.regions.iter_mut().find(|region| region.name == name) {
+            Some(existing) => {
+                log::warn!("Replaced the rule of the region named {name:?}.");
+                *existing = region;
+            }
+            None => self.regions.push(region),
+        }
+    }
+
+    /// Sets the remainder rule, see [Self::with_remainder].
+    pub(crate) fn set_remainder(&mut self, rule: Box<dyn Rule<T>>) {
+        self.remainder = Some(rule);
+    }
+
+    /// Applies each rule using the passed function to a copy of the grid and takes over the cells it is responsible for.
+    fn apply(
+        &self,
+        grid: &mut grid::Grid<T>,
+        mut transform: impl FnMut(&dyn Rule<T>, &mut grid::Grid<T>),
+    ) {
+        let mut copy = grid.clone();
+        for (index, region) in self.regions.iter().enumerate() {
+            trace_span!("region", index = index);
+            copy.clone_from(grid);
+            transform(region.rule.as_ref(), &mut copy);
+            if copy.size() != grid.size() {
+                log::error!(
+                    "The rule of region {:?} changed the size of the grid, discarding its result.",
+                    region.name
+                );
+                continue;
+            }
+            for (((row, col), cell), new) in grid.indexed_iter_mut().zip(copy.iter()) {
+                if region.mask.contains(row, col) {
+                    *cell = *new;
+                }
+            }
+        }
+        if let Some(remainder) = &self.remainder {
+            copy.clone_from(grid);
+            transform(remainder.as_ref(), &mut copy);
+            if copy.size() != grid.size() {
+                log::error!(
+                    "The remainder rule changed the size of the grid, discarding its result."
+                );
+                return;
+            }
+            for (((row, col), cell), new) in grid.indexed_iter_mut().zip(copy.iter()) {
+                if !self
+                    .regions
+                    .iter()
+                    .any(|region| region.mask.contains(row, col))
+                {
+                    *cell = *new;
+                }
+            }
+        }
+    }
+
+    /// Iterates over the rules of all regions followed by the remainder rule.
+    fn rules(&self) -> impl Iterator<Item = &dyn Rule<T>> {
+        self.regions
+            .iter()
+            .map(|region| region.rule.as_ref())
+            .chain(self.remainder.as_deref())
+    }
+}
+
+impl<T: Cell> Rule<T> for RegionRules<T> {
+    fn transform(&self, grid: &mut grid::Grid<T>) {
+        self.apply(grid, |rule, grid| rule.transform(grid));
+    }
+
+    fn transform_with_aux(
+        &self,
+        grid: &mut grid::Grid<T>,
+        buffer: &mut grid::Grid<T>,
+        aux: &CellGrid,
+    ) -> bool {
+        self.apply(grid, |rule, grid| {
+            if rule.transform_with_aux(grid, buffer, aux) {
+                std::mem::swap(grid, buffer);
+            }
+        });
+        false
+    }
+
+    fn reseed(&self, seed: u64) {
+        // derive a different seed for each rule, like a MultiRule
+        for (index, rule) in self.rules().enumerate() {
+            rule.reseed(seed.wrapping_add((index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+        }
+    }
+
+    fn set_rng(&self, mut rng: Box<dyn crate::CellRng>) {
+        for (index, rule) in self.rules().enumerate() {
+            rule.set_rng(rng.fork(index as u64));
+        }
+    }
+
+    fn reach(&self) -> Option<usize> {
+        // the masks never change, so a cell can only be affected by changes within the reach of some rule
+        self.rules()
+            .map(|rule| rule.reach())
+            .try_fold(0, |max, reach| Some(reach?.max(max)))
+    }
+
+    fn remap_symbols(&mut self, map: &HashMap<T, T>) {
+        for region in self.regions.iter_mut() {
+            region.rule.remap_symbols(map);
+        }
+        if let Some(remainder) = &mut self.remainder {
+            remainder.remap_symbols(map);
+        }
+    }
+
+    fn set_frozen_symbols(&mut self, symbols: &[T]) {
+        for region in self.regions.iter_mut() {
+            region.rule.set_frozen_symbols(symbols);
+        }
+        if let Some(remainder) = &mut self.remainder {
+            remainder.set_frozen_symbols(symbols);
+        }
+    }
+
+    fn shift_columns(&self, dx: i64) {
+        for region in self.regions.iter() {
+            region.rule.shift_columns(dx);
+        }
+        if let Some(remainder) = &self.remainder {
+            remainder.shift_columns(dx);
+        }
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn Rule<T>>> {
+        Some(Box::new(Self {
+            regions: self
+                .regions
+                .iter()
+                .map(|region| {
+                    Some(NamedRegion {
+                        name: region.name.clone(),
+                        mask: region.mask.clone(),
+                        rule: region.rule.clone_boxed()?,
+                    })
+                })
+                .collect::<Option<_>>()?,
+            remainder: match &self.remainder {
+                Some(remainder) => Some(remainder.clone_boxed()?),
+                None => None,
+            },
+        }))
+    }
+}
+
+#[test]
+fn region_rules_test() {
+    let shift = |dx| super::ShiftRule {
+        dy: 0,
+        dx,
+        fill: Some(0),
+    };
+    // two areas of a row with a static gap in between, the left one moving right and the right one moving left
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 0, 0, 3, 4, 0, 0, 2], 8)
+        .with_region(
+            "left",
+            Region {
+                rows: 0..1,
+                cols: 0..3,
+            },
+            shift(1),
+        )
+        .with_region(
+            "right",
+            Region {
+                rows: 0..1,
+                cols: 5..8,
+            },
+            shift(-1),
+        )
+        .build();
+    for expected in [
+        [0, 1, 0, 3, 4, 0, 2, 0],
+        [0, 0, 1, 3, 4, 2, 0, 0],
+        // cells leave their region, but the gap never changes
+        [0, 0, 0, 3, 4, 0, 0, 0],
+    ] {
+        automaton.next_step();
+        assert_eq!(
+            automaton.state().iter().copied().collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    // other rules of the builder become the remainder rule
+    let mut automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 1, 1, 1], 4)
+        .with_region(
+            "water",
+            RegionMask::from_symbol(&grid::grid![[0, 5, 0, 5]], 5),
+            super::FnRule::new("flood", |grid| grid.fill(2)),
+        )
+        .with_fn_rule("land", |grid| grid.fill(3))
+        .build();
+    automaton.next_step();
+    assert_eq!(automaton.state(), &grid::grid![[3, 2, 3, 2]]);
+
+    // overlapping regions apply in order, the later one having the final say
+    let mut grid = grid::grid![[0, 0, 0]];
+    RegionRules::new()
+        .with_region(
+            "a",
+            Region {
+                rows: 0..1,
+                cols: 0..2,
+            },
+            super::FnRule::new("fill", |grid| grid.fill(1)),
+        )
+        .with_region(
+            "b",
+            Region {
+                rows: 0..1,
+                cols: 1..3,
+            },
+            super::FnRule::new("add", |grid| grid.iter_mut().for_each(|cell| *cell += 5)),
+        )
+        .transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1, 6, 5]]);
+
+    // masks from images
+    let colors = HashMap::from([(1, [0, 0, 255, 255]), (2, [0, 255, 0, 255])]);
+    let image = image::RgbaImage::from_fn(3, 1, |x, _| {
+        image::Rgba(if x == 1 { colors[&2] } else { colors[&1] })
+    });
+    assert_eq!(
+        RegionMask::from_image(&image, &colors, 1),
+        RegionMask::Cells(grid::grid![[true, false, true]])
+    );
+}
@@ -0,0 +1,285 @@
+use std::sync::Mutex;
+
+use super::environment_rule::{environment_shape, fill_environment, with_environments};
+use crate::CellGrid;
+
+/// A scripted environment rule works like an [EnvironmentRule](super::EnvironmentRule), but calculates the next value of each cell with a function written in Lua,
+/// so rules can be authored without recompiling.
+///
+/// The script is a Lua chunk returning a function, which receives the environment of a cell as a table of rows,
+/// each a table of the symbols in that row, followed by the row and column of the cell (both starting at 0), and returns the next value of the cell.
+/// As usual in Lua, the tables start at index 1, so the cell itself is at ```env[top + 1][left + 1]```, where ```[top, right, bottom, left]``` is the ```environment_size```.
+/// Parts of the environment outside the grid are filled according to the boundaries, as for environment rules.
+///
+/// The script is compiled once, when the rule is created, and the compiled function is reused for every cell.
+/// Still, calling into Lua for every cell of every step is a lot slower than a native ```cell_transform``` function, often by two orders of magnitude,
+/// and all cells are calculated on a single thread, so scripted rules are best suited to trying out ideas on small grids.
+///
+/// If the function fails or returns something that is not a symbol, the cell keeps its value and an error is logged.
+///
+/// As scripts may use the position of the cell, random numbers or global variables, scripted rules are not assumed to be local and always transform the entire grid, even with dirty tracking.
+/// Scripts only depending on the environment can opt into dirty tracking with [Self::with_pure_environment].
+/// ```
+/// # use cellumina::rule::{BoundaryBehaviour, Rule, ScriptedEnvironmentRule};
+/// // every cell becomes the number of living cells to its left and right
+/// let rule = ScriptedEnvironmentRule::from_lua(
+///     "return function(env, row, col) return env[1][1] + env[1][3] end",
+///     [0, 1, 0, 1],
+///     BoundaryBehaviour::Symbol(0),
+/// )
+/// .unwrap();
+/// let mut grid = grid::grid![[1, 0, 1, 1]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 2, 1, 1]]);
+/// ```
+pub struct ScriptedEnvironmentRule {
+    /// The distance the considered environment extends from the cell to be set, in order ```[top, right, bottom, left]```, see [EnvironmentRule::environment_size](super::EnvironmentRule::environment_size).
+    environment_size: [usize; 4],
+    /// Behaviour of this rule when encountering cases in which the environment of a cell contains rows or columns that go out of bounds of the state grid.
+    boundaries: super::BoundaryBehaviour,
+    /// The source of the script, kept to compile it again when copying the rule.
+    source: String,
+    /// The Lua state holding the compiled function, locked while transforming as Lua states can not be shared between threads.
+    script: Mutex<Script>,
+    /// Wether the next value of a cell only depends on its environment, see [Self::with_pure_environment].
+    pure_environment: bool,
+}
+
+/// A Lua state and the key of the compiled function in its registry.
+struct Script {
+    /// The Lua state.
+    lua: mlua::Lua,
+    /// The key of the compiled function.
+    function: mlua::RegistryKey,
+}
+
+impl std::fmt::Debug for ScriptedEnvironmentRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptedEnvironmentRule")
+            .field("environment_size", &self.environment_size)
+            .field("boundaries", &self.boundaries)
+            .field("source", &self.source)
+            .field("pure_environment", &self.pure_environment)
+            .finish()
+    }
+}
+
+impl ScriptedEnvironmentRule {
+    /// Creates a rule from the passed Lua source, which has to evaluate to a function as described for [ScriptedEnvironmentRule].
+    /// The passed boundaries apply to both rows and columns.
+    ///
+    /// ## Errors
+    /// Returns a [ParseError](crate::CelluminaError::ParseError) if the source can not be compiled, fails when evaluated or does not evaluate to a function.
+    pub fn from_lua(
+        source: &str,
+        environment_size: [usize; 4],
+        boundaries: super::BoundaryBehaviour,
+    ) -> Result<Self, crate::CelluminaError> {
+        let lua = mlua::Lua::new();
+        let function = lua
+            .load(source)
+            .set_name("rule")
+            .eval::<mlua::Function>()
+            .and_then(|function| lua.create_registry_value(function))
+            .map_err(|err| {
+                let message = match err {
+                    mlua::Error::SyntaxError { message, .. } => message,
+                    err => err.to_string(),
+                };
+                crate::CelluminaError::ParseError {
+                    source_kind: crate::SourceKind::Script,
+                    line: error_line(&message).unwrap_or(1),
+                    column: 1,
+                    message,
+                }
+            })?;
+        Ok(Self {
+            environment_size,
+            boundaries,
+            source: source.to_string(),
+            script: Mutex::new(Script { lua, function }),
+            pure_environment: false,
+        })
+    }
+
+    /// Declares that the script only depends on the environment passed to it, not on the position of the cell, random numbers or any state kept between calls.
+    /// Only then the rule reports its environment size as its [reach](super::Rule::reach), so unchanged parts of the grid are skipped with dirty tracking.
+    pub fn with_pure_environment(mut self) -> Self {
+        self.pure_environment = true;
+        self
+    }
+
+    /// Returns the Lua source of this rule.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Extracts the line number from a Lua error message such as ```[string "rule"]:3: unexpected symbol```.
+fn error_line(message: &str) -> Option<usize> {
+    let (_, rest) = message.split_once("]:")?;
+    rest.split(':').next()?.parse().ok()
+}
+
+impl super::Rule for ScriptedEnvironmentRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        // the source compiled before, so it compiles again
+        Self::from_lua(&self.source, self.environment_size, self.boundaries)
+            .ok()
+            .map(|rule| Self {
+                pure_environment: self.pure_environment,
+                ..rule
+            })
+            .map(|rule| Box::new(rule) as Box<dyn super::Rule>)
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let (rows, cols) = grid.size();
+        if buffer.size() != (rows, cols) {
+            *buffer = CellGrid::new(rows, cols);
+        }
+
+        let script = self.script.lock().unwrap_or_else(|err| err.into_inner());
+        let mut failures = 0;
+        let mut first_error = None;
+        let result = with_environments(
+            environment_shape(self.environment_size),
+            |[environment, _]| {
+                let function = script
+                    .lua
+                    .registry_value::<mlua::Function>(&script.function)?;
+                // the tables are reused for all cells, only their contents change
+                let table = script.lua.create_table()?;
+                for row in 1..=environment.rows() {
+                    table.raw_set(row, script.lua.create_table()?)?;
+                }
+                for row in 0..rows {
+                    for col in 0..cols {
+                        fill_environment(
+                            self.environment_size,
+                            self.boundaries,
+                            self.boundaries,
+                            grid,
+                            (row, col),
+                            environment,
+                        );
+                        for (env_row, cells) in environment.iter_rows().enumerate() {
+                            let line = table.raw_get::<_, mlua::Table>(env_row + 1)?;
+                            for (env_col, &cell) in cells.enumerate() {
+                                line.raw_set(env_col + 1, cell)?;
+                            }
+                        }
+                        buffer[row][col] = match function.call::<_, u8>((table.clone(), row, col)) {
+                            Ok(cell) => cell,
+                            Err(err) => {
+                                failures += 1;
+                                first_error.get_or_insert(err);
+                                grid[row][col]
+                            }
+                        };
+                    }
+                }
+                Ok::<_, mlua::Error>(())
+            },
+        );
+
+        if let Err(err) = result {
+            log::error!("Could not prepare the scripted rule, leaving the grid unchanged: {err}");
+            buffer.clone_from(grid);
+        }
+        if let Some(err) = first_error {
+            log::error!(
+                "The scripted rule failed for {failures} cells, which keep their value: {err}"
+            );
+        }
+        true
+    }
+
+    fn reach(&self) -> Option<usize> {
+        self.pure_environment
+            .then(|| self.environment_size.iter().copied().max())
+            .flatten()
+    }
+}
+
+#[test]
+fn scripted_rule_test() {
+    use super::Rule;
+
+    let life = ScriptedEnvironmentRule::from_lua(
+        r"
+        -- the game of life, counting the eight neighbors of each cell
+        return function(env, row, col)
+            local sum = 0
+            for r = 1, 3 do
+                for c = 1, 3 do
+                    sum = sum + env[r][c]
+                end
+            end
+            local alive = env[2][2]
+            sum = sum - alive
+            if sum == 3 or (alive == 1 and sum == 2) then
+                return 1
+            end
+            return 0
+        end
+        ",
+        [1, 1, 1, 1],
+        super::BoundaryBehaviour::Periodic,
+    )
+    .unwrap();
+    let native = super::LifeLikeRule::parse("B3/S23").unwrap();
+
+    // a glider on a torus behaves the same with both rules
+    let mut scripted = grid::grid![[0, 1, 0, 0, 0, 0][0, 0, 1, 0, 0, 0][1, 1, 1, 0, 0, 0][0, 0, 0, 0, 0, 0][0, 0, 0, 0, 0, 0]];
+    let mut expected = scripted.clone();
+    for _ in 0..8 {
+        life.transform(&mut scripted);
+        native.transform(&mut expected);
+        assert_eq!(scripted, expected);
+    }
+    // only scripts declared to depend on nothing but the environment are local
+    assert_eq!(life.reach(), None);
+    let life = life.with_pure_environment();
+    assert_eq!(life.reach(), Some(1));
+    assert_eq!(life.clone_boxed().unwrap().reach(), Some(1));
+
+    // compile errors name the line
+    let err = ScriptedEnvironmentRule::from_lua(
+        "return function(env)\n  return env[1][1] +\nend",
+        [0; 4],
+        super::BoundaryBehaviour::Periodic,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::Script,
+            line: 3,
+            ..
+        }
+    ));
+    assert!(ScriptedEnvironmentRule::from_lua(
+        "return 4",
+        [0; 4],
+        super::BoundaryBehaviour::Periodic
+    )
+    .is_err());
+
+    // failing cells keep their value
+    let rule = ScriptedEnvironmentRule::from_lua(
+        "return function(env, row, col) if col == 0 then error('no') end return env[1][1] + 1 end",
+        [0; 4],
+        super::BoundaryBehaviour::Periodic,
+    )
+    .unwrap();
+    let mut grid = grid::grid![[5, 5][7, 7]];
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[5, 6][7, 8]]);
+}
@@ -0,0 +1,460 @@
+use super::BoundaryBehaviour;
+use crate::CellGrid;
+
+/// A totalistic rule calculates the next state of a cell only from its current state and the sum of the states of its neighbors.
+///
+/// This is a special case of an [EnvironmentRule](super::EnvironmentRule), but as the sums of all neighborhoods are calculated using sliding windows,
+/// each cell only costs a constant amount of work independent of the radius, making it considerably faster for large grids.
+///
+/// The neighborhood of a cell is the square of cells at most ```radius``` rows and columns away from it, not including the cell itself.
+/// ```
+/// # use cellumina::rule::{BoundaryBehaviour, Rule, TotalisticRule};
+/// // Conway's Game of Life
+/// let rule = TotalisticRule {
+///     radius: 1,
+///     row_boundary: BoundaryBehaviour::Symbol(0),
+///     col_boundary: BoundaryBehaviour::Symbol(0),
+///     cell_transform: |center, sum| match sum {
+///         2 => center,
+///         3 => 1,
+///         _ => 0,
+///     },
+/// };
+/// let mut grid = grid::grid![[0, 1, 0][0, 1, 0][0, 1, 0]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 0, 0][1, 1, 1][0, 0, 0]]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct TotalisticRule {
+    /// The distance the neighborhood extends from the cell in every direction.
+    pub radius: usize,
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains rows that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    pub row_boundary: BoundaryBehaviour,
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains columns that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    /// Cells out of bounds in both directions use the row boundary, if it is not periodic.
+    pub col_boundary: BoundaryBehaviour,
+    /// The function that calculates the next state of a single cell from its current state and the sum of its neighbors' states.
+    pub cell_transform: fn(u8, u32) -> u8,
+}
+
+impl Default for TotalisticRule {
+    fn default() -> Self {
+        Self {
+            radius: 1,
+            row_boundary: Default::default(),
+            col_boundary: Default::default(),
+            cell_transform: |_, _| 0,
+        }
+    }
+}
+
+impl std::fmt::Debug for TotalisticRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TotalisticRule")
+            .field("radius", &self.radius)
+            .field("row_boundary", &self.row_boundary)
+            .field("col_boundary", &self.col_boundary)
+            .finish()
+    }
+}
+
+/// Returns the value of cells outside the grid for the passed boundary.
+fn symbol(boundary: BoundaryBehaviour) -> u32 {
+    match boundary {
+        BoundaryBehaviour::Periodic | BoundaryBehaviour::Clamp => 0,
+        BoundaryBehaviour::Symbol(symbol) | BoundaryBehaviour::Sink(symbol) => symbol as u32,
+    }
+}
+
+/// Writes the sums of all windows of size ```2 * radius + 1``` of ```values``` to ```sums```, which must be ```2 * radius``` shorter.
+fn sliding_sums(values: &[u32], radius: usize, sums: &mut [u32]) {
+    let width = 2 * radius + 1;
+    let mut sum = values[..width].iter().sum::<u32>();
+    sums[0] = sum;
+    for index in 1..sums.len() {
+        sum = sum + values[index + width - 1] - values[index - 1];
+        sums[index] = sum;
+    }
+}
+
+/// The intermediate results of [TotalisticRule::transform_sums].
+struct Scratch {
+    /// The sum of each column over ```2 * radius + 1``` rows, for each cell.
+    vertical: grid::Grid<u32>,
+    /// The values of a single column or row, including those beyond the grid.
+    values: Vec<u32>,
+    /// The sliding sums over ```values```.
+    sums: Vec<u32>,
+}
+
+thread_local! {
+    /// Holds the intermediate results of totalistic rules, reused between transformations to avoid allocations.
+    static SCRATCH: std::cell::RefCell<Scratch> = std::cell::RefCell::new(Scratch {
+        vertical: grid::Grid::new(0, 0),
+        values: Vec::new(),
+        sums: Vec::new(),
+    });
+}
+
+/// Clears the passed vector and fills it with ```len``` zeros, keeping its allocation.
+fn reset(vec: &mut Vec<u32>, len: usize) {
+    vec.clear();
+    vec.resize(len, 0);
+}
+
+impl super::Rule for TotalisticRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(*self))
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        self.transform_sums(grid, buffer, self.cell_transform);
+        true
+    }
+
+    fn reach(&self) -> Option<usize> {
+        Some(self.radius)
+    }
+}
+
+impl TotalisticRule {
+    /// Writes the result of the passed cell transformation for each cell of the grid and the sum of its neighborhood to the buffer.
+    fn transform_sums(
+        &self,
+        grid: &CellGrid,
+        buffer: &mut CellGrid,
+        cell_transform: impl Fn(u8, u32) -> u8,
+    ) {
+        let (rows, cols) = grid.size();
+        if buffer.size() != (rows, cols) {
+            *buffer = CellGrid::new(rows, cols);
+        }
+        if rows == 0 || cols == 0 {
+            return;
+        }
+
+        SCRATCH.with(|scratch| {
+            let Scratch {
+                vertical,
+                values,
+                sums,
+            } = &mut *scratch.borrow_mut();
+            if vertical.size() != (rows, cols) {
+                *vertical = grid::Grid::new(rows, cols);
+            }
+            self.sum_neighborhoods(grid, buffer, &cell_transform, vertical, values, sums);
+        });
+    }
+
+    /// Calculates the sums of all neighborhoods like [Self::transform_sums], using the passed space for intermediate results.
+    fn sum_neighborhoods(
+        &self,
+        grid: &CellGrid,
+        buffer: &mut CellGrid,
+        cell_transform: impl Fn(u8, u32) -> u8,
+        vertical: &mut grid::Grid<u32>,
+        values: &mut Vec<u32>,
+        sums: &mut Vec<u32>,
+    ) {
+        let (rows, cols) = grid.size();
+        let radius = self.radius;
+
+        // first, sum up each column over 2 * radius + 1 rows
+        reset(values, rows + 2 * radius);
+        reset(sums, rows);
+        for col in 0..cols {
+            for (index, value) in values.iter_mut().enumerate() {
+                *value = match self
+                    .row_boundary
+                    .resolve(index as isize - radius as isize, rows)
+                {
+                    Ok(row) => grid[row][col] as u32,
+                    Err(symbol) => symbol as u32,
+                };
+            }
+            sliding_sums(values, radius, sums);
+            for (row, &sum) in sums.iter().enumerate() {
+                vertical[row][col] = sum;
+            }
+        }
+
+        // then, sum up these column sums over 2 * radius + 1 columns
+        reset(values, cols + 2 * radius);
+        reset(sums, cols);
+        for row in 0..rows {
+            // the sum of a column outside the grid, with out-of-bounds rows using the row symbol
+            let outside = (-(radius as isize)..=radius as isize)
+                .map(
+                    |del| match self.row_boundary.resolve(row as isize + del, rows) {
+                        Ok(_) => symbol(self.col_boundary),
+                        Err(symbol) => symbol as u32,
+                    },
+                )
+                .sum::<u32>();
+
+            for (index, value) in values.iter_mut().enumerate() {
+                *value = match self
+                    .col_boundary
+                    .resolve(index as isize - radius as isize, cols)
+                {
+                    Ok(col) => vertical[row][col],
+                    Err(_) => outside,
+                };
+            }
+            sliding_sums(values, radius, sums);
+            for (col, &sum) in sums.iter().enumerate() {
+                let center = grid[row][col];
+                buffer[row][col] = cell_transform(center, sum - center as u32);
+            }
+        }
+    }
+}
+
+/// A life-like rule on dead (0) and live (1) cells: Dead cells are born and live cells survive depending on their number of live neighbors among the eight surrounding cells.
+///
+/// Life-like rules are commonly written in the B/S notation, listing the neighbor counts for births and survival, which [LifeLikeRule::parse] reads.
+/// ```
+/// # use cellumina::rule::{LifeLikeRule, Rule};
+/// // Conway's Game of Life
+/// let rule = LifeLikeRule::parse("B3/S23").unwrap();
+/// let mut grid = grid::grid![[0, 1, 0][0, 1, 0][0, 1, 0]];
+/// rule.transform(&mut grid);
+/// assert_eq!(grid, grid::grid![[0, 0, 0][1, 1, 1][0, 0, 0]]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifeLikeRule {
+    /// For each number of live neighbors, wether a dead cell with that many live neighbors is born.
+    pub birth: [bool; 9],
+    /// For each number of live neighbors, wether a live cell with that many live neighbors survives.
+    pub survival: [bool; 9],
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains rows that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    pub row_boundary: BoundaryBehaviour,
+    /// Behaviour of this rule when encountering cases in which the neighborhood of a cell contains columns that go out of bounds of the state grid.
+    /// For [BoundaryBehaviour::Symbol], cells outside the grid count with the value of the symbol.
+    pub col_boundary: BoundaryBehaviour,
+}
+
+impl LifeLikeRule {
+    /// Reads a rule in the B/S notation, such as ```B3/S23``` for the Game of Life or ```B36/S23``` for HighLife.
+    /// Both parts are required, may be given in any order and are not case sensitive, ```S23/B3``` and ```b3/s23``` are valid as well.
+    ///
+    /// Cells outside the grid count as dead.
+    /// ## Error
+    /// When the notation is malformed, for example when it contains neighbor counts above 8.
+    pub fn parse(notation: &str) -> Result<Self, crate::CelluminaError> {
+        let error = |column: usize, message: &str| crate::CelluminaError::ParseError {
+            source_kind: crate::SourceKind::RuleNotation,
+            line: 1,
+            column,
+            message: message.to_string(),
+        };
+
+        let mut birth = None;
+        let mut survival = None;
+        let mut column = 1;
+        for part in notation.trim().split('/') {
+            let mut chars = part.chars();
+            let counts = match chars.next().map(|char| char.to_ascii_uppercase()) {
+                Some('B') => &mut birth,
+                Some('S') => &mut survival,
+                _ => return Err(error(column, "expected a part starting with B or S")),
+            };
+            if counts.is_some() {
+                return Err(error(column, "duplicate part"));
+            }
+            let mut res = [false; 9];
+            for (offset, char) in chars.enumerate() {
+                match char.to_digit(10) {
+                    Some(count) if count <= 8 => res[count as usize] = true,
+                    _ => {
+                        return Err(error(
+                            column + offset + 1,
+                            "expected a number of neighbors from 0 to 8",
+                        ))
+                    }
+                }
+            }
+            *counts = Some(res);
+            column += part.chars().count() + 1;
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self {
+                birth,
+                survival,
+                row_boundary: BoundaryBehaviour::Symbol(0),
+                col_boundary: BoundaryBehaviour::Symbol(0),
+            }),
+            _ => Err(error(column - 1, "expected both a B and an S part")),
+        }
+    }
+}
+
+impl std::str::FromStr for LifeLikeRule {
+    type Err = crate::CelluminaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl super::Rule for LifeLikeRule {
+    fn transform(&self, grid: &mut CellGrid) {
+        let mut res = CellGrid::new(0, 0);
+        self.transform_buffered(grid, &mut res);
+        *grid = res;
+    }
+
+    fn clone_boxed(&self) -> Option<Box<dyn super::Rule>> {
+        Some(Box::new(*self))
+    }
+
+    fn transform_buffered(&self, grid: &mut CellGrid, buffer: &mut CellGrid) -> bool {
+        let sums = TotalisticRule {
+            radius: 1,
+            row_boundary: self.row_boundary,
+            col_boundary: self.col_boundary,
+            cell_transform: |_, _| 0,
+        };
+        sums.transform_sums(grid, buffer, |center, sum| {
+            let counts = if center == 0 {
+                &self.birth
+            } else {
+                &self.survival
+            };
+            u8::from(counts.get(sum as usize).copied().unwrap_or_default())
+        });
+        true
+    }
+
+    fn reach(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[test]
+fn totalistic_test() {
+    use super::{EnvironmentRule, Rule};
+    use rand::Rng;
+
+    fn transform(center: u8, sum: u32) -> u8 {
+        ((center as u32 * 7 + sum) % 4) as u8
+    }
+
+    let environment_rules = [
+        EnvironmentRule {
+            environment_size: [1, 1, 1, 1],
+            cell_transform: |env| {
+                transform(
+                    env[1][1],
+                    env.iter().map(|&cell| cell as u32).sum::<u32>() - env[1][1] as u32,
+                )
+            },
+            ..Default::default()
+        },
+        EnvironmentRule {
+            environment_size: [2, 2, 2, 2],
+            cell_transform: |env| {
+                transform(
+                    env[2][2],
+                    env.iter().map(|&cell| cell as u32).sum::<u32>() - env[2][2] as u32,
+                )
+            },
+            ..Default::default()
+        },
+    ];
+
+    let boundaries = [
+        BoundaryBehaviour::Periodic,
+        BoundaryBehaviour::Symbol(0),
+        BoundaryBehaviour::Symbol(3),
+        BoundaryBehaviour::Clamp,
+    ];
+
+    let mut rng = rand::thread_rng();
+    for (radius, environment_rule) in (1..).zip(environment_rules) {
+        for row_boundary in boundaries {
+            for col_boundary in boundaries {
+                let environment_rule = EnvironmentRule {
+                    row_boundary,
+                    col_boundary,
+                    ..environment_rule
+                };
+                let totalistic_rule = TotalisticRule {
+                    radius,
+                    row_boundary,
+                    col_boundary,
+                    cell_transform: transform,
+                };
+
+                let mut expected =
+                    CellGrid::from_vec((0..32 * 16).map(|_| rng.gen_range(0..4)).collect(), 16);
+                let mut res = expected.clone();
+                for _ in 0..3 {
+                    environment_rule.transform(&mut expected);
+                    totalistic_rule.transform(&mut res);
+                    assert_eq!(
+                        res, expected,
+                        "radius {radius}, boundaries {row_boundary}, {col_boundary}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn life_like_test() {
+    use super::Rule;
+    use rand::{Rng, SeedableRng};
+
+    // agrees with the game of life preset
+    let life = LifeLikeRule::parse("B3/S23").unwrap();
+    assert_eq!("s23/b3".parse::<LifeLikeRule>().unwrap(), life);
+    let preset = super::presets::game_of_life();
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let mut expected = CellGrid::from_vec((0..24 * 16).map(|_| rng.gen_range(0..2)).collect(), 16);
+    let mut res = expected.clone();
+    for _ in 0..5 {
+        preset.rule.transform(&mut expected);
+        life.transform(&mut res);
+        assert_eq!(res, expected);
+    }
+
+    // births with zero neighbors and survival with eight
+    let rule = LifeLikeRule::parse("B0/S8").unwrap();
+    let mut grid = grid::grid![[1, 1, 1][1, 1, 1][1, 1, 1]];
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[0, 0, 0][0, 1, 0][0, 0, 0]]);
+    rule.transform(&mut grid);
+    assert_eq!(grid, CellGrid::new(3, 3));
+    rule.transform(&mut grid);
+    assert_eq!(grid, grid::grid![[1, 1, 1][1, 1, 1][1, 1, 1]]);
+
+    // malformed notations
+    for (notation, column) in [
+        ("B3", 3),
+        ("B3/S29", 6),
+        ("B3/X23", 4),
+        ("B3/B3", 4),
+        ("", 1),
+    ] {
+        match LifeLikeRule::parse(notation) {
+            Err(crate::CelluminaError::ParseError {
+                source_kind: crate::SourceKind::RuleNotation,
+                column: got,
+                ..
+            }) => assert_eq!(got, column, "{notation}"),
+            res => panic!("{notation}: unexpected {res:?}"),
+        }
+    }
+}
@@ -0,0 +1,599 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread, time,
+};
+
+use crate::{AutomatonGeneric, Cell};
+
+/// The number of events a subscription created by [AutomatonHandle::subscribe] holds before dropping the oldest ones.
+pub const DEFAULT_EVENT_CAPACITY: usize = 64;
+
+/// The longest the runner waits for the next step before checking again, so changes to the speed or time step of the automaton made through [AutomatonHandle::automaton] take effect.
+const MAX_WAIT: time::Duration = time::Duration::from_millis(100);
+
+/// A notification about an automaton running on its own thread, see [AutomatonHandle::subscribe].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonEvent {
+    /// The automaton performed a time step.
+    StepCompleted {
+        /// The generation of the automaton after the step.
+        generation: u64,
+        /// The number of cells the step changed.
+        changed: usize,
+    },
+    /// A step changed no cell, after the previous step did or as the first step.
+    /// For deterministic rules, the automaton will not change anymore.
+    Stabilized {
+        /// The generation of the automaton after the step.
+        generation: u64,
+    },
+    /// The runner was stopped. This is the last event of every subscription.
+    Stopped,
+}
+
+/// The queue shared between the runner and one [EventReceiver].
+#[derive(Debug)]
+struct EventQueue {
+    /// The events not yet received, oldest first.
+    events: VecDeque<AutomatonEvent>,
+    /// The maximum number of events held.
+    capacity: usize,
+    /// The number of events dropped because the queue was full.
+    dropped: u64,
+    /// Wether the runner has stopped, so no more events will arrive.
+    closed: bool,
+}
+
+/// A bounded channel of events that drops its oldest event when full, so a slow receiver never stalls the runner.
+#[derive(Debug)]
+struct EventChannel {
+    /// The queue of events.
+    queue: Mutex<EventQueue>,
+    /// Notified whenever an event arrives or the channel is closed.
+    available: Condvar,
+}
+
+impl EventChannel {
+    /// Adds an event to the channel, dropping the oldest event if necessary.
+    fn send(&self, event: AutomatonEvent) {
+        let mut queue = lock(&self.queue);
+        if queue.events.len() >= queue.capacity {
+            queue.events.pop_front();
+            queue.dropped += 1;
+        }
+        queue.events.push_back(event);
+        self.available.notify_all();
+    }
+
+    /// Marks the channel as closed, waking up all waiting receivers.
+    fn close(&self) {
+        lock(&self.queue).closed = true;
+        self.available.notify_all();
+    }
+}
+
+/// Receives the [AutomatonEvent]s of an automaton running on its own thread, as created by [AutomatonHandle::subscribe].
+///
+/// The channel holds a limited number of events. When a new event arrives while it is full, the oldest event is dropped, which [EventReceiver::dropped] counts.
+/// The receiver is also an [Iterator], blocking for each event until the runner is stopped and all events have been received.
+#[derive(Debug)]
+pub struct EventReceiver {
+    /// The channel shared with the runner.
+    channel: Arc<EventChannel>,
+}
+
+impl EventReceiver {
+    /// Waits for the next event. Returns [None] once the runner is stopped and all events have been received.
+    pub fn recv(&self) -> Option<AutomatonEvent> {
+        let mut queue = lock(&self.channel.queue);
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self
+                .channel
+                .available
+                .wait(queue)
+                .unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    /// Waits at most for the passed duration for the next event.
+    /// Returns [None] if no event arrived in time, or once the runner is stopped and all events have been received.
+    pub fn recv_timeout(&self, timeout: time::Duration) -> Option<AutomatonEvent> {
+        let deadline = time::Instant::now() + timeout;
+        let mut queue = lock(&self.channel.queue);
+        loop {
+            if let Some(event) = queue.events.pop_front() {
+                return Some(event);
+            }
+            let now = time::Instant::now();
+            if queue.closed || now >= deadline {
+                return None;
+            }
+            queue = self
+                .channel
+                .available
+                .wait_timeout(queue, deadline - now)
+                .unwrap_or_else(|err| err.into_inner())
+                .0;
+        }
+    }
+
+    /// Returns the next event if there is one, without waiting.
+    pub fn try_recv(&self) -> Option<AutomatonEvent> {
+        lock(&self.channel.queue).events.pop_front()
+    }
+
+    /// Returns the number of events that were dropped so far because the channel was full.
+    pub fn dropped(&self) -> u64 {
+        lock(&self.channel.queue).dropped
+    }
+}
+
+impl Iterator for EventReceiver {
+    type Item = AutomatonEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// Tells the runner thread what to do.
+#[derive(Debug)]
+struct Control {
+    /// Wether the runner is currently performing steps.
+    running: bool,
+    /// The number of steps to perform before pausing, or [None] to run until paused.
+    remaining: Option<u64>,
+    /// Wether the runner should stop entirely.
+    stop: bool,
+    /// The channels of all subscriptions.
+    subscribers: Vec<Arc<EventChannel>>,
+}
+
+/// The state shared between an [AutomatonHandle] and its runner thread.
+#[derive(Debug)]
+struct Shared {
+    /// The instructions for the runner.
+    control: Mutex<Control>,
+    /// Notified whenever the instructions change.
+    changed: Condvar,
+}
+
+impl Shared {
+    /// Sends the passed event to all subscribers.
+    fn emit(&self, event: AutomatonEvent) {
+        for subscriber in &lock(&self.control).subscribers {
+            subscriber.send(event);
+        }
+    }
+}
+
+/// Locks the passed mutex, ignoring poisoning as the data behind it remains consistent.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// An automaton running on its own thread, for example as part of a server.
+///
+/// The runner starts paused and performs steps after a call to [AutomatonHandle::run], respecting the [minimum time step](crate::AutomatonBuilder::with_min_time_step) of the automaton.
+/// Other threads receive notifications about its progress by [subscribing](AutomatonHandle::subscribe), and may access the automaton between steps with [AutomatonHandle::automaton].
+/// ```
+/// use cellumina::{AutomatonEvent, AutomatonHandle};
+///
+/// let automaton = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+///     .with_preset(cellumina::rule::presets::game_of_life())
+///     .build();
+/// let handle = AutomatonHandle::spawn(automaton).unwrap();
+/// let events = handle.subscribe();
+/// handle.run(Some(2));
+/// assert_eq!(events.recv(), Some(AutomatonEvent::StepCompleted { generation: 1, changed: 4 }));
+/// assert_eq!(events.recv(), Some(AutomatonEvent::StepCompleted { generation: 2, changed: 4 }));
+///
+/// let automaton = handle.stop();
+/// assert_eq!(automaton.generation(), 2);
+/// assert_eq!(events.recv(), Some(AutomatonEvent::Stopped));
+/// ```
+#[derive(Debug)]
+pub struct AutomatonHandle<T: Cell = u8> {
+    /// The automaton, locked by the runner while it performs a step.
+    automaton: Arc<Mutex<AutomatonGeneric<T>>>,
+    /// The runner thread.
+    runner: Runner,
+}
+
+/// The runner thread of an [AutomatonHandle], stopped when dropped.
+#[derive(Debug)]
+struct Runner {
+    /// The state shared with the runner.
+    shared: Arc<Shared>,
+    /// The runner thread, until it is stopped.
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl<T: Cell> AutomatonHandle<T> {
+    /// Moves the automaton to a new, paused runner thread.
+    ///
+    /// While the automaton runs, it counts the cells changed by each step, so rules keeping the state in memory of their own, such as on the GPU, hand it back after every step.
+    ///
+    /// ## Errors
+    /// Returns the error of [thread::Builder::spawn] if the runner thread can not be created.
+    pub fn spawn(mut automaton: AutomatonGeneric<T>) -> std::io::Result<Self> {
+        automaton.changes = Some(0);
+        let automaton = Arc::new(Mutex::new(automaton));
+        let shared = Arc::new(Shared {
+            control: Mutex::new(Control {
+                running: false,
+                remaining: None,
+                stop: false,
+                subscribers: Vec::new(),
+            }),
+            changed: Condvar::new(),
+        });
+        let thread = {
+            let automaton = automaton.clone();
+            let shared = shared.clone();
+            thread::Builder::new()
+                .name("cellumina-runner".to_string())
+                .spawn(move || run_thread(&automaton, &shared))?
+        };
+        Ok(Self {
+            automaton,
+            runner: Runner {
+                shared,
+                thread: Some(thread),
+            },
+        })
+    }
+
+    /// Subscribes to the events of this runner, with a channel of [DEFAULT_EVENT_CAPACITY] events.
+    /// Only events after the subscription are received.
+    pub fn subscribe(&self) -> EventReceiver {
+        self.subscribe_with_capacity(DEFAULT_EVENT_CAPACITY)
+    }
+
+    /// Subscribes to the events of this runner, with a channel holding at most ```capacity``` events before dropping the oldest ones.
+    /// Only events after the subscription are received.
+    pub fn subscribe_with_capacity(&self, capacity: usize) -> EventReceiver {
+        let channel = Arc::new(EventChannel {
+            queue: Mutex::new(EventQueue {
+                events: VecDeque::new(),
+                capacity: capacity.max(1),
+                dropped: 0,
+                closed: false,
+            }),
+            available: Condvar::new(),
+        });
+        lock(&self.runner.shared.control)
+            .subscribers
+            .push(channel.clone());
+        EventReceiver { channel }
+    }
+
+    /// Starts performing steps, replacing any previous instruction. With some number of steps, the runner pauses after performing them.
+    pub fn run(&self, steps: Option<u64>) {
+        let mut control = lock(&self.runner.shared.control);
+        control.running = steps != Some(0);
+        control.remaining = steps;
+        self.runner.shared.changed.notify_all();
+    }
+
+    /// Pauses the runner after the current step, if it is performing one.
+    pub fn pause(&self) {
+        lock(&self.runner.shared.control).running = false;
+        self.runner.shared.changed.notify_all();
+    }
+
+    /// Gives access to the automaton, waiting for the current step to complete. The runner performs no steps while the access is held.
+    pub fn automaton(&self) -> MutexGuard<'_, AutomatonGeneric<T>> {
+        lock(&self.automaton)
+    }
+
+    /// Stops the runner after the current step and returns the automaton.
+    /// All subscribers receive [AutomatonEvent::Stopped] as their last event.
+    pub fn stop(self) -> AutomatonGeneric<T> {
+        let Self { automaton, runner } = self;
+        // the runner thread holds the only other reference to the automaton until it ends
+        drop(runner);
+        match Arc::try_unwrap(automaton) {
+            Ok(automaton) => {
+                let mut automaton = automaton
+                    .into_inner()
+                    .unwrap_or_else(|err| err.into_inner());
+                automaton.changes = None;
+                automaton
+            }
+            Err(_) => unreachable!("The runner thread has ended, so the automaton is not shared."),
+        }
+    }
+}
+
+impl Drop for Runner {
+    /// Tells the runner thread to stop and waits for it to end.
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            lock(&self.shared.control).stop = true;
+            self.shared.changed.notify_all();
+            if thread.join().is_err() {
+                log::error!("The runner thread panicked.");
+            }
+        }
+    }
+}
+
+/// Performs steps as instructed until told to stop, then informs all subscribers.
+fn run_thread<T: Cell>(automaton: &Mutex<AutomatonGeneric<T>>, shared: &Shared) {
+    let mut stable = false;
+    loop {
+        // wait for instructions
+        {
+            let mut control = lock(&shared.control);
+            while !control.stop && !control.running {
+                control = shared
+                    .changed
+                    .wait(control)
+                    .unwrap_or_else(|err| err.into_inner());
+            }
+            if control.stop {
+                break;
+            }
+        }
+
+        // wait for the minimum time step, without holding the automaton
+        let wait = lock(automaton).time_until_next_step().min(MAX_WAIT);
+        if !wait.is_zero() {
+            let control = lock(&shared.control);
+            let (control, _) = shared
+                .changed
+                .wait_timeout(control, wait)
+                .unwrap_or_else(|err| err.into_inner());
+            // re-check the instructions, they may have changed while waiting
+            if control.stop || !control.running {
+                continue;
+            }
+        }
+
+        // perform the step
+        let (generation, changed) = {
+            let mut automaton = lock(automaton);
+            if !automaton.next_step() {
+                continue;
+            }
+            (automaton.generation, automaton.changes.unwrap_or_default())
+        };
+
+        shared.emit(AutomatonEvent::StepCompleted {
+            generation,
+            changed,
+        });
+        if changed == 0 && !stable {
+            shared.emit(AutomatonEvent::Stabilized { generation });
+        }
+        stable = changed == 0;
+
+        let mut control = lock(&shared.control);
+        if let Some(remaining) = &mut control.remaining {
+            *remaining = remaining.saturating_sub(1);
+            if *remaining == 0 {
+                control.running = false;
+            }
+        }
+    }
+
+    let control = lock(&shared.control);
+    for subscriber in &control.subscribers {
+        subscriber.send(AutomatonEvent::Stopped);
+        subscriber.close();
+    }
+}
+
+#[test]
+fn event_test() {
+    // a single cell falls down by one row each step and comes to rest at the bottom
+    let automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 0, 0, 0], 1)
+        .with_pattern(crate::rule::Pattern {
+            before: grid::grid![[1][0]],
+            after: grid::grid![[0][1]],
+            ..Default::default()
+        })
+        .build();
+
+    let handle = AutomatonHandle::spawn(automaton).unwrap();
+    let events = handle.subscribe();
+    handle.run(Some(5));
+
+    let expected = [
+        AutomatonEvent::StepCompleted {
+            generation: 1,
+            changed: 2,
+        },
+        AutomatonEvent::StepCompleted {
+            generation: 2,
+            changed: 2,
+        },
+        AutomatonEvent::StepCompleted {
+            generation: 3,
+            changed: 2,
+        },
+        AutomatonEvent::StepCompleted {
+            generation: 4,
+            changed: 0,
+        },
+        AutomatonEvent::Stabilized { generation: 4 },
+        AutomatonEvent::StepCompleted {
+            generation: 5,
+            changed: 0,
+        },
+    ];
+    for event in expected {
+        assert_eq!(events.recv(), Some(event));
+    }
+
+    // the runner pauses after the requested steps
+    assert_eq!(events.recv_timeout(time::Duration::from_millis(50)), None);
+    assert_eq!(handle.automaton().generation(), 5);
+    assert_eq!(handle.automaton().state()[3][0], 1);
+
+    // later subscriptions only receive later events
+    let late = handle.subscribe();
+    handle.run(Some(1));
+    assert_eq!(
+        late.recv(),
+        Some(AutomatonEvent::StepCompleted {
+            generation: 6,
+            changed: 0
+        })
+    );
+
+    let automaton = handle.stop();
+    assert_eq!(automaton.generation(), 6);
+    assert_eq!(
+        events.collect::<Vec<_>>(),
+        [
+            AutomatonEvent::StepCompleted {
+                generation: 6,
+                changed: 0
+            },
+            AutomatonEvent::Stopped
+        ]
+    );
+    assert_eq!(late.recv(), Some(AutomatonEvent::Stopped));
+    assert_eq!(late.recv(), None);
+}
+
+#[test]
+fn changed_cells_test() {
+    // the automaton counts the changed cells itself, with and without dirty tracking
+    for dirty_tracking in [false, true] {
+        // a blinker, transformed into a buffer, and a falling cell, transformed in-place
+        let blinker = crate::AutomatonBuilder::new()
+            .from_vec(vec![0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0], 4)
+            .with_preset(crate::rule::presets::game_of_life());
+        let falling = crate::AutomatonBuilder::new()
+            .from_vec(vec![1, 0, 0], 1)
+            .with_pattern(crate::rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            });
+        for (builder, changes) in [(blinker, [4, 4, 4]), (falling, [2, 2, 0])] {
+            let builder = if dirty_tracking {
+                builder.with_dirty_tracking()
+            } else {
+                builder
+            };
+            let handle = AutomatonHandle::spawn(builder.build()).unwrap();
+            let events = handle.subscribe();
+            handle.run(Some(3));
+            let changed = events
+                .filter_map(|event| match event {
+                    AutomatonEvent::StepCompleted { changed, .. } => Some(changed),
+                    _ => None,
+                })
+                .take(3)
+                .collect::<Vec<_>>();
+            assert_eq!(changed, changes);
+
+            // automata no longer count their changes once stopped
+            assert_eq!(handle.stop().changes, None);
+        }
+    }
+}
+
+#[test]
+fn backpressure_test() {
+    // a subscriber that does not keep up only misses the oldest events
+    let handle = AutomatonHandle::spawn(
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0; 4], 2)
+            .build(),
+    )
+    .unwrap();
+    let events = handle.subscribe_with_capacity(3);
+    handle.run(Some(10));
+    while handle.automaton().generation() < 10 {
+        thread::yield_now();
+    }
+    drop(handle);
+
+    assert_eq!(events.dropped(), 9);
+    assert_eq!(
+        events.collect::<Vec<_>>(),
+        [
+            AutomatonEvent::StepCompleted {
+                generation: 9,
+                changed: 0
+            },
+            AutomatonEvent::StepCompleted {
+                generation: 10,
+                changed: 0
+            },
+            AutomatonEvent::Stopped
+        ]
+    );
+}
+
+#[test]
+fn speed_multiplier_test() {
+    let build = || {
+        crate::AutomatonBuilder::new()
+            .from_vec(vec![0; 4], 2)
+            .with_min_time_step(time::Duration::from_millis(20))
+            .build()
+    };
+
+    // at half speed, steps take twice as long
+    let mut automaton = build();
+    automaton.set_speed_multiplier(0.5).unwrap();
+    let handle = AutomatonHandle::spawn(automaton).unwrap();
+    let events = handle.subscribe();
+    let start = time::Instant::now();
+    handle.run(Some(3));
+    let steps = events
+        .take_while(|event| {
+            *event
+                != AutomatonEvent::StepCompleted {
+                    generation: 3,
+                    changed: 0,
+                }
+        })
+        .filter(|event| matches!(event, AutomatonEvent::StepCompleted { .. }))
+        .count();
+    assert_eq!(steps, 2);
+    assert!(start.elapsed() >= time::Duration::from_millis(120));
+    drop(handle);
+
+    // at speeds too slow for a duration, the runner waits without stepping, but can still be stopped
+    let mut automaton = build();
+    automaton.set_speed_multiplier(f32::MIN_POSITIVE).unwrap();
+    let handle = AutomatonHandle::spawn(automaton).unwrap();
+    let events = handle.subscribe();
+    handle.run(None);
+    assert_eq!(events.recv_timeout(time::Duration::from_millis(150)), None);
+    let automaton = handle.stop();
+    assert_eq!(automaton.generation(), 0);
+    assert_eq!(events.recv(), Some(AutomatonEvent::Stopped));
+
+    // speeding up while waiting takes effect
+    let mut automaton = build();
+    automaton.set_speed_multiplier(f32::MIN_POSITIVE).unwrap();
+    let handle = AutomatonHandle::spawn(automaton).unwrap();
+    let events = handle.subscribe();
+    handle.run(Some(1));
+    thread::sleep(time::Duration::from_millis(50));
+    handle.automaton().set_speed_multiplier(1.).unwrap();
+    assert_eq!(
+        events.recv_timeout(time::Duration::from_secs(5)),
+        Some(AutomatonEvent::StepCompleted {
+            generation: 1,
+            changed: 0
+        })
+    );
+}
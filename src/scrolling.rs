@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use crate::{Automaton, CellGrid};
+
+/// An automaton showing a window onto a world that extends indefinitely to the left and right, such as the landscape of a falling sand game.
+///
+/// Scrolling shifts the columns of the state, discarding the ones moving out of the window and generating the ones moving in with a callback,
+/// which receives the global index of the column to generate.
+/// Global column indices count from the leftmost column of the initial state, so a column keeps its global index no matter how far the window was scrolled.
+/// Discarded columns are forgotten, unless [persistence](ScrollingAutomaton::with_persistence) is enabled, in which case they are restored instead of generated when scrolling back.
+///
+/// The [auxiliary layer](crate::AutomatonBuilder::with_aux_layer) and the state rules keep for each cell, such as cooldowns, scroll along with the cells.
+/// Auxiliary cells moving in are restored along with their column if it was persisted and set to ```0``` otherwise, rules start them without any state.
+///
+/// The rule of the wrapped automaton knows nothing about the world outside the window,
+/// so column boundaries are best set to a fixed symbol or to [Clamp](crate::rule::BoundaryBehaviour::Clamp) rather than to [Periodic](crate::rule::BoundaryBehaviour::Periodic).
+/// ```
+/// # use cellumina::{AutomatonBuilder, ScrollingAutomaton};
+/// let automaton = AutomatonBuilder::new().from_vec(vec![1, 2, 3, 4, 5, 6], 3).build();
+/// let mut world = ScrollingAutomaton::new(automaton).with_persistence();
+/// // columns left of the initial state are filled with 7, columns right of it with 9
+/// let terrain = |col: i64| if col < 0 { vec![7, 7] } else { vec![9, 9] };
+/// world.scroll(2, terrain).unwrap();
+/// assert_eq!(world.automaton().state(), &grid::grid![[3, 9, 9][6, 9, 9]]);
+/// assert_eq!(world.to_global(0), 2);
+/// // the columns scrolled out before are restored
+/// world.scroll(-3, terrain).unwrap();
+/// assert_eq!(world.automaton().state(), &grid::grid![[7, 1, 2][7, 4, 5]]);
+/// assert_eq!(world.to_local(0), Some(1));
+/// ```
+pub struct ScrollingAutomaton {
+    /// The automaton holding the visible window of the world.
+    automaton: Automaton,
+    /// The global index of the leftmost column of the state.
+    offset: i64,
+    /// The columns scrolled out of the window by their global index, if discarded columns are persisted.
+    persisted: Option<HashMap<i64, Column>>,
+    /// The symbol the window follows in [ScrollingAutomaton::next_step], if any.
+    following: Option<u8>,
+}
+
+/// The cells of a column of the world outside the window, from top to bottom.
+#[derive(Debug, Clone)]
+struct Column {
+    /// The cells of the state.
+    cells: Vec<u8>,
+    /// The cells of the auxiliary layer, if the automaton has one.
+    aux: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for ScrollingAutomaton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScrollingAutomaton")
+            .field("dimensions", &self.automaton.dimensions())
+            .field("offset", &self.offset)
+            .field(
+                "persisted",
+                &self.persisted.as_ref().map(|persisted| persisted.len()),
+            )
+            .field("following", &self.following)
+            .finish()
+    }
+}
+
+impl ScrollingAutomaton {
+    /// Wraps the passed automaton, its state becoming the window onto the world between the global columns ```0``` and ```cols - 1```.
+    pub fn new(automaton: Automaton) -> Self {
+        Self {
+            automaton,
+            offset: 0,
+            persisted: None,
+            following: None,
+        }
+    }
+
+    /// Keeps the columns scrolled out of the window and restores them when they are scrolled back in, instead of generating them anew.
+    pub fn with_persistence(mut self) -> Self {
+        self.persisted.get_or_insert_with(HashMap::new);
+        self
+    }
+
+    /// Returns the wrapped automaton.
+    pub fn automaton(&self) -> &Automaton {
+        &self.automaton
+    }
+
+    /// Returns the wrapped automaton, for example to set cells manually. Cells are addressed by their position in the window, see [Self::to_local].
+    pub fn automaton_mut(&mut self) -> &mut Automaton {
+        &mut self.automaton
+    }
+
+    /// Returns the global index of the leftmost column of the window, which is the total distance scrolled to the right.
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Returns the global index of the column at the passed position in the window.
+    pub fn to_global(&self, col: usize) -> i64 {
+        self.offset + col as i64
+    }
+
+    /// Returns the position in the window of the column with the passed global index, if it is currently visible.
+    pub fn to_local(&self, col: i64) -> Option<usize> {
+        let local = usize::try_from(col - self.offset).ok()?;
+        (local < self.automaton.state().cols()).then_some(local)
+    }
+
+    /// Returns the persisted column with the passed global index, if discarded columns are persisted and the column was scrolled out of the window.
+    pub fn persisted_column(&self, col: i64) -> Option<&[u8]> {
+        self.persisted
+            .as_ref()?
+            .get(&col)
+            .map(|column| column.cells.as_slice())
+    }
+
+    /// Makes [Self::next_step] scroll the window after each step to keep the cells holding the passed symbol away from its left and right edges.
+    pub fn follow(&mut self, symbol: u8) {
+        self.following = Some(symbol);
+    }
+
+    /// Stops following a symbol, so [Self::next_step] no longer scrolls the window.
+    pub fn stop_following(&mut self) {
+        self.following = None;
+    }
+
+    /// Returns the symbol followed by the window, if any.
+    pub fn following(&self) -> Option<u8> {
+        self.following
+    }
+
+    /// Moves the window ```dx``` columns to the right, or to the left for negative values, shifting the content of the state the opposite way.
+    /// Columns moving into the window are restored if they were persisted and otherwise generated by calling ```generator``` with their global index.
+    /// The generated columns list their cells from top to bottom.
+    ///
+    /// The cells of the state are set like with [Automaton::set_region](crate::AutomatonGeneric::set_region), so a step in progress is discarded.
+    /// The auxiliary layer and the state the rule keeps for each cell are shifted along, see [ScrollingAutomaton].
+    /// ## Error
+    /// When the generator returns a column that does not match the number of rows of the state. In that case, the state and the offset remain unchanged.
+    pub fn scroll(
+        &mut self,
+        dx: i32,
+        generator: impl Fn(i64) -> Vec<u8>,
+    ) -> Result<(), crate::CelluminaError> {
+        let (rows, cols) = self.automaton.state().size();
+        if dx == 0 || cols == 0 {
+            return Ok(());
+        }
+        let dx = dx as i64;
+        let new_offset = self.offset + dx;
+
+        // generate all columns first, so nothing changes if one of them fails
+        let mut incoming = HashMap::new();
+        for col in new_offset..new_offset + cols as i64 {
+            if self.to_local(col).is_some() {
+                continue;
+            }
+            let column = match self
+                .persisted
+                .as_ref()
+                .and_then(|persisted| persisted.get(&col))
+            {
+                Some(column) => column.clone(),
+                None => Column {
+                    cells: generator(col),
+                    aux: None,
+                },
+            };
+            if column.cells.len() != rows {
+                return Err(crate::CelluminaError::DimensionMismatch {
+                    expected: (rows, 1),
+                    got: (column.cells.len(), 1),
+                });
+            }
+            incoming.insert(col, column);
+        }
+
+        let old = self.automaton.state();
+        let state = self.shifted(old, new_offset, |col| &incoming[&col].cells);
+        let empty = vec![0; rows];
+        let aux = self.automaton.aux_layer().map(|aux| {
+            self.shifted(aux, new_offset, |col| {
+                incoming[&col].aux.as_ref().unwrap_or(&empty)
+            })
+        });
+
+        if let Some(persisted) = &mut self.persisted {
+            for local in 0..cols {
+                let col = self.offset + local as i64;
+                if col < new_offset || col >= new_offset + cols as i64 {
+                    let column = Column {
+                        cells: old.iter_col(local).copied().collect(),
+                        aux: self
+                            .automaton
+                            .aux_layer()
+                            .map(|aux| aux.iter_col(local).copied().collect()),
+                    };
+                    persisted.insert(col, column);
+                }
+            }
+            for col in incoming.keys() {
+                persisted.remove(col);
+            }
+        }
+
+        self.offset = new_offset;
+        // the sizes match, so this can not fail
+        let _ = self.automaton.set_region(0, 0, &state);
+        if aux.is_some() {
+            // rules reading the auxiliary layer may change cells even where the state stayed the same
+            self.automaton.aux = aux;
+            self.automaton
+                .mark_dirty(crate::rule::Region::full(rows, cols));
+        }
+        // the content moves the opposite way of the window
+        self.automaton.rule.shift_columns(-dx);
+        Ok(())
+    }
+
+    /// Returns the passed grid of the window moved to start at the passed global column, taking the columns moving in from ```incoming```.
+    fn shifted<'a>(
+        &self,
+        old: &CellGrid,
+        new_offset: i64,
+        incoming: impl Fn(i64) -> &'a Vec<u8>,
+    ) -> CellGrid {
+        let (rows, cols) = old.size();
+        let mut res = CellGrid::new(rows, cols);
+        for local in 0..cols {
+            let col = new_offset + local as i64;
+            match self.to_local(col) {
+                Some(old_local) => {
+                    for row in 0..rows {
+                        res[row][local] = old[row][old_local];
+                    }
+                }
+                None => {
+                    for (row, &cell) in incoming(col).iter().enumerate() {
+                        res[row][local] = cell;
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Performs a time step of the wrapped automaton if permitted by its step mode, see [Automaton::next_step](crate::AutomatonGeneric::next_step).
+    /// If the window follows a symbol and the average column of the cells holding it moved into the outer quarter of the window, the window then scrolls to center it again,
+    /// generating new columns with the passed generator as for [Self::scroll].
+    /// Returns wether a step was performed.
+    /// ## Error
+    /// When the generator returns a column of the wrong length, see [Self::scroll]. The step is still performed.
+    pub fn next_step(
+        &mut self,
+        generator: impl Fn(i64) -> Vec<u8>,
+    ) -> Result<bool, crate::CelluminaError> {
+        let stepped = self.automaton.next_step();
+        if let Some(dx) = self.follow_distance() {
+            self.scroll(dx, generator)?;
+        }
+        Ok(stepped)
+    }
+
+    /// Returns the distance to scroll to center the followed symbol, if it is followed, present and too close to an edge.
+    fn follow_distance(&self) -> Option<i32> {
+        let symbol = self.following?;
+        let state = self.automaton.state();
+        let cols = state.cols();
+        let (sum, count) = state
+            .iter_rows()
+            .flat_map(|row| row.enumerate())
+            .filter(|(_, &cell)| cell == symbol)
+            .fold((0, 0), |(sum, count), (col, _)| (sum + col, count + 1));
+        if count == 0 {
+            return None;
+        }
+        let center = sum / count;
+        if center >= cols / 4 && center < cols - cols / 4 {
+            return None;
+        }
+        Some(center as i32 - (cols / 2) as i32)
+    }
+}
+
+#[test]
+fn scrolling_test() {
+    let cells = (0..12).collect::<Vec<u8>>();
+    let automaton = crate::AutomatonBuilder::new().from_vec(cells, 4).build();
+    let mut world = ScrollingAutomaton::new(automaton).with_persistence();
+    // generated columns contain their global index, offset to tell them apart
+    let generator = |col: i64| vec![(100 + col) as u8; 3];
+
+    world.scroll(1, generator).unwrap();
+    assert_eq!(
+        world.automaton().state(),
+        &grid::grid![[1, 2, 3, 104][5, 6, 7, 104][9, 10, 11, 104]]
+    );
+    assert_eq!(world.persisted_column(0), Some([0, 4, 8].as_slice()));
+
+    // scrolling back restores the persisted column and keeps the generated one
+    world.scroll(-2, generator).unwrap();
+    assert_eq!(
+        world.automaton().state(),
+        &grid::grid![[99, 0, 1, 2][99, 4, 5, 6][99, 8, 9, 10]]
+    );
+    assert_eq!(world.persisted_column(0), None);
+    assert_eq!(world.persisted_column(4), Some([104; 3].as_slice()));
+    assert_eq!(world.persisted_column(3), Some([3, 7, 11].as_slice()));
+
+    // scrolling further than the window is wide replaces every column
+    world.scroll(7, generator).unwrap();
+    assert_eq!(world.offset(), 6);
+    assert_eq!(
+        world
+            .automaton()
+            .state()
+            .iter_col(0)
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![106; 3]
+    );
+    world.scroll(-6, generator).unwrap();
+    assert_eq!(
+        world.automaton().state(),
+        &grid::grid![[0, 1, 2, 3][4, 5, 6, 7][8, 9, 10, 11]]
+    );
+
+    // global coordinates stay consistent across scrolls
+    for dx in [3, -1, 5, -8, 2] {
+        world.scroll(dx, generator).unwrap();
+        for local in 0..4 {
+            let global = world.to_global(local);
+            assert_eq!(world.to_local(global), Some(local));
+            let expected = match global {
+                0..=3 => vec![global as u8, global as u8 + 4, global as u8 + 8],
+                _ => vec![(100 + global) as u8; 3],
+            };
+            assert_eq!(
+                world
+                    .automaton()
+                    .state()
+                    .iter_col(local)
+                    .copied()
+                    .collect::<Vec<_>>(),
+                expected
+            );
+        }
+    }
+    assert_eq!(world.offset(), 1);
+    assert_eq!(world.to_local(0), None);
+    assert_eq!(world.to_local(5), None);
+
+    // generated columns of the wrong length leave everything unchanged
+    let before = world.automaton().state().clone();
+    assert!(matches!(
+        world.scroll(-4, |_| vec![0; 2]),
+        Err(crate::CelluminaError::DimensionMismatch {
+            expected: (3, 1),
+            got: (2, 1)
+        })
+    ));
+    assert_eq!(world.automaton().state(), &before);
+    assert_eq!(world.offset(), 1);
+}
+
+#[test]
+fn follow_test() {
+    use crate::rule;
+
+    // a single cell moving right by one column each step
+    let mut cells = vec![0; 8];
+    cells[1] = 1;
+    let automaton = crate::AutomatonBuilder::new()
+        .from_vec(cells, 8)
+        .with_rule(rule::ShiftRule {
+            dy: 0,
+            dx: 1,
+            fill: Some(0),
+        })
+        .build();
+    let mut world = ScrollingAutomaton::new(automaton);
+    world.follow(1);
+    assert_eq!(world.following(), Some(1));
+
+    let mut global = 1;
+    for _ in 0..20 {
+        assert!(world.next_step(|_| vec![0]).unwrap());
+        global += 1;
+        let local = world.to_local(global).unwrap();
+        assert_eq!(world.automaton().state()[0][local], 1);
+        assert!((2..6).contains(&local));
+    }
+    assert!(world.offset() > 10);
+
+    world.stop_following();
+    let offset = world.offset();
+    for _ in 0..3 {
+        world.next_step(|_| vec![0]).unwrap();
+    }
+    assert_eq!(world.offset(), offset);
+}
+
+#[test]
+fn scroll_layers_test() {
+    use crate::rule;
+
+    // every cell counts up by its value in the auxiliary layer
+    let aux = grid::grid![[1, 2, 3, 4][5, 6, 7, 8]];
+    for dirty_tracking in [false, true] {
+        let mut builder = crate::AutomatonBuilder::new()
+            .from_vec(vec![0; 8], 4)
+            .with_aux_layer(aux.clone())
+            .with_rule(rule::AuxEnvironmentRule {
+                environment_size: [0, 0, 0, 0],
+                cell_transform: |env, aux| env[0][0] + aux[0][0],
+                ..Default::default()
+            });
+        if dirty_tracking {
+            builder = builder.with_dirty_tracking();
+        }
+        let mut world = ScrollingAutomaton::new(builder.build()).with_persistence();
+
+        // the auxiliary layer moves along with the state, new columns start at zero
+        world.scroll(1, |_| vec![0; 2]).unwrap();
+        assert_eq!(
+            world.automaton().aux_layer(),
+            Some(&grid::grid![[2, 3, 4, 0][6, 7, 8, 0]])
+        );
+        world.automaton_mut().next_step();
+        assert_eq!(
+            world.automaton().state(),
+            &grid::grid![[2, 3, 4, 0][6, 7, 8, 0]]
+        );
+
+        // and is restored with persisted columns
+        world.scroll(-1, |_| vec![0; 2]).unwrap();
+        assert_eq!(world.automaton().aux_layer(), Some(&aux));
+        world.automaton_mut().next_step();
+        assert_eq!(
+            world.automaton().state(),
+            &grid::grid![[1, 4, 6, 8][5, 12, 14, 16]]
+        );
+    }
+
+    // fire burning out after 3 steps keeps its age when scrolled
+    let automaton = crate::AutomatonBuilder::new()
+        .from_vec(vec![1, 0, 0], 3)
+        .with_decay(&[(1, 2, 3)])
+        .build();
+    let mut world = ScrollingAutomaton::new(automaton);
+    world.automaton_mut().next_step();
+    world.automaton_mut().next_step();
+    world.scroll(-1, |_| vec![0]).unwrap();
+    assert_eq!(world.automaton().state(), &grid::grid![[0, 1, 0]]);
+    world.automaton_mut().next_step();
+    assert_eq!(world.automaton().state(), &grid::grid![[0, 2, 0]]);
+}
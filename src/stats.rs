@@ -0,0 +1,217 @@
+use crate::Cell;
+
+/// The number of generations a [StatsRecorder] keeps by default.
+pub const DEFAULT_STATS_CAPACITY: usize = 10_000;
+
+/// Records how many cells of each tracked symbol an automaton's state contained in every generation, for example to plot population curves.
+///
+/// Attach a recorder to an automaton with [AutomatonBuilder::with_stats_recorder](crate::AutomatonBuilder::with_stats_recorder) and read it using [Automaton::stats_recorder](crate::Automaton::stats_recorder),
+/// or feed it states yourself using [StatsRecorder::record].
+/// Only the most recent generations up to the capacity of the recorder are kept.
+/// ```
+/// # use cellumina::StatsRecorder;
+/// let mut auto = cellumina::AutomatonBuilder::new()
+///     .from_vec(vec![0, 1, 0, 0, 1, 0, 0, 1, 0], 3)
+///     .with_rule(cellumina::rule::LifeLikeRule::parse("B3/S23").unwrap())
+///     .with_stats_recorder(vec![1])
+///     .build();
+/// auto.next_step();
+/// let recorder = auto.stats_recorder().unwrap();
+/// assert_eq!(recorder.generations(), [0, 1]);
+/// assert_eq!(recorder.series(1), [3, 3]);
+///
+/// let mut csv = Vec::new();
+/// recorder.write_csv(&mut csv).unwrap();
+/// assert_eq!(String::from_utf8(csv).unwrap(), "generation,1\n0,3\n1,3\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StatsRecorder<T: Cell = u8> {
+    /// The tracked symbols, in the order of their columns in CSV output.
+    symbols: Vec<T>,
+    /// The recorded generations.
+    generations: RingBuffer<u64>,
+    /// The recorded counts, one buffer per tracked symbol.
+    counts: Vec<RingBuffer<usize>>,
+}
+
+impl<T: Cell> StatsRecorder<T> {
+    /// Creates a recorder tracking the passed symbols and keeping the last [DEFAULT_STATS_CAPACITY] generations.
+    pub fn new(symbols: Vec<T>) -> Self {
+        Self::with_capacity(symbols, DEFAULT_STATS_CAPACITY)
+    }
+
+    /// Creates a recorder tracking the passed symbols and keeping the last ```capacity``` generations, but at least one.
+    pub fn with_capacity(symbols: Vec<T>, capacity: usize) -> Self {
+        Self {
+            generations: RingBuffer::new(capacity),
+            counts: symbols.iter().map(|_| RingBuffer::new(capacity)).collect(),
+            symbols,
+        }
+    }
+
+    /// Counts the tracked symbols in the passed state and records the counts for the passed generation.
+    pub fn record(&mut self, generation: u64, state: &grid::Grid<T>) {
+        let mut counts = vec![0; self.symbols.len()];
+        for cell in state.iter() {
+            if let Some(index) = self.symbols.iter().position(|symbol| symbol == cell) {
+                counts[index] += 1;
+            }
+        }
+        self.generations.push(generation);
+        for (series, count) in self.counts.iter_mut().zip(counts) {
+            series.push(count);
+        }
+    }
+
+    /// Removes all recorded generations.
+    pub fn clear(&mut self) {
+        self.generations.clear();
+        self.counts.iter_mut().for_each(RingBuffer::clear);
+    }
+
+    /// Returns the symbols tracked by this recorder.
+    pub fn symbols(&self) -> &[T] {
+        &self.symbols
+    }
+
+    /// Returns the recorded generations, oldest first.
+    pub fn generations(&self) -> &[u64] {
+        self.generations.as_slice()
+    }
+
+    /// Returns the recorded counts of the passed symbol, matching the [generations](StatsRecorder::generations), or an empty slice if the symbol is not tracked.
+    pub fn series(&self, symbol: T) -> &[usize] {
+        self.symbols
+            .iter()
+            .position(|&tracked| tracked == symbol)
+            .map_or(&[], |index| self.counts[index].as_slice())
+    }
+
+    /// Writes the recorded counts as CSV, with a header row and then one row per generation.
+    /// The first column contains the generation, followed by one column per tracked symbol.
+    pub fn write_csv(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        write!(writer, "generation")?;
+        for symbol in &self.symbols {
+            write!(writer, ",{symbol:?}")?;
+        }
+        writeln!(writer)?;
+        for (index, generation) in self.generations().iter().enumerate() {
+            write!(writer, "{generation}")?;
+            for series in &self.counts {
+                write!(writer, ",{}", series.as_slice()[index])?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// A buffer keeping the last values pushed to it up to its capacity, readable as a single slice.
+///
+/// Values are stored in a vector of up to twice the capacity, of which the older half is dropped once it is full.
+#[derive(Debug, Clone)]
+struct RingBuffer<V> {
+    /// The stored values, of which only the last ```capacity``` are still valid.
+    data: Vec<V>,
+    /// The number of values kept.
+    capacity: usize,
+}
+
+impl<V> RingBuffer<V> {
+    /// Creates an empty buffer keeping the passed number of values, but at least one.
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Adds a value, dropping the oldest one if the buffer is full.
+    fn push(&mut self, value: V) {
+        if self.data.len() == 2 * self.capacity {
+            self.data.drain(..self.capacity);
+        }
+        self.data.push(value);
+    }
+
+    /// Removes all values.
+    fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns the kept values, oldest first.
+    fn as_slice(&self) -> &[V] {
+        &self.data[self.data.len().saturating_sub(self.capacity)..]
+    }
+}
+
+#[test]
+fn stats_recorder_test() {
+    use crate::rule::Rule;
+
+    // a glider and a blinker on a torus, counted manually after each step
+    let mut state = crate::CellGrid::new(12, 12);
+    for (row, col) in [
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (2, 1),
+        (2, 2),
+        (7, 6),
+        (7, 7),
+        (7, 8),
+    ] {
+        state[row][col] = 1;
+    }
+    let rule = crate::rule::LifeLikeRule {
+        row_boundary: crate::rule::BoundaryBehaviour::Periodic,
+        col_boundary: crate::rule::BoundaryBehaviour::Periodic,
+        ..crate::rule::LifeLikeRule::parse("B3/S23").unwrap()
+    };
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_grid(state.clone())
+        .with_rule(rule)
+        .with_stats_recorder(vec![1, 0, 5])
+        .build();
+
+    let mut expected = (vec![0], vec![8], vec![136]);
+    for generation in 1..=40 {
+        auto.next_step();
+        rule.transform(&mut state);
+        let alive = state.iter().filter(|&&cell| cell == 1).count();
+        expected.0.push(generation);
+        expected.1.push(alive);
+        expected.2.push(144 - alive);
+    }
+    assert_eq!(auto.state(), &state);
+
+    let recorder = auto.stats_recorder().unwrap();
+    assert_eq!(recorder.symbols(), [1, 0, 5]);
+    assert_eq!(recorder.generations(), expected.0);
+    assert_eq!(recorder.series(1), expected.1);
+    assert_eq!(recorder.series(0), expected.2);
+    assert_eq!(recorder.series(5), [0; 41]);
+    assert!(recorder.series(2).is_empty());
+
+    // one row per generation
+    let mut csv = Vec::new();
+    recorder.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 42);
+    assert_eq!(lines[0], "generation,1,0,5");
+    assert_eq!(lines[1], "0,8,136,0");
+
+    // only the most recent generations are kept
+    let mut recorder = StatsRecorder::with_capacity(vec![1], 3);
+    for generation in 0..10 {
+        recorder.record(generation, &grid::Grid::init(1, generation as usize, 1));
+        let kept = generation.min(2) as usize + 1;
+        assert_eq!(recorder.generations().len(), kept);
+        assert_eq!(recorder.generations().last(), Some(&generation));
+        assert_eq!(recorder.series(1).last(), Some(&(generation as usize)));
+    }
+    assert_eq!(recorder.series(1), [7, 8, 9]);
+    recorder.clear();
+    assert!(recorder.generations().is_empty());
+}
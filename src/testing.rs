@@ -0,0 +1,199 @@
+//! Helpers for testing rules, comparing grids written as text and printing readable differences on failure.
+//!
+//! Grids are written with one line per row and one character per cell, as given by [char_to_id](crate::char_to_id).
+//! Lines are trimmed and blank lines are skipped, so grids can be indented freely, but empty cells at either end of a row have to be written as ```0``` instead of a space.
+//! ```
+//! # use cellumina::{rule, testing};
+//! let sand = rule::PatternRule::from_patterns(
+//!     &[rule::Pattern {
+//!         before: grid::grid![[1][0]],
+//!         after: grid::grid![[0][1]],
+//!         ..Default::default()
+//!     }],
+//!     rule::BoundaryBehaviour::blocking_boundary(),
+//!     rule::BoundaryBehaviour::blocking_boundary(),
+//! );
+//! testing::assert_rule_step(
+//!     &sand,
+//!     "1 1
+//!      0 1
+//!      000",
+//!     "0 1
+//!      1 0
+//!      001",
+//! );
+//! testing::assert_rule_evolution(&sand, "1\n0\n0", "0\n0\n1", 5, 0);
+//! ```
+use crate::{rule, CellGrid};
+
+/// The number of mismatching cells listed individually by [grid_diff].
+const LISTED_MISMATCHES: usize = 10;
+
+/// Parses a grid written as text, see the [module documentation](self).
+/// ## Panics
+/// If the rows are of different lengths or contain characters without an associated cell value.
+#[track_caller]
+pub fn parse_grid(text: &str) -> CellGrid {
+    let rows = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let cols = rows.first().map_or(0, |row| row.chars().count());
+    let mut cells = Vec::with_capacity(rows.len() * cols);
+    for (index, row) in rows.iter().enumerate() {
+        assert_eq!(
+            row.chars().count(),
+            cols,
+            "Row {index} of the grid {row:?} has a different length than the first row."
+        );
+        for symbol in row.chars() {
+            cells
+                .push(crate::try_char_to_id(symbol).unwrap_or_else(|err| {
+                    panic!("Could not parse row {index} of the grid: {err}")
+                }));
+        }
+    }
+    CellGrid::from_vec(cells, cols)
+}
+
+/// Describes the differences between two grids, or returns [None] if they are equal.
+///
+/// The description contains both grids side by side, followed by the coordinates and values of the first mismatching cells.
+/// ```
+/// # use cellumina::testing::{grid_diff, parse_grid};
+/// assert_eq!(grid_diff(&parse_grid("12"), &parse_grid("12")), None);
+/// let diff = grid_diff(&parse_grid("12"), &parse_grid("13")).unwrap();
+/// assert!(diff.contains("(0, 1): expected '3' (3), found '2' (2)"));
+/// ```
+pub fn grid_diff(actual: &CellGrid, expected: &CellGrid) -> Option<String> {
+    if actual == expected {
+        return None;
+    }
+    let mut res = String::new();
+    if actual.size() != expected.size() {
+        res.push_str(&format!(
+            "expected a grid of {} rows and {} columns, found {} rows and {} columns\n",
+            expected.rows(),
+            expected.cols(),
+            actual.rows(),
+            actual.cols()
+        ));
+    }
+
+    let mismatches = (0..actual.rows().max(expected.rows()))
+        .flat_map(|row| (0..actual.cols().max(expected.cols())).map(move |col| (row, col)))
+        .filter(|&(row, col)| actual.get(row, col) != expected.get(row, col))
+        .collect::<Vec<_>>();
+    res.push_str(&format!("grids differ in {} cells\n", mismatches.len()));
+
+    // both grids side by side
+    let width = expected.cols().max("expected".len());
+    res.push_str(&format!("{:width$} | actual\n", "expected"));
+    for row in 0..actual.rows().max(expected.rows()) {
+        let line = |grid: &CellGrid| {
+            (0..grid.cols())
+                .filter_map(|col| grid.get(row, col))
+                .map(|&cell| symbol(cell))
+                .collect::<String>()
+        };
+        res.push_str(&format!("{:width$} | {}\n", line(expected), line(actual)));
+    }
+
+    for &(row, col) in mismatches.iter().take(LISTED_MISMATCHES) {
+        let describe = |cell: Option<&u8>| match cell {
+            Some(&cell) => format!("'{}' ({cell})", symbol(cell)),
+            None => "nothing".to_string(),
+        };
+        res.push_str(&format!(
+            "({row}, {col}): expected {}, found {}\n",
+            describe(expected.get(row, col)),
+            describe(actual.get(row, col))
+        ));
+    }
+    if mismatches.len() > LISTED_MISMATCHES {
+        res.push_str(&format!(
+            "and {} more\n",
+            mismatches.len() - LISTED_MISMATCHES
+        ));
+    }
+    Some(res)
+}
+
+/// Asserts that the passed grid equals the grid written as text, printing the differences otherwise.
+#[track_caller]
+pub fn assert_grid_eq(actual: &CellGrid, expected: &str) {
+    if let Some(diff) = grid_diff(actual, &parse_grid(expected)) {
+        panic!("{diff}");
+    }
+}
+
+/// Asserts that a single application of the rule to the ```before``` grid results in the ```after``` grid.
+#[track_caller]
+pub fn assert_rule_step(rule: &dyn rule::Rule, before: &str, after: &str) {
+    let mut grid = parse_grid(before);
+    rule.transform(&mut grid);
+    assert_grid_eq(&grid, after);
+}
+
+/// Asserts that applying the rule ```steps``` times to the ```before``` grid results in the ```expected``` grid.
+/// The rule is [reseeded](rule::Rule::reseed) with the passed seed first, so the result of rules using randomness is reproducible.
+#[track_caller]
+pub fn assert_rule_evolution(
+    rule: &dyn rule::Rule,
+    before: &str,
+    expected: &str,
+    steps: usize,
+    seed: u64,
+) {
+    rule.reseed(seed);
+    let mut grid = parse_grid(before);
+    for _ in 0..steps {
+        rule.transform(&mut grid);
+    }
+    assert_grid_eq(&grid, expected);
+}
+
+/// Returns the character a cell is displayed as, using ```0``` for empty cells to keep them visible.
+fn symbol(cell: u8) -> char {
+    match cell {
+        0 => '0',
+        cell => crate::id_to_char(cell),
+    }
+}
+
+#[test]
+fn grid_diff_test() {
+    let grid = parse_grid(
+        "
+        0120
+        3_*0
+        ",
+    );
+    assert_eq!(grid, grid::grid![[0, 1, 2, 0][3, 126, 127, 0]]);
+    assert_eq!(parse_grid("").size(), (0, 0));
+
+    let diff = grid_diff(&grid, &parse_grid("0121\n3_*0")).unwrap();
+    assert_eq!(
+        diff,
+        "grids differ in 1 cells\n\
+         expected | actual\n\
+         0121     | 0120\n\
+         3_*0     | 3_*0\n\
+         (0, 3): expected '1' (1), found '0' (0)\n"
+    );
+
+    // grids of different sizes
+    let diff = grid_diff(&grid, &parse_grid("012")).unwrap();
+    assert!(diff.starts_with(
+        "expected a grid of 1 rows and 3 columns, found 2 rows and 4 columns\ngrids differ in 5 cells\n"
+    ));
+    assert!(diff.contains("(1, 1): expected nothing, found '_' (126)\n"));
+
+    // long lists of mismatches are cut short
+    let diff = grid_diff(&CellGrid::new(4, 4), &parse_grid("1111\n1111\n1111\n1111")).unwrap();
+    assert!(diff.ends_with("and 6 more\n"));
+
+    let res = std::panic::catch_unwind(|| assert_grid_eq(&grid, "0120\n3_*1"));
+    assert!(res.is_err());
+}
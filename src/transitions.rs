@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use crate::Cell;
+
+/// Counts how many cells changed from which symbol to which other symbol between consecutive states of an automaton, for example to see how a rule moves material around.
+///
+/// Attach a tracker to an automaton with [AutomatonBuilder::with_transition_tracker](crate::AutomatonBuilder::with_transition_tracker) and read it using [Automaton::transition_tracker](crate::Automaton::transition_tracker),
+/// or feed it states yourself using [TransitionTracker::update].
+/// Cells that keep their symbol are not counted.
+/// ```
+/// # use cellumina::TransitionTracker;
+/// let mut tracker = TransitionTracker::new();
+/// tracker.update(&grid::grid![[1, 0, 0]]);
+/// tracker.update(&grid::grid![[0, 1, 0]]);
+/// tracker.update(&grid::grid![[0, 0, 1]]);
+/// assert_eq!(tracker.count(1, 0), 2);
+/// assert_eq!(tracker.count(0, 1), 2);
+/// assert_eq!(tracker.count(0, 0), 0);
+///
+/// let mut csv = Vec::new();
+/// tracker.write_csv(&mut csv).unwrap();
+/// assert!(String::from_utf8(csv).unwrap().starts_with("from,to,count\n"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransitionTracker<T: Cell = u8> {
+    /// The state passed in the last update, to compare the next one with.
+    previous: Option<grid::Grid<T>>,
+    /// The number of cells that changed from the first to the second symbol of each pair.
+    counts: HashMap<(T, T), u64>,
+}
+
+impl<T: Cell> Default for TransitionTracker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Cell> TransitionTracker<T> {
+    /// Creates a tracker without any counted transitions, which starts counting with the second state passed to [Self::update].
+    pub fn new() -> Self {
+        Self {
+            previous: None,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Compares the passed state with the previous one and counts the transitions of all changed cells.
+    /// If the dimensions of the state changed, the states can not be compared and nothing is counted.
+    pub fn update(&mut self, state: &grid::Grid<T>) {
+        if let Some(previous) = &self.previous {
+            if let Ok(pairs) = crate::analysis::cell_pairs(previous, state) {
+                for (from, to) in pairs.filter(|(from, to)| from != to) {
+                    *self.counts.entry((from, to)).or_insert(0) += 1;
+                }
+            }
+        }
+        self.previous
+            .get_or_insert_with(|| grid::Grid::new(0, 0))
+            .clone_from(state);
+    }
+
+    /// Removes all counted transitions. The last state is kept, so the next update still counts the transitions since then.
+    pub fn reset(&mut self) {
+        self.counts.clear();
+    }
+
+    /// Returns the number of cells that changed from the symbol ```from``` to the symbol ```to```.
+    pub fn count(&self, from: T, to: T) -> u64 {
+        self.counts.get(&(from, to)).copied().unwrap_or(0)
+    }
+
+    /// Returns the counts of all transitions that occured at least once, by the pair of symbols ```(from, to)```.
+    pub fn counts(&self) -> &HashMap<(T, T), u64> {
+        &self.counts
+    }
+
+    /// Returns the ```n``` most frequent transitions with their counts, the most frequent first. Equally frequent transitions are in no particular order.
+    pub fn top_transitions(&self, n: usize) -> Vec<((T, T), u64)> {
+        let mut res = self
+            .counts
+            .iter()
+            .map(|(&pair, &count)| (pair, count))
+            .collect::<Vec<_>>();
+        res.sort_by(|(_, count1), (_, count2)| count2.cmp(count1));
+        res.truncate(n);
+        res
+    }
+
+    /// Writes the counted transitions as CSV, with a header row and then one row per transition, the most frequent first.
+    /// The columns contain the symbol before, the symbol after and the number of cells.
+    pub fn write_csv(&self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        writeln!(writer, "from,to,count")?;
+        for ((from, to), count) in self.top_transitions(usize::MAX) {
+            writeln!(writer, "{from:?},{to:?},{count}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn transition_test() {
+    use crate::rule::presets;
+
+    let mut auto = crate::AutomatonBuilder::new()
+        .from_vec([vec![0; 12], vec![59; 12], vec![0; 24]].concat(), 6)
+        .with_preset(presets::falling_sand())
+        .with_seed(3)
+        .with_transition_tracker()
+        .build();
+    let mut manual = TransitionTracker::new();
+    manual.update(auto.state());
+    for _ in 0..5 {
+        auto.next_step();
+        manual.update(auto.state());
+    }
+
+    // sand only moves, so every cell it leaves is matched by a cell it enters
+    let tracker = auto.transition_tracker().unwrap();
+    assert!(tracker.count(59, 0) > 0);
+    assert_eq!(tracker.count(59, 0), tracker.count(0, 59));
+    assert_eq!(tracker.counts().len(), 2);
+    assert_eq!(tracker.counts(), manual.counts());
+    assert_eq!(tracker.top_transitions(1).len(), 1);
+    assert_eq!(tracker.top_transitions(5).len(), 2);
+
+    let mut csv = Vec::new();
+    tracker.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains(&format!("59,0,{}", tracker.count(59, 0))));
+
+    // resetting keeps counting from the last state
+    let tracker = auto.transition_tracker_mut().unwrap();
+    tracker.reset();
+    assert!(tracker.counts().is_empty());
+    auto.set_cell(0, 0, 7).unwrap();
+    auto.next_step();
+    let tracker = auto.transition_tracker().unwrap();
+    assert_eq!(tracker.count(0, 7), 1);
+
+    // states of different sizes are not compared
+    manual.reset();
+    manual.update(&grid::grid![[1, 2]]);
+    assert!(manual.counts().is_empty());
+    manual.update(&grid::grid![[2, 1]]);
+    assert_eq!(manual.count(1, 2), 1);
+    assert_eq!(manual.count(2, 1), 1);
+}
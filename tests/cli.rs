@@ -0,0 +1,96 @@
+#![cfg(feature = "cli")]
+//! Runs the command line interface on the files in ```tests/fixtures```.
+use assert_cmd::Command;
+
+/// Returns the path of the passed fixture.
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Returns a command running the cellumina binary.
+fn cellumina() -> Command {
+    Command::cargo_bin("cellumina").unwrap()
+}
+
+#[test]
+fn run_preset_test() {
+    // a blinker turns by 90 degrees each step
+    for (steps, expected) in [("1", " 1 \n 1 \n 1 \n"), ("2", "   \n111\n   \n")] {
+        cellumina()
+            .args(["run", "--preset", "game-of-life", "--steps", steps])
+            .args(["--init", &fixture("blinker.txt")])
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+}
+
+#[test]
+fn run_rules_test() {
+    // the cell falls down by one row each step
+    cellumina()
+        .args(["run", "--steps", "2", "--seed", "0"])
+        .args(["--init", &fixture("falling.txt")])
+        .args(["--rules", &fixture("basic_rule.cel")])
+        .assert()
+        .success()
+        .stdout(" \n \n1\n");
+}
+
+#[test]
+fn save_and_convert_test() {
+    let image = std::env::temp_dir().join("cellumina_cli_test.png");
+    let text = std::env::temp_dir().join("cellumina_cli_test.txt");
+
+    cellumina()
+        .args(["run", "--preset", "game-of-life", "--steps", "1"])
+        .args(["--init", &fixture("blinker.txt")])
+        .arg("--out")
+        .arg(&image)
+        .assert()
+        .success()
+        .stdout("");
+
+    // converting the image back needs the colors
+    cellumina()
+        .arg("convert")
+        .arg(&image)
+        .arg(&text)
+        .args(["--colors", &fixture("palette.toml")])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&text).unwrap(), " 1 \n 1 \n 1 \n");
+
+    cellumina()
+        .arg("convert")
+        .arg(&image)
+        .arg(&text)
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicates::str::contains("no colors"));
+
+    std::fs::remove_file(image).unwrap();
+    std::fs::remove_file(text).unwrap();
+}
+
+#[test]
+fn exit_code_test() {
+    // files that can not be read are errors
+    cellumina()
+        .args(["run", "--preset", "seeds", "--init", "missing.txt"])
+        .assert()
+        .code(1)
+        .stderr(predicates::str::starts_with("error:"));
+    cellumina()
+        .args(["convert", &fixture("blinker.txt"), "state.xyz"])
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("unsupported file format: xyz"));
+
+    // usage errors are reported by the argument parser
+    cellumina()
+        .args(["run", "--init", &fixture("blinker.txt")])
+        .assert()
+        .code(2);
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "ffi")]
+//! Drives automata through the C interface, calling its functions directly as a C program would.
+use std::ffi::CString;
+use std::ptr;
+
+use cellumina::ffi::*;
+
+/// Builds the passed builder, asserting success.
+unsafe fn build(builder: *mut CelluminaBuilder) -> *mut CelluminaAutomaton {
+    let mut automaton = ptr::null_mut();
+    assert_eq!(
+        cellumina_builder_build(builder, &mut automaton),
+        CelluminaStatus::Ok
+    );
+    assert!(!automaton.is_null());
+    automaton
+}
+
+/// Reads the state of the passed automaton.
+unsafe fn read_state(automaton: *const CelluminaAutomaton) -> Vec<u8> {
+    let (mut rows, mut cols) = (0, 0);
+    assert_eq!(
+        cellumina_automaton_size(automaton, &mut rows, &mut cols),
+        CelluminaStatus::Ok
+    );
+    let mut state = vec![0; rows * cols];
+    assert_eq!(
+        cellumina_automaton_read_state(automaton, state.as_mut_ptr(), state.len()),
+        CelluminaStatus::Ok
+    );
+    state
+}
+
+#[test]
+fn life_like_test() {
+    unsafe {
+        let builder = cellumina_builder_new();
+        let blinker = [0, 1, 0, 0, 1, 0, 0, 1, 0];
+        assert_eq!(
+            cellumina_builder_set_state(builder, blinker.as_ptr(), blinker.len(), 3),
+            CelluminaStatus::Ok
+        );
+        let notation = CString::new("B3/S23").unwrap();
+        assert_eq!(
+            cellumina_builder_add_life_like_rule(builder, notation.as_ptr()),
+            CelluminaStatus::Ok
+        );
+        let automaton = build(builder);
+
+        // a blinker turns by 90 degrees each step
+        assert_eq!(cellumina_automaton_step(automaton, 1), CelluminaStatus::Ok);
+        assert_eq!(read_state(automaton), [0, 0, 0, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(cellumina_automaton_step(automaton, 3), CelluminaStatus::Ok);
+        assert_eq!(read_state(automaton), blinker);
+
+        // the buffer has to fit the entire state
+        let mut small = [0; 8];
+        assert_eq!(
+            cellumina_automaton_read_state(automaton, small.as_mut_ptr(), small.len()),
+            CelluminaStatus::BufferTooSmall
+        );
+        cellumina_automaton_free(automaton);
+    }
+}
+
+#[test]
+fn pattern_rule_test() {
+    unsafe {
+        // a single cell falls down by one row each step
+        let builder = cellumina_builder_new();
+        let state = [1, 0, 0];
+        assert_eq!(
+            cellumina_builder_set_state(builder, state.as_ptr(), state.len(), 1),
+            CelluminaStatus::Ok
+        );
+        let cel = cellumina::rule::PatternRule::from_patterns(
+            &[cellumina::rule::Pattern {
+                before: grid::grid![[1][0]],
+                after: grid::grid![[0][1]],
+                ..Default::default()
+            }],
+            cellumina::rule::BoundaryBehaviour::blocking_boundary(),
+            cellumina::rule::BoundaryBehaviour::blocking_boundary(),
+        )
+        .to_string();
+        let cel = CString::new(cel).unwrap();
+        assert_eq!(
+            cellumina_builder_add_pattern_rule(builder, cel.as_ptr()),
+            CelluminaStatus::Ok
+        );
+        let automaton = build(builder);
+
+        assert_eq!(cellumina_automaton_step(automaton, 1), CelluminaStatus::Ok);
+        assert_eq!(read_state(automaton), [0, 1, 0]);
+        assert_eq!(cellumina_automaton_step(automaton, 5), CelluminaStatus::Ok);
+        assert_eq!(read_state(automaton), [0, 0, 1]);
+        cellumina_automaton_free(automaton);
+    }
+}
+
+#[test]
+fn error_test() {
+    unsafe {
+        let builder = cellumina_builder_new();
+
+        // null pointers and invalid arguments
+        assert_eq!(
+            cellumina_builder_set_state(builder, ptr::null(), 0, 1),
+            CelluminaStatus::NullPointer
+        );
+        let state = [1, 0, 0];
+        assert_eq!(
+            cellumina_builder_set_state(builder, state.as_ptr(), state.len(), 0),
+            CelluminaStatus::InvalidArgument
+        );
+        assert_eq!(
+            cellumina_builder_set_state(builder, state.as_ptr(), state.len(), 2),
+            CelluminaStatus::InvalidArgument
+        );
+        assert_eq!(
+            cellumina_automaton_step(ptr::null_mut(), 1),
+            CelluminaStatus::NullPointer
+        );
+
+        // malformed rules
+        let notation = CString::new("B3/S239").unwrap();
+        assert_eq!(
+            cellumina_builder_add_life_like_rule(builder, notation.as_ptr()),
+            CelluminaStatus::ParseError
+        );
+        let cel = CString::new("Periodic;\n\nPeriodic;\n\n1;\n1;\n\n0.5;\n1;").unwrap();
+        assert_eq!(
+            cellumina_builder_add_pattern_rule(builder, cel.as_ptr()),
+            CelluminaStatus::ParseError
+        );
+
+        // the builder remains usable after errors
+        assert_eq!(
+            cellumina_builder_set_state(builder, state.as_ptr(), state.len(), 3),
+            CelluminaStatus::Ok
+        );
+        let automaton = build(builder);
+        assert_eq!(read_state(automaton), state);
+        cellumina_automaton_free(automaton);
+
+        // freeing null does nothing
+        cellumina_builder_free(ptr::null_mut());
+        cellumina_automaton_free(ptr::null_mut());
+    }
+}
@@ -0,0 +1,79 @@
+//! Runs the headless workflow of ```examples/headless```: building an automaton, stepping it, inspecting it and exporting it, without any features.
+use cellumina::prelude::*;
+use cellumina::rule::GridTopology;
+
+/// Builds a Game of Life on a torus with a glider in the top left corner, recording the number of live cells.
+fn glider() -> Automaton {
+    let mut cells = vec![0; 10 * 10];
+    for (row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)] {
+        cells[row * 10 + col] = 1;
+    }
+    AutomatonBuilder::new()
+        .from_vec(cells, 10)
+        .with_rule(LifeLikeRule {
+            row_boundary: BoundaryBehaviour::Periodic,
+            col_boundary: BoundaryBehaviour::Periodic,
+            ..LifeLikeRule::parse("B3/S23").unwrap()
+        })
+        .with_stats_recorder(vec![1])
+        .with_color(0, [0, 0, 0, 255])
+        .with_color(1, [95, 205, 228, 255])
+        .build()
+}
+
+#[test]
+fn headless_pipeline_test() {
+    let mut automaton = glider();
+    let initial = automaton.state().clone();
+    for _ in 0..100 {
+        assert!(automaton.next_step());
+    }
+    assert_eq!(automaton.generation(), 100);
+
+    // a glider keeps its five cells and returns to its shape every four generations, one cell further down and right
+    let stats = automaton.stats_recorder().unwrap();
+    assert_eq!(stats.generations().len(), 101);
+    assert!(stats.series(1).iter().all(|&count| count == 5));
+    let mut expected = initial.clone();
+    for _ in 0..25 {
+        cellumina::rule::ShiftRule {
+            dy: 1,
+            dx: 1,
+            fill: None,
+        }
+        .transform(&mut expected);
+    }
+    assert_eq!(automaton.state(), &expected);
+    assert_eq!(automaton.positions_of(1).len(), 5);
+
+    // the scaled image has one square of pixels per cell
+    let directory = std::env::temp_dir();
+    let image_path = directory.join("cellumina_headless_test.png");
+    automaton
+        .create_image_buffer_scaled(4, GridTopology::Square)
+        .save(&image_path)
+        .unwrap();
+    let image = image::open(&image_path).unwrap().into_rgba8();
+    std::fs::remove_file(&image_path).unwrap();
+    assert_eq!(image.dimensions(), (40, 40));
+    let (row, col) = automaton.first_cell_of(1).unwrap();
+    assert_eq!(
+        image.get_pixel(col as u32 * 4 + 3, row as u32 * 4 + 3).0,
+        [95, 205, 228, 255]
+    );
+
+    // the text snapshot loads into the same state
+    let text_path = directory.join("cellumina_headless_test.txt");
+    automaton.save(&text_path).unwrap();
+    let reloaded = AutomatonBuilder::new()
+        .from_text_file(text_path.clone())
+        .with_strict_parsing()
+        .build();
+    std::fs::remove_file(&text_path).unwrap();
+    assert_eq!(reloaded.state(), automaton.state());
+
+    // the population curve exports as CSV with a header and one row per generation
+    let mut csv = Vec::new();
+    stats.write_csv(&mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 102);
+}